@@ -0,0 +1,131 @@
+//! Benchmarks for response deserialization.
+//!
+//! Run with `cargo bench --bench decode`, and with `--features rayon` to compare
+//! parallel frame decoding.
+
+use std::hint::black_box;
+use std::io::Write;
+
+use base64::{Engine as _, engine::general_purpose};
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use flate2::{Compression, write::ZlibEncoder};
+
+use brightsky::types::{RadarResponse, WeatherResponse};
+
+/// Full radar grid is 1100×1200 pixels.
+const RADAR_SIZES: [(usize, usize); 3] = [(100, 100), (400, 400), (1100, 1200)];
+
+/// Deterministic precipitation values with the sparse structure of real radar
+/// frames: mostly dry with a few rain cells.
+fn precipitation(width: usize, height: usize) -> Vec<u16> {
+    (0..width * height)
+        .map(|i| {
+            let (x, y) = (i % width, i / width);
+            if (x / 37 + y / 23) % 5 == 0 {
+                ((x * 7 + y * 13) % 250) as u16
+            } else {
+                0
+            }
+        })
+        .collect()
+}
+
+fn radar_json(width: usize, height: usize, format: &str) -> String {
+    radar_sequence_json(width, height, format, 1)
+}
+
+/// A response with `frames` identical records, 5 minutes apart.
+fn radar_sequence_json(width: usize, height: usize, format: &str, frames: usize) -> String {
+    let values = precipitation(width, height);
+    let bytes: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+
+    let precipitation_5 = match format {
+        "compressed" => {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&bytes).unwrap();
+            serde_json::Value::String(general_purpose::STANDARD.encode(encoder.finish().unwrap()))
+        }
+        "bytes" => serde_json::Value::String(general_purpose::STANDARD.encode(&bytes)),
+        _ => serde_json::to_value(values.chunks(width).collect::<Vec<_>>()).unwrap(),
+    };
+
+    let start = chrono::DateTime::parse_from_rfc3339("2023-08-07T12:45:00+00:00").unwrap();
+    let radar: Vec<serde_json::Value> = (0..frames)
+        .map(|i| {
+            serde_json::json!({
+                "timestamp": start + chrono::TimeDelta::minutes(5 * i as i64),
+                "source": "RADOLAN::RV::2023-08-07T12:45:00+00:00",
+                "precipitation_5": precipitation_5,
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "radar": radar,
+        "geometry": {
+            "type": "Polygon",
+            "coordinates": [[5.0, 55.0], [15.0, 55.0], [15.0, 47.0], [5.0, 47.0], [5.0, 55.0]],
+        },
+        "bbox": [0, 0, height - 1, width - 1],
+        "latlon_position": {"x": 0.0, "y": 0.0},
+    })
+    .to_string()
+}
+
+/// A `/weather` response with the bundled fixture records repeated `records / 8` times.
+fn weather_json(records: usize) -> String {
+    let mut fixture: serde_json::Value =
+        serde_json::from_str(include_str!("../fixtures/weather.json")).unwrap();
+    let sample = fixture["weather"].as_array().unwrap().clone();
+    fixture["weather"] = sample.iter().cycle().take(records).cloned().collect();
+    fixture.to_string()
+}
+
+fn bench_radar(c: &mut Criterion) {
+    let mut group = c.benchmark_group("radar");
+    for format in ["compressed", "bytes", "plain"] {
+        for (width, height) in RADAR_SIZES {
+            let json = radar_json(width, height, format);
+            group.throughput(Throughput::Elements((width * height) as u64));
+            group.bench_with_input(
+                BenchmarkId::new(format, format!("{width}x{height}")),
+                &json,
+                |b, json| {
+                    b.iter(|| serde_json::from_str::<RadarResponse>(black_box(json)).unwrap())
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+/// Decompressing and reshaping a two-hour nowcast (25 frames) of the full grid,
+/// in parallel with the `rayon` feature.
+fn bench_radar_frames(c: &mut Criterion) {
+    let mut group = c.benchmark_group("radar_frames");
+    group.sample_size(20);
+    let (width, height, frames) = (1100, 1200, 25);
+    let response: RadarResponse =
+        serde_json::from_str(&radar_sequence_json(width, height, "compressed", frames)).unwrap();
+    group.throughput(Throughput::Elements((width * height * frames) as u64));
+    group.bench_function(BenchmarkId::new("decode_all", frames), |b| {
+        b.iter(|| black_box(&response).decode_all().unwrap())
+    });
+    group.finish();
+}
+
+fn bench_weather(c: &mut Criterion) {
+    let mut group = c.benchmark_group("weather");
+    // One day, ten days and a month of hourly records
+    for records in [24, 240, 744] {
+        let json = weather_json(records);
+        group.throughput(Throughput::Bytes(json.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(records), &json, |b, json| {
+            b.iter(|| serde_json::from_str::<WeatherResponse>(black_box(json)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_radar, bench_radar_frames, bench_weather);
+criterion_main!(benches);
@@ -0,0 +1,120 @@
+//! Benchmarks for query building and URL generation.
+//!
+//! Run with `cargo bench --bench url`.
+
+use std::hint::black_box;
+
+use chrono::NaiveDate;
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+
+use brightsky::{
+    AlertsQuery, AlertsQueryBuilder, BRIGHT_SKY_API, CurrentWeatherQuery,
+    CurrentWeatherQueryBuilder, RadarWeatherQuery, RadarWeatherQueryBuilder, ToBrightSkyUrl,
+    WeatherQueryBuilder,
+    types::{RadarCompressionFormat, UnitType},
+};
+
+fn weather_query() -> WeatherQueryBuilder {
+    WeatherQueryBuilder::new()
+        .with_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap())
+        .with_last_date(NaiveDate::from_ymd_opt(2023, 8, 8).unwrap())
+        .with_lat_lon((52.52, 13.4))
+        .with_max_dist(50000)
+        .with_tz("Europe/Berlin")
+        .with_units(UnitType::Dwd)
+}
+
+fn bench_build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build");
+    group.bench_function("weather", |b| {
+        b.iter(|| black_box(weather_query()).build().unwrap())
+    });
+    group.bench_function("current_weather", |b| {
+        b.iter(|| {
+            CurrentWeatherQueryBuilder::new()
+                .with_lat_lon(black_box((52.52, 13.4)))
+                .build()
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+fn current_weather_query() -> CurrentWeatherQuery {
+    CurrentWeatherQueryBuilder::new()
+        .with_lat_lon((52.52, 13.4))
+        .build()
+        .unwrap()
+}
+
+fn radar_query() -> RadarWeatherQuery {
+    RadarWeatherQueryBuilder::new()
+        .with_lat_lon((52.52, 13.4))
+        .with_distance(100_000)
+        .with_compression_format(RadarCompressionFormat::Compressed)
+        .build()
+        .unwrap()
+}
+
+fn alerts_query() -> AlertsQuery {
+    AlertsQueryBuilder::new()
+        .with_lat_lon((52.52, 13.4))
+        .build()
+        .unwrap()
+}
+
+fn bench_to_url(c: &mut Criterion) {
+    let weather = || weather_query().build().unwrap();
+
+    // Queries are consumed by `to_url`, so each iteration gets a fresh one
+    let mut group = c.benchmark_group("to_url");
+    group.bench_function("weather", |b| {
+        b.iter_batched(
+            weather,
+            |q| q.to_url(BRIGHT_SKY_API).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("current_weather", |b| {
+        b.iter_batched(
+            current_weather_query,
+            |q| q.to_url(BRIGHT_SKY_API).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("radar", |b| {
+        b.iter_batched(
+            radar_query,
+            |q| q.to_url(BRIGHT_SKY_API).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("alerts", |b| {
+        b.iter_batched(
+            alerts_query,
+            |q| q.to_url(BRIGHT_SKY_API).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("to_url_string");
+    group.bench_function("weather", |b| {
+        b.iter_batched(
+            weather,
+            |q| q.to_url_string(BRIGHT_SKY_API).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("current_weather", |b| {
+        b.iter_batched(
+            current_weather_query,
+            |q| q.to_url_string(BRIGHT_SKY_API).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_build, bench_to_url);
+criterion_main!(benches);
@@ -0,0 +1,58 @@
+//! Watch the alerts of a warn cell and post every change to a webhook as JSON.
+//!
+//! ```text
+//! cargo run --example alerts_webhook --features reqwest,stream -- 803159016 https://example.com/hook
+//! ```
+//!
+//! Without a webhook URL, events are only printed. Stops on Ctrl+C.
+
+use std::time::Duration;
+
+use brightsky::{
+    AlertsQueryBuilder, BrightSkyClient, poll,
+    sink::{AlertSink, StdoutSink, WebhookSink},
+    types::AlertsResponse,
+};
+use futures_util::StreamExt;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let warn_cell_id: i64 = args.next().as_deref().unwrap_or("803159016").parse()?;
+    let webhook = args.next();
+
+    let http = reqwest::Client::new();
+    let client = BrightSkyClient::new(http.clone());
+    let webhook = webhook.map(|url| WebhookSink::new(http, url));
+
+    let query = AlertsQueryBuilder::new()
+        .with_warn_cell_id(warn_cell_id)
+        .build()?;
+    let events = poll::alerts_stream(query, Duration::from_secs(300), |query| {
+        client.get::<_, AlertsResponse>(query)
+    });
+    let shutdown = async {
+        tokio::signal::ctrl_c().await.ok();
+    };
+    let mut events = std::pin::pin!(poll::until_shutdown(events, shutdown));
+
+    println!("Watching warn cell {}", warn_cell_id);
+    while let Some(event) = events.next().await {
+        let event = match event {
+            Ok(event) => event,
+            Err(err) => {
+                eprintln!("Fetching alerts failed: {}", err);
+                continue;
+            }
+        };
+
+        StdoutSink.notify_event(&event).await?;
+        if let Some(webhook) = &webhook
+            && let Err(err) = webhook.notify_event(&event).await
+        {
+            eprintln!("Webhook failed: {}", err);
+        }
+    }
+    println!("Stopped");
+    Ok(())
+}
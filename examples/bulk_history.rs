@@ -0,0 +1,36 @@
+//! Download the hourly records of a date range in chunks and write them to CSV.
+//!
+//! ```text
+//! cargo run --example bulk_history --features reqwest -- 2023-01-01 2024-01-01 weather.csv
+//! ```
+
+use brightsky::{BrightSkyClient, export::ExportProfile};
+use chrono::NaiveDate;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let start: NaiveDate = args.next().as_deref().unwrap_or("2023-01-01").parse()?;
+    let end: NaiveDate = args.next().as_deref().unwrap_or("2023-02-01").parse()?;
+    let path = args.next().unwrap_or_else(|| "weather.csv".to_string());
+
+    let client = BrightSkyClient::new(reqwest::Client::new());
+    let request = client.get_history((52.52, 13.4), start..end);
+    println!(
+        "Fetching {} to {} in {} requests",
+        start,
+        end,
+        request.chunks().len()
+    );
+
+    let history = request.with_tz("Europe/Berlin").fetch().await?;
+    history.write_csv(std::fs::File::create(&path)?, ExportProfile::Full)?;
+
+    println!(
+        "Wrote {} records from {} sources to {}",
+        history.weather.len(),
+        history.sources.len(),
+        path
+    );
+    Ok(())
+}
@@ -0,0 +1,38 @@
+//! Build request URLs the way firmware would: without the `std` feature, as
+//! plain strings for an embedded HTTP client.
+//!
+//! ```text
+//! cargo run --example embedded_urls --no-default-features
+//! ```
+
+use brightsky::{
+    AlertsQueryBuilder, BRIGHT_SKY_API, CurrentWeatherQueryBuilder, RadarWeatherQueryBuilder,
+    ToBrightSkyUrl, WeatherQueryBuilder,
+    types::{RadarCompressionFormat, UnitType},
+};
+use chrono::NaiveDate;
+
+fn main() -> Result<(), brightsky::BrightSkyError> {
+    let location = (52.52, 13.4);
+
+    let current = CurrentWeatherQueryBuilder::new()
+        .with_lat_lon(location)
+        .with_units(UnitType::Si)
+        .build()?;
+    let weather = WeatherQueryBuilder::new()
+        .with_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap())
+        .with_lat_lon(location)
+        .build()?;
+    let radar = RadarWeatherQueryBuilder::new()
+        .with_lat_lon(location)
+        .with_distance(10_000)
+        .with_compression_format(RadarCompressionFormat::Bytes)
+        .build()?;
+    let alerts = AlertsQueryBuilder::new().with_lat_lon(location).build()?;
+
+    println!("{}", current.to_url_string(BRIGHT_SKY_API)?);
+    println!("{}", weather.to_url_string(BRIGHT_SKY_API)?);
+    println!("{}", radar.to_url_string(BRIGHT_SKY_API)?);
+    println!("{}", alerts.to_url_string(BRIGHT_SKY_API)?);
+    Ok(())
+}
@@ -0,0 +1,47 @@
+//! Render the radar frames around a location as PNG images, one file per frame,
+//! plus the total precipitation of the sequence.
+//!
+//! ```text
+//! cargo run --example radar_png --features reqwest,render -- 52.52 13.4 frames/
+//! ```
+
+use std::path::PathBuf;
+
+use brightsky::{
+    BrightSkyClient, RadarFrame, RadarWeatherQueryBuilder,
+    render::{ColorRamp, RenderStyle},
+    types::{RadarCompressionFormat, RadarResponse},
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let lat: f64 = args.next().as_deref().unwrap_or("52.52").parse()?;
+    let lon: f64 = args.next().as_deref().unwrap_or("13.4").parse()?;
+    let dir = PathBuf::from(args.next().unwrap_or_else(|| "frames".to_string()));
+    std::fs::create_dir_all(&dir)?;
+
+    let client = BrightSkyClient::new(reqwest::Client::new());
+    let query = RadarWeatherQueryBuilder::new()
+        .with_lat_lon((lat, lon))
+        .with_distance(100_000)
+        .with_compression_format(RadarCompressionFormat::Compressed)
+        .build()?;
+    let response: RadarResponse = client.get(query).await?;
+
+    let style = RenderStyle::Color(ColorRamp::default());
+    let frames: Vec<RadarFrame> = response.frames().collect();
+    for frame in &frames {
+        let path = dir.join(format!("{}.png", frame.time.format("%Y%m%d-%H%M")));
+        frame.grid.save_png(&path, &style)?;
+        println!("Wrote {}", path.display());
+    }
+
+    if let Some(total) = RadarFrame::accumulate(&frames) {
+        // Totals span a wider range than single frames
+        let path = dir.join("total.png");
+        total.save_png(&path, &RenderStyle::Grayscale { max_mm: 20.0 })?;
+        println!("Wrote {}", path.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,82 @@
+//! Radar frames around a location: the heaviest and the total precipitation of
+//! the next two hours as text maps, and the nowcast at the center.
+//!
+//! ```text
+//! cargo run --example rain_map --features reqwest -- 52.52 13.4
+//! ```
+
+use brightsky::{
+    BrightSkyClient, RadarFrame, RadarGrid, RadarWeatherQueryBuilder,
+    types::{RadarCompressionFormat, RadarResponse},
+};
+
+/// Characters for increasing precipitation, in 0.01 mm.
+const SHADES: [(u16, char); 5] = [(0, '.'), (10, ':'), (50, '+'), (100, '#'), (250, '@')];
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let (lat, lon) = location()?;
+    let client = BrightSkyClient::new(reqwest::Client::new());
+
+    let query = RadarWeatherQueryBuilder::new()
+        .with_lat_lon((lat, lon))
+        .with_distance(25_000)
+        .with_compression_format(RadarCompressionFormat::Compressed)
+        .build()?;
+    let response: RadarResponse = client.get(query).await?;
+
+    // Keep the current frame and the forecast
+    let since = chrono::Utc::now().fixed_offset() - RadarResponse::FRAME_INTERVAL;
+    let frames: Vec<RadarFrame> = response.frames().filter(|f| f.time >= since).collect();
+    let (Some(first), Some(last)) = (frames.first(), frames.last()) else {
+        println!("No radar frames for the next hours");
+        return Ok(());
+    };
+    println!(
+        "{} frames from {} to {}",
+        frames.len(),
+        first.time.format("%H:%M"),
+        last.time.format("%H:%M")
+    );
+
+    if let Some(max) = RadarFrame::max(&frames) {
+        println!("\nHeaviest rain per 5 minutes:");
+        print_map(&max);
+    }
+    if let Some(total) = RadarFrame::accumulate(&frames) {
+        println!("\nTotal rain:");
+        print_map(&total);
+    }
+
+    println!("\nAt {}, {}:", lat, lon);
+    for (time, mm) in response.precipitation_at_position() {
+        println!("{}  {:.2} mm", time.format("%H:%M"), mm);
+    }
+    Ok(())
+}
+
+fn print_map(grid: &RadarGrid) {
+    // Two pixels per character horizontally keep the map roughly square
+    for row in grid.rows().step_by(2) {
+        let line: String = row
+            .chunks(2)
+            .map(|pair| {
+                let value = pair.iter().copied().max().unwrap_or(0);
+                SHADES
+                    .iter()
+                    .rev()
+                    .find(|(min, _)| value >= *min)
+                    .map_or(' ', |(_, shade)| *shade)
+            })
+            .collect();
+        println!("{}", line);
+    }
+}
+
+fn location() -> Result<(f64, f64), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    match (args.next(), args.next()) {
+        (Some(lat), Some(lon)) => Ok((lat.parse()?, lon.parse()?)),
+        _ => Ok((52.52, 13.4)),
+    }
+}
@@ -0,0 +1,329 @@
+//! Aggregation helpers over hourly weather records.
+//!
+//! The `/weather` endpoint returns one record per hour. This module condenses
+//! those records into per-day summaries with minimum, maximum, mean and sum for
+//! each numerical parameter.
+//!
+//! ## Day Boundaries
+//!
+//! Records are grouped by the calendar day of their timestamp in the UTC offset
+//! returned by the API, i.e. in the timezone requested with `with_tz`. Parameters
+//! that describe the *previous* hour (precipitation, sunshine, solar irradiation
+//! and wind) are attributed to the day in which that hour started, so the record
+//! at midnight counts towards the day before.
+//!
+//! ## Conditions and Icons
+//!
+//! [`WeatherResponse::dominant_condition_per_day`] and
+//! [`WeatherResponse::icon_for_day`] pick one condition or icon per calendar day
+//! for summary cards. Precipitation wins as soon as one record of the day has
+//! it, in the order thunderstorm > hail > snow > sleet > rain. Days without
+//! precipitation get the most frequent of the remaining values; ties go to the
+//! more notable one (wind > fog > cloudy > partly cloudy > clear, fog > dry).
+//! Icons are always the day variant.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use brightsky::types::WeatherResponse;
+//!
+//! let response: WeatherResponse = serde_json::from_str(r#"{
+//!     "weather": [
+//!         {"timestamp": "2023-08-07T13:00:00+02:00", "source_id": 1, "temperature": 24.0, "precipitation": 0.5},
+//!         {"timestamp": "2023-08-07T14:00:00+02:00", "source_id": 1, "temperature": 26.0, "precipitation": 1.0}
+//!     ],
+//!     "sources": []
+//! }"#).unwrap();
+//!
+//! let days = response.daily_summary();
+//! assert_eq!(days.len(), 1);
+//! assert_eq!(days[0].temperature.unwrap().max, 26.0);
+//! assert_eq!(days[0].precipitation_total(), Some(1.5));
+//! ```
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{collections::BTreeMap, vec::Vec};
+
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+use chrono::{NaiveDate, TimeDelta};
+
+use crate::types::{Weather, WeatherCondition, WeatherIcon, WeatherResponse};
+#[cfg(feature = "std")]
+use crate::wind::WindDirection;
+
+/// Minimum, maximum, mean and sum of a series of values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    /// Smallest value
+    pub min: f64,
+    /// Largest value
+    pub max: f64,
+    /// Arithmetic mean
+    pub mean: f64,
+    /// Sum of all values
+    pub sum: f64,
+    /// Number of values
+    pub count: usize,
+}
+
+impl Stats {
+    /// Compute statistics over the given values.
+    ///
+    /// Returns `None` if there are no values.
+    pub fn from_values<I: IntoIterator<Item = f64>>(values: I) -> Option<Self> {
+        let mut values = values.into_iter();
+        let first = values.next()?;
+        let mut stats = Self {
+            min: first,
+            max: first,
+            mean: 0.0,
+            sum: first,
+            count: 1,
+        };
+        for value in values {
+            stats.min = stats.min.min(value);
+            stats.max = stats.max.max(value);
+            stats.sum += value;
+            stats.count += 1;
+        }
+        stats.mean = stats.sum / stats.count as f64;
+        Some(stats)
+    }
+}
+
+/// Summary of all hourly records of one calendar day.
+///
+/// Each parameter is `None` if no record of that day had a value for it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailySummary {
+    /// Calendar day in the UTC offset of the record timestamps
+    pub date: NaiveDate,
+    /// Number of hourly records attributed to this day
+    pub records: usize,
+    /// Air temperature (°C or K)
+    pub temperature: Option<Stats>,
+    /// Dew point (°C or K)
+    pub dew_point: Option<Stats>,
+    /// Pressure reduced to mean sea level (hPa or Pa)
+    pub pressure_msl: Option<Stats>,
+    /// Relative humidity (percentage)
+    pub relative_humidity: Option<Stats>,
+    /// Total cloud cover (percentage)
+    pub cloud_cover: Option<Stats>,
+    /// Visibility (meters)
+    pub visibility: Option<Stats>,
+    /// Hourly precipitation (mm)
+    pub precipitation: Option<Stats>,
+    /// Hourly sunshine duration (minutes or seconds)
+    pub sunshine: Option<Stats>,
+    /// Hourly solar irradiation (kWh/m² or J/m²)
+    pub solar: Option<Stats>,
+    /// Hourly mean wind speed (km/h or m/s)
+    pub wind_speed: Option<Stats>,
+    /// Hourly maximum wind gust speed (km/h or m/s)
+    pub wind_gust_speed: Option<Stats>,
+    /// Circular mean of the hourly mean wind directions
+    #[cfg(feature = "std")]
+    pub wind_direction: Option<WindDirection>,
+}
+
+impl DailySummary {
+    /// Total precipitation of the day (mm).
+    pub fn precipitation_total(&self) -> Option<f64> {
+        self.precipitation.map(|stats| stats.sum)
+    }
+
+    /// Total sunshine duration of the day (minutes or seconds).
+    pub fn sunshine_total(&self) -> Option<f64> {
+        self.sunshine.map(|stats| stats.sum)
+    }
+
+    /// Total solar irradiation of the day (kWh/m² or J/m²).
+    pub fn solar_total(&self) -> Option<f64> {
+        self.solar.map(|stats| stats.sum)
+    }
+}
+
+impl WeatherResponse {
+    /// Summarize the hourly records per calendar day, in chronological order.
+    ///
+    /// One summary is returned for every day that has at least one record.
+    /// See the [module documentation](crate::aggregate) for how day boundaries are
+    /// determined.
+    pub fn daily_summary(&self) -> Vec<DailySummary> {
+        let mut instant: BTreeMap<NaiveDate, Vec<&Weather>> = BTreeMap::new();
+        let mut period: BTreeMap<NaiveDate, Vec<&Weather>> = BTreeMap::new();
+
+        for record in &self.weather {
+            instant
+                .entry(record.timestamp.date_naive())
+                .or_default()
+                .push(record);
+            period.entry(period_day(record)).or_default().push(record);
+        }
+
+        instant
+            .iter()
+            .map(|(&date, instant)| {
+                let period = period.get(&date).map(Vec::as_slice).unwrap_or_default();
+
+                DailySummary {
+                    date,
+                    records: instant.len(),
+                    temperature: stats(instant, |r| r.temperature),
+                    dew_point: stats(instant, |r| r.dew_point),
+                    pressure_msl: stats(instant, |r| r.pressure_msl),
+                    relative_humidity: stats(instant, |r| r.relative_humidity.map(|v| v as f64)),
+                    cloud_cover: stats(instant, |r| r.cloud_cover),
+                    visibility: stats(instant, |r| r.visibility.map(|v| v as f64)),
+                    precipitation: stats(period, |r| r.precipitation),
+                    sunshine: stats(period, |r| r.sunshine),
+                    solar: stats(period, |r| r.solar),
+                    wind_speed: stats(period, |r| r.wind_speed),
+                    wind_gust_speed: stats(period, |r| r.wind_gust_speed),
+                    #[cfg(feature = "std")]
+                    wind_direction: WindDirection::circular_mean(
+                        period.iter().filter_map(|r| r.typed_wind_direction()),
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    /// The representative condition of each calendar day, in chronological
+    /// order.
+    ///
+    /// Days on which every record has an unknown or missing condition are
+    /// skipped. See the [module documentation](crate::aggregate) for the
+    /// precedence.
+    ///
+    /// ```rust
+    /// use brightsky::types::{WeatherCondition, WeatherResponse};
+    ///
+    /// let response: WeatherResponse = serde_json::from_str(r#"{
+    ///     "weather": [
+    ///         {"timestamp": "2023-08-07T13:00:00+02:00", "source_id": 1, "condition": "dry"},
+    ///         {"timestamp": "2023-08-07T14:00:00+02:00", "source_id": 1, "condition": "thunderstorm"},
+    ///         {"timestamp": "2023-08-07T15:00:00+02:00", "source_id": 1, "condition": "rain"}
+    ///     ],
+    ///     "sources": []
+    /// }"#).unwrap();
+    ///
+    /// let days = response.dominant_condition_per_day();
+    /// assert_eq!(days[0].1, WeatherCondition::Thunderstorm);
+    /// ```
+    pub fn dominant_condition_per_day(&self) -> Vec<(NaiveDate, WeatherCondition)> {
+        let mut days: BTreeMap<NaiveDate, Vec<&WeatherCondition>> = BTreeMap::new();
+        for record in &self.weather {
+            let conditions = days.entry(record.timestamp.date_naive()).or_default();
+            conditions.extend(record.condition.as_ref());
+        }
+
+        days.into_iter()
+            .filter_map(|(date, conditions)| {
+                representative(conditions.into_iter().cloned(), condition_rank)
+                    .map(|condition| (date, condition))
+            })
+            .collect()
+    }
+
+    /// The representative icon of a calendar day, or `None` if the day has no
+    /// records with a known icon.
+    ///
+    /// See the [module documentation](crate::aggregate) for the precedence.
+    pub fn icon_for_day(&self, date: NaiveDate) -> Option<WeatherIcon> {
+        let icons = self
+            .weather
+            .iter()
+            .filter(|record| record.timestamp.date_naive() == date)
+            .filter_map(|record| record.icon.clone())
+            .map(|icon| icon.with_daylight(true));
+        representative(icons, icon_rank)
+    }
+}
+
+/// How a value competes for the representative value of a day. Higher ranks
+/// are more notable.
+enum Rank {
+    /// Wins as soon as one record has it
+    Precedence(u8),
+    /// Wins by number of records, ties go to the higher rank
+    Frequency(u8),
+}
+
+fn condition_rank(condition: &WeatherCondition) -> Option<Rank> {
+    match condition {
+        WeatherCondition::Thunderstorm => Some(Rank::Precedence(5)),
+        WeatherCondition::Hail => Some(Rank::Precedence(4)),
+        WeatherCondition::Snow => Some(Rank::Precedence(3)),
+        WeatherCondition::Sleet => Some(Rank::Precedence(2)),
+        WeatherCondition::Rain => Some(Rank::Precedence(1)),
+        WeatherCondition::Fog => Some(Rank::Frequency(2)),
+        WeatherCondition::Dry => Some(Rank::Frequency(1)),
+        WeatherCondition::Unknown => None,
+    }
+}
+
+fn icon_rank(icon: &WeatherIcon) -> Option<Rank> {
+    match icon {
+        WeatherIcon::Thunderstorm => Some(Rank::Precedence(5)),
+        WeatherIcon::Hail => Some(Rank::Precedence(4)),
+        WeatherIcon::Snow => Some(Rank::Precedence(3)),
+        WeatherIcon::Sleet => Some(Rank::Precedence(2)),
+        WeatherIcon::Rain => Some(Rank::Precedence(1)),
+        WeatherIcon::Wind => Some(Rank::Frequency(5)),
+        WeatherIcon::Fog => Some(Rank::Frequency(4)),
+        WeatherIcon::Cloudy => Some(Rank::Frequency(3)),
+        WeatherIcon::PartlyCloudyDay | WeatherIcon::PartlyCloudyNight => Some(Rank::Frequency(2)),
+        WeatherIcon::ClearDay | WeatherIcon::ClearNight => Some(Rank::Frequency(1)),
+        WeatherIcon::Unknown => None,
+    }
+}
+
+/// Pick the representative of `values` according to `rank`.
+fn representative<T>(
+    values: impl IntoIterator<Item = T>,
+    rank: impl Fn(&T) -> Option<Rank>,
+) -> Option<T> {
+    let mut highest: Option<(u8, T)> = None;
+    // (rank, count, value); there are only a handful of distinct values
+    let mut counts: Vec<(u8, usize, T)> = Vec::new();
+
+    for value in values {
+        match rank(&value) {
+            Some(Rank::Precedence(rank))
+                if highest.as_ref().is_none_or(|(highest, _)| rank > *highest) =>
+            {
+                highest = Some((rank, value));
+            }
+            Some(Rank::Frequency(rank)) => {
+                match counts.iter_mut().find(|(other, ..)| *other == rank) {
+                    Some((_, count, _)) => *count += 1,
+                    None => counts.push((rank, 1, value)),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    highest.map(|(_, value)| value).or_else(|| {
+        counts
+            .into_iter()
+            .max_by_key(|(rank, count, _)| (*count, *rank))
+            .map(|(.., value)| value)
+    })
+}
+
+/// Day in which the hour described by a record's period values started.
+pub(crate) fn period_day(record: &Weather) -> NaiveDate {
+    (record.timestamp - TimeDelta::hours(1)).date_naive()
+}
+
+fn stats(records: &[&Weather], field: impl Fn(&Weather) -> Option<f64>) -> Option<Stats> {
+    Stats::from_values(records.iter().filter_map(|record| field(record)))
+}
@@ -0,0 +1,72 @@
+use chrono::{DateTime, TimeZone};
+
+use crate::types::{Alert, AlertSeverity, AlertsResponse};
+
+impl Alert {
+    /// Whether the weather event is in progress at `time`.
+    ///
+    /// Following CAP, the event begins at `onset` and ends at `expires`
+    /// (exclusive). `effective`, when the alert was issued, is earlier and
+    /// does not matter. Alerts without `expires` are active from their onset
+    /// until they are withdrawn.
+    pub fn is_active_at<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> bool {
+        self.onset <= *time && self.expires.is_none_or(|expires| *time < expires)
+    }
+
+    /// Whether the weather event has not begun at `time`.
+    pub fn is_upcoming_at<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> bool {
+        *time < self.onset
+    }
+}
+
+impl AlertsResponse {
+    /// Keep the alerts whose weather event is in progress at `time`, see
+    /// [`Alert::is_active_at`].
+    ///
+    /// ```rust
+    /// use brightsky::types::{AlertSeverity, AlertsResponse};
+    /// use chrono::Utc;
+    ///
+    /// # fn alerts() -> AlertsResponse { AlertsResponse { alerts: vec![], location: None } }
+    /// let relevant = alerts()
+    ///     .active_at(&Utc::now())
+    ///     .min_severity(AlertSeverity::Severe)
+    ///     .sorted_by_onset();
+    ///
+    /// for alert in &relevant.alerts {
+    ///     println!("{}", alert.headline_en);
+    /// }
+    /// ```
+    pub fn active_at<Tz: TimeZone>(mut self, time: &DateTime<Tz>) -> Self {
+        self.alerts.retain(|alert| alert.is_active_at(time));
+        self
+    }
+
+    /// Keep the alerts whose weather event begins after `time`.
+    pub fn upcoming_at<Tz: TimeZone>(mut self, time: &DateTime<Tz>) -> Self {
+        self.alerts.retain(|alert| alert.is_upcoming_at(time));
+        self
+    }
+
+    /// Keep the alerts of at least `severity`. Alerts without a severity are
+    /// removed.
+    pub fn min_severity(mut self, severity: AlertSeverity) -> Self {
+        self.alerts.retain(|alert| alert.severity >= Some(severity));
+        self
+    }
+
+    /// Keep the alerts with one of the DWD event codes, e.g. `51` for wind
+    /// gusts.
+    pub fn by_event_code(mut self, codes: &[i64]) -> Self {
+        self.alerts
+            .retain(|alert| alert.event_code.is_some_and(|code| codes.contains(&code)));
+        self
+    }
+
+    /// Sort the alerts by the start of their weather event, earliest first.
+    /// Alerts with the same onset keep their order.
+    pub fn sorted_by_onset(mut self) -> Self {
+        self.alerts.sort_by_key(|alert| alert.onset);
+        self
+    }
+}
@@ -45,7 +45,9 @@
 //!     for alert in response.alerts {
 //!         println!("Alert: {}", alert.headline_en);
 //!         println!("   Severity: {:?}", alert.severity);
-//!         println!("   From {} to {}", alert.onset, alert.expires.unwrap_or_default());
+//!         if let Some(expires) = alert.expires {
+//!             println!("   From {} to {}", alert.onset, expires);
+//!         }
 //!         println!("   {}", alert.description_en);
 //!         println!();
 //!     }
@@ -97,6 +99,19 @@
 //! }
 //! ```
 //!
+//! ### Filter alerts
+//!
+//! [`AlertsResponse`](crate::types::AlertsResponse) provides filters following
+//! the CAP timing semantics, e.g. `active_at`, `min_severity`, `by_event_code`
+//! and `sorted_by_onset`.
+//!
+//! ### Choose a language
+//!
+//! `Alert::headline`, `description`, `instruction` and `event` return the text
+//! in a [`Language`](crate::Language), falling back to the other language if
+//! it is empty. `Location::display_name` joins municipality, district and
+//! state for display.
+//!
 //! ### Monitor alerts by warn cell ID
 //! ```rust,no_run
 //! use brightsky::{AlertsQueryBuilder, ToBrightSkyUrl, BRIGHT_SKY_API, types::AlertsResponse};
@@ -129,5 +144,7 @@
 //! }
 //! ```
 
+mod filter;
 mod query_builder;
+mod text;
 pub use query_builder::*;
@@ -4,7 +4,15 @@ extern crate alloc;
 #[cfg(not(feature = "std"))]
 use alloc::{format, string::String, string::ToString};
 
-use crate::{BrightSkyError, ToBrightSkyUrl};
+use core::ops::Deref;
+
+use crate::{
+    BrightSkyError, DEFAULT_COORDINATE_PRECISION, QueryError, ToBrightSkyUrl, TzName,
+    coordinates::{
+        CoordinateFuzzing, QueryLocation, check_coordinates, check_location, url_coordinates,
+    },
+    tz::check_tz,
+};
 
 #[cfg(feature = "std")]
 use url::Url;
@@ -67,16 +75,22 @@ use url::Url;
 ///     Ok(())
 /// }
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct AlertsQueryBuilder {
     /// Latitude in decimal degrees (-90.0 to 90.0)
-    pub lat: Option<String>,
+    pub lat: Option<f64>,
     /// Longitude in decimal degrees (-180.0 to 180.0)
-    pub lon: Option<String>,
+    pub lon: Option<f64>,
+    /// Number of decimal places used for `lat` and `lon` in URLs
+    pub coordinate_precision: u8,
+    /// Privacy grid applied to `lat` and `lon`, if any
+    pub coordinate_fuzzing: Option<CoordinateFuzzing>,
     /// Municipality warn cell ID
     pub warn_cell_id: Option<String>,
     /// Timezone for timestamp presentation (tz database format)
     pub tz: Option<String>,
+    /// Parameter of a [`QueryLocation`] that `/alerts` does not support
+    unsupported_location: Option<&'static str>,
 }
 
 impl AlertsQueryBuilder {
@@ -88,28 +102,35 @@ impl AlertsQueryBuilder {
         Self {
             lat: None,
             lon: None,
+            coordinate_precision: DEFAULT_COORDINATE_PRECISION,
+            coordinate_fuzzing: None,
             warn_cell_id: None,
             tz: None,
+            unsupported_location: None,
         }
     }
 
     /// Set the geographic coordinates for the alerts query.
-    pub fn with_lat_lon(mut self, lat_lon: (f64, f64)) -> Self {
-        let lat_str = format!("{}", lat_lon.0);
-        let lon_str = format!("{}", lat_lon.1);
-
-        self.lat = Some(if !lat_str.contains('.') {
-            format!("{}.0", lat_str)
-        } else {
-            lat_str
-        });
-
-        self.lon = Some(if !lon_str.contains('.') {
-            format!("{}.0", lon_str)
-        } else {
-            lon_str
-        });
+    pub fn with_lat_lon(mut self, lat_lon: impl Into<(f64, f64)>) -> Self {
+        let (lat, lon) = lat_lon.into();
+        self.lat = Some(lat);
+        self.lon = Some(lon);
+        self
+    }
 
+    /// Set the number of decimal places used for `lat` and `lon` in the URL,
+    /// see [`DEFAULT_COORDINATE_PRECISION`].
+    pub fn with_coordinate_precision(mut self, decimals: u8) -> Self {
+        self.coordinate_precision = decimals;
+        self
+    }
+
+    /// Move `lat` and `lon` onto a coarse grid before they are sent to the API.
+    ///
+    /// Applies to coordinates set before or after this call. See
+    /// [`CoordinateFuzzing`] for the available strategies.
+    pub fn with_coordinate_fuzzing(mut self, fuzzing: CoordinateFuzzing) -> Self {
+        self.coordinate_fuzzing = Some(fuzzing);
         self
     }
 
@@ -119,66 +140,105 @@ impl AlertsQueryBuilder {
         self
     }
 
+    /// Set the location from a [`QueryLocation`], replacing any location set before.
+    ///
+    /// `LatLon` sets `lat`/`lon`; alerts are not limited by distance, so
+    /// `max_dist` is ignored. Station and source locations make `build()` fail
+    /// with `UnsupportedLocation`.
+    pub fn with_location(mut self, location: impl Into<QueryLocation>) -> Self {
+        self.lat = None;
+        self.lon = None;
+        self.warn_cell_id = None;
+        self.unsupported_location = None;
+        match location.into() {
+            QueryLocation::LatLon { lat, lon, .. } => self.with_lat_lon((lat, lon)),
+            location => {
+                self.unsupported_location = Some(location.parameter());
+                self
+            }
+        }
+    }
+
     /// Set the timezone for timestamp presentation.
-    pub fn with_tz(mut self, tz: &str) -> Self {
-        self.tz = Some(tz.to_string());
+    pub fn with_tz(mut self, tz: impl TzName) -> Self {
+        self.tz = Some(tz.tz_name().into());
         self
     }
 
     /// Build and validate the query.
-    pub fn build(self) -> Result<Self, BrightSkyError> {
-        if let Some(lat_str) = &self.lat {
-            lat_str
-                .parse::<f64>()
-                .map_err(BrightSkyError::ParseFloatError)
-                .and_then(|lat| -> Result<(), BrightSkyError> {
-                    if !(-90.0..=90.0).contains(&lat) {
-                        Err(BrightSkyError::InvalidLatitude(lat))
-                    } else {
-                        Ok(())
-                    }
-                })?;
-        }
-        if let Some(lon_str) = &self.lon {
-            lon_str
-                .parse::<f64>()
-                .map_err(BrightSkyError::ParseFloatError)
-                .and_then(|lon| -> Result<(), BrightSkyError> {
-                    if !(-180.0..=180.0).contains(&lon) {
-                        Err(BrightSkyError::InvalidLongitude(lon))
-                    } else {
-                        Ok(())
-                    }
-                })?;
+    pub fn build(self) -> Result<AlertsQuery, BrightSkyError> {
+        if let Some(parameter) = self.unsupported_location {
+            return Err(QueryError::UnsupportedLocation(parameter).into());
         }
+        check_coordinates(self.lat, self.lon)?;
+        check_location(
+            &[
+                ("lat/lon", self.lat.is_some() || self.lon.is_some()),
+                ("warn_cell_id", self.warn_cell_id.is_some()),
+            ],
+            false,
+        )?;
+        check_tz(self.tz.as_deref())?;
         if let Some(warn_cell_id_str) = &self.warn_cell_id {
             warn_cell_id_str
                 .parse::<i64>()
-                .map_err(BrightSkyError::ParseIntError)?;
+                .map_err(QueryError::ParseIntError)?;
         }
 
-        Ok(self)
+        Ok(AlertsQuery { builder: self })
+    }
+}
+
+/// A validated `/alerts` query, returned by [`AlertsQueryBuilder::build`].
+///
+/// Only validated queries implement [`ToBrightSkyUrl`] and can be sent with a
+/// client. The parameters can be read through [`Deref`] to the builder; use
+/// [`into_builder`](Self::into_builder) to change them and build again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertsQuery {
+    builder: AlertsQueryBuilder,
+}
+
+impl AlertsQuery {
+    /// Turn the query back into a builder, e.g. to change a parameter.
+    pub fn into_builder(self) -> AlertsQueryBuilder {
+        self.builder
+    }
+}
+
+impl Deref for AlertsQuery {
+    type Target = AlertsQueryBuilder;
+
+    fn deref(&self) -> &Self::Target {
+        &self.builder
     }
 }
 
-impl ToBrightSkyUrl for AlertsQueryBuilder {
+impl ToBrightSkyUrl for AlertsQuery {
     #[cfg(feature = "std")]
     fn to_url(self, host: &str) -> Result<Url, BrightSkyError> {
+        let builder = self.builder;
+        let (lat, lon) = url_coordinates(
+            builder.lat,
+            builder.lon,
+            builder.coordinate_precision,
+            builder.coordinate_fuzzing,
+        );
         let base = Url::parse(host)?;
         let mut url = base.join("alerts")?;
 
         let mut query = url.query_pairs_mut();
 
-        if let Some(lat) = self.lat {
+        if let Some(lat) = lat {
             query.append_pair("lat", &lat);
         }
-        if let Some(lon) = self.lon {
+        if let Some(lon) = lon {
             query.append_pair("lon", &lon);
         }
-        if let Some(warn_cell_id) = self.warn_cell_id {
+        if let Some(warn_cell_id) = builder.warn_cell_id {
             query.append_pair("warn_cell_id", &warn_cell_id);
         }
-        if let Some(tz) = self.tz {
+        if let Some(tz) = builder.tz {
             query.append_pair("tz", &tz);
         }
 
@@ -187,24 +247,31 @@ impl ToBrightSkyUrl for AlertsQueryBuilder {
     }
 
     fn to_url_string(self, host: &str) -> Result<String, BrightSkyError> {
+        let builder = self.builder;
         #[cfg(not(feature = "std"))]
         use alloc::vec::Vec;
         #[cfg(feature = "std")]
         use std::vec::Vec;
 
+        let (lat, lon) = url_coordinates(
+            builder.lat,
+            builder.lon,
+            builder.coordinate_precision,
+            builder.coordinate_fuzzing,
+        );
         let mut url = format!("{}/alerts", host.trim_end_matches('/'));
         let mut params = Vec::new();
 
-        if let Some(lat) = self.lat {
+        if let Some(lat) = lat {
             params.push(format!("lat={}", lat));
         }
-        if let Some(lon) = self.lon {
+        if let Some(lon) = lon {
             params.push(format!("lon={}", lon));
         }
-        if let Some(warn_cell_id) = self.warn_cell_id {
+        if let Some(warn_cell_id) = builder.warn_cell_id {
             params.push(format!("warn_cell_id={}", warn_cell_id));
         }
-        if let Some(tz) = self.tz {
+        if let Some(tz) = builder.tz {
             params.push(format!("tz={}", tz));
         }
 
@@ -0,0 +1,86 @@
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+use crate::{
+    Language,
+    types::{Alert, Location},
+};
+
+impl Alert {
+    /// The headline in `language`, or in the other language if it is empty.
+    ///
+    /// ```rust
+    /// use brightsky::{Language, types::Alert};
+    ///
+    /// # let mut alert: Alert = serde_json::from_value(serde_json::json!({
+    /// #     "id": 1, "alert_id": "a", "status": "actual",
+    /// #     "effective": "2023-08-07T08:00:00+00:00", "onset": "2023-08-07T10:00:00+00:00",
+    /// #     "headline_en": "", "headline_de": "", "description_en": "", "description_de": ""
+    /// # })).unwrap();
+    /// alert.headline_de = "Amtliche WARNUNG vor STURMBÖEN".to_string();
+    /// assert_eq!(alert.headline(Language::De), "Amtliche WARNUNG vor STURMBÖEN");
+    /// // No English text, so the German one is used
+    /// assert_eq!(alert.headline(Language::En), "Amtliche WARNUNG vor STURMBÖEN");
+    /// ```
+    pub fn headline(&self, language: Language) -> &str {
+        pick(language, &self.headline_en, &self.headline_de)
+    }
+
+    /// The description in `language`, or in the other language if it is
+    /// empty.
+    pub fn description(&self, language: Language) -> &str {
+        pick(language, &self.description_en, &self.description_de)
+    }
+
+    /// The safety instructions in `language`, or in the other language if
+    /// they are missing or empty.
+    pub fn instruction(&self, language: Language) -> Option<&str> {
+        pick_option(language, &self.instruction_en, &self.instruction_de)
+    }
+
+    /// The label of the event code in `language`, e.g. `wind gusts` or
+    /// `WINDBÖEN`, or in the other language if it is missing or empty.
+    pub fn event(&self, language: Language) -> Option<&str> {
+        pick_option(language, &self.event_en, &self.event_de)
+    }
+}
+
+impl Location {
+    /// Short municipality name, district and state, e.g. `Göttingen,
+    /// Niedersachsen`. A part equal to the one before it is left out, so
+    /// Berlin is just `Berlin`.
+    pub fn display_name(&self) -> String {
+        let mut parts: Vec<&str> = Vec::with_capacity(3);
+        for part in [&self.name_short, &self.district, &self.state] {
+            if !part.is_empty() && parts.last() != Some(&part.as_str()) {
+                parts.push(part);
+            }
+        }
+        parts.join(", ")
+    }
+}
+
+fn pick<'a>(language: Language, en: &'a str, de: &'a str) -> &'a str {
+    let (preferred, fallback) = match language {
+        Language::En => (en, de),
+        Language::De => (de, en),
+    };
+    if preferred.is_empty() {
+        fallback
+    } else {
+        preferred
+    }
+}
+
+fn pick_option<'a>(
+    language: Language,
+    en: &'a Option<String>,
+    de: &'a Option<String>,
+) -> Option<&'a str> {
+    let text = pick(
+        language,
+        en.as_deref().unwrap_or_default(),
+        de.as_deref().unwrap_or_default(),
+    );
+    (!text.is_empty()).then_some(text)
+}
@@ -0,0 +1,66 @@
+//! Attribution of the data source.
+//!
+//! Bright Sky serves open data of the Deutscher Wetterdienst (DWD), which may
+//! be used freely under the [GeoNutzV](https://www.dwd.de/EN/service/copyright/copyright_node.html)
+//! as long as the DWD is named as the source. Data that was processed (e.g.
+//! aggregated or converted) must be marked as such.
+//!
+//! [`attribution`] and [`attribution_html`] return a notice to show next to the
+//! data, including the time of the most recent record shown:
+//!
+//! ```rust
+//! use brightsky::attribution;
+//!
+//! let timestamp = chrono::DateTime::parse_from_rfc3339("2023-08-07T14:00:00+02:00").unwrap();
+//! assert_eq!(
+//!     attribution(timestamp),
+//!     "Source: Deutscher Wetterdienst (DWD), data as of 2023-08-07 12:00 UTC, via Bright Sky"
+//! );
+//! ```
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+
+use chrono::{DateTime, FixedOffset, Utc};
+
+/// Homepage of the Deutscher Wetterdienst
+pub const DWD_URL: &str = "https://www.dwd.de/";
+
+/// Homepage of Bright Sky
+pub const BRIGHT_SKY_URL: &str = "https://brightsky.dev/";
+
+/// Plain text attribution for data as of `timestamp`.
+///
+/// Show it wherever Bright Sky data is displayed or published, as required by
+/// the DWD terms of use. The timestamp is shown in UTC.
+pub fn attribution(timestamp: DateTime<FixedOffset>) -> String {
+    format!(
+        "Source: Deutscher Wetterdienst (DWD), data as of {}, via Bright Sky",
+        format_timestamp(timestamp)
+    )
+}
+
+/// HTML attribution for data as of `timestamp`, linking to the DWD and Bright Sky.
+///
+/// The timestamp is wrapped in a `<time>` element with the original RFC 3339
+/// timestamp as `datetime` attribute.
+pub fn attribution_html(timestamp: DateTime<FixedOffset>) -> String {
+    format!(
+        "Source: <a href=\"{}\">Deutscher Wetterdienst (DWD)</a>, data as of \
+         <time datetime=\"{}\">{}</time>, via <a href=\"{}\">Bright Sky</a>",
+        DWD_URL,
+        timestamp.to_rfc3339(),
+        format_timestamp(timestamp),
+        BRIGHT_SKY_URL
+    )
+}
+
+fn format_timestamp(timestamp: DateTime<FixedOffset>) -> String {
+    timestamp
+        .with_timezone(&Utc)
+        .format("%Y-%m-%d %H:%M UTC")
+        .to_string()
+}
@@ -0,0 +1,273 @@
+//! `brightsky` command: DWD weather data from the Bright Sky API in the terminal.
+//!
+//! ```text
+//! brightsky current München
+//! brightsky forecast 52.52,13.4 --days 5 --units si
+//! brightsky radar Hamburg --distance 20000 --map
+//! brightsky alerts --warn-cell 803159016 --json
+//! brightsky sources Köln
+//! ```
+//!
+//! Locations are `lat,lon` or a place name resolved with Nominatim.
+
+use std::error::Error;
+use std::io::IsTerminal;
+use std::time::Duration;
+
+use brightsky::display::{Table, TableOptions};
+use brightsky::geocoding::{Geocoder, Nominatim};
+use brightsky::types::{AlertsResponse, CurrentWeatherResponse, UnitType, WeatherResponse};
+use brightsky::{
+    AlertsQueryBuilder, BrightSkyClient, BrightSkyClientBuilder, Coordinates,
+    CurrentWeatherQueryBuilder, RadarFrame, RadarGrid, RadarWeatherQueryBuilder,
+    WeatherQueryBuilder,
+};
+use clap::{Arg, ArgAction, ArgMatches, Command, value_parser};
+use serde::Serialize;
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+#[tokio::main]
+async fn main() {
+    let matches = command().get_matches();
+    if let Err(err) = run(&matches).await {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    }
+}
+
+fn command() -> Command {
+    let location = Arg::new("location")
+        .value_name("LOCATION")
+        .help("Coordinates as `lat,lon` or a place name, e.g. `München` or `80331`")
+        .required(true);
+
+    Command::new("brightsky")
+        .about("DWD weather data from the Bright Sky API")
+        .version(env!("CARGO_PKG_VERSION"))
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Print the response as JSON"),
+        )
+        .arg(
+            Arg::new("units")
+                .long("units")
+                .global(true)
+                .value_name("UNITS")
+                .value_parser(["dwd", "si"])
+                .help("Unit system of the values [default: dwd]"),
+        )
+        .arg(
+            Arg::new("tz")
+                .long("tz")
+                .global(true)
+                .value_name("TZ")
+                .help("Timezone of the timestamps, e.g. `Europe/Berlin`"),
+        )
+        .arg(
+            Arg::new("no-color")
+                .long("no-color")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Do not color table rows"),
+        )
+        .subcommand(
+            Command::new("current")
+                .about("Current weather from the nearest SYNOP stations")
+                .arg(location.clone()),
+        )
+        .subcommand(
+            Command::new("forecast")
+                .about("Hourly forecast for the next days")
+                .arg(location.clone())
+                .arg(
+                    Arg::new("days")
+                        .long("days")
+                        .short('d')
+                        .value_name("DAYS")
+                        .value_parser(value_parser!(u32).range(1..=10))
+                        .default_value("3")
+                        .help("Number of days, starting today"),
+                ),
+        )
+        .subcommand(
+            Command::new("radar")
+                .about("Precipitation radar frames around a location")
+                .arg(location.clone())
+                .arg(
+                    Arg::new("distance")
+                        .long("distance")
+                        .value_name("METERS")
+                        .value_parser(value_parser!(u64))
+                        .default_value("10000")
+                        .help("Radius around the location in meters"),
+                )
+                .arg(
+                    Arg::new("map")
+                        .long("map")
+                        .value_name("WIDTH")
+                        .value_parser(value_parser!(usize))
+                        .num_args(0..=1)
+                        .default_missing_value("60")
+                        .help("Draw the heaviest precipitation of all frames, 60 characters wide by default"),
+                ),
+        )
+        .subcommand(
+            Command::new("alerts")
+                .about("Weather alerts for a location, a warn cell or all of Germany")
+                .arg(location.clone().required(false))
+                .arg(
+                    Arg::new("warn-cell")
+                        .long("warn-cell")
+                        .value_name("ID")
+                        .value_parser(value_parser!(i64))
+                        .conflicts_with("location")
+                        .help("Warn cell ID instead of a location"),
+                ),
+        )
+        .subcommand(
+            Command::new("sources")
+                .about("Weather stations near a location")
+                .arg(location),
+        )
+}
+
+async fn run(matches: &ArgMatches) -> Result<()> {
+    let client: BrightSkyClient<reqwest::Client> = BrightSkyClientBuilder::new()
+        .timeout(Duration::from_secs(30))
+        .build()?;
+    let output = Output::new(matches);
+    let units = match matches.get_one::<String>("units").map(String::as_str) {
+        Some("si") => UnitType::Si,
+        _ => UnitType::Dwd,
+    };
+    let tz = matches.get_one::<String>("tz");
+
+    match matches.subcommand() {
+        Some(("current", args)) => {
+            let mut query = CurrentWeatherQueryBuilder::new()
+                .with_lat_lon(locate(args).await?)
+                .with_units(units);
+            if let Some(tz) = tz {
+                query = query.with_tz(tz);
+            }
+            let response: CurrentWeatherResponse = client.get(query.build()?).await?;
+            output.print(&response, &response)
+        }
+        Some(("forecast", args)) => {
+            let mut request = client.forecast(locate(args).await?).with_units(units);
+            if let Some(tz) = tz {
+                request = request.with_tz(tz);
+            }
+            let days = *args.get_one::<u32>("days").expect("has a default");
+            let forecast = request.days(days).await?;
+            let table = WeatherResponse {
+                weather: forecast.hours().cloned().collect(),
+                sources: forecast.sources.clone(),
+            };
+            output.print(&forecast, &table)
+        }
+        Some(("radar", args)) => {
+            let mut query = RadarWeatherQueryBuilder::new()
+                .with_lat_lon(locate(args).await?)
+                .with_distance(*args.get_one::<u64>("distance").expect("has a default"));
+            if let Some(tz) = tz {
+                query = query.with_tz(tz);
+            }
+            let response = client.get_radar(query.build()?).await?;
+            if let Some(&width) = args.get_one::<usize>("map")
+                && !output.json
+                && let Some(grid) = RadarFrame::max(&response.frames().collect::<Vec<_>>())
+            {
+                print!("{}", output.map(&grid, width));
+            }
+            output.print(&response, &response)
+        }
+        Some(("alerts", args)) => {
+            let mut query = AlertsQueryBuilder::new();
+            if let Some(&warn_cell_id) = args.get_one::<i64>("warn-cell") {
+                query = query.with_warn_cell_id(warn_cell_id);
+            } else if args.contains_id("location") {
+                query = query.with_lat_lon(locate(args).await?);
+            }
+            if let Some(tz) = tz {
+                query = query.with_tz(tz);
+            }
+            let response: AlertsResponse = client.get(query.build()?).await?;
+            output.print(&response, &response)
+        }
+        Some(("sources", args)) => {
+            let today = chrono::Utc::now().date_naive();
+            let query = WeatherQueryBuilder::new()
+                .with_lat_lon(locate(args).await?)
+                .with_date(today)
+                .build()?;
+            let response: WeatherResponse = client.get(query).await?;
+            output.print(&response.sources, response.sources.as_slice())
+        }
+        _ => unreachable!("a subcommand is required"),
+    }
+}
+
+/// Coordinates of the `location` argument, geocoding place names.
+async fn locate(args: &ArgMatches) -> Result<Coordinates> {
+    let location = args
+        .get_one::<String>("location")
+        .ok_or("a location is required")?;
+
+    if let Some((lat, lon)) = location.split_once(',')
+        && let (Ok(lat), Ok(lon)) = (lat.trim().parse(), lon.trim().parse())
+    {
+        return Ok(Coordinates::new(lat, lon)?);
+    }
+
+    let geocoder = Nominatim::new(reqwest::Client::new());
+    let place = geocoder
+        .locate(location)
+        .await?
+        .ok_or_else(|| format!("no place found for `{location}`"))?;
+    eprintln!("{}", place.display_name);
+    Ok(place.coordinates)
+}
+
+/// How responses are printed.
+struct Output {
+    json: bool,
+    color: bool,
+    table: TableOptions,
+}
+
+impl Output {
+    fn new(matches: &ArgMatches) -> Self {
+        let color = !matches.get_flag("no-color")
+            && std::env::var_os("NO_COLOR").is_none()
+            && std::io::stdout().is_terminal();
+        Self {
+            json: matches.get_flag("json"),
+            color,
+            table: TableOptions::new().with_color(color),
+        }
+    }
+
+    fn map(&self, grid: &RadarGrid, width: usize) -> String {
+        if self.color {
+            grid.render_ascii(width)
+        } else {
+            grid.render_ascii_plain(width)
+        }
+    }
+
+    fn print<T: Serialize + ?Sized, U: Table + ?Sized>(&self, json: &T, table: &U) -> Result<()> {
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(json)?);
+        } else {
+            print!("{}", table.to_table_with(&self.table));
+        }
+        Ok(())
+    }
+}
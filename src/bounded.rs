@@ -0,0 +1,185 @@
+//! Fixed-capacity response types for targets without a heap.
+//!
+//! The types in [`crate::types`] use `String`, `Vec` and maps, which need an
+//! allocator. With the `heapless` feature, this module provides a
+//! [`CurrentWeatherResponse`] with the same measurements stored in
+//! `heapless::String` and `heapless::Vec`, parsed with `serde-json-core`
+//! straight from the response body. Nothing is allocated while parsing.
+//!
+//! Compared to [`crate::types::CurrentWeatherResponse`]:
+//!
+//! - At most `SOURCES` sources are kept (default [`MAX_SOURCES`]); a response
+//!   with more sources fails to parse instead of being truncated.
+//! - Station names longer than [`STATION_NAME_CAPACITY`] bytes fail to parse.
+//! - `fallback_source_ids` is skipped.
+//! - `observation_type` is kept as the raw API string.
+//!
+//! ```rust
+//! use brightsky::bounded::CurrentWeatherResponse;
+//!
+//! let body = br#"{
+//!     "weather": {
+//!         "source_id": 1234, "timestamp": "2023-08-07T12:30:00+00:00",
+//!         "temperature": 21.5, "icon": "partly-cloudy-day", "condition": "dry"
+//!     },
+//!     "sources": [{
+//!         "id": 1234, "dwd_station_id": "00433", "wmo_station_id": "10384",
+//!         "station_name": "Berlin-Tempelhof", "observation_type": "synop",
+//!         "first_record": "2023-08-06T12:30:00+00:00",
+//!         "last_record": "2023-08-07T12:30:00+00:00",
+//!         "lat": 52.4676, "lon": 13.4020, "height": 48.0, "distance": 4732.0
+//!     }]
+//! }"#;
+//!
+//! let response: CurrentWeatherResponse = CurrentWeatherResponse::from_slice(body).unwrap();
+//! assert_eq!(response.weather.temperature, Some(21.5));
+//! assert_eq!(response.sources[0].station_name, "Berlin-Tempelhof");
+//! ```
+
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Deserializer};
+
+use crate::types::{WeatherCondition, WeatherIcon};
+
+/// Default number of sources kept by [`CurrentWeatherResponse`].
+pub const MAX_SOURCES: usize = 4;
+
+/// Maximum length of a station name in bytes.
+pub const STATION_NAME_CAPACITY: usize = 64;
+
+/// DWD or WMO station ID (5 characters).
+pub type StationId = heapless::String<8>;
+
+/// Error parsing a response with `serde-json-core`.
+pub type Error = serde_json_core::de::Error;
+
+/// Fixed-capacity variant of [`crate::types::CurrentWeatherResponse`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CurrentWeatherResponse<const SOURCES: usize = MAX_SOURCES> {
+    /// Current weather conditions compiled from recent observations
+    pub weather: CurrentWeather,
+    /// Information about weather stations used as data sources
+    pub sources: heapless::Vec<CurrentWeatherSource, SOURCES>,
+}
+
+impl<const SOURCES: usize> CurrentWeatherResponse<SOURCES> {
+    /// Parse a `/current_weather` response body.
+    ///
+    /// Escaped characters in strings (e.g. `\u00fc`) are decoded into a
+    /// buffer on the stack.
+    pub fn from_slice(body: &[u8]) -> Result<Self, Error> {
+        let mut buffer = [0; STATION_NAME_CAPACITY];
+        serde_json_core::from_slice_escaped(body, &mut buffer).map(|(response, _)| response)
+    }
+}
+
+/// Fixed-capacity variant of [`crate::types::CurrentWeather`], without
+/// `fallback_source_ids`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CurrentWeather {
+    /// Timestamp of this weather record
+    pub timestamp: DateTime<FixedOffset>,
+    /// Bright Sky source ID for this record
+    pub source_id: i64,
+    /// Total cloud cover at timestamp (percentage)
+    pub cloud_cover: Option<f64>,
+    /// Current weather conditions (derived field)
+    #[serde(default, deserialize_with = "condition")]
+    pub condition: Option<WeatherCondition>,
+    /// Dew point at timestamp, 2m above ground (°C or K)
+    pub dew_point: Option<f64>,
+    /// Icon alias suitable for current weather conditions (derived field)
+    #[serde(default, deserialize_with = "icon")]
+    pub icon: Option<WeatherIcon>,
+    /// Atmospheric pressure at timestamp, reduced to mean sea level (hPa or Pa)
+    pub pressure_msl: Option<f64>,
+    /// Relative humidity at timestamp (percentage)
+    pub relative_humidity: Option<i64>,
+    /// Air temperature at timestamp, 2m above ground (°C or K)
+    pub temperature: Option<f64>,
+    /// Visibility at timestamp (meters)
+    pub visibility: Option<i64>,
+    /// Total precipitation during previous 10 minutes (mm)
+    pub precipitation_10: Option<f64>,
+    /// Total precipitation during previous 30 minutes (mm)
+    pub precipitation_30: Option<f64>,
+    /// Total precipitation during previous 60 minutes (mm)
+    pub precipitation_60: Option<f64>,
+    /// Solar irradiation during previous 10 minutes (kWh/m² or J/m²)
+    pub solar_10: Option<f64>,
+    /// Solar irradiation during previous 30 minutes (kWh/m² or J/m²)
+    pub solar_30: Option<f64>,
+    /// Solar irradiation during previous 60 minutes (kWh/m² or J/m²)
+    pub solar_60: Option<f64>,
+    /// Sunshine duration during previous 30 minutes (minutes or seconds)
+    pub sunshine_30: Option<f64>,
+    /// Sunshine duration during previous 60 minutes (minutes or seconds)
+    pub sunshine_60: Option<f64>,
+    /// Mean wind direction during previous 10 minutes, 10m above ground (degrees)
+    pub wind_direction_10: Option<i64>,
+    /// Mean wind direction during previous 30 minutes, 10m above ground (degrees)
+    pub wind_direction_30: Option<i64>,
+    /// Mean wind direction during previous 60 minutes, 10m above ground (degrees)
+    pub wind_direction_60: Option<i64>,
+    /// Mean wind speed during previous 10 minutes, 10m above ground (km/h or m/s)
+    pub wind_speed_10: Option<f64>,
+    /// Mean wind speed during previous 30 minutes, 10m above ground (km/h or m/s)
+    pub wind_speed_30: Option<f64>,
+    /// Mean wind speed during previous 60 minutes, 10m above ground (km/h or m/s)
+    pub wind_speed_60: Option<f64>,
+    /// Direction of maximum wind gust during previous 10 minutes, 10m above ground (degrees)
+    pub wind_gust_direction_10: Option<i64>,
+    /// Direction of maximum wind gust during previous 30 minutes, 10m above ground (degrees)
+    pub wind_gust_direction_30: Option<i64>,
+    /// Direction of maximum wind gust during previous 60 minutes, 10m above ground (degrees)
+    pub wind_gust_direction_60: Option<i64>,
+    /// Speed of maximum wind gust during previous 10 minutes, 10m above ground (km/h or m/s)
+    pub wind_gust_speed_10: Option<f64>,
+    /// Speed of maximum wind gust during previous 30 minutes, 10m above ground (km/h or m/s)
+    pub wind_gust_speed_30: Option<f64>,
+    /// Speed of maximum wind gust during previous 60 minutes, 10m above ground (km/h or m/s)
+    pub wind_gust_speed_60: Option<f64>,
+}
+
+/// Fixed-capacity variant of [`crate::types::CurrentWeatherSource`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CurrentWeatherSource {
+    /// Bright Sky source ID
+    pub id: i64,
+    /// DWD weather station ID
+    pub dwd_station_id: StationId,
+    /// WMO weather station ID
+    pub wmo_station_id: StationId,
+    /// Human-readable weather station name
+    pub station_name: heapless::String<STATION_NAME_CAPACITY>,
+    /// Type of observations provided by this source, e.g. `synop`
+    pub observation_type: heapless::String<16>,
+    /// Timestamp of first available record for this source
+    pub first_record: DateTime<FixedOffset>,
+    /// Timestamp of latest available record for this source
+    pub last_record: DateTime<FixedOffset>,
+    /// Station latitude in decimal degrees
+    pub lat: f64,
+    /// Station longitude in decimal degrees
+    pub lon: f64,
+    /// Station height above sea level in meters
+    pub height: f64,
+    /// Distance to requested lat/lon in meters (when applicable)
+    pub distance: Option<f64>,
+}
+
+/// The enums' own `Deserialize` reads into a `String`; go through a
+/// fixed-capacity string instead. The longest known value has 19 bytes.
+type EnumValue = heapless::String<32>;
+
+fn icon<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<WeatherIcon>, D::Error> {
+    let value = Option::<EnumValue>::deserialize(deserializer)?;
+    Ok(value.map(|value| WeatherIcon::from_api(&value)))
+}
+
+fn condition<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<WeatherCondition>, D::Error> {
+    let value = Option::<EnumValue>::deserialize(deserializer)?;
+    Ok(value.map(|value| WeatherCondition::from_api(&value)))
+}
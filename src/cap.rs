@@ -0,0 +1,224 @@
+//! Parse DWD CAP 1.2 XML into [`Alert`].
+//!
+//! The DWD publishes its warnings as [CAP 1.2](https://docs.oasis-open.org/emergency/cap/v1.2/CAP-v1.2.html)
+//! documents on its open data server, and Bright Sky's `/alerts` endpoint is
+//! built from the same feeds. [`Alert::from_cap`] reads one CAP `alert`
+//! document into the [`Alert`] type of the JSON endpoint, so archived feeds
+//! and API responses can go through the same code.
+//!
+//! ```rust
+//! use brightsky::types::{Alert, AlertSeverity};
+//!
+//! let xml = r#"<alert xmlns="urn:oasis:names:tc:emergency:cap:1.2">
+//!     <identifier>2.49.0.0.276.0.DWD.PVW.1691383740000.1</identifier>
+//!     <sent>2023-08-07T06:49:00+02:00</sent>
+//!     <status>Actual</status>
+//!     <msgType>Alert</msgType>
+//!     <info>
+//!         <language>de-DE</language>
+//!         <category>Met</category>
+//!         <event>WINDBÖEN</event>
+//!         <urgency>Immediate</urgency>
+//!         <severity>Minor</severity>
+//!         <certainty>Likely</certainty>
+//!         <eventCode><valueName>II</valueName><value>51</value></eventCode>
+//!         <onset>2023-08-07T08:00:00+02:00</onset>
+//!         <expires>2023-08-07T18:00:00+02:00</expires>
+//!         <headline>Amtliche WARNUNG vor WINDBÖEN</headline>
+//!         <description>Es treten Windböen mit Geschwindigkeiten um 55 km/h auf.</description>
+//!     </info>
+//! </alert>"#;
+//!
+//! let alert = Alert::from_cap(xml).unwrap();
+//! assert_eq!(alert.event_code, Some(51));
+//! assert_eq!(alert.severity, Some(AlertSeverity::Minor));
+//! assert_eq!(alert.headline_de, "Amtliche WARNUNG vor WINDBÖEN");
+//! assert_eq!(alert.headline_en, "");
+//! ```
+//!
+//! The fields map as follows:
+//!
+//! - `alert_id` is the CAP `identifier`. CAP has no Bright Sky ID, so `id` is 0.
+//! - `status` comes from the `alert`, all other fields from its `info` blocks.
+//!   Shared values such as `severity` and `onset` are read from the first
+//!   `info` block, the text fields from the first block in German (`de-*`) and
+//!   in English (`en-*`, or no `language`). If a document has no block in
+//!   one of the languages, its text fields are empty.
+//! - `effective` falls back to the `sent` time of the message.
+//! - `event_code` is the value of the `II` event code.
+//! - A severity of `Unknown` becomes `None`.
+//!
+//! The `msgType`, `references` and `area` elements have no counterpart in
+//! [`Alert`] and are ignored.
+//!
+//! Only available with the `cap` feature.
+
+use chrono::{DateTime, FixedOffset};
+use roxmltree::{Document, Node};
+use serde::de::{Deserialize, IntoDeserializer, value::Error as ValueError};
+
+use crate::types::Alert;
+
+/// XML namespace of CAP 1.2 documents.
+pub const CAP_NAMESPACE: &str = "urn:oasis:names:tc:emergency:cap:1.2";
+
+/// Error parsing a CAP document, see [`Alert::from_cap`].
+#[derive(Debug)]
+pub enum CapError {
+    /// The document is not well-formed XML.
+    Xml(roxmltree::Error),
+    /// The root element is not a CAP 1.2 `alert`.
+    NotCap,
+    /// A required element is missing or empty.
+    MissingElement(&'static str),
+    /// A timestamp is not in the CAP date format.
+    InvalidTimestamp {
+        /// Name of the element
+        element: &'static str,
+        /// The value as found in the document
+        value: String,
+    },
+}
+
+impl core::fmt::Display for CapError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Xml(e) => write!(f, "Invalid XML: {}", e),
+            Self::NotCap => write!(f, "Not a CAP 1.2 alert"),
+            Self::MissingElement(element) => write!(f, "Missing CAP element <{}>", element),
+            Self::InvalidTimestamp { element, value } => {
+                write!(f, "Invalid timestamp in <{}>: {:?}", element, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CapError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Xml(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<roxmltree::Error> for CapError {
+    fn from(err: roxmltree::Error) -> Self {
+        Self::Xml(err)
+    }
+}
+
+impl Alert {
+    /// Read a CAP 1.2 `alert` document, see the [`cap`](crate::cap) module.
+    ///
+    /// # Errors
+    ///
+    /// - The document is not well-formed XML ([`CapError::Xml`])
+    /// - The root element is not a CAP 1.2 `alert` ([`CapError::NotCap`])
+    /// - `identifier`, `status`, `info` or `onset` is missing
+    ///   ([`CapError::MissingElement`])
+    /// - A timestamp is invalid ([`CapError::InvalidTimestamp`])
+    pub fn from_cap(xml: &str) -> Result<Self, CapError> {
+        let document = Document::parse(xml)?;
+        let root = document.root_element();
+        if !root.has_tag_name((CAP_NAMESPACE, "alert")) {
+            return Err(CapError::NotCap);
+        }
+
+        let infos: Vec<Node> = root
+            .children()
+            .filter(|node| node.has_tag_name((CAP_NAMESPACE, "info")))
+            .collect();
+        let info = *infos.first().ok_or(CapError::MissingElement("info"))?;
+        let in_language = |prefix: &str| {
+            infos.iter().copied().find(|info| {
+                text(*info, "language")
+                    .unwrap_or("en-US")
+                    .to_ascii_lowercase()
+                    .starts_with(prefix)
+            })
+        };
+        let de = in_language("de");
+        let en = in_language("en");
+
+        let sent = timestamp(root, "sent")?;
+        let effective = match timestamp(info, "effective")?.or(sent) {
+            Some(effective) => effective,
+            None => return Err(CapError::MissingElement("effective")),
+        };
+
+        Ok(Alert {
+            id: 0,
+            alert_id: required(root, "identifier")?.to_string(),
+            status: value(required(root, "status")?).ok_or(CapError::MissingElement("status"))?,
+            effective,
+            onset: timestamp(info, "onset")?.ok_or(CapError::MissingElement("onset"))?,
+            expires: timestamp(info, "expires")?,
+            category: text(info, "category").and_then(value),
+            response_type: text(info, "responseType").and_then(value),
+            urgency: text(info, "urgency").and_then(value),
+            severity: text(info, "severity").and_then(|s| value(&s.to_ascii_lowercase())),
+            certainty: text(info, "certainty").and_then(value),
+            event_code: event_code(info),
+            event_en: text_in(en, "event").map(String::from),
+            event_de: text_in(de, "event").map(String::from),
+            headline_en: text_in(en, "headline").unwrap_or_default().to_string(),
+            headline_de: text_in(de, "headline").unwrap_or_default().to_string(),
+            description_en: text_in(en, "description").unwrap_or_default().to_string(),
+            description_de: text_in(de, "description").unwrap_or_default().to_string(),
+            instruction_en: text_in(en, "instruction").map(String::from),
+            instruction_de: text_in(de, "instruction").map(String::from),
+        })
+    }
+}
+
+/// First child element of `node` with the given CAP name.
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children()
+        .find(|child| child.has_tag_name((CAP_NAMESPACE, name)))
+}
+
+/// Trimmed text of a child element, `None` if it is missing or empty.
+fn text<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    child(node, name)
+        .and_then(|child| child.text())
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+}
+
+/// [`text`] of an `info` block that may be missing.
+fn text_in<'a>(info: Option<Node<'a, '_>>, name: &str) -> Option<&'a str> {
+    info.and_then(|info| text(info, name))
+}
+
+fn required<'a>(node: Node<'a, '_>, name: &'static str) -> Result<&'a str, CapError> {
+    text(node, name).ok_or(CapError::MissingElement(name))
+}
+
+fn timestamp(
+    node: Node<'_, '_>,
+    name: &'static str,
+) -> Result<Option<DateTime<FixedOffset>>, CapError> {
+    text(node, name)
+        .map(|value| {
+            DateTime::parse_from_rfc3339(value).map_err(|_| CapError::InvalidTimestamp {
+                element: name,
+                value: value.to_string(),
+            })
+        })
+        .transpose()
+}
+
+/// Parse a CAP value with the `Deserialize` impl used for the JSON endpoint.
+fn value<T: for<'de> Deserialize<'de>>(raw: &str) -> Option<T> {
+    T::deserialize(IntoDeserializer::<ValueError>::into_deserializer(raw)).ok()
+}
+
+/// The DWD event code (`II`) of an `info` block.
+fn event_code(info: Node<'_, '_>) -> Option<i64> {
+    info.children()
+        .filter(|node| node.has_tag_name((CAP_NAMESPACE, "eventCode")))
+        .find(|code| text(*code, "valueName") == Some("II"))
+        .and_then(|code| text(code, "value"))
+        .and_then(|value| value.parse().ok())
+}
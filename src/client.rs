@@ -0,0 +1,763 @@
+//! HTTP client abstraction.
+//!
+//! The query builders only produce URLs, so any HTTP client can be used. For
+//! code that should not depend on a particular client, [`HttpClient`] describes
+//! the single operation Bright Sky needs (a `GET` request returning a status and
+//! a body) and [`BrightSkyClient`] combines it with URL generation and JSON
+//! deserialization.
+//!
+//! With the `reqwest` feature, `reqwest::Client` implements [`HttpClient`]. The
+//! `testing` feature provides `testing::MockHttpClient` for unit tests.
+//!
+//! [`BrightSkyClientBuilder`] creates the HTTP client as well, with timeouts and
+//! a `User-Agent` applied through [`FromHttpOptions`].
+//!
+//! ## Usage
+//!
+//! ```rust,no_run
+//! # #[cfg(feature = "reqwest")]
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! use brightsky::{BrightSkyClient, CurrentWeatherQueryBuilder, types::CurrentWeatherResponse};
+//!
+//! let client = BrightSkyClient::new(reqwest::Client::new());
+//!
+//! let query = CurrentWeatherQueryBuilder::new()
+//!     .with_lat_lon((52.52, 13.4))
+//!     .build()?;
+//!
+//! let response: CurrentWeatherResponse = client.get(query).await?;
+//! println!("Temperature: {:?}°C", response.weather.temperature);
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{borrow::Cow, boxed::Box, string::String, string::ToString, vec::Vec};
+
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+
+use core::{
+    convert::Infallible,
+    future::{Future, poll_fn},
+    pin::Pin,
+    task::Poll,
+    time::Duration,
+};
+
+use serde::de::DeserializeOwned;
+
+#[cfg(feature = "std")]
+use serde::Serialize;
+
+#[cfg(feature = "std")]
+use crate::{lenient::Lenient, provenance::Provenance};
+
+use crate::{
+    BRIGHT_SKY_API, BrightSkyApiError, BrightSkyError, DecodeError, RadarWeatherQuery,
+    ToBrightSkyUrl, TransportError, WeatherRecords, types::RadarResponse,
+};
+
+/// Status code and body of an HTTP response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    /// HTTP status code
+    pub status: u16,
+    /// Raw response body
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Whether the status code is in the `2xx` range.
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// An HTTP client able to perform `GET` requests.
+///
+/// Implement this for your HTTP client of choice to use it with
+/// [`BrightSkyClient`]. Non-`2xx` responses should be returned as
+/// `Ok(HttpResponse)`; `Err` is meant for transport failures.
+pub trait HttpClient {
+    /// Transport error of the client
+    type Error;
+
+    /// Perform a `GET` request for the given absolute URL.
+    fn get(&self, url: &str) -> impl Future<Output = Result<HttpResponse, Self::Error>>;
+
+    /// Perform a `GET` request sending additional headers.
+    ///
+    /// [`BrightSkyClient`] sends its default headers (such as the `User-Agent`
+    /// set with [`BrightSkyClient::with_user_agent`]) through this method. The
+    /// default implementation ignores `headers` and calls [`get`](Self::get),
+    /// so override it if your client can send headers.
+    fn get_with_headers(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+    ) -> impl Future<Output = Result<HttpResponse, Self::Error>> {
+        let _ = headers;
+        self.get(url)
+    }
+
+    /// Perform a `GET` request like [`get_with_headers`](Self::get_with_headers),
+    /// returning `None` instead of a body larger than `limit` bytes.
+    ///
+    /// [`BrightSkyClient`] calls this with the limit set with
+    /// [`BrightSkyClient::with_max_response_bytes`]. The default implementation
+    /// downloads the whole body and checks its size afterwards; override it if
+    /// your client can check the `Content-Length` or stop reading early, as the
+    /// implementation for `reqwest::Client` does.
+    fn get_with_limit(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+        limit: usize,
+    ) -> impl Future<Output = Result<Option<HttpResponse>, Self::Error>> {
+        async move {
+            let response = self.get_with_headers(url, headers).await?;
+            Ok((response.body.len() <= limit).then_some(response))
+        }
+    }
+}
+
+/// `User-Agent` of clients created by [`BrightSkyClientBuilder`] unless set
+/// otherwise.
+pub const DEFAULT_USER_AGENT: &str = concat!("brightsky-rs/", env!("CARGO_PKG_VERSION"));
+
+/// Settings applied when a [`BrightSkyClientBuilder`] creates the HTTP client.
+///
+/// Unset options keep the defaults of the HTTP client.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpOptions {
+    /// Time limit for a whole request, from connecting until the body was read
+    pub timeout: Option<Duration>,
+    /// Time limit for establishing a connection
+    pub connect_timeout: Option<Duration>,
+    /// Value of the `User-Agent` header
+    pub user_agent: Option<String>,
+}
+
+/// An [`HttpClient`] that can be created from [`HttpOptions`].
+///
+/// Implemented for `reqwest::Client` with the `reqwest` feature and for
+/// `testing::MockHttpClient`. Implement it for other clients to create them with
+/// [`BrightSkyClientBuilder::build`].
+pub trait FromHttpOptions: HttpClient + Sized {
+    /// Create a client applying all supported options.
+    fn from_http_options(options: &HttpOptions) -> Result<Self, Self::Error>;
+}
+
+/// Error returned by [`BrightSkyClient`].
+#[derive(Debug)]
+pub enum ClientError<E> {
+    /// Error building the query or URL
+    Query(BrightSkyError),
+    /// The HTTP client failed to perform the request
+    Http(E),
+    /// The API responded with a non-`2xx` status code
+    Api(BrightSkyApiError),
+    /// The response body could not be deserialized
+    Json(serde_json::Error),
+    /// The response body could not be decoded before deserializing it
+    Decode(DecodeError),
+}
+
+impl<E: core::fmt::Display> core::fmt::Display for ClientError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Query(e) => write!(f, "Query error: {}", e),
+            Self::Http(e) => write!(f, "Request error: {}", e),
+            Self::Api(e) => write!(f, "{}", e),
+            Self::Json(e) => write!(f, "JSON error: {}", e),
+            Self::Decode(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: std::error::Error + 'static> std::error::Error for ClientError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Query(e) => Some(e),
+            Self::Http(e) => Some(e),
+            Self::Api(e) => Some(e),
+            Self::Json(e) => Some(e),
+            Self::Decode(e) => Some(e),
+        }
+    }
+}
+
+impl<E> From<BrightSkyError> for ClientError<E> {
+    fn from(err: BrightSkyError) -> Self {
+        Self::Query(err)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: std::error::Error + Send + Sync + 'static> From<ClientError<E>> for BrightSkyError {
+    fn from(err: ClientError<E>) -> Self {
+        match err {
+            ClientError::Query(e) => e,
+            ClientError::Http(e) => TransportError::Http(Box::new(e)).into(),
+            ClientError::Api(e) => TransportError::Api(e).into(),
+            ClientError::Json(e) => DecodeError::Json(e).into(),
+            ClientError::Decode(e) => e.into(),
+        }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<E: core::fmt::Display> From<ClientError<E>> for BrightSkyError {
+    fn from(err: ClientError<E>) -> Self {
+        match err {
+            ClientError::Query(e) => e,
+            ClientError::Http(e) => TransportError::Http(e.to_string()).into(),
+            ClientError::Api(e) => TransportError::Api(e).into(),
+            ClientError::Json(e) => DecodeError::Json(e).into(),
+            ClientError::Decode(e) => e.into(),
+        }
+    }
+}
+
+/// Requests [`BrightSkyClient::get_many`] has in flight at the same time by default.
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 8;
+
+/// Builder for a [`BrightSkyClient`] that creates its HTTP client.
+///
+/// ```rust,no_run
+/// # #[cfg(feature = "reqwest")]
+/// # fn run() -> Result<(), reqwest::Error> {
+/// use brightsky::{BrightSkyClient, BrightSkyClientBuilder};
+/// use std::time::Duration;
+///
+/// let client: BrightSkyClient<reqwest::Client> = BrightSkyClientBuilder::new()
+///     .timeout(Duration::from_secs(10))
+///     .connect_timeout(Duration::from_secs(3))
+///     .user_agent("weather-station/1.0 (ops@example.org)")
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct BrightSkyClientBuilder {
+    options: HttpOptions,
+    headers: Vec<(String, String)>,
+    host: String,
+    batch_concurrency: usize,
+    max_response_bytes: Option<usize>,
+    #[cfg(feature = "compression")]
+    compression: bool,
+}
+
+impl Default for BrightSkyClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BrightSkyClientBuilder {
+    /// Start with the defaults of [`BrightSkyClient::new`] and
+    /// [`DEFAULT_USER_AGENT`].
+    pub fn new() -> Self {
+        Self {
+            options: HttpOptions {
+                user_agent: Some(DEFAULT_USER_AGENT.to_string()),
+                ..HttpOptions::default()
+            },
+            headers: Vec::new(),
+            host: BRIGHT_SKY_API.to_string(),
+            batch_concurrency: DEFAULT_BATCH_CONCURRENCY,
+            max_response_bytes: None,
+            #[cfg(feature = "compression")]
+            compression: true,
+        }
+    }
+
+    /// Set the time limit for a whole request.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the time limit for establishing a connection.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.options.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the `User-Agent` header sent with every request.
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.options.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// Add a header sent with every request, see [`BrightSkyClient::with_header`].
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        set_header(&mut self.headers, name, value);
+        self
+    }
+
+    /// Use a different API host, see [`BrightSkyClient::with_host`].
+    pub fn host(mut self, host: &str) -> Self {
+        self.host = host.to_string();
+        self
+    }
+
+    /// Set the concurrency of batches, see
+    /// [`BrightSkyClient::with_batch_concurrency`].
+    pub fn batch_concurrency(mut self, requests: usize) -> Self {
+        self.batch_concurrency = requests.max(1);
+        self
+    }
+
+    /// Limit the size of response bodies, see
+    /// [`BrightSkyClient::with_max_response_bytes`].
+    pub fn max_response_bytes(mut self, limit: usize) -> Self {
+        self.max_response_bytes = Some(limit);
+        self
+    }
+
+    /// Request and decompress compressed responses, see
+    /// [`BrightSkyClient::with_compression`].
+    #[cfg(feature = "compression")]
+    pub fn compression(mut self, enabled: bool) -> Self {
+        self.compression = enabled;
+        self
+    }
+
+    /// The options passed to the HTTP client.
+    pub fn options(&self) -> &HttpOptions {
+        &self.options
+    }
+
+    /// Create the HTTP client and wrap it in a [`BrightSkyClient`].
+    pub fn build<C: FromHttpOptions>(self) -> Result<BrightSkyClient<C>, C::Error> {
+        let http = C::from_http_options(&self.options)?;
+        Ok(BrightSkyClient {
+            http,
+            host: self.host,
+            headers: self.headers,
+            batch_concurrency: self.batch_concurrency,
+            max_response_bytes: self.max_response_bytes,
+            #[cfg(feature = "compression")]
+            compression: self.compression,
+        })
+    }
+}
+
+/// Replace the values of header `name` (case-insensitive) with `value`.
+fn set_header(headers: &mut Vec<(String, String)>, name: &str, value: &str) {
+    headers.retain(|(existing, _)| !existing.eq_ignore_ascii_case(name));
+    headers.push((name.to_string(), value.to_string()));
+}
+
+/// Bright Sky API client on top of any [`HttpClient`].
+#[derive(Debug, Clone)]
+pub struct BrightSkyClient<C> {
+    http: C,
+    host: String,
+    headers: Vec<(String, String)>,
+    batch_concurrency: usize,
+    max_response_bytes: Option<usize>,
+    #[cfg(feature = "compression")]
+    compression: bool,
+}
+
+impl<C: HttpClient> BrightSkyClient<C> {
+    /// Create a client for the public API at [`BRIGHT_SKY_API`].
+    pub fn new(http: C) -> Self {
+        Self {
+            http,
+            host: BRIGHT_SKY_API.to_string(),
+            headers: Vec::new(),
+            batch_concurrency: DEFAULT_BATCH_CONCURRENCY,
+            max_response_bytes: None,
+            #[cfg(feature = "compression")]
+            compression: true,
+        }
+    }
+
+    /// Use a different API host, e.g. a self-hosted instance or a mock server.
+    pub fn with_host(mut self, host: &str) -> Self {
+        self.host = host.to_string();
+        self
+    }
+
+    /// Identify the application with a `User-Agent` header on every request.
+    ///
+    /// The Bright Sky maintainers ask clients to identify themselves, ideally
+    /// with a name and a way to reach you, e.g. `my-app/1.0 (ops@example.org)`.
+    pub fn with_user_agent(self, user_agent: &str) -> Self {
+        self.with_header("User-Agent", user_agent)
+    }
+
+    /// Send a header with every request, replacing earlier values of the same
+    /// header (names are case-insensitive).
+    ///
+    /// Headers are passed to [`HttpClient::get_with_headers`], which clients
+    /// without header support ignore.
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        set_header(&mut self.headers, name, value);
+        self
+    }
+
+    /// Headers sent with every request.
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// Request compressed responses and decompress them (enabled by default).
+    ///
+    /// Sends `Accept-Encoding: gzip, deflate` and inflates gzip and zlib
+    /// bodies, whether or not the HTTP client supports compression. Disable it
+    /// on memory-constrained targets, where holding the compressed and the
+    /// decompressed body at the same time does not fit.
+    ///
+    /// Only available with the `compression` feature.
+    #[cfg(feature = "compression")]
+    pub fn with_compression(mut self, enabled: bool) -> Self {
+        self.compression = enabled;
+        self
+    }
+
+    /// Fail responses with bodies larger than `limit` bytes instead of
+    /// deserializing them (no limit by default).
+    ///
+    /// Guards small services against accidentally requesting e.g. the full
+    /// radar grid in the plain format, 1.32 million values per frame.
+    /// Compressed bodies are checked before and while inflating, so
+    /// they never expand past the limit. Oversized responses fail with
+    /// [`DecodeError::ResponseTooLarge`].
+    ///
+    /// The limit is passed to [`HttpClient::get_with_limit`]: reqwest rejects
+    /// bodies by their `Content-Length` and stops downloading once a body
+    /// exceeds it, other clients may download the whole body first. See
+    /// `RadarWeatherQueryBuilder::require_location` to refuse unrestricted
+    /// radar queries before sending them.
+    pub fn with_max_response_bytes(mut self, limit: usize) -> Self {
+        self.max_response_bytes = Some(limit);
+        self
+    }
+
+    /// Set how many requests [`get_many`](Self::get_many) sends at the same time
+    /// (at least 1).
+    pub fn with_batch_concurrency(mut self, requests: usize) -> Self {
+        self.batch_concurrency = requests.max(1);
+        self
+    }
+
+    /// The API host requests are sent to.
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// The underlying HTTP client.
+    pub fn http_client(&self) -> &C {
+        &self.http
+    }
+
+    /// Fetch and deserialize the response for a query.
+    pub async fn get<Q, R>(&self, query: Q) -> Result<R, ClientError<C::Error>>
+    where
+        Q: ToBrightSkyUrl,
+        R: DeserializeOwned,
+    {
+        #[cfg(feature = "std")]
+        let url = query.to_url(&self.host)?.to_string();
+        #[cfg(not(feature = "std"))]
+        let url = query.to_url_string(&self.host)?;
+
+        self.get_url(&url).await
+    }
+
+    /// Like [`get`](Self::get), also returning where the response came from.
+    ///
+    /// Only available with the `std` feature.
+    #[cfg(feature = "std")]
+    pub async fn get_with_provenance<Q, R>(
+        &self,
+        query: Q,
+    ) -> Result<(R, Provenance), ClientError<C::Error>>
+    where
+        Q: ToBrightSkyUrl,
+        R: DeserializeOwned,
+    {
+        let url = query.to_url(&self.host)?.to_string();
+        let fetched_at = chrono::Utc::now();
+        let response = self.get_url(&url).await?;
+        Ok((response, Provenance::live(&self.host, url, fetched_at)))
+    }
+
+    /// Fetch the response for a query without deserializing it.
+    ///
+    /// Returns the status and (decompressed) body of any response, including
+    /// API errors, e.g. to archive responses or debug schema mismatches. Only
+    /// transport and decompression failures are errors.
+    pub async fn get_raw<Q: ToBrightSkyUrl>(
+        &self,
+        query: Q,
+    ) -> Result<HttpResponse, ClientError<C::Error>> {
+        #[cfg(feature = "std")]
+        let url = query.to_url(&self.host)?.to_string();
+        #[cfg(not(feature = "std"))]
+        let url = query.to_url_string(&self.host)?;
+
+        self.instrumented(&url, self.fetch(&url)).await
+    }
+
+    /// Fetch the response for a query as untyped JSON.
+    ///
+    /// An escape hatch for responses that do not fit the typed path, e.g. to
+    /// inspect fields this crate does not model yet. Use
+    /// [`get_raw`](Self::get_raw) for the unparsed body.
+    pub async fn get_value<Q: ToBrightSkyUrl>(
+        &self,
+        query: Q,
+    ) -> Result<serde_json::Value, ClientError<C::Error>> {
+        self.get(query).await
+    }
+
+    /// Fetch and leniently deserialize the response for a query.
+    ///
+    /// Unlike [`get`](Self::get), invalid values and unknown fields are
+    /// reported alongside the response instead of failing it, see the
+    /// [`lenient`](crate::lenient) module.
+    ///
+    /// Only available with the `std` feature.
+    #[cfg(feature = "std")]
+    pub async fn get_lenient<Q, R>(&self, query: Q) -> Result<Lenient<R>, ClientError<C::Error>>
+    where
+        Q: ToBrightSkyUrl,
+        R: DeserializeOwned + Serialize,
+    {
+        let url = query.to_url(&self.host)?.to_string();
+        self.instrumented(&url, async {
+            let body = self.fetch_body(&url).await?;
+            let response = crate::lenient::from_slice(&body).map_err(ClientError::Json)?;
+            #[cfg(feature = "tracing")]
+            for warning in &response.warnings {
+                tracing::debug!(url, %warning, "Bright Sky response repaired");
+            }
+            Ok(response)
+        })
+        .await
+    }
+
+    async fn get_url<R: DeserializeOwned>(&self, url: &str) -> Result<R, ClientError<C::Error>> {
+        self.instrumented(url, async {
+            let body = self.fetch_body(url).await?;
+            serde_json::from_slice(&body).map_err(|err| {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(url, error = %err, "Bright Sky response does not match the expected type");
+                ClientError::Json(err)
+            })
+        })
+        .await
+    }
+
+    /// Fetch a `/radar` query and decode the precipitation data in the
+    /// requested format.
+    ///
+    /// Unlike [`get`](Self::get), a compressed payload with a damaged zlib
+    /// header or data in another format is an error
+    /// ([`ClientError::Decode`]) instead of being guessed from its contents,
+    /// see [`RadarResponse::into_format`].
+    pub async fn get_radar(
+        &self,
+        query: RadarWeatherQuery,
+    ) -> Result<RadarResponse, ClientError<C::Error>> {
+        let format = query.response_format();
+        let response: RadarResponse = self.get(query).await?;
+        response.into_format(format).map_err(ClientError::Decode)
+    }
+
+    /// Fetch the hourly records of a `/weather` query, parsing them one at a
+    /// time while iterating.
+    ///
+    /// Keeps the peak memory of requests spanning weeks close to the size of
+    /// the response body, see [`WeatherRecords`]. Records that fail to parse
+    /// end the iteration with an error.
+    pub async fn get_weather_records<Q: ToBrightSkyUrl>(
+        &self,
+        query: Q,
+    ) -> Result<WeatherRecords<'static>, ClientError<C::Error>> {
+        #[cfg(feature = "std")]
+        let url = query.to_url(&self.host)?.to_string();
+        #[cfg(not(feature = "std"))]
+        let url = query.to_url_string(&self.host)?;
+
+        let body = self.instrumented(&url, self.fetch_body(&url)).await?;
+        Ok(WeatherRecords::from_vec(body))
+    }
+
+    /// Run a request, recording it with the `metrics` feature.
+    async fn instrumented<T>(
+        &self,
+        _url: &str,
+        request: impl Future<Output = Result<T, ClientError<C::Error>>>,
+    ) -> Result<T, ClientError<C::Error>> {
+        #[cfg(feature = "metrics")]
+        let started = std::time::Instant::now();
+        let result = request.await;
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_request(_url, started.elapsed(), &result);
+        result
+    }
+
+    /// The decoded body of a successful response.
+    async fn fetch_body(&self, url: &str) -> Result<Vec<u8>, ClientError<C::Error>> {
+        let response = self.fetch(url).await?;
+        if !response.is_success() {
+            return Err(ClientError::Api(BrightSkyApiError::from_response(
+                response.status,
+                &response.body,
+            )));
+        }
+        Ok(response.body)
+    }
+
+    /// The response with its body decoded, whatever its status.
+    async fn fetch(&self, url: &str) -> Result<HttpResponse, ClientError<C::Error>> {
+        let limit = self.max_response_bytes.unwrap_or(usize::MAX);
+        #[allow(unused_mut)]
+        let mut response = self
+            .http
+            .get_with_limit(url, &self.request_headers(), limit)
+            .await
+            .map_err(ClientError::Http)?
+            .ok_or(ClientError::Decode(DecodeError::ResponseTooLarge { limit }))?;
+        #[cfg(feature = "compression")]
+        if self.compression {
+            response.body = crate::content_encoding::decode_body(response.body, limit)
+                .map_err(ClientError::Decode)?;
+        }
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            url,
+            status = response.status,
+            bytes = response.body.len(),
+            "Bright Sky response"
+        );
+        Ok(response)
+    }
+
+    /// The default headers, plus `Accept-Encoding` if compression is enabled
+    /// and it was not set explicitly.
+    fn request_headers(&self) -> Cow<'_, [(String, String)]> {
+        #[cfg(feature = "compression")]
+        if self.compression
+            && !self
+                .headers
+                .iter()
+                .any(|(name, _)| name.eq_ignore_ascii_case("Accept-Encoding"))
+        {
+            let mut headers = self.headers.clone();
+            headers.push((
+                "Accept-Encoding".to_string(),
+                crate::content_encoding::ACCEPT_ENCODING.to_string(),
+            ));
+            return Cow::Owned(headers);
+        }
+        Cow::Borrowed(&self.headers)
+    }
+
+    /// Fetch and deserialize the responses for several queries.
+    ///
+    /// At most [`with_batch_concurrency`](Self::with_batch_concurrency) requests
+    /// are in flight at the same time. Results are in the order of `queries`,
+    /// and a failing query does not affect the others.
+    ///
+    /// ```rust,no_run
+    /// # #[cfg(feature = "reqwest")]
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// use brightsky::{BrightSkyClient, CurrentWeatherQueryBuilder, types::CurrentWeatherResponse};
+    ///
+    /// let client = BrightSkyClient::new(reqwest::Client::new()).with_batch_concurrency(4);
+    ///
+    /// let cities = [(52.52, 13.4), (48.14, 11.58), (53.55, 9.99)];
+    /// let queries = cities
+    ///     .into_iter()
+    ///     .map(|city| CurrentWeatherQueryBuilder::new().with_lat_lon(city).build())
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    ///
+    /// for result in client.get_many::<CurrentWeatherResponse>(queries).await {
+    ///     match result {
+    ///         Ok(response) => println!("{:?}°C", response.weather.temperature),
+    ///         Err(err) => println!("Failed: {}", err),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_many<R>(
+        &self,
+        queries: impl IntoIterator<Item = impl ToBrightSkyUrl>,
+    ) -> Vec<Result<R, ClientError<C::Error>>>
+    where
+        R: DeserializeOwned,
+    {
+        let requests = queries
+            .into_iter()
+            .map(|query| async { Ok::<_, Infallible>(self.get(query).await) })
+            .collect();
+
+        match try_join_bounded(requests, self.batch_concurrency).await {
+            Ok(results) => results,
+            Err(never) => match never {},
+        }
+    }
+}
+
+/// Run `futures` with at most `limit` of them in flight, in order of the list.
+///
+/// Returns all outputs in the order of `futures`, or the first error, in which
+/// case the remaining futures are dropped without being started.
+pub(crate) async fn try_join_bounded<F, T, E>(futures: Vec<F>, limit: usize) -> Result<Vec<T>, E>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    let limit = limit.max(1);
+    let mut pending: Vec<Option<Pin<Box<F>>>> =
+        futures.into_iter().map(|f| Some(Box::pin(f))).collect();
+    let mut outputs: Vec<Option<T>> = pending.iter().map(|_| None).collect();
+    let mut started = 0;
+    let mut in_flight = 0;
+
+    poll_fn(|cx| {
+        loop {
+            while in_flight < limit && started < pending.len() {
+                started += 1;
+                in_flight += 1;
+            }
+
+            let mut progress = false;
+            for (slot, output) in pending[..started].iter_mut().zip(&mut outputs) {
+                let Some(future) = slot else { continue };
+                if let Poll::Ready(result) = future.as_mut().poll(cx) {
+                    *slot = None;
+                    in_flight -= 1;
+                    progress = true;
+                    match result {
+                        Ok(value) => *output = Some(value),
+                        Err(err) => return Poll::Ready(Err(err)),
+                    }
+                }
+            }
+
+            if in_flight == 0 && started == pending.len() {
+                return Poll::Ready(Ok(()));
+            }
+            if !progress {
+                return Poll::Pending;
+            }
+        }
+    })
+    .await?;
+
+    Ok(outputs.into_iter().flatten().collect())
+}
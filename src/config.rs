@@ -0,0 +1,464 @@
+//! Queries from configuration files.
+//!
+//! With the `config` feature, all built query types implement
+//! [`serde::Deserialize`] and [`serde::Serialize`], so monitoring locations can
+//! be defined in TOML, YAML or JSON configuration instead of code, and built
+//! queries can be persisted and loaded again. Deserialization runs the builder's
+//! `build()` validation and fails with its error message, so an invalid
+//! configuration is rejected at startup rather than at the first request.
+//!
+//! The fields are named after the API parameters:
+//!
+//! | Field | Queries | Type |
+//! |-------|----------|------|
+//! | `lat`, `lon` | all | number, both or neither |
+//! | `coordinate_precision` | all | integer |
+//! | `tz` | all | tz database name |
+//! | `date`, `last_date` | weather, radar | `YYYY-MM-DD` or RFC 3339 timestamp |
+//! | `max_dist` | weather, current weather | meters |
+//! | `dwd_station_id`, `wmo_station_id` | weather, current weather | list of strings |
+//! | `source_id` | weather, current weather | list of integers |
+//! | `units` | weather, current weather | `dwd` or `si` |
+//! | `bbox` | radar | `[top, left, bottom, right]` |
+//! | `distance` | radar | meters |
+//! | `format` | radar | `compressed`, `bytes` or `plain` |
+//! | `warn_cell_id` | alerts | integer |
+//!
+//! Unknown fields are rejected to catch typos. Serialization writes the same
+//! fields, leaving out unset parameters and the default `coordinate_precision`.
+//! Coordinate fuzzing is not stored; the serialized `lat` and `lon` are the
+//! coordinates as they are sent, fuzzed and rounded.
+//!
+//! ```rust
+//! use brightsky::{CurrentWeatherQuery, ToBrightSkyUrl, BRIGHT_SKY_API};
+//!
+//! let query: CurrentWeatherQuery = serde_json::from_str(
+//!     r#"{"lat": 52.52, "lon": 13.4, "tz": "Europe/Berlin", "units": "si"}"#,
+//! ).unwrap();
+//! assert_eq!(
+//!     query.to_url_string(BRIGHT_SKY_API).unwrap(),
+//!     "https://api.brightsky.dev/current_weather?lat=52.52&lon=13.4&tz=Europe/Berlin&units=si"
+//! );
+//!
+//! let invalid = serde_json::from_str::<CurrentWeatherQuery>(r#"{"lat": 152.0, "lon": 13.4}"#);
+//! assert!(invalid.is_err());
+//! ```
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+use chrono::{DateTime, NaiveDate};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+
+use crate::{
+    AlertsQuery, AlertsQueryBuilder, CoordinateFuzzing, CurrentWeatherQuery,
+    CurrentWeatherQueryBuilder, DEFAULT_COORDINATE_PRECISION, RadarWeatherQuery,
+    RadarWeatherQueryBuilder, WeatherQuery, WeatherQueryBuilder,
+    coordinates::url_coordinates,
+    types::{QueryDate, RadarCompressionFormat, UnitType},
+};
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WeatherConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lat: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lon: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    coordinate_precision: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tz: Option<String>,
+    #[serde(
+        default,
+        serialize_with = "serialize_query_date",
+        deserialize_with = "query_date",
+        skip_serializing_if = "Option::is_none"
+    )]
+    date: Option<QueryDate>,
+    #[serde(
+        default,
+        serialize_with = "serialize_query_date",
+        deserialize_with = "query_date",
+        skip_serializing_if = "Option::is_none"
+    )]
+    last_date: Option<QueryDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_dist: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dwd_station_id: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    wmo_station_id: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_id: Option<Vec<i64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    units: Option<UnitType>,
+}
+
+impl<'de> Deserialize<'de> for WeatherQuery {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let config = WeatherConfig::deserialize(deserializer)?;
+
+        let mut query = WeatherQueryBuilder::new();
+        if let Some(decimals) = config.coordinate_precision {
+            query = query.with_coordinate_precision(decimals);
+        }
+        if let Some(lat_lon) = lat_lon(config.lat, config.lon)? {
+            query = query.with_lat_lon(lat_lon);
+        }
+        if let Some(tz) = config.tz {
+            query = query.with_tz(tz);
+        }
+        query.date = config.date;
+        query.last_date = config.last_date;
+        if let Some(max_dist) = config.max_dist {
+            query = query.with_max_dist(max_dist);
+        }
+        if let Some(ids) = config.dwd_station_id {
+            query = query.with_dwd_station_id(ids);
+        }
+        if let Some(ids) = config.wmo_station_id {
+            query = query.with_wmo_station_id(ids);
+        }
+        if let Some(ids) = config.source_id {
+            query = query.with_source_id(ids);
+        }
+        if let Some(units) = config.units {
+            query = query.with_units(units);
+        }
+        query.build().map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for WeatherQuery {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (lat, lon) = sent_coordinates(
+            self.lat,
+            self.lon,
+            self.coordinate_precision,
+            self.coordinate_fuzzing,
+        );
+        WeatherConfig {
+            lat,
+            lon,
+            coordinate_precision: coordinate_precision(self.coordinate_precision),
+            tz: self.tz.clone(),
+            date: self.date,
+            last_date: self.last_date,
+            max_dist: parsed(&self.max_dist),
+            dwd_station_id: self.dwd_station_id.clone(),
+            wmo_station_id: self.wmo_station_id.clone(),
+            source_id: source_ids(&self.source_id),
+            units: self.units,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CurrentWeatherConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lat: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lon: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    coordinate_precision: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tz: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_dist: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dwd_station_id: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    wmo_station_id: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_id: Option<Vec<i64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    units: Option<UnitType>,
+}
+
+impl<'de> Deserialize<'de> for CurrentWeatherQuery {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let config = CurrentWeatherConfig::deserialize(deserializer)?;
+
+        let mut query = CurrentWeatherQueryBuilder::new();
+        if let Some(decimals) = config.coordinate_precision {
+            query = query.with_coordinate_precision(decimals);
+        }
+        if let Some(lat_lon) = lat_lon(config.lat, config.lon)? {
+            query = query.with_lat_lon(lat_lon);
+        }
+        if let Some(tz) = config.tz {
+            query = query.with_tz(tz);
+        }
+        if let Some(max_dist) = config.max_dist {
+            query = query.with_max_dist(max_dist);
+        }
+        if let Some(ids) = config.dwd_station_id {
+            query = query.with_dwd_station_id(ids);
+        }
+        if let Some(ids) = config.wmo_station_id {
+            query = query.with_wmo_station_id(ids);
+        }
+        if let Some(ids) = config.source_id {
+            query = query.with_source_id(ids);
+        }
+        if let Some(units) = config.units {
+            query = query.with_units(units);
+        }
+        query.build().map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for CurrentWeatherQuery {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (lat, lon) = sent_coordinates(
+            self.lat,
+            self.lon,
+            self.coordinate_precision,
+            self.coordinate_fuzzing,
+        );
+        CurrentWeatherConfig {
+            lat,
+            lon,
+            coordinate_precision: coordinate_precision(self.coordinate_precision),
+            tz: self.tz.clone(),
+            max_dist: parsed(&self.max_dist),
+            dwd_station_id: self.dwd_station_id.clone(),
+            wmo_station_id: self.wmo_station_id.clone(),
+            source_id: source_ids(&self.source_id),
+            units: self.units,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RadarConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lat: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lon: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    coordinate_precision: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tz: Option<String>,
+    #[serde(
+        default,
+        serialize_with = "serialize_query_date",
+        deserialize_with = "query_date",
+        skip_serializing_if = "Option::is_none"
+    )]
+    date: Option<QueryDate>,
+    #[serde(
+        default,
+        serialize_with = "serialize_query_date",
+        deserialize_with = "query_date",
+        skip_serializing_if = "Option::is_none"
+    )]
+    last_date: Option<QueryDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bbox: Option<[i64; 4]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    distance: Option<u64>,
+    #[serde(
+        default,
+        serialize_with = "serialize_compression_format",
+        deserialize_with = "compression_format",
+        skip_serializing_if = "Option::is_none"
+    )]
+    format: Option<RadarCompressionFormat>,
+}
+
+impl<'de> Deserialize<'de> for RadarWeatherQuery {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let config = RadarConfig::deserialize(deserializer)?;
+
+        let mut query = RadarWeatherQueryBuilder::new();
+        if let Some(decimals) = config.coordinate_precision {
+            query = query.with_coordinate_precision(decimals);
+        }
+        if let Some(lat_lon) = lat_lon(config.lat, config.lon)? {
+            query = query.with_lat_lon(lat_lon);
+        }
+        if let Some(tz) = config.tz {
+            query = query.with_tz(tz);
+        }
+        query.date = config.date;
+        query.last_date = config.last_date;
+        if let Some(bbox) = config.bbox {
+            query = query.with_bbox(bbox);
+        }
+        if let Some(distance) = config.distance {
+            query = query.with_distance(distance);
+        }
+        if let Some(format) = config.format {
+            query = query.with_compression_format(format);
+        }
+        query.build().map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for RadarWeatherQuery {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (lat, lon) = sent_coordinates(
+            self.lat,
+            self.lon,
+            self.coordinate_precision,
+            self.coordinate_fuzzing,
+        );
+        RadarConfig {
+            lat,
+            lon,
+            coordinate_precision: coordinate_precision(self.coordinate_precision),
+            tz: self.tz.clone(),
+            date: self.date,
+            last_date: self.last_date,
+            bbox: self.bbox.map(|bbox| bbox.to_array()),
+            distance: self.distance,
+            format: self.compression_format,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AlertsConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lat: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lon: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    coordinate_precision: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tz: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    warn_cell_id: Option<i64>,
+}
+
+impl<'de> Deserialize<'de> for AlertsQuery {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let config = AlertsConfig::deserialize(deserializer)?;
+
+        let mut query = AlertsQueryBuilder::new();
+        if let Some(decimals) = config.coordinate_precision {
+            query = query.with_coordinate_precision(decimals);
+        }
+        if let Some(lat_lon) = lat_lon(config.lat, config.lon)? {
+            query = query.with_lat_lon(lat_lon);
+        }
+        if let Some(tz) = config.tz {
+            query = query.with_tz(tz);
+        }
+        if let Some(warn_cell_id) = config.warn_cell_id {
+            query = query.with_warn_cell_id(warn_cell_id);
+        }
+        query.build().map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for AlertsQuery {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (lat, lon) = sent_coordinates(
+            self.lat,
+            self.lon,
+            self.coordinate_precision,
+            self.coordinate_fuzzing,
+        );
+        AlertsConfig {
+            lat,
+            lon,
+            coordinate_precision: coordinate_precision(self.coordinate_precision),
+            tz: self.tz.clone(),
+            warn_cell_id: parsed(&self.warn_cell_id),
+        }
+        .serialize(serializer)
+    }
+}
+
+fn lat_lon<E: Error>(lat: Option<f64>, lon: Option<f64>) -> Result<Option<(f64, f64)>, E> {
+    match (lat, lon) {
+        (Some(lat), Some(lon)) => Ok(Some((lat, lon))),
+        (None, None) => Ok(None),
+        _ => Err(E::custom("`lat` and `lon` must be set together")),
+    }
+}
+
+/// The coordinates of a query as they are sent, fuzzed and rounded.
+fn sent_coordinates(
+    lat: Option<f64>,
+    lon: Option<f64>,
+    precision: u8,
+    fuzzing: Option<CoordinateFuzzing>,
+) -> (Option<f64>, Option<f64>) {
+    let (lat, lon) = url_coordinates(lat, lon, precision, fuzzing);
+    (parsed(&lat), parsed(&lon))
+}
+
+/// Numeric parameter that the builder stores pre-formatted for the URL.
+fn parsed<T: core::str::FromStr>(value: &Option<String>) -> Option<T> {
+    value.as_deref().and_then(|value| value.parse().ok())
+}
+
+fn source_ids(ids: &Option<Vec<String>>) -> Option<Vec<i64>> {
+    ids.as_ref()
+        .map(|ids| ids.iter().filter_map(|id| id.parse().ok()).collect())
+}
+
+/// Only written when it differs from the default, to keep configs minimal.
+fn coordinate_precision(decimals: u8) -> Option<u8> {
+    (decimals != DEFAULT_COORDINATE_PRECISION).then_some(decimals)
+}
+
+fn serialize_query_date<S: Serializer>(
+    date: &Option<QueryDate>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match date {
+        Some(date) => serializer.collect_str(date),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn serialize_compression_format<S: Serializer>(
+    format: &Option<RadarCompressionFormat>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match format {
+        Some(format) => serializer.serialize_str(format.as_str()),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// A date (`2023-08-07`) or RFC 3339 timestamp (`2023-08-07T14:00:00+02:00`).
+fn query_date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<QueryDate>, D::Error> {
+    let Some(value) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    if let Ok(date) = value.parse::<NaiveDate>() {
+        return Ok(Some(date.into()));
+    }
+    DateTime::parse_from_rfc3339(&value)
+        .map(|datetime| Some(datetime.into()))
+        .map_err(|_| D::Error::custom("expected a date (YYYY-MM-DD) or an RFC 3339 timestamp"))
+}
+
+fn compression_format<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<RadarCompressionFormat>, D::Error> {
+    let Some(value) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    match value.as_str() {
+        "compressed" => Ok(Some(RadarCompressionFormat::Compressed)),
+        "bytes" => Ok(Some(RadarCompressionFormat::Bytes)),
+        "plain" => Ok(Some(RadarCompressionFormat::Plain)),
+        other => Err(D::Error::unknown_variant(
+            other,
+            &["compressed", "bytes", "plain"],
+        )),
+    }
+}
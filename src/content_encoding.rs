@@ -0,0 +1,97 @@
+//! Transparent decompression of gzip and zlib response bodies.
+//!
+//! HTTP clients differ in whether they decompress responses themselves, and
+//! [`HttpResponse`](crate::HttpResponse) carries no headers. Compressed bodies
+//! are therefore recognized by their magic bytes, which can never start a JSON
+//! document, and inflated with `miniz_oxide`, which also works without `std`.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use miniz_oxide::inflate::{
+    DecompressError, TINFLStatus, decompress_to_vec_with_limit, decompress_to_vec_zlib_with_limit,
+};
+
+use crate::DecodeError;
+
+/// Value of the `Accept-Encoding` header sent when compression is enabled.
+pub(crate) const ACCEPT_ENCODING: &str = "gzip, deflate";
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+// Flags of the gzip member header (RFC 1952)
+const FHCRC: u8 = 0x02;
+const FEXTRA: u8 = 0x04;
+const FNAME: u8 = 0x08;
+const FCOMMENT: u8 = 0x10;
+
+/// Inflate `body` if it is a gzip or zlib stream, otherwise return it as is.
+///
+/// Inflating stops with [`DecodeError::ResponseTooLarge`] once the output
+/// would exceed `limit` bytes.
+pub(crate) fn decode_body(body: Vec<u8>, limit: usize) -> Result<Vec<u8>, DecodeError> {
+    match body.as_slice() {
+        [a, b, ..] if [*a, *b] == GZIP_MAGIC => gunzip(&body, limit),
+        [cmf, flg, ..] if is_zlib_header(*cmf, *flg) => {
+            decompress_to_vec_zlib_with_limit(&body, limit).map_err(|e| inflate_error(e, limit))
+        }
+        _ => Ok(body),
+    }
+}
+
+fn inflate_error(err: DecompressError, limit: usize) -> DecodeError {
+    match err.status {
+        TINFLStatus::HasMoreOutput => DecodeError::ResponseTooLarge { limit },
+        _ => DecodeError::ContentEncoding,
+    }
+}
+
+/// Deflate method, a valid window size and a header checksum divisible by 31.
+///
+/// `8` (0x38) would be a valid first byte as well, but also starts JSON numbers.
+fn is_zlib_header(cmf: u8, flg: u8) -> bool {
+    cmf & 0x0f == 8
+        && cmf >> 4 <= 7
+        && cmf != b'8'
+        && (u16::from(cmf) << 8 | u16::from(flg)) % 31 == 0
+}
+
+/// Inflate the first member of a gzip stream, checking its length trailer.
+fn gunzip(data: &[u8], limit: usize) -> Result<Vec<u8>, DecodeError> {
+    let header = data.get(..10).ok_or(DecodeError::ContentEncoding)?;
+    let flags = header[3];
+    let mut offset = 10;
+
+    if flags & FEXTRA != 0 {
+        let len = data
+            .get(offset..offset + 2)
+            .ok_or(DecodeError::ContentEncoding)?;
+        offset += 2 + usize::from(u16::from_le_bytes([len[0], len[1]]));
+    }
+    for flag in [FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            let end = data
+                .get(offset..)
+                .and_then(|rest| rest.iter().position(|&byte| byte == 0))
+                .ok_or(DecodeError::ContentEncoding)?;
+            offset += end + 1;
+        }
+    }
+    if flags & FHCRC != 0 {
+        offset += 2;
+    }
+
+    let deflated = data.get(offset..).ok_or(DecodeError::ContentEncoding)?;
+    let inflated =
+        decompress_to_vec_with_limit(deflated, limit).map_err(|e| inflate_error(e, limit))?;
+
+    let size = data
+        .len()
+        .checked_sub(4)
+        .and_then(|start| data.get(start..))
+        .ok_or(DecodeError::ContentEncoding)?;
+    if u32::from_le_bytes([size[0], size[1], size[2], size[3]]) != inflated.len() as u32 {
+        return Err(DecodeError::ContentEncoding);
+    }
+    Ok(inflated)
+}
@@ -0,0 +1,391 @@
+//! Coordinate handling shared by all query builders.
+//!
+//! ## Privacy
+//!
+//! Exact device locations sent to the public Bright Sky API end up in its
+//! access logs. Weather data does not need that accuracy: the nearest station is
+//! usually kilometers away and radar pixels are 1 km wide. [`CoordinateFuzzing`]
+//! moves coordinates to a coarse grid before they are sent, either to the center
+//! of the grid cell (`Snap`) or to a fixed pseudo-random point inside it
+//! (`Jitter`). The point only depends on the cell and the seed, so repeated
+//! queries from the same place always send the same coordinates and cannot be
+//! averaged to recover the original location.
+//!
+//! ```rust
+//! use brightsky::{CoordinateFuzzing, Coordinates, CurrentWeatherQueryBuilder, ToBrightSkyUrl};
+//!
+//! let grid = CoordinateFuzzing::Snap { cell_size: 0.1 };
+//!
+//! let home = Coordinates::new(52.5186, 13.3761)?.fuzzed(grid);
+//! assert!((home.lat - 52.55).abs() < 1e-9);
+//! assert!((home.lon - 13.35).abs() < 1e-9);
+//!
+//! // Or let the query builder apply it to any coordinates it is given
+//! let query = CurrentWeatherQueryBuilder::new()
+//!     .with_coordinate_fuzzing(grid)
+//!     .with_lat_lon((52.5186, 13.3761))
+//!     .build()?;
+//! assert_eq!(
+//!     query.to_url_string("https://api.brightsky.dev")?,
+//!     "https://api.brightsky.dev/current_weather?lat=52.55&lon=13.35"
+//! );
+//! # Ok::<(), brightsky::BrightSkyError>(())
+//! ```
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec::Vec};
+
+use crate::{BrightSkyError, QueryError};
+
+/// Mean Earth radius in meters, for distances on a sphere.
+#[cfg(feature = "std")]
+pub(crate) const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// Default number of decimal places used for coordinates in URLs.
+///
+/// Four decimal places correspond to roughly 11 meters, which is far below the
+/// spacing of weather stations and radar pixels, and keeps URLs short so that
+/// nearby requests share HTTP cache entries. Every query builder has a
+/// `with_coordinate_precision` setter to change it.
+///
+/// Builders keep coordinates as they were set and round them once when the URL
+/// is created, after applying [`CoordinateFuzzing`], so the order of the
+/// setters does not matter:
+///
+/// ```rust
+/// use brightsky::{CurrentWeatherQueryBuilder, ToBrightSkyUrl};
+///
+/// let query = CurrentWeatherQueryBuilder::new()
+///     .with_lat_lon((52.520008, 13.404954))
+///     .with_coordinate_precision(2)
+///     .build()?;
+/// assert_eq!(
+///     query.to_url_string("https://api.brightsky.dev")?,
+///     "https://api.brightsky.dev/current_weather?lat=52.52&lon=13.4"
+/// );
+/// # Ok::<(), brightsky::BrightSkyError>(())
+/// ```
+pub const DEFAULT_COORDINATE_PRECISION: u8 = 4;
+
+/// Format a coordinate rounded to `precision` decimal places.
+///
+/// Trailing zeros are removed, but at least one decimal place is always shown
+/// (`52.52`, `13.4`, `52.0`).
+pub(crate) fn format_coordinate(value: f64, precision: u8) -> String {
+    let formatted = format!("{:.*}", precision as usize, value);
+    let (int, frac) = formatted.split_once('.').unwrap_or((&formatted, ""));
+    let frac = frac.trim_end_matches('0');
+    // Avoid "-0.0" for tiny negative values that round to zero
+    let int = if int == "-0" && frac.is_empty() {
+        "0"
+    } else {
+        int
+    };
+
+    if frac.is_empty() {
+        format!("{}.0", int)
+    } else {
+        format!("{}.{}", int, frac)
+    }
+}
+
+/// Format the `lat`/`lon` of a query builder for its URL.
+///
+/// Fuzzing is applied to the coordinates as set, and the result is rounded
+/// once.
+pub(crate) fn url_coordinates(
+    lat: Option<f64>,
+    lon: Option<f64>,
+    precision: u8,
+    fuzzing: Option<CoordinateFuzzing>,
+) -> (Option<String>, Option<String>) {
+    let (lat, lon) = match (lat, lon, fuzzing) {
+        (Some(lat), Some(lon), Some(fuzzing)) => {
+            let fuzzed = Coordinates { lat, lon }.fuzzed(fuzzing);
+            (Some(fuzzed.lat), Some(fuzzed.lon))
+        }
+        _ => (lat, lon),
+    };
+    (
+        lat.map(|lat| format_coordinate(lat, precision)),
+        lon.map(|lon| format_coordinate(lon, precision)),
+    )
+}
+
+/// A validated latitude/longitude pair in decimal degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinates {
+    /// Latitude in decimal degrees (-90.0 to 90.0)
+    pub lat: f64,
+    /// Longitude in decimal degrees (-180.0 to 180.0)
+    pub lon: f64,
+}
+
+impl Coordinates {
+    /// Create coordinates, checking that both values are within range.
+    pub fn new(lat: f64, lon: f64) -> Result<Self, BrightSkyError> {
+        Ok(Self {
+            lat: check_latitude(lat)?,
+            lon: check_longitude(lon)?,
+        })
+    }
+
+    /// Move the coordinates onto a privacy grid, see [`CoordinateFuzzing`].
+    pub fn fuzzed(&self, fuzzing: CoordinateFuzzing) -> Self {
+        let (cell_size, seed) = match fuzzing {
+            CoordinateFuzzing::Snap { cell_size } => (cell_size, None),
+            CoordinateFuzzing::Jitter { cell_size, seed } => (cell_size, Some(seed)),
+        };
+        if !(cell_size.is_finite() && cell_size > 0.0) {
+            return *self;
+        }
+
+        let lat_cell = floor(self.lat / cell_size);
+        let lon_cell = floor(self.lon / cell_size);
+        let (lat_offset, lon_offset) = match seed {
+            None => (0.5, 0.5),
+            Some(seed) => {
+                let hash = mix(mix(seed ^ lat_cell as i64 as u64) ^ lon_cell as i64 as u64);
+                (unit_interval(hash), unit_interval(mix(hash)))
+            }
+        };
+
+        Self {
+            lat: ((lat_cell + lat_offset) * cell_size).clamp(-90.0, 90.0),
+            lon: ((lon_cell + lon_offset) * cell_size).clamp(-180.0, 180.0),
+        }
+    }
+
+    /// Great-circle distance to `other` in meters, on a sphere with the mean
+    /// Earth radius.
+    ///
+    /// ```rust
+    /// use brightsky::Coordinates;
+    ///
+    /// let berlin = Coordinates::new(52.52, 13.405)?;
+    /// let munich = Coordinates::new(48.137, 11.575)?;
+    /// assert!((berlin.distance_to(munich) - 504_000.0).abs() < 1_000.0);
+    /// # Ok::<(), brightsky::BrightSkyError>(())
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn distance_to(&self, other: Coordinates) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let d_lat = lat2 - lat1;
+        let d_lon = (other.lon - self.lon).to_radians();
+        let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS * a.sqrt().min(1.0).asin()
+    }
+}
+
+impl TryFrom<(f64, f64)> for Coordinates {
+    type Error = BrightSkyError;
+
+    /// Same as [`Coordinates::new`].
+    fn try_from((lat, lon): (f64, f64)) -> Result<Self, Self::Error> {
+        Self::new(lat, lon)
+    }
+}
+
+impl From<Coordinates> for (f64, f64) {
+    fn from(coordinates: Coordinates) -> Self {
+        (coordinates.lat, coordinates.lon)
+    }
+}
+
+/// Where a query is made for, independent of the endpoint.
+///
+/// Every builder accepts a `QueryLocation` with `with_location`, so an
+/// application can keep one value per monitored place and use it for all
+/// endpoints. It replaces any location set before.
+///
+/// The radar and alerts endpoints only support `LatLon`; for radar,
+/// `max_dist` becomes the `distance` around the point. Other variants make
+/// their `build()` fail with [`QueryError::UnsupportedLocation`].
+///
+/// ```rust
+/// use brightsky::{CurrentWeatherQueryBuilder, QueryLocation, RadarWeatherQueryBuilder};
+///
+/// let home = QueryLocation::LatLon { lat: 52.52, lon: 13.4, max_dist: Some(10_000) };
+///
+/// let current = CurrentWeatherQueryBuilder::new().with_location(home.clone()).build()?;
+/// assert_eq!(current.max_dist.as_deref(), Some("10000"));
+///
+/// let radar = RadarWeatherQueryBuilder::new().with_location(home).build()?;
+/// assert_eq!(radar.distance, Some(10_000));
+///
+/// let station = QueryLocation::DwdStations(vec!["01766".into()]);
+/// assert!(RadarWeatherQueryBuilder::new().with_location(station).build().is_err());
+/// # Ok::<(), brightsky::BrightSkyError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryLocation {
+    /// Coordinates in decimal degrees, with an optional search radius in meters
+    LatLon {
+        /// Latitude in decimal degrees (-90.0 to 90.0)
+        lat: f64,
+        /// Longitude in decimal degrees (-180.0 to 180.0)
+        lon: f64,
+        /// Maximum distance in meters (`max_dist`, or `distance` for radar)
+        max_dist: Option<u32>,
+    },
+    /// DWD station IDs, ordered from highest to lowest priority
+    DwdStations(Vec<String>),
+    /// WMO station IDs, ordered from highest to lowest priority
+    WmoStations(Vec<String>),
+    /// Bright Sky source IDs, ordered from highest to lowest priority
+    Sources(Vec<i64>),
+}
+
+impl QueryLocation {
+    /// Name of the API parameter, for error messages.
+    pub(crate) fn parameter(&self) -> &'static str {
+        match self {
+            Self::LatLon { .. } => "lat/lon",
+            Self::DwdStations(_) => "dwd_station_id",
+            Self::WmoStations(_) => "wmo_station_id",
+            Self::Sources(_) => "source_id",
+        }
+    }
+}
+
+impl From<Coordinates> for QueryLocation {
+    fn from(Coordinates { lat, lon }: Coordinates) -> Self {
+        Self::LatLon {
+            lat,
+            lon,
+            max_dist: None,
+        }
+    }
+}
+
+/// The coordinates are checked by the `build()` of the builder they are
+/// passed to.
+impl From<(f64, f64)> for QueryLocation {
+    fn from((lat, lon): (f64, f64)) -> Self {
+        Self::LatLon {
+            lat,
+            lon,
+            max_dist: None,
+        }
+    }
+}
+
+fn check_latitude(lat: f64) -> Result<f64, QueryError> {
+    if (-90.0..=90.0).contains(&lat) {
+        Ok(lat)
+    } else {
+        Err(QueryError::InvalidLatitude(lat))
+    }
+}
+
+fn check_longitude(lon: f64) -> Result<f64, QueryError> {
+    if (-180.0..=180.0).contains(&lon) {
+        Ok(lon)
+    } else {
+        Err(QueryError::InvalidLongitude(lon))
+    }
+}
+
+/// Validate the `lat`/`lon` of a query builder.
+///
+/// Either value may be missing, in which case only the other one is checked
+/// and `None` is returned.
+pub(crate) fn check_coordinates(
+    lat: Option<f64>,
+    lon: Option<f64>,
+) -> Result<Option<Coordinates>, BrightSkyError> {
+    match (lat, lon) {
+        (Some(lat), Some(lon)) => Coordinates::new(lat, lon).map(Some),
+        (Some(lat), None) => check_latitude(lat).map(|_| None).map_err(Into::into),
+        (None, Some(lon)) => check_longitude(lon).map(|_| None).map_err(Into::into),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Check that a query names its location in at most one way, and in exactly
+/// one way if `required`.
+///
+/// `parameters` lists the names of the alternative location parameters and
+/// whether the query sets them.
+pub(crate) fn check_location(
+    parameters: &[(&'static str, bool)],
+    required: bool,
+) -> Result<(), QueryError> {
+    let mut set = parameters
+        .iter()
+        .filter(|(_, is_set)| *is_set)
+        .map(|(name, _)| *name);
+    match (set.next(), set.next()) {
+        (Some(first), Some(second)) => {
+            Err(QueryError::ConflictingLocationParameters(first, second))
+        }
+        (None, _) if required => Err(QueryError::MissingLocation),
+        _ => Ok(()),
+    }
+}
+
+/// Whether a list of station or source IDs is set and not empty.
+pub(crate) fn has_ids<T>(ids: Option<&[T]>) -> bool {
+    ids.is_some_and(|ids| !ids.is_empty())
+}
+
+/// Check that DWD and WMO station IDs consist of 5 ASCII letters or digits.
+pub(crate) fn check_station_ids<T: AsRef<str>>(ids: Option<&[T]>) -> Result<(), QueryError> {
+    for id in ids.unwrap_or_default() {
+        let id = id.as_ref();
+        if id.len() != 5 || !id.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            return Err(QueryError::InvalidStationId(id.into()));
+        }
+    }
+    Ok(())
+}
+
+/// How coordinates are coarsened before being sent to the API.
+///
+/// `cell_size` is the edge length of a grid cell in degrees. 0.01° is roughly
+/// 1.1 km in north-south direction and 0.7 km in east-west direction in Germany.
+/// A non-positive or non-finite `cell_size` leaves coordinates unchanged.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CoordinateFuzzing {
+    /// Replace coordinates with the center of their grid cell.
+    Snap {
+        /// Grid cell size in degrees
+        cell_size: f64,
+    },
+    /// Replace coordinates with a point inside their grid cell derived from `seed`.
+    ///
+    /// Use a seed that is stable for one user or installation but unknown to
+    /// others, so that cell centers cannot be told apart from real locations.
+    Jitter {
+        /// Grid cell size in degrees
+        cell_size: f64,
+        /// Seed for the position within each cell
+        seed: u64,
+    },
+}
+
+/// `f64::floor` is not available in `core`.
+fn floor(value: f64) -> f64 {
+    let truncated = value as i64 as f64;
+    if truncated > value {
+        truncated - 1.0
+    } else {
+        truncated
+    }
+}
+
+/// SplitMix64 finalizer, used as a small deterministic hash.
+fn mix(mut value: u64) -> u64 {
+    value = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    value ^ (value >> 31)
+}
+
+/// Map a hash onto `[0, 1)`.
+fn unit_interval(hash: u64) -> f64 {
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
@@ -0,0 +1,104 @@
+use crate::types::CurrentWeather;
+
+/// Length of the observation period a `CurrentWeather` value was compiled over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ObservationInterval {
+    /// Previous 10 minutes (`*_10` fields)
+    Minutes10,
+    /// Previous 30 minutes (`*_30` fields)
+    Minutes30,
+    /// Previous 60 minutes (`*_60` fields)
+    Minutes60,
+}
+
+impl ObservationInterval {
+    /// Length of the interval in minutes.
+    pub fn minutes(&self) -> u32 {
+        match self {
+            Self::Minutes10 => 10,
+            Self::Minutes30 => 30,
+            Self::Minutes60 => 60,
+        }
+    }
+}
+
+/// A value together with the observation interval it was taken from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IntervalValue<T> {
+    /// The observed value
+    pub value: T,
+    /// The interval the value refers to
+    pub interval: ObservationInterval,
+}
+
+impl CurrentWeather {
+    /// Precipitation of the shortest available interval (mm).
+    ///
+    /// The amount is not normalized: 0.5 mm over 10 minutes and 0.5 mm over
+    /// 60 minutes are reported as-is, check `interval` to compare them.
+    pub fn best_precipitation(&self) -> Option<IntervalValue<f64>> {
+        best(
+            self.precipitation_10,
+            self.precipitation_30,
+            self.precipitation_60,
+        )
+    }
+
+    /// Solar irradiation of the shortest available interval (kWh/m² or J/m²).
+    pub fn best_solar(&self) -> Option<IntervalValue<f64>> {
+        best(self.solar_10, self.solar_30, self.solar_60)
+    }
+
+    /// Sunshine duration of the shortest available interval (minutes or seconds).
+    ///
+    /// Sunshine is not reported for 10 minutes, so this starts at 30 minutes.
+    pub fn best_sunshine(&self) -> Option<IntervalValue<f64>> {
+        best(None, self.sunshine_30, self.sunshine_60)
+    }
+
+    /// Mean wind direction of the shortest available interval (degrees).
+    pub fn best_wind_direction(&self) -> Option<IntervalValue<i64>> {
+        best(
+            self.wind_direction_10,
+            self.wind_direction_30,
+            self.wind_direction_60,
+        )
+    }
+
+    /// Mean wind speed of the shortest available interval (km/h or m/s).
+    pub fn best_wind_speed(&self) -> Option<IntervalValue<f64>> {
+        best(self.wind_speed_10, self.wind_speed_30, self.wind_speed_60)
+    }
+
+    /// Direction of the maximum wind gust of the shortest available interval (degrees).
+    pub fn best_wind_gust_direction(&self) -> Option<IntervalValue<i64>> {
+        best(
+            self.wind_gust_direction_10,
+            self.wind_gust_direction_30,
+            self.wind_gust_direction_60,
+        )
+    }
+
+    /// Speed of the maximum wind gust of the shortest available interval (km/h or m/s).
+    pub fn best_wind_gust_speed(&self) -> Option<IntervalValue<f64>> {
+        best(
+            self.wind_gust_speed_10,
+            self.wind_gust_speed_30,
+            self.wind_gust_speed_60,
+        )
+    }
+}
+
+/// Prefer the 10-minute value, then 30 minutes, then 60 minutes.
+fn best<T>(
+    minutes_10: Option<T>,
+    minutes_30: Option<T>,
+    minutes_60: Option<T>,
+) -> Option<IntervalValue<T>> {
+    let with_interval = |interval| move |value| IntervalValue { value, interval };
+
+    minutes_10
+        .map(with_interval(ObservationInterval::Minutes10))
+        .or_else(|| minutes_30.map(with_interval(ObservationInterval::Minutes30)))
+        .or_else(|| minutes_60.map(with_interval(ObservationInterval::Minutes60)))
+}
@@ -0,0 +1,63 @@
+use crate::types::{CurrentWeather, Weather};
+
+impl CurrentWeather {
+    /// Convert into an hourly [`Weather`] record.
+    ///
+    /// Instantaneous values are copied as they are, period values are taken from
+    /// the `*_60` fields, which describe the same previous hour as the hourly
+    /// record fields. The timestamp is kept unchanged, so it usually is not on
+    /// the full hour. Precipitation probabilities are only part of forecasts and
+    /// are always `None`.
+    ///
+    /// Fallback source IDs are carried over for the instantaneous and 60-minute
+    /// parameters, with their keys mapped by
+    /// [`WeatherParameter::hourly`](crate::types::WeatherParameter::hourly).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brightsky::types::CurrentWeather;
+    ///
+    /// let current: CurrentWeather = serde_json::from_str(r#"{
+    ///     "timestamp": "2023-08-07T12:30:00+00:00",
+    ///     "source_id": 1234,
+    ///     "temperature": 22.3,
+    ///     "wind_speed_10": 15.0,
+    ///     "wind_speed_60": 12.0
+    /// }"#).unwrap();
+    ///
+    /// let hourly = current.as_hourly();
+    /// assert_eq!(hourly.temperature, Some(22.3));
+    /// assert_eq!(hourly.wind_speed, Some(12.0));
+    /// ```
+    pub fn as_hourly(&self) -> Weather {
+        let fallback_source_ids = self.fallback_source_ids.as_ref().map(|ids| {
+            ids.iter()
+                .filter_map(|(key, id)| key.hourly().map(|key| (key, *id)))
+                .collect()
+        });
+
+        Weather {
+            timestamp: self.timestamp,
+            source_id: self.source_id,
+            cloud_cover: self.cloud_cover,
+            condition: self.condition.clone(),
+            dew_point: self.dew_point,
+            icon: self.icon.clone(),
+            pressure_msl: self.pressure_msl,
+            relative_humidity: self.relative_humidity,
+            temperature: self.temperature,
+            visibility: self.visibility,
+            fallback_source_ids,
+            precipitation: self.precipitation_60,
+            solar: self.solar_60,
+            sunshine: self.sunshine_60,
+            wind_direction: self.wind_direction_60,
+            wind_speed: self.wind_speed_60,
+            wind_gust_direction: self.wind_gust_direction_60,
+            wind_gust_speed: self.wind_gust_speed_60,
+            precipitation_probability: None,
+            precipitation_probability_6h: None,
+        }
+    }
+}
@@ -38,6 +38,31 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! ## Observation Intervals
+//!
+//! Wind, precipitation, solar and sunshine values come in 10, 30 and 60-minute
+//! variants, not all of which are reported by every station. The `best_*`
+//! accessors such as `CurrentWeather::best_wind_speed()` return the shortest
+//! interval that has a value, together with the [`ObservationInterval`] used:
+//!
+//! ```rust
+//! use brightsky::{ObservationInterval, types::CurrentWeather};
+//!
+//! let weather: CurrentWeather = serde_json::from_str(r#"{
+//!     "timestamp": "2023-08-07T12:30:00+00:00",
+//!     "source_id": 1234,
+//!     "wind_speed_30": 12.5,
+//!     "wind_speed_60": 14.0
+//! }"#).unwrap();
+//!
+//! let wind = weather.best_wind_speed().unwrap();
+//! assert_eq!(wind.value, 12.5);
+//! assert_eq!(wind.interval, ObservationInterval::Minutes30);
+//! ```
 
+mod best;
+mod hourly;
 mod query_builder;
+pub use best::{IntervalValue, ObservationInterval};
 pub use query_builder::*;
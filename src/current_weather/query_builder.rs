@@ -4,7 +4,17 @@ extern crate alloc;
 #[cfg(not(feature = "std"))]
 use alloc::{format, string::String, string::ToString, vec::Vec};
 
-use crate::{BrightSkyError, ToBrightSkyUrl, types::UnitType};
+use core::ops::Deref;
+
+use crate::{
+    BrightSkyError, DEFAULT_COORDINATE_PRECISION, QueryError, ToBrightSkyUrl, TzName,
+    coordinates::{
+        CoordinateFuzzing, QueryLocation, check_coordinates, check_location, check_station_ids,
+        has_ids, url_coordinates,
+    },
+    types::UnitType,
+    tz::check_tz,
+};
 
 #[cfg(feature = "std")]
 use url::Url;
@@ -44,17 +54,21 @@ use url::Url;
 /// #[tokio::main]
 /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
 ///     let query = CurrentWeatherQueryBuilder::new()
-///         .with_dwd_station_id(vec!["01766".to_string()])  // Münster/Osnabrück
+///         .with_dwd_station_id(vec!["01766"])  // Münster/Osnabrück
 ///         .build()?;
 ///     Ok(())
 /// }
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct CurrentWeatherQueryBuilder {
     /// Latitude in decimal degrees (-90.0 to 90.0)
-    pub lat: Option<String>,
+    pub lat: Option<f64>,
     /// Longitude in decimal degrees (-180.0 to 180.0)
-    pub lon: Option<String>,
+    pub lon: Option<f64>,
+    /// Number of decimal places used for `lat` and `lon` in URLs
+    pub coordinate_precision: u8,
+    /// Privacy grid applied to `lat` and `lon`, if any
+    pub coordinate_fuzzing: Option<CoordinateFuzzing>,
     /// Maximum distance from lat/lon in meters (0 to 500,000)
     pub max_dist: Option<String>,
     /// DWD station IDs (5 alphanumeric characters each)
@@ -78,6 +92,8 @@ impl CurrentWeatherQueryBuilder {
         Self {
             lat: None,
             lon: None,
+            coordinate_precision: DEFAULT_COORDINATE_PRECISION,
+            coordinate_fuzzing: None,
             max_dist: None,
             dwd_station_id: None,
             wmo_station_id: None,
@@ -91,7 +107,8 @@ impl CurrentWeatherQueryBuilder {
     ///
     /// # Parameters
     ///
-    /// * `lat_lon` - Tuple of (latitude, longitude) in decimal degrees
+    /// * `lat_lon` - Tuple of (latitude, longitude) in decimal degrees, or
+    ///   [`Coordinates`](crate::Coordinates)
     ///
     /// # Constraints
     ///
@@ -106,24 +123,26 @@ impl CurrentWeatherQueryBuilder {
     /// let query = CurrentWeatherQueryBuilder::new()
     ///     .with_lat_lon((52.52, 13.4));  // Berlin coordinates
     /// ```
-    pub fn with_lat_lon(mut self, lat_lon: (f64, f64)) -> Self {
-        // Format coordinates preserving all decimal precision
-        // For whole numbers, ensure at least one decimal place is shown
-        let lat_str = format!("{}", lat_lon.0);
-        let lon_str = format!("{}", lat_lon.1);
-
-        self.lat = Some(if !lat_str.contains('.') {
-            format!("{}.0", lat_str)
-        } else {
-            lat_str
-        });
-
-        self.lon = Some(if !lon_str.contains('.') {
-            format!("{}.0", lon_str)
-        } else {
-            lon_str
-        });
+    pub fn with_lat_lon(mut self, lat_lon: impl Into<(f64, f64)>) -> Self {
+        let (lat, lon) = lat_lon.into();
+        self.lat = Some(lat);
+        self.lon = Some(lon);
+        self
+    }
 
+    /// Set the number of decimal places used for `lat` and `lon` in the URL,
+    /// see [`DEFAULT_COORDINATE_PRECISION`].
+    pub fn with_coordinate_precision(mut self, decimals: u8) -> Self {
+        self.coordinate_precision = decimals;
+        self
+    }
+
+    /// Move `lat` and `lon` onto a coarse grid before they are sent to the API.
+    ///
+    /// Applies to coordinates set before or after this call. See
+    /// [`CoordinateFuzzing`] for the available strategies.
+    pub fn with_coordinate_fuzzing(mut self, fuzzing: CoordinateFuzzing) -> Self {
+        self.coordinate_fuzzing = Some(fuzzing);
         self
     }
 
@@ -157,7 +176,7 @@ impl CurrentWeatherQueryBuilder {
     ///
     /// # Parameters
     ///
-    /// * `ids` - Vector of DWD station ID strings
+    /// * `ids` - DWD station IDs (`&str` or `String`), e.g. a `Vec` or array
     ///
     /// # Examples
     ///
@@ -166,12 +185,30 @@ impl CurrentWeatherQueryBuilder {
     ///
     /// let query = CurrentWeatherQueryBuilder::new()
     ///     .with_dwd_station_id(vec![
-    ///         "01766".to_string(),  // Münster/Osnabrück (primary)
-    ///         "00420".to_string(),  // Fallback station
+    ///         "01766",  // Münster/Osnabrück (primary)
+    ///         "00420",  // Fallback station
     ///     ]);
     /// ```
-    pub fn with_dwd_station_id(mut self, ids: Vec<String>) -> Self {
-        self.dwd_station_id = Some(ids);
+    pub fn with_dwd_station_id(mut self, ids: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.dwd_station_id = Some(ids.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Add a single DWD station ID after any already set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brightsky::CurrentWeatherQueryBuilder;
+    ///
+    /// let query = CurrentWeatherQueryBuilder::new()
+    ///     .with_dwd_station("01766")
+    ///     .with_dwd_station("00420");  // Fallback station
+    /// ```
+    pub fn with_dwd_station(mut self, id: impl Into<String>) -> Self {
+        self.dwd_station_id
+            .get_or_insert_with(Vec::new)
+            .push(id.into());
         self
     }
 
@@ -182,7 +219,22 @@ impl CurrentWeatherQueryBuilder {
     ///
     /// # Parameters
     ///
-    /// * `ids` - Vector of WMO station ID strings
+    /// * `ids` - WMO station IDs (`&str` or `String`), e.g. a `Vec` or array
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brightsky::CurrentWeatherQueryBuilder;
+    ///
+    /// let query = CurrentWeatherQueryBuilder::new()
+    ///     .with_wmo_station_id(vec!["10315"]);
+    /// ```
+    pub fn with_wmo_station_id(mut self, ids: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.wmo_station_id = Some(ids.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Add a single WMO station ID after any already set.
     ///
     /// # Examples
     ///
@@ -190,10 +242,13 @@ impl CurrentWeatherQueryBuilder {
     /// use brightsky::CurrentWeatherQueryBuilder;
     ///
     /// let query = CurrentWeatherQueryBuilder::new()
-    ///     .with_wmo_station_id(vec!["10315".to_string()]);
+    ///     .with_wmo_station("10315")
+    ///     .with_wmo_station("10147");  // Fallback station
     /// ```
-    pub fn with_wmo_station_id(mut self, ids: Vec<String>) -> Self {
-        self.wmo_station_id = Some(ids);
+    pub fn with_wmo_station(mut self, id: impl Into<String>) -> Self {
+        self.wmo_station_id
+            .get_or_insert_with(Vec::new)
+            .push(id.into());
         self
     }
 
@@ -204,7 +259,7 @@ impl CurrentWeatherQueryBuilder {
     ///
     /// # Parameters
     ///
-    /// * `ids` - Vector of Bright Sky source IDs
+    /// * `ids` - Bright Sky source IDs, e.g. a `Vec` or array
     ///
     /// # Examples
     ///
@@ -214,11 +269,50 @@ impl CurrentWeatherQueryBuilder {
     /// let query = CurrentWeatherQueryBuilder::new()
     ///     .with_source_id(vec![1234, 2345]);
     /// ```
-    pub fn with_source_id(mut self, ids: Vec<i64>) -> Self {
+    pub fn with_source_id(mut self, ids: impl IntoIterator<Item = i64>) -> Self {
         self.source_id = Some(ids.into_iter().map(|id| id.to_string()).collect());
         self
     }
 
+    /// Add a single Bright Sky source ID after any already set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brightsky::CurrentWeatherQueryBuilder;
+    ///
+    /// let query = CurrentWeatherQueryBuilder::new()
+    ///     .with_source(1234);
+    /// ```
+    pub fn with_source(mut self, id: i64) -> Self {
+        self.source_id
+            .get_or_insert_with(Vec::new)
+            .push(id.to_string());
+        self
+    }
+
+    /// Set the location from a [`QueryLocation`], replacing any location set before.
+    ///
+    /// Coordinates are formatted like with [`with_lat_lon`](Self::with_lat_lon),
+    /// so coordinate precision and fuzzing apply.
+    pub fn with_location(mut self, location: impl Into<QueryLocation>) -> Self {
+        self.lat = None;
+        self.lon = None;
+        self.max_dist = None;
+        self.dwd_station_id = None;
+        self.wmo_station_id = None;
+        self.source_id = None;
+        match location.into() {
+            QueryLocation::LatLon { lat, lon, max_dist } => {
+                self.max_dist = max_dist.map(|max_dist| max_dist.to_string());
+                self.with_lat_lon((lat, lon))
+            }
+            QueryLocation::DwdStations(ids) => self.with_dwd_station_id(ids),
+            QueryLocation::WmoStations(ids) => self.with_wmo_station_id(ids),
+            QueryLocation::Sources(ids) => self.with_source_id(ids),
+        }
+    }
+
     /// Set the timezone for timestamp presentation.
     ///
     /// Timestamps in the response will be presented in this timezone.
@@ -226,7 +320,8 @@ impl CurrentWeatherQueryBuilder {
     ///
     /// # Parameters
     ///
-    /// * `tz` - Timezone name from the tz database
+    /// * `tz` - Timezone name from the tz database, or a `chrono_tz::Tz` with the
+    ///   `chrono-tz` feature
     ///
     /// # Examples
     ///
@@ -237,8 +332,8 @@ impl CurrentWeatherQueryBuilder {
     ///     .with_lat_lon((52.52, 13.4))
     ///     .with_tz("Europe/Berlin");
     /// ```
-    pub fn with_tz(mut self, tz: &str) -> Self {
-        self.tz = Some(tz.to_string());
+    pub fn with_tz(mut self, tz: impl TzName) -> Self {
+        self.tz = Some(tz.tz_name().into());
         self
     }
 
@@ -276,6 +371,10 @@ impl CurrentWeatherQueryBuilder {
     /// - `InvalidLatitude`/`InvalidLongitude` - Coordinates out of valid range
     /// - `InvalidMaxDistance` - Distance greater than 500,000 meters
     /// - `ParseFloatError`/`ParseIntError` - Invalid numeric values
+    /// - `InvalidStationId` - A DWD or WMO station ID is not 5 alphanumeric characters
+    /// - `MissingLocation` - Neither coordinates nor station or source IDs were set
+    /// - `ConflictingLocationParameters` - More than one of coordinates, DWD
+    ///   station IDs, WMO station IDs and source IDs were set
     ///
     /// # Examples
     ///
@@ -290,84 +389,106 @@ impl CurrentWeatherQueryBuilder {
     ///     Ok(())
     /// }
     /// ```
-    pub fn build(self) -> Result<Self, BrightSkyError> {
-        if let Some(lat_str) = &self.lat {
-            lat_str
-                .parse::<f64>()
-                .map_err(BrightSkyError::ParseFloatError)
-                .and_then(|lat| -> Result<(), BrightSkyError> {
-                    if !(-90.0..=90.0).contains(&lat) {
-                        Err(BrightSkyError::InvalidLongitude(lat))
-                    } else {
-                        Ok(())
-                    }
-                })?;
-        }
-        if let Some(lon_str) = &self.lon {
-            lon_str
-                .parse::<f64>()
-                .map_err(BrightSkyError::ParseFloatError)
-                .and_then(|lon| -> Result<(), BrightSkyError> {
-                    if !(-180.0..=180.0).contains(&lon) {
-                        Err(BrightSkyError::InvalidLongitude(lon))
-                    } else {
-                        Ok(())
-                    }
-                })?;
-        }
+    pub fn build(self) -> Result<CurrentWeatherQuery, BrightSkyError> {
+        check_coordinates(self.lat, self.lon)?;
+        check_tz(self.tz.as_deref())?;
         if let Some(max_dist_str) = &self.max_dist {
             max_dist_str
                 .parse::<u32>()
-                .map_err(BrightSkyError::ParseIntError)
+                .map_err(QueryError::ParseIntError)
                 .and_then(|max_dist| {
                     if max_dist > 500000 {
-                        Err(BrightSkyError::InvalidMaxDistance(max_dist))
+                        Err(QueryError::InvalidMaxDistance(max_dist))
                     } else {
                         Ok(())
                     }
                 })?;
         }
 
-        Ok(self)
+        check_station_ids(self.dwd_station_id.as_deref())?;
+        check_station_ids(self.wmo_station_id.as_deref())?;
+        check_location(
+            &[
+                ("lat/lon", self.lat.is_some() && self.lon.is_some()),
+                ("dwd_station_id", has_ids(self.dwd_station_id.as_deref())),
+                ("wmo_station_id", has_ids(self.wmo_station_id.as_deref())),
+                ("source_id", has_ids(self.source_id.as_deref())),
+            ],
+            true,
+        )?;
+
+        Ok(CurrentWeatherQuery { builder: self })
+    }
+}
+
+/// A validated `/current_weather` query, returned by [`CurrentWeatherQueryBuilder::build`].
+///
+/// Only validated queries implement [`ToBrightSkyUrl`] and can be sent with a
+/// client. The parameters can be read through [`Deref`] to the builder; use
+/// [`into_builder`](Self::into_builder) to change them and build again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurrentWeatherQuery {
+    builder: CurrentWeatherQueryBuilder,
+}
+
+impl CurrentWeatherQuery {
+    /// Turn the query back into a builder, e.g. to change a parameter.
+    pub fn into_builder(self) -> CurrentWeatherQueryBuilder {
+        self.builder
+    }
+}
+
+impl Deref for CurrentWeatherQuery {
+    type Target = CurrentWeatherQueryBuilder;
+
+    fn deref(&self) -> &Self::Target {
+        &self.builder
     }
 }
 
-impl ToBrightSkyUrl for CurrentWeatherQueryBuilder {
+impl ToBrightSkyUrl for CurrentWeatherQuery {
     #[cfg(feature = "std")]
     fn to_url(self, host: &str) -> Result<Url, BrightSkyError> {
+        let builder = self.builder;
+        let (lat, lon) = url_coordinates(
+            builder.lat,
+            builder.lon,
+            builder.coordinate_precision,
+            builder.coordinate_fuzzing,
+        );
         let base = Url::parse(host)?;
         let mut url = base.join("current_weather")?;
 
         let mut query = url.query_pairs_mut();
 
-        if let Some(lat) = self.lat {
+        if let Some(lat) = lat {
             query.append_pair("lat", &lat);
         }
-        if let Some(lon) = self.lon {
+        if let Some(lon) = lon {
             query.append_pair("lon", &lon);
         }
-        if let Some(max_dist) = self.max_dist {
+        if let Some(max_dist) = builder.max_dist {
             query.append_pair("max_dist", &max_dist);
         }
-        if let Some(dwd_station_id) = self.dwd_station_id {
+        if let Some(dwd_station_id) = builder.dwd_station_id {
             for id in dwd_station_id {
                 query.append_pair("dwd_station_id", &id);
             }
         }
-        if let Some(wmo_station_id) = self.wmo_station_id {
+        if let Some(wmo_station_id) = builder.wmo_station_id {
             for id in wmo_station_id {
                 query.append_pair("wmo_station_id", &id);
             }
         }
-        if let Some(source_id) = self.source_id {
+        if let Some(source_id) = builder.source_id {
             for id in source_id {
                 query.append_pair("source_id", &id);
             }
         }
-        if let Some(tz) = self.tz {
+        if let Some(tz) = builder.tz {
             query.append_pair("tz", &tz);
         }
-        if let Some(units) = self.units {
+        if let Some(units) = builder.units {
             let unit_string = serde_json::to_string(&units).unwrap();
             query.append_pair("units", unit_string.trim_matches('"'));
         }
@@ -376,42 +497,49 @@ impl ToBrightSkyUrl for CurrentWeatherQueryBuilder {
     }
 
     fn to_url_string(self, host: &str) -> Result<String, BrightSkyError> {
+        let builder = self.builder;
         #[cfg(not(feature = "std"))]
         use alloc::vec::Vec;
         #[cfg(feature = "std")]
         use std::vec::Vec;
 
+        let (lat, lon) = url_coordinates(
+            builder.lat,
+            builder.lon,
+            builder.coordinate_precision,
+            builder.coordinate_fuzzing,
+        );
         let mut url = format!("{}/current_weather", host.trim_end_matches('/'));
         let mut params = Vec::new();
 
-        if let Some(lat) = self.lat {
+        if let Some(lat) = lat {
             params.push(format!("lat={}", lat));
         }
-        if let Some(lon) = self.lon {
+        if let Some(lon) = lon {
             params.push(format!("lon={}", lon));
         }
-        if let Some(max_dist) = self.max_dist {
+        if let Some(max_dist) = builder.max_dist {
             params.push(format!("max_dist={}", max_dist));
         }
-        if let Some(dwd_station_id) = self.dwd_station_id {
+        if let Some(dwd_station_id) = builder.dwd_station_id {
             for id in dwd_station_id {
                 params.push(format!("dwd_station_id={}", id));
             }
         }
-        if let Some(wmo_station_id) = self.wmo_station_id {
+        if let Some(wmo_station_id) = builder.wmo_station_id {
             for id in wmo_station_id {
                 params.push(format!("wmo_station_id={}", id));
             }
         }
-        if let Some(source_id) = self.source_id {
+        if let Some(source_id) = builder.source_id {
             for id in source_id {
                 params.push(format!("source_id={}", id));
             }
         }
-        if let Some(tz) = self.tz {
+        if let Some(tz) = builder.tz {
             params.push(format!("tz={}", tz));
         }
-        if let Some(units) = self.units {
+        if let Some(units) = builder.units {
             let unit_string = serde_json::to_string(&units).unwrap();
             params.push(format!("units={}", unit_string.trim_matches('"')));
         }
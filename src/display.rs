@@ -0,0 +1,601 @@
+//! Terminal table rendering for API responses.
+//!
+//! Renders [`WeatherResponse`], [`CurrentWeatherResponse`], [`AlertsResponse`],
+//! [`RadarResponse`] (one row per frame) and lists of [`Source`]s as aligned
+//! plain-text tables, optionally colored with ANSI escape codes by
+//! weather condition or alert severity. Values are printed as returned by the
+//! API, so their units depend on the `units` parameter of the query.
+//!
+//! Only available with the `display` feature.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use brightsky::{display::{Table, TableOptions}, types::WeatherResponse};
+//!
+//! let response: WeatherResponse = serde_json::from_str(r#"{
+//!     "weather": [
+//!         {"timestamp": "2023-08-07T12:00:00+00:00", "source_id": 1, "temperature": 22.3, "condition": "dry"},
+//!         {"timestamp": "2023-08-07T13:00:00+00:00", "source_id": 1, "temperature": 19.8, "condition": "rain"}
+//!     ],
+//!     "sources": []
+//! }"#).unwrap();
+//!
+//! // Plain text, e.g. for logs
+//! println!("{}", response.to_table());
+//!
+//! // Colored rows for a terminal
+//! println!("{}", response.to_table_with(&TableOptions::new().with_color(true)));
+//! ```
+//!
+//! ## One-Line Summaries
+//!
+//! `CurrentWeatherResponse::one_liner()` condenses the current conditions into a
+//! single line for status bars and chat messages:
+//!
+//! ```rust
+//! use brightsky::{Language, types::CurrentWeatherResponse};
+//!
+//! let response: CurrentWeatherResponse = serde_json::from_str(r#"{
+//!     "weather": {
+//!         "timestamp": "2023-08-07T12:30:00+00:00",
+//!         "source_id": 1,
+//!         "temperature": 22.3,
+//!         "relative_humidity": 85,
+//!         "icon": "rain",
+//!         "wind_speed_10": 15.0,
+//!         "wind_direction_10": 225
+//!     },
+//!     "sources": []
+//! }"#).unwrap();
+//!
+//! assert_eq!(response.one_liner(Language::En), "22.3°C 🌧 85% RH, wind 15 km/h SW");
+//! assert_eq!(response.one_liner(Language::De), "22.3°C 🌧 85% rF, Wind 15 km/h SW");
+//! ```
+//!
+//! ## Status Bars
+//!
+//! `CurrentWeatherResponse::waybar()` produces the JSON expected by waybar
+//! `custom` modules (`"return-type": "json"`), with the weather condition as CSS
+//! class. `CurrentWeatherResponse::i3bar()` produces an i3bar protocol block for
+//! i3status-rust, i3blocks and similar.
+//!
+//! ```rust
+//! use brightsky::{Language, types::CurrentWeatherResponse};
+//!
+//! let response: CurrentWeatherResponse = serde_json::from_str(r#"{
+//!     "weather": {"timestamp": "2023-08-07T12:30:00+00:00", "source_id": 1, "temperature": 22.3, "condition": "rain", "icon": "rain"},
+//!     "sources": []
+//! }"#).unwrap();
+//!
+//! let output = response.waybar(Language::En);
+//! assert_eq!(output.text, "🌧 22°C");
+//! assert_eq!(output.class, "rain");
+//!
+//! // Print this from the module's `exec` script
+//! println!("{}", output.to_json());
+//! ```
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+mod statusbar;
+pub use statusbar::{I3barBlock, WaybarOutput};
+
+use crate::{
+    Language,
+    formatting::FormatOptions,
+    types::{
+        AlertSeverity, AlertsResponse, CurrentWeatherResponse, RadarResponse, Source,
+        WeatherCondition, WeatherResponse,
+    },
+    wind::{CompassPoints, WindDirection},
+};
+
+/// Options controlling how tables are rendered.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableOptions {
+    /// Color rows with ANSI escape codes
+    pub color: bool,
+    /// Formatting of measurement values
+    pub format: FormatOptions,
+}
+
+impl TableOptions {
+    /// Create options for plain tables without color.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable or disable ANSI colors.
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Set the formatting of measurement values.
+    pub fn with_format(mut self, format: FormatOptions) -> Self {
+        self.format = format;
+        self
+    }
+}
+
+/// Rendering of a response as an aligned text table.
+pub trait Table {
+    /// Render as a table with the given options.
+    fn to_table_with(&self, options: &TableOptions) -> String;
+
+    /// Render as a plain table without color.
+    fn to_table(&self) -> String {
+        self.to_table_with(&TableOptions::default())
+    }
+}
+
+impl Table for WeatherResponse {
+    fn to_table_with(&self, options: &TableOptions) -> String {
+        let mut table = TextTable::new(&[
+            ("Time", Align::Left),
+            ("Condition", Align::Left),
+            ("Temp", Align::Right),
+            ("Dew point", Align::Right),
+            ("RH %", Align::Right),
+            ("Precip", Align::Right),
+            ("Wind", Align::Right),
+            ("Gust", Align::Right),
+            ("Clouds %", Align::Right),
+        ]);
+
+        let format = &options.format;
+        for record in &self.weather {
+            table.push(
+                vec![
+                    record.timestamp.format("%Y-%m-%d %H:%M").to_string(),
+                    record
+                        .condition
+                        .as_ref()
+                        .map_or("-", condition_label)
+                        .to_string(),
+                    cell(format, "temperature", record.temperature),
+                    cell(format, "dew_point", record.dew_point),
+                    cell(
+                        format,
+                        "relative_humidity",
+                        record.relative_humidity.map(|v| v as f64),
+                    ),
+                    cell(format, "precipitation", record.precipitation),
+                    cell(format, "wind_speed", record.wind_speed),
+                    cell(format, "wind_gust_speed", record.wind_gust_speed),
+                    cell(format, "cloud_cover", record.cloud_cover),
+                ],
+                record.condition.as_ref().and_then(condition_color),
+            );
+        }
+
+        table.render(options)
+    }
+}
+
+impl Table for CurrentWeatherResponse {
+    fn to_table_with(&self, options: &TableOptions) -> String {
+        let weather = &self.weather;
+        let format = &options.format;
+        let mut table = TextTable::new(&[("Parameter", Align::Left), ("Value", Align::Right)]);
+
+        let interval = |field: &str, value: Option<crate::IntervalValue<f64>>| {
+            value.map_or("-".to_string(), |value| {
+                format!(
+                    "{} ({} min)",
+                    format.format_value(field, value.value),
+                    value.interval.minutes()
+                )
+            })
+        };
+
+        let rows = [
+            (
+                "Time",
+                weather.timestamp.format("%Y-%m-%d %H:%M").to_string(),
+            ),
+            (
+                "Condition",
+                weather
+                    .condition
+                    .as_ref()
+                    .map_or("-", condition_label)
+                    .to_string(),
+            ),
+            (
+                "Temperature",
+                cell(format, "temperature", weather.temperature),
+            ),
+            ("Dew point", cell(format, "dew_point", weather.dew_point)),
+            (
+                "Relative humidity %",
+                cell(
+                    format,
+                    "relative_humidity",
+                    weather.relative_humidity.map(|v| v as f64),
+                ),
+            ),
+            (
+                "Pressure",
+                cell(format, "pressure_msl", weather.pressure_msl),
+            ),
+            (
+                "Cloud cover %",
+                cell(format, "cloud_cover", weather.cloud_cover),
+            ),
+            (
+                "Visibility",
+                cell(format, "visibility", weather.visibility.map(|v| v as f64)),
+            ),
+            (
+                "Precipitation",
+                interval("precipitation", weather.best_precipitation()),
+            ),
+            (
+                "Wind speed",
+                interval("wind_speed", weather.best_wind_speed()),
+            ),
+            (
+                "Wind gust speed",
+                interval("wind_gust_speed", weather.best_wind_gust_speed()),
+            ),
+        ];
+
+        let color = weather.condition.as_ref().and_then(condition_color);
+        for (name, value) in rows {
+            table.push(vec![name.to_string(), value], color);
+        }
+
+        table.render(options)
+    }
+}
+
+impl Table for AlertsResponse {
+    fn to_table_with(&self, options: &TableOptions) -> String {
+        let mut table = TextTable::new(&[
+            ("Severity", Align::Left),
+            ("Event", Align::Left),
+            ("Onset", Align::Left),
+            ("Expires", Align::Left),
+            ("Headline", Align::Left),
+        ]);
+
+        for alert in &self.alerts {
+            table.push(
+                vec![
+                    alert
+                        .severity
+                        .map_or("-", |severity| severity.as_str())
+                        .to_string(),
+                    alert.event_en.clone().unwrap_or_else(|| "-".to_string()),
+                    alert.onset.format("%Y-%m-%d %H:%M").to_string(),
+                    alert.expires.map_or("-".to_string(), |expires| {
+                        expires.format("%Y-%m-%d %H:%M").to_string()
+                    }),
+                    alert.headline_en.clone(),
+                ],
+                alert.severity.as_ref().map(severity_color),
+            );
+        }
+
+        let mut output = String::new();
+        if let Some(location) = &self.location {
+            output.push_str(&format!("{} ({})\n", location.name, location.state_short));
+        }
+        output.push_str(&table.render(options));
+        output
+    }
+}
+
+impl Table for [Source] {
+    fn to_table_with(&self, options: &TableOptions) -> String {
+        let mut table = TextTable::new(&[
+            ("ID", Align::Right),
+            ("DWD", Align::Left),
+            ("WMO", Align::Left),
+            ("Station", Align::Left),
+            ("Type", Align::Left),
+            ("Distance km", Align::Right),
+            ("Height m", Align::Right),
+            ("Records", Align::Left),
+        ]);
+
+        for source in self {
+            table.push(
+                vec![
+                    source.id.to_string(),
+                    source
+                        .dwd_station_id
+                        .clone()
+                        .unwrap_or_else(|| "-".to_string()),
+                    source
+                        .wmo_station_id
+                        .clone()
+                        .unwrap_or_else(|| "-".to_string()),
+                    source
+                        .station_name
+                        .clone()
+                        .unwrap_or_else(|| "-".to_string()),
+                    source.observation_type.as_str().to_string(),
+                    source
+                        .distance
+                        .map_or("-".to_string(), |meters| format!("{:.1}", meters / 1000.0)),
+                    format!("{:.0}", source.height),
+                    format!(
+                        "{} - {}",
+                        source.first_record.format("%Y-%m-%d"),
+                        source.last_record.format("%Y-%m-%d")
+                    ),
+                ],
+                None,
+            );
+        }
+
+        table.render(options)
+    }
+}
+
+impl Table for RadarResponse {
+    /// One row per frame with the heaviest and mean precipitation in mm per
+    /// 5 minutes and the share of pixels with any precipitation.
+    fn to_table_with(&self, options: &TableOptions) -> String {
+        let mut table = TextTable::new(&[
+            ("Time", Align::Left),
+            ("Kind", Align::Left),
+            ("Max mm", Align::Right),
+            ("Mean mm", Align::Right),
+            ("Rainy %", Align::Right),
+        ]);
+
+        for frame in self.frames() {
+            let forecast = self
+                .radar
+                .iter()
+                .find(|record| record.timestamp == frame.time)
+                .is_some_and(|record| record.is_forecast());
+            let data = frame.grid.data();
+            let max = data.iter().copied().max().unwrap_or(0);
+            let total: u64 = data.iter().map(|&value| u64::from(value)).sum();
+            let rainy = data.iter().filter(|&&value| value > 0).count();
+            let pixels = data.len().max(1) as f64;
+            table.push(
+                vec![
+                    frame.time.format("%Y-%m-%d %H:%M").to_string(),
+                    if forecast { "forecast" } else { "observed" }.to_string(),
+                    format!("{:.2}", f64::from(max) / 100.0),
+                    format!("{:.3}", total as f64 / pixels / 100.0),
+                    format!("{:.1}", rainy as f64 / pixels * 100.0),
+                ],
+                (max > 0).then_some("36"),
+            );
+        }
+
+        table.render(options)
+    }
+}
+
+impl CurrentWeatherResponse {
+    /// Compact one-line summary, e.g. `Berlin-Tempelhof 22.3°C 🌧 85% RH, wind 15 km/h SW`.
+    ///
+    /// Starts with the name of the station the record was compiled from, if it
+    /// is part of the response. Missing values are left out. Values are labeled
+    /// with DWD units (the API default); wind uses the shortest available
+    /// observation interval.
+    pub fn one_liner(&self, language: Language) -> String {
+        self.summary(language, false, &FormatOptions::summary())
+    }
+
+    /// Like [`CurrentWeatherResponse::one_liner`], with values formatted by
+    /// `options` instead of [`FormatOptions::summary`].
+    pub fn one_liner_with(&self, language: Language, options: &FormatOptions) -> String {
+        self.summary(language, false, options)
+    }
+
+    /// Like [`CurrentWeatherResponse::one_liner`], with the temperature colored
+    /// by ANSI escape codes from cold (cyan) to hot (red).
+    pub fn one_liner_colored(&self, language: Language) -> String {
+        self.summary(language, true, &FormatOptions::summary())
+    }
+
+    fn summary(&self, language: Language, color: bool, options: &FormatOptions) -> String {
+        let weather = &self.weather;
+        let mut parts: Vec<String> = Vec::new();
+
+        let station = self
+            .source_by_id(weather.source_id)
+            .or(self.sources.first());
+        if let Some(station) = station {
+            parts.push(station.station_name.clone());
+        }
+
+        if let Some(temperature) = weather.temperature {
+            let text = options.format_value("temperature", temperature);
+            parts.push(if color {
+                format!("\x1b[{}m{}\x1b[0m", temperature_color(temperature), text)
+            } else {
+                text
+            });
+        }
+        if let Some(icon) = &weather.icon {
+            parts.push(icon.emoji().to_string());
+        }
+
+        let mut line = parts.join(" ");
+
+        let mut details: Vec<String> = Vec::new();
+        if let Some(humidity) = weather.relative_humidity {
+            let label = match language {
+                Language::En => "RH",
+                Language::De => "rF",
+            };
+            let humidity = options.format_number("relative_humidity", humidity as f64, humidity);
+            details.push(format!("{}% {}", humidity, label));
+        }
+        if let Some(speed) = weather.best_wind_speed() {
+            let label = match language {
+                Language::En => "wind",
+                Language::De => "Wind",
+            };
+            let mut wind = format!(
+                "{} {}",
+                label,
+                options.format_value("wind_speed", speed.value)
+            );
+            if let Some(direction) = weather.best_wind_direction() {
+                wind.push(' ');
+                wind.push_str(compass_point(direction.value, language));
+            }
+            details.push(wind);
+        }
+
+        if !details.is_empty() {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&details.join(", "));
+        }
+        line
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Align {
+    Left,
+    Right,
+}
+
+/// A minimal column-aligned text table.
+struct TextTable {
+    columns: Vec<(&'static str, Align)>,
+    rows: Vec<(Vec<String>, Option<&'static str>)>,
+}
+
+impl TextTable {
+    fn new(columns: &[(&'static str, Align)]) -> Self {
+        Self {
+            columns: columns.to_vec(),
+            rows: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, cells: Vec<String>, color: Option<&'static str>) {
+        self.rows.push((cells, color));
+    }
+
+    fn render(&self, options: &TableOptions) -> String {
+        let widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, (header, _))| {
+                self.rows
+                    .iter()
+                    .map(|(cells, _)| cells[i].chars().count())
+                    .chain([header.chars().count()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let headers: Vec<String> = self.columns.iter().map(|(h, _)| h.to_string()).collect();
+        let mut output = self.render_row(&headers, &widths);
+        output.push('\n');
+        output.push_str(&"-".repeat(widths.iter().sum::<usize>() + 2 * (widths.len() - 1)));
+        output.push('\n');
+
+        for (cells, color) in &self.rows {
+            let line = self.render_row(cells, &widths);
+            match color {
+                Some(code) if options.color => {
+                    output.push_str(&format!("\x1b[{}m{}\x1b[0m", code, line));
+                }
+                _ => output.push_str(&line),
+            }
+            output.push('\n');
+        }
+        output
+    }
+
+    fn render_row(&self, cells: &[String], widths: &[usize]) -> String {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .zip(&self.columns)
+            .map(|((cell, &width), (_, align))| match align {
+                Align::Left => format!("{:<width$}", cell),
+                Align::Right => format!("{:>width$}", cell),
+            })
+            .collect();
+        padded.join("  ").trim_end().to_string()
+    }
+}
+
+/// A formatted table cell, `-` if the value is missing.
+fn cell(format: &FormatOptions, field: &str, value: Option<f64>) -> String {
+    value.map_or_else(
+        || "-".to_string(),
+        |value| format.format_value(field, value),
+    )
+}
+
+fn condition_label(condition: &WeatherCondition) -> &'static str {
+    match condition {
+        WeatherCondition::Dry => "dry",
+        WeatherCondition::Fog => "fog",
+        WeatherCondition::Rain => "rain",
+        WeatherCondition::Sleet => "sleet",
+        WeatherCondition::Snow => "snow",
+        WeatherCondition::Hail => "hail",
+        WeatherCondition::Thunderstorm => "thunderstorm",
+        WeatherCondition::Unknown => "unknown",
+    }
+}
+
+/// ANSI SGR parameters used for a weather condition.
+fn condition_color(condition: &WeatherCondition) -> Option<&'static str> {
+    match condition {
+        WeatherCondition::Dry | WeatherCondition::Unknown => None,
+        WeatherCondition::Fog => Some("2"),
+        WeatherCondition::Rain => Some("34"),
+        WeatherCondition::Sleet => Some("36"),
+        WeatherCondition::Snow => Some("1;37"),
+        WeatherCondition::Hail => Some("35"),
+        WeatherCondition::Thunderstorm => Some("1;33"),
+    }
+}
+
+/// ANSI SGR parameters used for an alert severity.
+fn severity_color(severity: &AlertSeverity) -> &'static str {
+    match severity {
+        AlertSeverity::Minor => "33",
+        AlertSeverity::Moderate => "38;5;208",
+        AlertSeverity::Severe => "31",
+        AlertSeverity::Extreme => "1;35",
+    }
+}
+
+/// Eight-point compass direction for a direction in degrees.
+fn compass_point(degrees: i64, language: Language) -> &'static str {
+    WindDirection::from(degrees).to_compass(CompassPoints::Eight, language)
+}
+
+/// ANSI SGR parameters for a temperature in °C.
+fn temperature_color(celsius: f64) -> &'static str {
+    match celsius {
+        t if t < 0.0 => "36",
+        t if t < 10.0 => "34",
+        t if t < 20.0 => "32",
+        t if t < 30.0 => "33",
+        _ => "31",
+    }
+}
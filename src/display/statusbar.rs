@@ -0,0 +1,198 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use serde::Serialize;
+
+use super::{compass_point, condition_label};
+use crate::{
+    Language,
+    types::{CurrentWeatherResponse, WeatherCondition},
+};
+
+/// Output of a waybar `custom` module with `"return-type": "json"`.
+///
+/// `class` is the weather condition (`dry`, `rain`, `thunderstorm`, ...), so the
+/// module can be styled per condition in the waybar CSS, e.g.
+/// `#custom-weather.rain { color: #5e81ac; }`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WaybarOutput {
+    /// Short text shown in the bar, e.g. `🌧 22°C`
+    pub text: String,
+    /// Multi-line details shown on hover
+    pub tooltip: String,
+    /// CSS class of the module
+    pub class: String,
+    /// Icon alias (e.g. `partly-cloudy-day`) for `format-icons`
+    pub alt: String,
+}
+
+impl WaybarOutput {
+    /// Serialize to the single JSON line waybar expects on stdout.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("serializable")
+    }
+}
+
+/// A block of the i3bar protocol, as used by i3status-rust and i3blocks.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct I3barBlock {
+    /// Text shown in the bar
+    pub full_text: String,
+    /// Shorter text used when the bar runs out of space
+    pub short_text: String,
+    /// Text color by weather condition (`#rrggbb`), if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Block name, always `weather`
+    pub name: String,
+}
+
+impl I3barBlock {
+    /// Serialize to a single JSON object.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("serializable")
+    }
+}
+
+impl CurrentWeatherResponse {
+    /// Status bar output for a waybar `custom` module.
+    ///
+    /// Values are labeled with DWD units (the API default).
+    pub fn waybar(&self, language: Language) -> WaybarOutput {
+        let weather = &self.weather;
+
+        WaybarOutput {
+            text: self.short_text(),
+            tooltip: self.tooltip(language),
+            class: weather
+                .condition
+                .as_ref()
+                .map_or("unknown", condition_label)
+                .to_string(),
+            alt: weather
+                .icon
+                .as_ref()
+                .and_then(|icon| serde_json::to_value(icon).ok())
+                .and_then(|value| value.as_str().map(ToString::to_string))
+                .unwrap_or_else(|| "unknown".to_string()),
+        }
+    }
+
+    /// Status bar block for i3bar compatible status lines.
+    pub fn i3bar(&self, language: Language) -> I3barBlock {
+        I3barBlock {
+            full_text: self.one_liner(language),
+            short_text: self.short_text(),
+            color: self
+                .weather
+                .condition
+                .as_ref()
+                .and_then(condition_hex_color)
+                .map(ToString::to_string),
+            name: "weather".to_string(),
+        }
+    }
+
+    fn short_text(&self) -> String {
+        let weather = &self.weather;
+        let mut parts = Vec::new();
+        if let Some(icon) = &weather.icon {
+            parts.push(icon.emoji().to_string());
+        }
+        if let Some(temperature) = weather.temperature {
+            parts.push(format!("{:.0}°C", temperature));
+        }
+        parts.join(" ")
+    }
+
+    fn tooltip(&self, language: Language) -> String {
+        let weather = &self.weather;
+        let labels = match language {
+            Language::En => [
+                "Temperature",
+                "Dew point",
+                "Humidity",
+                "Pressure",
+                "Wind",
+                "Gusts",
+                "Precipitation",
+            ],
+            Language::De => [
+                "Temperatur",
+                "Taupunkt",
+                "Luftfeuchte",
+                "Luftdruck",
+                "Wind",
+                "Böen",
+                "Niederschlag",
+            ],
+        };
+
+        let mut lines = Vec::new();
+        let station = self
+            .source_by_id(weather.source_id)
+            .or(self.sources.first());
+        lines.push(match station {
+            Some(station) => format!(
+                "{} ({})",
+                station.station_name,
+                weather.timestamp.format("%H:%M")
+            ),
+            None => weather.timestamp.format("%H:%M").to_string(),
+        });
+
+        if let Some(value) = weather.temperature {
+            lines.push(format!("{}: {:.1} °C", labels[0], value));
+        }
+        if let Some(value) = weather.dew_point {
+            lines.push(format!("{}: {:.1} °C", labels[1], value));
+        }
+        if let Some(value) = weather.relative_humidity {
+            lines.push(format!("{}: {} %", labels[2], value));
+        }
+        if let Some(value) = weather.pressure_msl {
+            lines.push(format!("{}: {:.1} hPa", labels[3], value));
+        }
+        if let Some(speed) = weather.best_wind_speed() {
+            let mut line = format!("{}: {:.0} km/h", labels[4], speed.value);
+            if let Some(direction) = weather.best_wind_direction() {
+                line.push(' ');
+                line.push_str(compass_point(direction.value, language));
+            }
+            lines.push(line);
+        }
+        if let Some(gust) = weather.best_wind_gust_speed() {
+            lines.push(format!("{}: {:.0} km/h", labels[5], gust.value));
+        }
+        if let Some(precipitation) = weather.best_precipitation() {
+            lines.push(format!(
+                "{}: {:.1} mm / {} min",
+                labels[6],
+                precipitation.value,
+                precipitation.interval.minutes()
+            ));
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Text color for a weather condition in status bars.
+fn condition_hex_color(condition: &WeatherCondition) -> Option<&'static str> {
+    match condition {
+        WeatherCondition::Dry | WeatherCondition::Unknown => None,
+        WeatherCondition::Fog => Some("#a0a0a0"),
+        WeatherCondition::Rain => Some("#5e81ac"),
+        WeatherCondition::Sleet => Some("#88c0d0"),
+        WeatherCondition::Snow => Some("#eceff4"),
+        WeatherCondition::Hail => Some("#b48ead"),
+        WeatherCondition::Thunderstorm => Some("#ebcb8b"),
+    }
+}
@@ -1,18 +1,146 @@
-//! Error types for Bright Sky query building.
+//! Error types for Bright Sky queries, requests and responses.
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::string::{String, ToString};
 
 use core::num::{ParseFloatError, ParseIntError};
 
-/// Error type for Bright Sky query building operations.
+use chrono::NaiveDate;
+
+use crate::{
+    DATA_START, MAX_DATE_RANGE_DAYS, RadarBbox,
+    types::{QueryDate, RadarCompressionFormat},
+};
+
+/// Error type for all Bright Sky operations.
+///
+/// Errors are grouped by the stage that failed, so matching on the outer enum
+/// stays stable when new failure cases are added to one of the stages:
+///
+/// - [`QueryError`]: the query parameters are invalid or no URL could be built
+/// - [`TransportError`]: the request failed or the API answered with an error
+/// - [`DecodeError`]: the response body could not be decoded
+///
+/// Query builders only return [`BrightSkyError::Query`]. The errors of
+/// [`BrightSkyClient`](crate::BrightSkyClient) and the reqwest extension convert
+/// into this type with `?`.
+///
+/// ```rust
+/// use brightsky::{BrightSkyError, QueryError, WeatherQueryBuilder};
+///
+/// let result = WeatherQueryBuilder::new().with_lat_lon((52.52, 13.4)).build();
+/// assert!(matches!(result, Err(BrightSkyError::Query(QueryError::DateNotSet))));
+/// ```
 #[derive(Debug)]
 pub enum BrightSkyError {
+    /// The query could not be built.
+    Query(QueryError),
+    /// The request failed or the API responded with an error.
+    Transport(TransportError),
+    /// The response could not be decoded.
+    Decode(DecodeError),
+}
+
+impl core::fmt::Display for BrightSkyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Query(e) => write!(f, "{}", e),
+            Self::Transport(e) => write!(f, "{}", e),
+            Self::Decode(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BrightSkyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Query(e) => Some(e),
+            Self::Transport(e) => Some(e),
+            Self::Decode(e) => Some(e),
+        }
+    }
+}
+
+impl From<QueryError> for BrightSkyError {
+    fn from(err: QueryError) -> Self {
+        Self::Query(err)
+    }
+}
+
+impl From<TransportError> for BrightSkyError {
+    fn from(err: TransportError) -> Self {
+        Self::Transport(err)
+    }
+}
+
+impl From<DecodeError> for BrightSkyError {
+    fn from(err: DecodeError) -> Self {
+        Self::Decode(err)
+    }
+}
+
+impl From<ParseIntError> for BrightSkyError {
+    fn from(err: ParseIntError) -> Self {
+        Self::Query(err.into())
+    }
+}
+
+impl From<ParseFloatError> for BrightSkyError {
+    fn from(err: ParseFloatError) -> Self {
+        Self::Query(err.into())
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<url::ParseError> for BrightSkyError {
+    fn from(err: url::ParseError) -> Self {
+        Self::Query(err.into())
+    }
+}
+
+/// Error building a query or its URL.
+#[derive(Debug)]
+pub enum QueryError {
     /// Date parameter is required but was not set.
     DateNotSet,
+    /// `last_date` is earlier than `date`.
+    InvalidDateRange {
+        /// First timestamp of the query
+        date: QueryDate,
+        /// Last timestamp of the query
+        last_date: QueryDate,
+    },
+    /// The range starts before the first day with data, see [`DATA_START`].
+    DateBeforeDataStart(NaiveDate),
+    /// The range spans more than [`MAX_DATE_RANGE_DAYS`] days; the value is
+    /// the number of days requested.
+    RangeTooLong(i64),
     /// Latitude value is out of valid range (-90 to 90).
     InvalidLatitude(f64),
     /// Longitude value is out of valid range (-180 to 180).
     InvalidLongitude(f64),
     /// Max distance value is out of valid range (0 to 500000 meters).
     InvalidMaxDistance(u32),
+    /// Radar bounding box is empty or outside the 1200×1100 pixel grid.
+    InvalidBbox(RadarBbox),
+    /// DWD or WMO station ID is not 5 alphanumeric characters.
+    InvalidStationId(String),
+    /// Neither `lat`/`lon` nor a station or source ID was set, but the
+    /// endpoint requires a location. For `/radar`, neither `lat`/`lon` nor
+    /// `bbox` was set with `require_location`.
+    MissingLocation,
+    /// Two location parameters were set that the API does not accept
+    /// together, e.g. `lat`/`lon` and `dwd_station_id`.
+    ConflictingLocationParameters(&'static str, &'static str),
+    /// A [`QueryLocation`](crate::QueryLocation) kind that the endpoint does
+    /// not support, e.g. station IDs for `/radar`.
+    UnsupportedLocation(&'static str),
+    /// Timezone is not in the tz database (only checked with the `chrono-tz` feature).
+    InvalidTimezone(String),
     /// Failed to parse an integer value.
     ParseIntError(ParseIntError),
     /// Failed to parse a float value.
@@ -25,10 +153,21 @@ pub enum BrightSkyError {
     UrlParseError,
 }
 
-impl core::fmt::Display for BrightSkyError {
+impl core::fmt::Display for QueryError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::DateNotSet => write!(f, "Date is required, but not set"),
+            Self::InvalidDateRange { date, last_date } => {
+                write!(f, "Last date {} is before date {}", last_date, date)
+            }
+            Self::DateBeforeDataStart(date) => {
+                write!(f, "No data before {}, got {}", DATA_START, date)
+            }
+            Self::RangeTooLong(days) => write!(
+                f,
+                "Date range must not exceed {} days, got {}",
+                MAX_DATE_RANGE_DAYS, days
+            ),
             Self::InvalidLatitude(lat) => {
                 write!(f, "Latitude must be between -90 and 90, got {}", lat)
             }
@@ -38,6 +177,31 @@ impl core::fmt::Display for BrightSkyError {
             Self::InvalidMaxDistance(dist) => {
                 write!(f, "Max distance must be between 0 and 500000, got {}", dist)
             }
+            Self::InvalidBbox(bbox) => write!(
+                f,
+                "Bounding box must lie within the 1200x1100 radar grid with top <= bottom and left <= right, got {}",
+                bbox
+            ),
+            Self::InvalidStationId(id) => write!(
+                f,
+                "Station ID must be 5 alphanumeric characters, got {:?}",
+                id
+            ),
+            Self::MissingLocation => write!(
+                f,
+                "Location is required: set lat/lon, or a station or source ID (bbox for radar)"
+            ),
+            Self::ConflictingLocationParameters(first, second) => {
+                write!(f, "Cannot combine {} with {}", first, second)
+            }
+            Self::UnsupportedLocation(parameter) => {
+                write!(
+                    f,
+                    "Location by {} is not supported by this endpoint",
+                    parameter
+                )
+            }
+            Self::InvalidTimezone(tz) => write!(f, "Unknown timezone: {}", tz),
             Self::ParseIntError(e) => write!(f, "Parse int failed: {}", e),
             Self::ParseFloatError(e) => write!(f, "Parse float failed: {}", e),
             #[cfg(feature = "std")]
@@ -49,7 +213,7 @@ impl core::fmt::Display for BrightSkyError {
 }
 
 #[cfg(feature = "std")]
-impl std::error::Error for BrightSkyError {
+impl std::error::Error for QueryError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             Self::ParseIntError(e) => Some(e),
@@ -60,21 +224,233 @@ impl std::error::Error for BrightSkyError {
     }
 }
 
-impl From<ParseIntError> for BrightSkyError {
+impl From<ParseIntError> for QueryError {
     fn from(err: ParseIntError) -> Self {
         Self::ParseIntError(err)
     }
 }
 
-impl From<ParseFloatError> for BrightSkyError {
+impl From<ParseFloatError> for QueryError {
     fn from(err: ParseFloatError) -> Self {
         Self::ParseFloatError(err)
     }
 }
 
 #[cfg(feature = "std")]
-impl From<url::ParseError> for BrightSkyError {
+impl From<url::ParseError> for QueryError {
     fn from(err: url::ParseError) -> Self {
         Self::UrlParseError(err)
     }
 }
+
+/// Error performing a request.
+#[derive(Debug)]
+pub enum TransportError {
+    /// The HTTP client failed to perform the request.
+    #[cfg(feature = "std")]
+    Http(Box<dyn std::error::Error + Send + Sync>),
+    /// The HTTP client failed to perform the request (no_std).
+    #[cfg(not(feature = "std"))]
+    Http(String),
+    /// The API responded with a non-`2xx` status code.
+    Api(BrightSkyApiError),
+}
+
+impl TransportError {
+    /// An [`Http`](Self::Http) error from a client error message, with or
+    /// without `std`.
+    pub fn http_message(message: impl core::fmt::Display) -> Self {
+        #[cfg(feature = "std")]
+        return Self::Http(message.to_string().into());
+        #[cfg(not(feature = "std"))]
+        return Self::Http(message.to_string());
+    }
+}
+
+impl core::fmt::Display for TransportError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "Request error: {}", e),
+            Self::Api(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TransportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Http(e) => Some(e.as_ref()),
+            Self::Api(e) => Some(e),
+        }
+    }
+}
+
+impl From<BrightSkyApiError> for TransportError {
+    fn from(err: BrightSkyApiError) -> Self {
+        Self::Api(err)
+    }
+}
+
+/// Error decoding a response body.
+#[derive(Debug)]
+pub enum DecodeError {
+    /// The body is not valid JSON for the expected response type.
+    Json(serde_json::Error),
+    /// Radar data is not valid base64.
+    Base64(base64::DecodeError),
+    /// Compressed radar data is not a valid zlib stream.
+    #[cfg(feature = "std")]
+    Zlib(flate2::DecompressError),
+    /// Compressed radar data ends before the end of the zlib stream.
+    TruncatedZlibStream,
+    /// Compressed radar data does not start with a zlib header.
+    InvalidZlibHeader,
+    /// Radar data is not in the format the query requested.
+    UnexpectedRadarFormat {
+        /// Format requested with `with_compression_format`
+        requested: RadarCompressionFormat,
+        /// Format of the data in the response
+        received: RadarCompressionFormat,
+    },
+    /// Compressed radar data cannot be decompressed without the `std` feature.
+    DecompressionUnavailable,
+    /// Radar data has an odd number of bytes and cannot hold 2-byte values.
+    OddByteLength(usize),
+    /// The response body looks gzip or zlib compressed, but cannot be inflated.
+    ContentEncoding,
+    /// The response body, or the body after inflating it, is larger than the
+    /// limit set with `BrightSkyClient::with_max_response_bytes`.
+    ResponseTooLarge {
+        /// Maximum body size in bytes
+        limit: usize,
+    },
+}
+
+impl core::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Json(e) => write!(f, "JSON error: {}", e),
+            Self::Base64(e) => write!(f, "Base64 decode error: {}", e),
+            #[cfg(feature = "std")]
+            Self::Zlib(e) => write!(f, "Zlib decompression error: {}", e),
+            Self::TruncatedZlibStream => write!(f, "Zlib stream is truncated"),
+            Self::InvalidZlibHeader => {
+                write!(f, "Compressed radar data does not start with a zlib header")
+            }
+            Self::UnexpectedRadarFormat {
+                requested,
+                received,
+            } => write!(
+                f,
+                "Requested {} radar data, but the response contains {} data",
+                requested.as_str(),
+                received.as_str()
+            ),
+            Self::DecompressionUnavailable => {
+                write!(f, "Decompressing radar data requires the std feature")
+            }
+            Self::OddByteLength(len) => {
+                write!(f, "Expected an even number of bytes, got {}", len)
+            }
+            Self::ContentEncoding => write!(f, "Compressed response body is invalid"),
+            Self::ResponseTooLarge { limit } => {
+                write!(
+                    f,
+                    "Response body is larger than the limit of {} bytes",
+                    limit
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Json(e) => Some(e),
+            Self::Base64(e) => Some(e),
+            Self::Zlib(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for DecodeError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+impl From<base64::DecodeError> for DecodeError {
+    fn from(err: base64::DecodeError) -> Self {
+        Self::Base64(err)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<flate2::DecompressError> for DecodeError {
+    fn from(err: flate2::DecompressError) -> Self {
+        Self::Zlib(err)
+    }
+}
+
+/// An error response of the Bright Sky API.
+///
+/// The API answers failed requests with a non-`2xx` status and a JSON body such
+/// as `{"detail": "No sources match your criteria"}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrightSkyApiError {
+    /// HTTP status code
+    pub status: u16,
+    /// Error message from the `detail` field of the body, if present
+    pub detail: Option<String>,
+}
+
+impl BrightSkyApiError {
+    /// Build an error from the status code and raw body of a failed response.
+    ///
+    /// If the body has no string `detail` field, a non-string `detail` (e.g. a
+    /// list of validation errors) is kept as JSON, and a non-JSON body is kept as
+    /// text.
+    pub fn from_response(status: u16, body: &[u8]) -> Self {
+        let detail = match serde_json::from_slice::<serde_json::Value>(body) {
+            Ok(serde_json::Value::Object(mut object)) => match object.remove("detail") {
+                Some(serde_json::Value::String(detail)) => Some(detail),
+                Some(serde_json::Value::Null) | None => None,
+                Some(other) => Some(other.to_string()),
+            },
+            Ok(_) => None,
+            Err(_) => core::str::from_utf8(body)
+                .ok()
+                .map(str::trim)
+                .filter(|text| !text.is_empty())
+                .map(ToString::to_string),
+        };
+        Self { status, detail }
+    }
+
+    /// Whether the API found no data for the query (HTTP 404), e.g. because no
+    /// weather station is close enough to the requested location.
+    pub fn is_not_found(&self) -> bool {
+        self.status == 404
+    }
+
+    /// Whether the API rejected the query parameters (HTTP 400 or 422).
+    pub fn is_bad_request(&self) -> bool {
+        self.status == 400 || self.status == 422
+    }
+}
+
+impl core::fmt::Display for BrightSkyApiError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match &self.detail {
+            Some(detail) => write!(f, "API error (HTTP {}): {}", self.status, detail),
+            None => write!(f, "API error (HTTP {})", self.status),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BrightSkyApiError {}
@@ -0,0 +1,278 @@
+//! Export of hourly weather records as CSV and GeoJSON.
+//!
+//! Every record becomes one row (CSV) or one `Point` feature (GeoJSON) at the
+//! location of its source station.
+//!
+//! There is no Parquet writer, as it would pull in the Arrow libraries. The CSV
+//! output can be converted with tools such as DuckDB or Polars; anonymize it
+//! here first, as the conversion keeps every column.
+//!
+//! ## Anonymized Exports
+//!
+//! Published datasets derived from DWD data often should not reveal which
+//! stations or locations were queried. [`ExportProfile::Anonymized`] drops the
+//! source ID, DWD/WMO station IDs, station name and station height, and rounds
+//! the station coordinates. Weather values and timestamps are kept unchanged.
+//!
+//! ```rust
+//! use brightsky::export::ExportProfile;
+//! use brightsky::types::WeatherResponse;
+//!
+//! let response: WeatherResponse = serde_json::from_str(r#"{
+//!     "weather": [{"timestamp": "2023-08-07T12:00:00+00:00", "source_id": 6007, "temperature": 21.4}],
+//!     "sources": [{
+//!         "id": 6007, "dwd_station_id": "01766", "wmo_station_id": "10315",
+//!         "station_name": "Münster/Osnabrück", "observation_type": "historical",
+//!         "first_record": "2010-01-01T00:00:00+00:00", "last_record": "2023-08-07T12:00:00+00:00",
+//!         "lat": 52.1344, "lon": 7.6969, "height": 47.8, "distance": 0.0
+//!     }]
+//! }"#).unwrap();
+//!
+//! let mut csv = Vec::new();
+//! response.write_csv(&mut csv, ExportProfile::anonymized()).unwrap();
+//! let csv = String::from_utf8(csv).unwrap();
+//!
+//! assert!(csv.lines().nth(1).unwrap().starts_with("2023-08-07T12:00:00+00:00,52.13,7.7,"));
+//! assert!(!csv.contains("01766"));
+//! ```
+//!
+//! Only available with the `std` feature.
+
+use std::io::{self, Write};
+
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+
+use crate::{
+    formatting::FormatOptions,
+    types::{Source, Weather, WeatherParameter, WeatherResponse},
+};
+
+/// Which identifying information an export contains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportProfile {
+    /// Source and station identifiers with exact station coordinates
+    #[default]
+    Full,
+    /// No source or station identifiers, coordinates rounded to
+    /// `coordinate_precision` decimal places
+    Anonymized {
+        /// Decimal places kept for latitude and longitude
+        coordinate_precision: u8,
+    },
+}
+
+impl ExportProfile {
+    /// Anonymized export with coordinates rounded to 2 decimal places (about 1 km).
+    pub fn anonymized() -> Self {
+        Self::Anonymized {
+            coordinate_precision: 2,
+        }
+    }
+
+    fn is_anonymized(self) -> bool {
+        matches!(self, Self::Anonymized { .. })
+    }
+}
+
+/// Station columns that are only part of [`ExportProfile::Full`] exports.
+const IDENTITY_COLUMNS: [&str; 4] = [
+    "source_id",
+    "dwd_station_id",
+    "wmo_station_id",
+    "station_name",
+];
+
+/// Weather value columns, in the order of [`Weather`]'s fields.
+static VALUE_COLUMNS: [WeatherParameter; 17] = WeatherParameter::HOURLY;
+
+impl WeatherResponse {
+    /// Write all records as CSV with a header row.
+    ///
+    /// Missing values are empty fields. Records whose source is not part of
+    /// the response have empty station columns.
+    pub fn write_csv<W: Write>(&self, writer: W, profile: ExportProfile) -> io::Result<()> {
+        self.write_csv_with(writer, profile, &FormatOptions::default())
+    }
+
+    /// Like [`WeatherResponse::write_csv`], with weather values formatted by
+    /// `format`.
+    ///
+    /// Decimal places apply to the weather value columns, the decimal separator
+    /// to all numbers. Fields containing a `,` separator are quoted. With unit
+    /// suffixes enabled, units are added to the header (`temperature [°C]`)
+    /// instead of the values.
+    pub fn write_csv_with<W: Write>(
+        &self,
+        mut writer: W,
+        profile: ExportProfile,
+        format: &FormatOptions,
+    ) -> io::Result<()> {
+        writeln!(writer, "{}", csv_header(profile, format))?;
+        self.write_csv_rows(writer, profile, format)
+    }
+
+    /// Write all records as CSV rows without a header row, e.g. to append to
+    /// an existing file.
+    pub fn write_csv_rows<W: Write>(
+        &self,
+        mut writer: W,
+        profile: ExportProfile,
+        format: &FormatOptions,
+    ) -> io::Result<()> {
+        for record in &self.weather {
+            let fields = record_fields(record, self.source_by_id(record.source_id), profile);
+            let row = fields
+                .iter()
+                .map(|(column, value)| csv_field(column, value, format))
+                .collect::<Vec<_>>()
+                .join(",");
+            writeln!(writer, "{}", row)?;
+        }
+        Ok(())
+    }
+
+    /// All records as a GeoJSON `FeatureCollection` of `Point` features.
+    ///
+    /// Records whose source is not part of the response have a `null` geometry.
+    pub fn to_geojson(&self, profile: ExportProfile) -> Value {
+        let features = self
+            .weather
+            .iter()
+            .map(|record| {
+                let source = self.source_by_id(record.source_id);
+                let geometry = source.map(|source| {
+                    let (lat, lon) = location(source, profile);
+                    json!({"type": "Point", "coordinates": [lon, lat]})
+                });
+                let properties: Map<String, Value> = record_fields(record, source, profile)
+                    .into_iter()
+                    .filter(|(name, _)| *name != "lat" && *name != "lon")
+                    .map(|(name, value)| (name.to_string(), value))
+                    .collect();
+
+                json!({"type": "Feature", "geometry": geometry, "properties": properties})
+            })
+            .collect::<Vec<_>>();
+
+        json!({"type": "FeatureCollection", "features": features})
+    }
+}
+
+fn columns(profile: ExportProfile) -> impl Iterator<Item = &'static str> {
+    let identity: &[&str] = if profile.is_anonymized() {
+        &[]
+    } else {
+        &IDENTITY_COLUMNS
+    };
+    let height: &[&str] = if profile.is_anonymized() {
+        &[]
+    } else {
+        &["height"]
+    };
+
+    core::iter::once("timestamp")
+        .chain(identity.iter().copied())
+        .chain(["lat", "lon"])
+        .chain(height.iter().copied())
+        .chain(VALUE_COLUMNS.iter().map(WeatherParameter::as_str))
+}
+
+fn is_value_column(column: &str) -> bool {
+    VALUE_COLUMNS
+        .iter()
+        .any(|parameter| parameter.as_str() == column)
+}
+
+fn record_fields(
+    record: &Weather,
+    source: Option<&Source>,
+    profile: ExportProfile,
+) -> Vec<(&'static str, Value)> {
+    columns(profile)
+        .map(|column| {
+            let value = match column {
+                "timestamp" => json!(record.timestamp.to_rfc3339()),
+                "source_id" => json!(record.source_id),
+                "dwd_station_id" => json!(source.and_then(|s| s.dwd_station_id.as_deref())),
+                "wmo_station_id" => json!(source.and_then(|s| s.wmo_station_id.as_deref())),
+                "station_name" => json!(source.and_then(|s| s.station_name.as_deref())),
+                "lat" => json!(source.map(|s| location(s, profile).0)),
+                "lon" => json!(source.map(|s| location(s, profile).1)),
+                "height" => json!(source.map(|s| s.height)),
+                "cloud_cover" => json!(record.cloud_cover),
+                "condition" => enum_value(&record.condition),
+                "dew_point" => json!(record.dew_point),
+                "icon" => enum_value(&record.icon),
+                "pressure_msl" => json!(record.pressure_msl),
+                "relative_humidity" => json!(record.relative_humidity),
+                "temperature" => json!(record.temperature),
+                "visibility" => json!(record.visibility),
+                "precipitation" => json!(record.precipitation),
+                "solar" => json!(record.solar),
+                "sunshine" => json!(record.sunshine),
+                "wind_direction" => json!(record.wind_direction),
+                "wind_speed" => json!(record.wind_speed),
+                "wind_gust_direction" => json!(record.wind_gust_direction),
+                "wind_gust_speed" => json!(record.wind_gust_speed),
+                "precipitation_probability" => json!(record.precipitation_probability),
+                "precipitation_probability_6h" => json!(record.precipitation_probability_6h),
+                _ => Value::Null,
+            };
+            (column, value)
+        })
+        .collect()
+}
+
+/// Station coordinates, rounded for anonymized exports.
+fn location(source: &Source, profile: ExportProfile) -> (f64, f64) {
+    match profile {
+        ExportProfile::Full => (source.lat, source.lon),
+        ExportProfile::Anonymized {
+            coordinate_precision,
+        } => {
+            let factor = 10f64.powi(i32::from(coordinate_precision));
+            (
+                (source.lat * factor).round() / factor,
+                (source.lon * factor).round() / factor,
+            )
+        }
+    }
+}
+
+/// API name of a `condition` or `icon` value, e.g. `partly-cloudy-day`.
+fn enum_value<T: Serialize>(value: &Option<T>) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+/// The CSV header row, with units if enabled.
+fn csv_header(profile: ExportProfile, format: &FormatOptions) -> String {
+    columns(profile)
+        .map(|column| match format.unit(column) {
+            Some(unit) if format.unit_suffix && is_value_column(column) => {
+                format!("{} [{}]", column, unit)
+            }
+            _ => column.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn csv_field(column: &str, value: &Value, format: &FormatOptions) -> String {
+    let text = match value {
+        Value::Null => return String::new(),
+        Value::String(text) => text.clone(),
+        Value::Number(number) if is_value_column(column) => {
+            format.format_number(column, number.as_f64().unwrap_or_default(), number)
+        }
+        Value::Number(number) => number
+            .to_string()
+            .replace('.', &format.decimal_separator.to_string()),
+        other => other.to_string(),
+    };
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
@@ -6,11 +6,13 @@
 //! ## Feature Flags
 //!
 //! - `reqwest`: Enables `BrightSkyReqwestExt` trait for `reqwest::Client`
+//! - `reqwless`: Enables `reqwless_get` for `reqwless::client::HttpClient`
 //!
 //! ## Embedded Usage
 //!
-//! For embedded systems using reqwless or similar clients, use `to_url_string()`
-//! directly and deserialize with `serde_json::from_slice()`. See the crate-level
+//! With the `reqwless` feature, `reqwless_get` fetches and deserializes a query
+//! without `std`. For other embedded clients, use `to_url_string()` directly and
+//! deserialize with `serde_json::from_slice()`. See the crate-level
 //! documentation for examples.
 
 #[cfg(feature = "reqwest")]
@@ -18,3 +20,9 @@ mod reqwest_ext;
 
 #[cfg(feature = "reqwest")]
 pub use reqwest_ext::*;
+
+#[cfg(feature = "reqwless")]
+mod reqwless_ext;
+
+#[cfg(feature = "reqwless")]
+pub use reqwless_ext::*;
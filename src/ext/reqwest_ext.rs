@@ -21,8 +21,18 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! ## WebAssembly
+//!
+//! On `wasm32` targets reqwest uses the browser's `fetch` API, whose futures are
+//! not `Send`. The futures returned by this trait are therefore only `Send` on
+//! other targets, see [`MaybeSend`]. Enable the `wasm` feature when building for
+//! the browser.
 
-use crate::{BRIGHT_SKY_API, BrightSkyError, ToBrightSkyUrl};
+use crate::{
+    BRIGHT_SKY_API, BrightSkyApiError, BrightSkyError, DecodeError, ToBrightSkyUrl, TransportError,
+    client::{FromHttpOptions, HttpClient, HttpOptions, HttpResponse},
+};
 use serde::de::DeserializeOwned;
 
 /// Error type for reqwest-based Bright Sky requests.
@@ -32,8 +42,12 @@ pub enum ReqwestBrightSkyError {
     Query(BrightSkyError),
     /// HTTP request failed
     Request(reqwest::Error),
+    /// The API responded with an error status and body
+    Api(BrightSkyApiError),
     /// JSON deserialization failed
-    Json(reqwest::Error),
+    Json(serde_json::Error),
+    /// The response body could not be decompressed
+    Decode(DecodeError),
 }
 
 impl std::fmt::Display for ReqwestBrightSkyError {
@@ -41,7 +55,9 @@ impl std::fmt::Display for ReqwestBrightSkyError {
         match self {
             Self::Query(e) => write!(f, "Query error: {}", e),
             Self::Request(e) => write!(f, "Request error: {}", e),
+            Self::Api(e) => write!(f, "{}", e),
             Self::Json(e) => write!(f, "JSON error: {}", e),
+            Self::Decode(e) => write!(f, "{}", e),
         }
     }
 }
@@ -51,7 +67,9 @@ impl std::error::Error for ReqwestBrightSkyError {
         match self {
             Self::Query(e) => Some(e),
             Self::Request(e) => Some(e),
+            Self::Api(e) => Some(e),
             Self::Json(e) => Some(e),
+            Self::Decode(e) => Some(e),
         }
     }
 }
@@ -62,6 +80,20 @@ impl From<BrightSkyError> for ReqwestBrightSkyError {
     }
 }
 
+impl From<ReqwestBrightSkyError> for BrightSkyError {
+    fn from(err: ReqwestBrightSkyError) -> Self {
+        match err {
+            ReqwestBrightSkyError::Query(e) => e,
+            ReqwestBrightSkyError::Request(e) => TransportError::Http(Box::new(e)).into(),
+            ReqwestBrightSkyError::Api(e) => TransportError::Api(e).into(),
+            ReqwestBrightSkyError::Json(e) => DecodeError::Json(e).into(),
+            ReqwestBrightSkyError::Decode(e) => e.into(),
+        }
+    }
+}
+
+pub use crate::MaybeSend;
+
 /// Extension trait that adds Bright Sky API methods to `reqwest::Client`.
 ///
 /// Import this trait to use `.get_brightsky()` on any reqwest Client.
@@ -89,8 +121,10 @@ pub trait BrightSkyReqwestExt {
     ///
     /// This method:
     /// 1. Converts the query to a URL using the default Bright Sky API endpoint
-    /// 2. Sends a GET request
-    /// 3. Deserializes the JSON response into the specified type
+    /// 2. Sends a GET request, accepting gzip and deflate bodies with the
+    ///    `compression` feature
+    /// 3. Inflates a compressed body and deserializes the JSON response into
+    ///    the specified type
     ///
     /// # Type Parameters
     ///
@@ -102,13 +136,15 @@ pub trait BrightSkyReqwestExt {
     /// Returns `ReqwestBrightSkyError` if:
     /// - Query building/URL generation fails
     /// - The HTTP request fails
+    /// - The API responds with an error status (`ReqwestBrightSkyError::Api`)
+    /// - A compressed body cannot be inflated (`ReqwestBrightSkyError::Decode`)
     /// - JSON deserialization fails
     fn get_brightsky<Q, R>(
         &self,
         query: Q,
-    ) -> impl std::future::Future<Output = Result<R, ReqwestBrightSkyError>> + Send
+    ) -> impl std::future::Future<Output = Result<R, ReqwestBrightSkyError>> + MaybeSend
     where
-        Q: ToBrightSkyUrl + Send,
+        Q: ToBrightSkyUrl + MaybeSend,
         R: DeserializeOwned;
 
     /// Fetch data from the Bright Sky API using a custom host URL.
@@ -119,16 +155,16 @@ pub trait BrightSkyReqwestExt {
         &self,
         query: Q,
         host: &str,
-    ) -> impl std::future::Future<Output = Result<R, ReqwestBrightSkyError>> + Send
+    ) -> impl std::future::Future<Output = Result<R, ReqwestBrightSkyError>> + MaybeSend
     where
-        Q: ToBrightSkyUrl + Send,
+        Q: ToBrightSkyUrl + MaybeSend,
         R: DeserializeOwned;
 }
 
 impl BrightSkyReqwestExt for reqwest::Client {
     async fn get_brightsky<Q, R>(&self, query: Q) -> Result<R, ReqwestBrightSkyError>
     where
-        Q: ToBrightSkyUrl + Send,
+        Q: ToBrightSkyUrl + MaybeSend,
         R: DeserializeOwned,
     {
         self.get_brightsky_with_host(query, BRIGHT_SKY_API).await
@@ -140,17 +176,135 @@ impl BrightSkyReqwestExt for reqwest::Client {
         host: &str,
     ) -> Result<R, ReqwestBrightSkyError>
     where
-        Q: ToBrightSkyUrl + Send,
+        Q: ToBrightSkyUrl + MaybeSend,
         R: DeserializeOwned,
     {
         let url = query.to_url(host)?;
 
-        let response = self
-            .get(url)
+        #[allow(unused_mut)]
+        let mut request = self.get(url);
+        #[cfg(feature = "compression")]
+        {
+            request = request.header("Accept-Encoding", crate::content_encoding::ACCEPT_ENCODING);
+        }
+        let response = request
             .send()
             .await
             .map_err(ReqwestBrightSkyError::Request)?;
 
-        response.json().await.map_err(ReqwestBrightSkyError::Json)
+        let status = response.status();
+        if !status.is_success() {
+            let body = response
+                .bytes()
+                .await
+                .map_err(ReqwestBrightSkyError::Request)?;
+            return Err(ReqwestBrightSkyError::Api(
+                BrightSkyApiError::from_response(status.as_u16(), &body),
+            ));
+        }
+
+        let body = response
+            .bytes()
+            .await
+            .map_err(ReqwestBrightSkyError::Request)?;
+        #[cfg(feature = "compression")]
+        let body = crate::content_encoding::decode_body(body.to_vec(), usize::MAX)
+            .map_err(ReqwestBrightSkyError::Decode)?;
+        serde_json::from_slice(&body).map_err(ReqwestBrightSkyError::Json)
+    }
+}
+
+impl HttpClient for reqwest::Client {
+    type Error = reqwest::Error;
+
+    async fn get(&self, url: &str) -> Result<HttpResponse, Self::Error> {
+        self.get_with_headers(url, &[]).await
+    }
+
+    async fn get_with_headers(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+    ) -> Result<HttpResponse, Self::Error> {
+        let response = send(self, url, headers).await?;
+        let status = response.status().as_u16();
+        let body = response.bytes().await?.to_vec();
+
+        Ok(HttpResponse { status, body })
+    }
+
+    /// Reject bodies by their `Content-Length` before reading them, and stop
+    /// reading once a body exceeds `limit`.
+    ///
+    /// On `wasm32`, the browser reads the whole body first.
+    async fn get_with_limit(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+        limit: usize,
+    ) -> Result<Option<HttpResponse>, Self::Error> {
+        #[allow(unused_mut)]
+        let mut response = send(self, url, headers).await?;
+        if response
+            .content_length()
+            .is_some_and(|len| len > limit as u64)
+        {
+            return Ok(None);
+        }
+        let status = response.status().as_u16();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let body = {
+            let mut body = Vec::new();
+            while let Some(chunk) = response.chunk().await? {
+                if chunk.len() > limit - body.len() {
+                    return Ok(None);
+                }
+                body.extend_from_slice(&chunk);
+            }
+            body
+        };
+        #[cfg(target_arch = "wasm32")]
+        let body = response.bytes().await?.to_vec();
+
+        Ok((body.len() <= limit).then_some(HttpResponse { status, body }))
+    }
+}
+
+async fn send(
+    client: &reqwest::Client,
+    url: &str,
+    headers: &[(String, String)],
+) -> Result<reqwest::Response, reqwest::Error> {
+    let mut request = client.get(url);
+    for (name, value) in headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    request.send().await
+}
+
+impl FromHttpOptions for reqwest::Client {
+    /// Build a client with the given options.
+    ///
+    /// On `wasm32`, the browser controls timeouts and the `User-Agent`, so the
+    /// options are ignored.
+    fn from_http_options(options: &HttpOptions) -> Result<Self, Self::Error> {
+        #[allow(unused_mut)]
+        let mut builder = reqwest::Client::builder();
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if let Some(timeout) = options.timeout {
+                builder = builder.timeout(timeout);
+            }
+            if let Some(timeout) = options.connect_timeout {
+                builder = builder.connect_timeout(timeout);
+            }
+            if let Some(user_agent) = &options.user_agent {
+                builder = builder.user_agent(user_agent);
+            }
+        }
+        #[cfg(target_arch = "wasm32")]
+        let _ = options;
+        builder.build()
     }
 }
@@ -0,0 +1,179 @@
+//! Request helper for reqwless, the async HTTP client used on embassy targets.
+//!
+//! [`reqwless_get`] builds the URL with [`ToBrightSkyUrl::to_url_string`], sends
+//! a GET request and deserializes the body with `serde_json::from_slice`. It
+//! works without `std`; the response is read into a caller-provided buffer.
+//!
+//! Requests identify themselves with [`DEFAULT_USER_AGENT`] and, with the
+//! `compression` feature, accept gzip and deflate bodies, which are inflated
+//! before deserializing. [`reqwless_get_with`] takes [`ReqwlessOptions`] to
+//! change either.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use brightsky::{CurrentWeatherQueryBuilder, ext::reqwless_get, types::CurrentWeatherResponse};
+//! use embassy_net::{dns::DnsSocket, tcp::client::{TcpClient, TcpClientState}};
+//! use reqwless::client::{HttpClient, TlsConfig, TlsVerify};
+//!
+//! let state = TcpClientState::<1, 4096, 4096>::new();
+//! let tcp = TcpClient::new(stack, &state);
+//! let dns = DnsSocket::new(stack);
+//! let tls = TlsConfig::new(seed, &mut tls_rx, &mut tls_tx, TlsVerify::None);
+//! let mut client = HttpClient::new_with_tls(&tcp, &dns, tls);
+//!
+//! let query = CurrentWeatherQueryBuilder::new()
+//!     .with_lat_lon((52.52, 13.4))
+//!     .build()?;
+//!
+//! let mut rx_buf = [0; 8192];
+//! let response: CurrentWeatherResponse =
+//!     reqwless_get(&mut client, query, brightsky::BRIGHT_SKY_API, &mut rx_buf).await?;
+//! ```
+//!
+//! The API is only served over HTTPS. This crate depends on reqwless without
+//! TLS; enable reqwless' `embedded-tls` feature in your own manifest and create
+//! the client with `HttpClient::new_with_tls`.
+
+use crate::{
+    BrightSkyApiError, BrightSkyError, DEFAULT_USER_AGENT, DecodeError, ToBrightSkyUrl,
+    TransportError,
+};
+use embedded_nal_async::{Dns, TcpConnect};
+use reqwless::{
+    client::HttpClient,
+    request::{Method, RequestBuilder},
+};
+use serde::de::DeserializeOwned;
+
+/// Request settings of [`reqwless_get_with`].
+#[derive(Debug, Clone, Copy)]
+pub struct ReqwlessOptions<'a> {
+    user_agent: &'a str,
+    #[cfg(feature = "compression")]
+    compression: bool,
+}
+
+impl Default for ReqwlessOptions<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> ReqwlessOptions<'a> {
+    /// [`DEFAULT_USER_AGENT`] and, with the `compression` feature, compressed
+    /// responses.
+    pub fn new() -> Self {
+        Self {
+            user_agent: DEFAULT_USER_AGENT,
+            #[cfg(feature = "compression")]
+            compression: true,
+        }
+    }
+
+    /// Set the `User-Agent` header, see [`BrightSkyClient::with_user_agent`].
+    ///
+    /// [`BrightSkyClient::with_user_agent`]: crate::BrightSkyClient::with_user_agent
+    pub fn with_user_agent(mut self, user_agent: &'a str) -> Self {
+        self.user_agent = user_agent;
+        self
+    }
+
+    /// Request compressed responses and inflate them (enabled by default).
+    ///
+    /// A compressed body is inflated into a new buffer next to `rx_buf`;
+    /// disable compression if both do not fit into memory.
+    ///
+    /// Only available with the `compression` feature.
+    #[cfg(feature = "compression")]
+    pub fn with_compression(mut self, enabled: bool) -> Self {
+        self.compression = enabled;
+        self
+    }
+}
+
+/// Fetch a query from `host` with a reqwless client and deserialize the body.
+///
+/// `rx_buf` holds the response headers and body, so it must be large enough
+/// for the whole response; a current weather response for one station is
+/// around 2 KiB, a day of hourly weather records around 12 KiB.
+///
+/// # Errors
+///
+/// - The query cannot be turned into a URL
+/// - The request fails or the response does not fit into `rx_buf`
+///   ([`TransportError::Http`])
+/// - The API responds with a non-`2xx` status ([`TransportError::Api`])
+/// - A compressed body cannot be inflated ([`DecodeError::ContentEncoding`])
+/// - The body is not valid JSON for `R` ([`DecodeError::Json`])
+pub async fn reqwless_get<Q, R, T, D>(
+    client: &mut HttpClient<'_, T, D>,
+    query: Q,
+    host: &str,
+    rx_buf: &mut [u8],
+) -> Result<R, BrightSkyError>
+where
+    Q: ToBrightSkyUrl,
+    R: DeserializeOwned,
+    T: TcpConnect,
+    D: Dns,
+{
+    reqwless_get_with(client, query, host, rx_buf, ReqwlessOptions::new()).await
+}
+
+/// [`reqwless_get`] with a different `User-Agent` or without compression.
+pub async fn reqwless_get_with<Q, R, T, D>(
+    client: &mut HttpClient<'_, T, D>,
+    query: Q,
+    host: &str,
+    rx_buf: &mut [u8],
+    options: ReqwlessOptions<'_>,
+) -> Result<R, BrightSkyError>
+where
+    Q: ToBrightSkyUrl,
+    R: DeserializeOwned,
+    T: TcpConnect,
+    D: Dns,
+{
+    let url = query.to_url_string(host)?;
+    let headers = [
+        ("User-Agent", options.user_agent),
+        #[cfg(feature = "compression")]
+        ("Accept-Encoding", crate::content_encoding::ACCEPT_ENCODING),
+    ];
+    // Only the `User-Agent` without compression
+    #[cfg(feature = "compression")]
+    let sent = if options.compression {
+        headers.len()
+    } else {
+        1
+    };
+    #[cfg(not(feature = "compression"))]
+    let sent = headers.len();
+    let mut request = client
+        .request(Method::GET, &url)
+        .await
+        .map_err(transport_error)?
+        .headers(&headers[..sent]);
+    let response = request.send(rx_buf).await.map_err(transport_error)?;
+    let status = response.status;
+    let body = response
+        .body()
+        .read_to_end()
+        .await
+        .map_err(transport_error)?;
+
+    if !status.is_successful() {
+        return Err(TransportError::Api(BrightSkyApiError::from_response(status.0, body)).into());
+    }
+    #[cfg(feature = "compression")]
+    if options.compression {
+        let body = crate::content_encoding::decode_body(body.to_vec(), usize::MAX)?;
+        return serde_json::from_slice(&body).map_err(|e| DecodeError::Json(e).into());
+    }
+    serde_json::from_slice(body).map_err(|e| DecodeError::Json(e).into())
+}
+
+fn transport_error(err: reqwless::Error) -> BrightSkyError {
+    TransportError::http_message(err).into()
+}
@@ -0,0 +1,170 @@
+//! Day-by-day forecasts with a single call.
+//!
+//! [`BrightSkyClient::forecast`] builds the `/weather` query for the next few
+//! days starting today, fetches it and groups the hourly records by calendar
+//! day:
+//!
+//! ```rust,no_run
+//! # #[cfg(feature = "reqwest")]
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! use brightsky::BrightSkyClient;
+//!
+//! let client = BrightSkyClient::new(reqwest::Client::new());
+//!
+//! let forecast = client
+//!     .forecast((52.52, 13.4))
+//!     .with_tz("Europe/Berlin")
+//!     .days(3)
+//!     .await?;
+//!
+//! for day in &forecast.days {
+//!     println!("{}: {} hourly records", day.date, day.hours.len());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! "Today" is the current UTC date unless set with
+//! [`ForecastRequest::with_start_date`]. Dates are passed to the API without a
+//! time of day, so days start at midnight in the requested timezone.
+//!
+//! Only available with the `std` feature, which provides the system clock.
+
+use chrono::{Days, NaiveDate, Utc};
+use serde::Serialize;
+
+use crate::{
+    BrightSkyClient, ClientError, HttpClient, TzName, WeatherQueryBuilder,
+    provenance::Provenance,
+    types::{Source, UnitType, Weather, WeatherResponse},
+};
+
+/// Hourly records grouped by calendar day.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Forecast {
+    /// One entry per requested day, in chronological order
+    pub days: Vec<ForecastDay>,
+    /// Information about weather stations used as data sources
+    pub sources: Vec<Source>,
+    /// Where the records came from, `None` if grouped with
+    /// [`Forecast::from_response`]
+    pub provenance: Option<Provenance>,
+}
+
+/// The hourly records of one calendar day.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ForecastDay {
+    /// Calendar day in the UTC offset of the record timestamps
+    pub date: NaiveDate,
+    /// Hourly records of this day in chronological order
+    pub hours: Vec<Weather>,
+}
+
+impl Forecast {
+    /// Group the records of a `/weather` response into `days` days from `start`.
+    ///
+    /// Every requested day gets an entry, with no hours if the response had no
+    /// records for it. Records outside of the range, like the one at midnight
+    /// after the last day, are dropped.
+    pub fn from_response(response: WeatherResponse, start: NaiveDate, days: u32) -> Self {
+        let mut grouped: Vec<ForecastDay> = (0..u64::from(days))
+            .filter_map(|offset| start.checked_add_days(Days::new(offset)))
+            .map(|date| ForecastDay {
+                date,
+                hours: Vec::new(),
+            })
+            .collect();
+
+        for record in response.weather {
+            let date = record.timestamp.date_naive();
+            if let Some(day) = grouped.iter_mut().find(|day| day.date == date) {
+                day.hours.push(record);
+            }
+        }
+        for day in &mut grouped {
+            day.hours.sort_by_key(|record| record.timestamp);
+        }
+
+        Self {
+            days: grouped,
+            sources: response.sources,
+            provenance: None,
+        }
+    }
+
+    /// All hourly records across all days.
+    pub fn hours(&self) -> impl Iterator<Item = &Weather> {
+        self.days.iter().flat_map(|day| day.hours.iter())
+    }
+}
+
+/// Builder for a forecast request, created by [`BrightSkyClient::forecast`].
+///
+/// Finish with [`ForecastRequest::days`], which sends the request.
+#[derive(Debug)]
+pub struct ForecastRequest<'a, C> {
+    client: &'a BrightSkyClient<C>,
+    location: (f64, f64),
+    start: Option<NaiveDate>,
+    tz: Option<String>,
+    units: Option<UnitType>,
+}
+
+impl<'a, C: HttpClient> ForecastRequest<'a, C> {
+    /// Set the timezone for day boundaries and timestamps (tz database format).
+    pub fn with_tz(mut self, tz: impl TzName) -> Self {
+        self.tz = Some(tz.tz_name().into());
+        self
+    }
+
+    /// Set the physical units system.
+    pub fn with_units(mut self, units: UnitType) -> Self {
+        self.units = Some(units);
+        self
+    }
+
+    /// Start at the given day instead of today.
+    pub fn with_start_date(mut self, date: NaiveDate) -> Self {
+        self.start = Some(date);
+        self
+    }
+
+    /// Fetch the forecast for `days` days, starting with today.
+    pub async fn days(self, days: u32) -> Result<Forecast, ClientError<C::Error>> {
+        let start = self.start.unwrap_or_else(|| Utc::now().date_naive());
+        let end = start
+            .checked_add_days(Days::new(u64::from(days)))
+            .unwrap_or(NaiveDate::MAX);
+
+        let mut query = WeatherQueryBuilder::new()
+            .with_date(start)
+            .with_last_date(end)
+            .with_lat_lon(self.location);
+        if let Some(tz) = &self.tz {
+            query = query.with_tz(tz);
+        }
+        if let Some(units) = self.units {
+            query = query.with_units(units);
+        }
+
+        let (response, provenance): (WeatherResponse, _) =
+            self.client.get_with_provenance(query.build()?).await?;
+        let mut forecast = Forecast::from_response(response, start, days);
+        forecast.provenance = Some(provenance);
+        Ok(forecast)
+    }
+}
+
+impl<C: HttpClient> BrightSkyClient<C> {
+    /// Start a forecast request for a location, see the [`forecast`](crate::forecast)
+    /// module.
+    pub fn forecast(&self, location: impl Into<(f64, f64)>) -> ForecastRequest<'_, C> {
+        ForecastRequest {
+            client: self,
+            location: location.into(),
+            start: None,
+            tz: None,
+            units: None,
+        }
+    }
+}
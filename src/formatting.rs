@@ -0,0 +1,184 @@
+//! Number formatting for human-readable outputs.
+//!
+//! [`FormatOptions`] controls how measurement values are printed by the tables
+//! and one-line summaries of the `display` module and by the CSV export:
+//! decimal places per field, unit suffixes and the decimal separator.
+//!
+//! Fields are identified by their API names (`temperature`, `wind_speed_10`,
+//! ...). Fields without configured decimal places are printed as returned by
+//! the API.
+//!
+//! ```rust
+//! use brightsky::formatting::FormatOptions;
+//!
+//! // German style: comma decimals, one decimal place for temperatures
+//! let options = FormatOptions::new()
+//!     .with_decimal_separator(',')
+//!     .with_decimal_places("temperature", 1)
+//!     .with_unit_suffix(true);
+//!
+//! assert_eq!(options.format_value("temperature", 22.0), "22,0°C");
+//! assert_eq!(options.format_value("wind_speed", 15.5), "15,5 km/h");
+//! assert_eq!(options.format_value("relative_humidity", 85.0), "85%");
+//! ```
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+};
+
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+use core::fmt::Display;
+
+use crate::types::{UnitType, WeatherParameter};
+
+/// How measurement values are formatted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Decimal places per field, by API field name
+    pub decimal_places: BTreeMap<String, u8>,
+    /// Decimal places of fields not listed in `decimal_places`, `None` to print
+    /// values as returned by the API
+    pub default_decimal_places: Option<u8>,
+    /// Append units such as `°C` or `km/h` to values
+    pub unit_suffix: bool,
+    /// Character separating the integer and fractional part
+    pub decimal_separator: char,
+    /// Unit system the values were requested in, used for unit suffixes
+    pub units: UnitType,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            decimal_places: BTreeMap::new(),
+            default_decimal_places: None,
+            unit_suffix: false,
+            decimal_separator: '.',
+            units: UnitType::Dwd,
+        }
+    }
+}
+
+impl FormatOptions {
+    /// Print values as returned by the API, without units.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The options used by `CurrentWeatherResponse::one_liner`: one decimal place
+    /// for temperatures, none for wind speeds, with unit suffixes.
+    pub fn summary() -> Self {
+        Self::new()
+            .with_decimal_places("temperature", 1)
+            .with_decimal_places("wind_speed", 0)
+            .with_unit_suffix(true)
+    }
+
+    /// Set the decimal places of a field.
+    ///
+    /// For current weather fields with an interval suffix (`wind_speed_10`), the
+    /// places of the field without suffix (`wind_speed`) apply unless the
+    /// suffixed field is configured itself.
+    pub fn with_decimal_places(mut self, field: &str, places: u8) -> Self {
+        self.decimal_places.insert(field.to_string(), places);
+        self
+    }
+
+    /// Set the decimal places of all fields without their own setting.
+    pub fn with_default_decimal_places(mut self, places: Option<u8>) -> Self {
+        self.default_decimal_places = places;
+        self
+    }
+
+    /// Enable or disable unit suffixes.
+    pub fn with_unit_suffix(mut self, enabled: bool) -> Self {
+        self.unit_suffix = enabled;
+        self
+    }
+
+    /// Set the decimal separator, e.g. `','` for German.
+    pub fn with_decimal_separator(mut self, separator: char) -> Self {
+        self.decimal_separator = separator;
+        self
+    }
+
+    /// Set the unit system the values were requested in.
+    pub fn with_units(mut self, units: UnitType) -> Self {
+        self.units = units;
+        self
+    }
+
+    /// Decimal places used for a field, if any.
+    pub fn places(&self, field: &str) -> Option<u8> {
+        self.decimal_places
+            .get(field)
+            .or_else(|| self.decimal_places.get(base_field(field)))
+            .copied()
+            .or(self.default_decimal_places)
+    }
+
+    /// Format a value of a field, with unit suffix if enabled.
+    pub fn format_value(&self, field: &str, value: f64) -> String {
+        let number = self.format_number(field, value, value);
+        match self.unit(field) {
+            Some(unit) if self.unit_suffix => with_unit(number, unit),
+            _ => number,
+        }
+    }
+
+    /// Format a value without unit, using `plain` if no decimal places are set.
+    pub(crate) fn format_number(&self, field: &str, value: f64, plain: impl Display) -> String {
+        let number = match self.places(field) {
+            Some(places) => format!("{:.*}", usize::from(places), value),
+            None => plain.to_string(),
+        };
+        number.replace('.', &self.decimal_separator.to_string())
+    }
+
+    /// Unit of a field in the configured unit system, if it has one.
+    pub fn unit(&self, field: &str) -> Option<&'static str> {
+        let si = self.units == UnitType::Si;
+        let unit = match WeatherParameter::from_api(base_field(field)) {
+            WeatherParameter::Temperature | WeatherParameter::DewPoint if si => "K",
+            WeatherParameter::Temperature | WeatherParameter::DewPoint => "°C",
+            WeatherParameter::PressureMsl if si => "Pa",
+            WeatherParameter::PressureMsl => "hPa",
+            WeatherParameter::WindSpeed | WeatherParameter::WindGustSpeed if si => "m/s",
+            WeatherParameter::WindSpeed | WeatherParameter::WindGustSpeed => "km/h",
+            WeatherParameter::WindDirection | WeatherParameter::WindGustDirection => "°",
+            WeatherParameter::Solar if si => "J/m²",
+            WeatherParameter::Solar => "kWh/m²",
+            WeatherParameter::Sunshine if si => "s",
+            WeatherParameter::Sunshine => "min",
+            WeatherParameter::Precipitation => "mm",
+            WeatherParameter::Visibility => "m",
+            WeatherParameter::CloudCover
+            | WeatherParameter::RelativeHumidity
+            | WeatherParameter::PrecipitationProbability => "%",
+            _ => return None,
+        };
+        Some(unit)
+    }
+}
+
+/// Field name without the interval suffix of current weather and probability fields.
+fn base_field(field: &str) -> &str {
+    ["_10", "_30", "_60", "_6h"]
+        .iter()
+        .find_map(|suffix| field.strip_suffix(suffix))
+        .unwrap_or(field)
+}
+
+/// Append a unit, without a space for `°C`, `°` and `%`.
+fn with_unit(number: String, unit: &str) -> String {
+    if unit.starts_with('°') || unit == "%" {
+        format!("{}{}", number, unit)
+    } else {
+        format!("{} {}", number, unit)
+    }
+}
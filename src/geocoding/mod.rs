@@ -0,0 +1,93 @@
+//! Place names to coordinates.
+//!
+//! The Bright Sky API only accepts coordinates and station IDs. A
+//! [`Geocoder`] resolves place names and postal codes such as `"München"` or
+//! `"80331"` to [`Place`]s, whose coordinates can be passed to any query
+//! builder:
+//!
+//! ```rust,no_run
+//! # #[cfg(all(feature = "nominatim", feature = "reqwest"))]
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! use brightsky::geocoding::{Geocoder, Nominatim};
+//! use brightsky::{BrightSkyClient, CurrentWeatherQueryBuilder};
+//! use brightsky::types::CurrentWeatherResponse;
+//!
+//! let geocoder = Nominatim::new(reqwest::Client::new()).with_user_agent("my-bot/1.0");
+//! let place = geocoder.locate("München").await?.ok_or("unknown place")?;
+//!
+//! let query = CurrentWeatherQueryBuilder::new().with_lat_lon(&place).build()?;
+//! let client = BrightSkyClient::new(reqwest::Client::new());
+//! let response: CurrentWeatherResponse = client.get(query).await?;
+//! println!("{}: {:?}°C", place.name, response.weather.temperature);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Implement [`Geocoder`] to use another service or a local gazetteer. With
+//! the `nominatim` feature, [`Nominatim`] queries the OpenStreetMap Nominatim
+//! API through any [`HttpClient`](crate::HttpClient).
+//!
+//! Only available with the `geocoding` feature.
+
+use core::future::Future;
+
+use crate::Coordinates;
+
+#[cfg(feature = "nominatim")]
+mod nominatim;
+#[cfg(feature = "nominatim")]
+pub use nominatim::{NOMINATIM_API, Nominatim, NominatimError};
+
+/// A place found by a [`Geocoder`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Place {
+    /// Name of the place, e.g. `München`
+    pub name: String,
+    /// Full name with its administrative areas, e.g.
+    /// `München, Bayern, Deutschland`
+    pub display_name: String,
+    /// Coordinates of the place
+    pub coordinates: Coordinates,
+    /// Postal code, if known
+    pub postal_code: Option<String>,
+    /// Federal state, if known
+    pub state: Option<String>,
+}
+
+impl From<&Place> for Coordinates {
+    fn from(place: &Place) -> Self {
+        place.coordinates
+    }
+}
+
+impl From<Place> for Coordinates {
+    fn from(place: Place) -> Self {
+        place.coordinates
+    }
+}
+
+impl From<&Place> for (f64, f64) {
+    fn from(place: &Place) -> Self {
+        place.coordinates.into()
+    }
+}
+
+impl From<Place> for (f64, f64) {
+    fn from(place: Place) -> Self {
+        place.coordinates.into()
+    }
+}
+
+/// A service resolving place names to coordinates.
+pub trait Geocoder {
+    /// Error of the lookup
+    type Error;
+
+    /// Places matching `query`, best match first.
+    fn search(&self, query: &str) -> impl Future<Output = Result<Vec<Place>, Self::Error>>;
+
+    /// The best match for `query`, if any.
+    fn locate(&self, query: &str) -> impl Future<Output = Result<Option<Place>, Self::Error>> {
+        async move { Ok(self.search(query).await?.into_iter().next()) }
+    }
+}
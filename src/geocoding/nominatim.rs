@@ -0,0 +1,177 @@
+use serde::Deserialize;
+use url::Url;
+
+use super::{Geocoder, Place};
+use crate::{Coordinates, DEFAULT_USER_AGENT, HttpClient};
+
+/// The public OpenStreetMap Nominatim instance.
+pub const NOMINATIM_API: &str = "https://nominatim.openstreetmap.org";
+
+/// [`Geocoder`] using the [Nominatim](https://nominatim.org/) search API.
+///
+/// Searches are restricted to Germany, the coverage of Bright Sky, unless set
+/// otherwise with [`with_country_codes`](Self::with_country_codes). The
+/// public instance allows one request per second and requires a `User-Agent`
+/// identifying the application, see its
+/// [usage policy](https://operations.osmfoundation.org/policies/nominatim/).
+#[derive(Debug, Clone)]
+pub struct Nominatim<C> {
+    http: C,
+    host: String,
+    user_agent: String,
+    country_codes: String,
+    limit: u8,
+}
+
+impl<C: HttpClient> Nominatim<C> {
+    /// Create a geocoder for the public instance.
+    pub fn new(http: C) -> Self {
+        Self {
+            http,
+            host: NOMINATIM_API.to_string(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            country_codes: "de".to_string(),
+            limit: 5,
+        }
+    }
+
+    /// Use another Nominatim instance, e.g. a self-hosted one.
+    pub fn with_host(mut self, host: impl Into<String>) -> Self {
+        self.host = host.into();
+        self
+    }
+
+    /// Set the `User-Agent` identifying the application.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Restrict searches to comma-separated ISO 3166-1 alpha-2 country codes,
+    /// `de` by default. An empty string searches worldwide.
+    pub fn with_country_codes(mut self, country_codes: impl Into<String>) -> Self {
+        self.country_codes = country_codes.into();
+        self
+    }
+
+    /// Set the maximum number of places returned by a search (1 to 40).
+    pub fn with_limit(mut self, limit: u8) -> Self {
+        self.limit = limit.clamp(1, 40);
+        self
+    }
+
+    fn search_url(&self, query: &str) -> Result<Url, url::ParseError> {
+        let mut url = Url::parse(&format!("{}/search", self.host.trim_end_matches('/')))?;
+        {
+            let mut params = url.query_pairs_mut();
+            params
+                .append_pair("q", query)
+                .append_pair("format", "jsonv2")
+                .append_pair("addressdetails", "1")
+                .append_pair("limit", &self.limit.to_string());
+            if !self.country_codes.is_empty() {
+                params.append_pair("countrycodes", &self.country_codes);
+            }
+        }
+        Ok(url)
+    }
+}
+
+impl<C: HttpClient> Geocoder for Nominatim<C> {
+    type Error = NominatimError<C::Error>;
+
+    async fn search(&self, query: &str) -> Result<Vec<Place>, Self::Error> {
+        let url = self.search_url(query).map_err(NominatimError::Url)?;
+        let headers = [("User-Agent".to_string(), self.user_agent.clone())];
+        let response = self
+            .http
+            .get_with_headers(url.as_str(), &headers)
+            .await
+            .map_err(NominatimError::Http)?;
+        if !response.is_success() {
+            return Err(NominatimError::Status(response.status));
+        }
+
+        let results: Vec<SearchResult> =
+            serde_json::from_slice(&response.body).map_err(NominatimError::Json)?;
+        Ok(results
+            .into_iter()
+            .filter_map(SearchResult::place)
+            .collect())
+    }
+}
+
+/// Error returned by [`Nominatim`].
+#[derive(Debug)]
+pub enum NominatimError<E> {
+    /// The host is not a valid URL
+    Url(url::ParseError),
+    /// The HTTP client failed to perform the request
+    Http(E),
+    /// Nominatim responded with a non-`2xx` status code
+    Status(u16),
+    /// The response body could not be deserialized
+    Json(serde_json::Error),
+}
+
+impl<E: core::fmt::Display> core::fmt::Display for NominatimError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Url(e) => write!(f, "Invalid Nominatim URL: {}", e),
+            Self::Http(e) => write!(f, "Request error: {}", e),
+            Self::Status(status) => write!(f, "Nominatim responded with status {}", status),
+            Self::Json(e) => write!(f, "JSON error: {}", e),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for NominatimError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Url(e) => Some(e),
+            Self::Http(e) => Some(e),
+            Self::Status(_) => None,
+            Self::Json(e) => Some(e),
+        }
+    }
+}
+
+/// An entry of the `jsonv2` search response.
+#[derive(Deserialize)]
+struct SearchResult {
+    lat: String,
+    lon: String,
+    #[serde(default)]
+    name: String,
+    display_name: String,
+    #[serde(default)]
+    address: Address,
+}
+
+#[derive(Default, Deserialize)]
+struct Address {
+    postcode: Option<String>,
+    state: Option<String>,
+}
+
+impl SearchResult {
+    fn place(self) -> Option<Place> {
+        let coordinates = Coordinates::new(self.lat.parse().ok()?, self.lon.parse().ok()?).ok()?;
+        let name = if self.name.is_empty() {
+            self.display_name
+                .split(',')
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        } else {
+            self.name
+        };
+        Some(Place {
+            name,
+            display_name: self.display_name,
+            coordinates,
+            postal_code: self.address.postcode,
+            state: self.address.state,
+        })
+    }
+}
@@ -0,0 +1,118 @@
+//! GeoJSON for radar coverage and alerts.
+//!
+//! The helpers produce [RFC 7946](https://www.rfc-editor.org/rfc/rfc7946)
+//! objects as [`serde_json::Value`], ready to be handed to Leaflet, Mapbox or
+//! OpenLayers:
+//!
+//! - [`Geometry::to_geojson`]: the radar bounding box as a `Polygon` geometry
+//! - [`RadarResponse::to_geojson`]: a `Feature` of the radar coverage
+//! - [`AlertsResponse::to_geojson`]: a `FeatureCollection` with one feature per alert
+//!
+//! Bright Sky returns the radar bounding box as a single list of positions.
+//! GeoJSON polygons are lists of closed rings, so the positions are wrapped
+//! into one ring, closing it if necessary.
+//!
+//! ```rust
+//! use brightsky::types::RadarResponse;
+//!
+//! let response: RadarResponse = serde_json::from_str(r#"{
+//!     "radar": [],
+//!     "geometry": {"type": "Polygon", "coordinates": [[6.5, 52.7], [6.6, 52.7], [6.6, 52.6], [6.5, 52.6]]},
+//!     "bbox": [400, 300, 403, 305]
+//! }"#).unwrap();
+//!
+//! let feature = response.to_geojson();
+//! assert_eq!(feature["type"], "Feature");
+//! assert_eq!(feature["geometry"]["coordinates"][0][4], serde_json::json!([6.5, 52.7]));
+//! assert_eq!(feature["properties"]["bbox"], serde_json::json!([400, 300, 403, 305]));
+//! ```
+//!
+//! Only available with the `geojson` feature.
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::ToString, vec::Vec};
+
+use serde_json::{Map, Value, json};
+
+use crate::{
+    Coordinates,
+    types::{AlertsResponse, Geometry, RadarResponse},
+};
+
+impl Geometry {
+    /// The geometry as GeoJSON `Polygon` with a single closed ring.
+    pub fn to_geojson(&self) -> Value {
+        let mut ring = self.coordinates.clone();
+        if ring.len() > 1 && ring.first() != ring.last() {
+            ring.push(ring[0].clone());
+        }
+        json!({"type": "Polygon", "coordinates": [ring]})
+    }
+}
+
+impl RadarResponse {
+    /// The area covered by the response as GeoJSON `Feature`.
+    ///
+    /// The geometry is `null` if the response has none. Properties are the
+    /// pixel `bbox`, the `latlon_position` (if any), the number of `frames`
+    /// and the timestamps of the first and last frame.
+    pub fn to_geojson(&self) -> Value {
+        let timestamps = self.timestamps();
+        let mut properties = Map::new();
+        properties.insert("bbox".to_string(), json!(self.bbox));
+        if let Some(position) = &self.latlon_position {
+            properties.insert(
+                "latlon_position".to_string(),
+                json!({"x": position.x, "y": position.y}),
+            );
+        }
+        properties.insert("frames".to_string(), json!(timestamps.len()));
+        properties.insert(
+            "first_timestamp".to_string(),
+            json!(timestamps.first().map(|t| t.to_rfc3339())),
+        );
+        properties.insert(
+            "last_timestamp".to_string(),
+            json!(timestamps.last().map(|t| t.to_rfc3339())),
+        );
+
+        json!({
+            "type": "Feature",
+            "geometry": self.geometry.as_ref().map(Geometry::to_geojson),
+            "properties": properties,
+        })
+    }
+}
+
+impl AlertsResponse {
+    /// The alerts as GeoJSON `FeatureCollection`.
+    ///
+    /// Alerts refer to warn cells, whose shapes are not part of the response.
+    /// Features are placed at `location`, usually the coordinates of the query,
+    /// or have a `null` geometry without one. Properties are the alert fields
+    /// plus the `warn_cell_id`, `name` and `state_short` of the response
+    /// location.
+    pub fn to_geojson(&self, location: Option<Coordinates>) -> Value {
+        let geometry =
+            location.map(|point| json!({"type": "Point", "coordinates": [point.lon, point.lat]}));
+
+        let features: Vec<Value> = self
+            .alerts
+            .iter()
+            .map(|alert| {
+                let mut properties = match serde_json::to_value(alert) {
+                    Ok(Value::Object(fields)) => fields,
+                    _ => Map::new(),
+                };
+                if let Some(cell) = &self.location {
+                    properties.insert("warn_cell_id".to_string(), json!(cell.warn_cell_id));
+                    properties.insert("name".to_string(), json!(cell.name));
+                    properties.insert("state_short".to_string(), json!(cell.state_short));
+                }
+                json!({"type": "Feature", "geometry": geometry, "properties": properties})
+            })
+            .collect();
+
+        json!({"type": "FeatureCollection", "features": features})
+    }
+}
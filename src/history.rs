@@ -0,0 +1,225 @@
+//! Weather records for long date ranges.
+//!
+//! The `/weather` endpoint limits the span between `date` and `last_date` of a
+//! single request. [`BrightSkyClient::get_history`] splits an arbitrary range
+//! into chunks of [`DEFAULT_HISTORY_CHUNK_DAYS`] days, requests them
+//! concurrently and merges the results into one [`WeatherResponse`]:
+//!
+//! ```rust,no_run
+//! # #[cfg(feature = "reqwest")]
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! use brightsky::BrightSkyClient;
+//! use chrono::NaiveDate;
+//!
+//! let client = BrightSkyClient::new(reqwest::Client::new());
+//!
+//! let start = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+//! let end = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+//! let year = client
+//!     .get_history((52.52, 13.4), start..end)
+//!     .with_concurrency(2)
+//!     .fetch()
+//!     .await?;
+//!
+//! println!("{} hourly records", year.weather.len());
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+use core::ops::Range;
+
+use chrono::{Days, NaiveDate};
+
+use crate::{
+    BrightSkyClient, BrightSkyError, ClientError, HttpClient, MAX_DATE_RANGE_DAYS, TzName,
+    WeatherQuery, WeatherQueryBuilder,
+    client::try_join_bounded,
+    types::{UnitType, WeatherResponse},
+};
+
+#[cfg(feature = "std")]
+use crate::provenance::Provenance;
+
+/// Days covered by each request of [`BrightSkyClient::get_history`].
+pub const DEFAULT_HISTORY_CHUNK_DAYS: u32 = 30;
+
+/// Requests [`BrightSkyClient::get_history`] has in flight at the same time.
+pub const DEFAULT_HISTORY_CONCURRENCY: usize = 4;
+
+/// Builder for a history request, created by [`BrightSkyClient::get_history`].
+///
+/// Finish with [`HistoryRequest::fetch`], which sends the requests.
+#[derive(Debug)]
+pub struct HistoryRequest<'a, C> {
+    client: &'a BrightSkyClient<C>,
+    location: (f64, f64),
+    range: Range<NaiveDate>,
+    chunk_days: u32,
+    concurrency: usize,
+    tz: Option<String>,
+    units: Option<UnitType>,
+}
+
+impl<'a, C: HttpClient> HistoryRequest<'a, C> {
+    /// Set the timezone for day boundaries and timestamps (tz database format).
+    pub fn with_tz(mut self, tz: impl TzName) -> Self {
+        self.tz = Some(tz.tz_name().into());
+        self
+    }
+
+    /// Set the physical units system.
+    pub fn with_units(mut self, units: UnitType) -> Self {
+        self.units = Some(units);
+        self
+    }
+
+    /// Set the number of days per request (at least 1, at most
+    /// [`MAX_DATE_RANGE_DAYS`]).
+    pub fn with_chunk_days(mut self, days: u32) -> Self {
+        self.chunk_days = days.clamp(1, MAX_DATE_RANGE_DAYS as u32);
+        self
+    }
+
+    /// Set the number of requests in flight at the same time (at least 1).
+    pub fn with_concurrency(mut self, requests: usize) -> Self {
+        self.concurrency = requests.max(1);
+        self
+    }
+
+    /// The `date`/`last_date` pairs of the individual requests.
+    ///
+    /// Chunks are half-open like the range itself: each ends where the next
+    /// one starts, and the last one ends at the end of the range.
+    pub fn chunks(&self) -> Vec<Range<NaiveDate>> {
+        let mut chunks = Vec::new();
+        let mut start = self.range.start;
+        while start < self.range.end {
+            let end = start
+                .checked_add_days(Days::new(u64::from(self.chunk_days)))
+                .map_or(self.range.end, |end| end.min(self.range.end));
+            chunks.push(start..end);
+            start = end;
+        }
+        chunks
+    }
+
+    /// Fetch all chunks and merge them into one response.
+    ///
+    /// Records are sorted by timestamp, records with the same timestamp and
+    /// source ID are only kept once, and every source is listed once. Fails
+    /// with the first error of any chunk.
+    pub async fn fetch(self) -> Result<WeatherResponse, ClientError<C::Error>> {
+        let requests = self
+            .queries()?
+            .into_iter()
+            .map(|query| self.client.get::<_, WeatherResponse>(query))
+            .collect();
+        let responses = try_join_bounded(requests, self.concurrency).await?;
+
+        Ok(merge(responses))
+    }
+
+    /// Like [`fetch`](Self::fetch), also returning where the records came from.
+    ///
+    /// The provenance lists the URLs of all chunks in chronological order. An
+    /// empty range sends no requests and has none.
+    ///
+    /// Only available with the `std` feature.
+    #[cfg(feature = "std")]
+    pub async fn fetch_with_provenance(
+        self,
+    ) -> Result<(WeatherResponse, Provenance), ClientError<C::Error>> {
+        let requests = self
+            .queries()?
+            .into_iter()
+            .map(|query| self.client.get_with_provenance::<_, WeatherResponse>(query))
+            .collect();
+        let (responses, provenances): (Vec<_>, Vec<_>) =
+            try_join_bounded(requests, self.concurrency)
+                .await?
+                .into_iter()
+                .unzip();
+
+        let provenance = provenances
+            .into_iter()
+            .reduce(Provenance::combine)
+            .unwrap_or_else(|| Provenance {
+                host: self.client.host().to_string(),
+                requests: Vec::new(),
+                fetched_at: chrono::Utc::now(),
+                cached: false,
+                units: self.units.unwrap_or(UnitType::Dwd),
+            });
+        Ok((merge(responses), provenance))
+    }
+
+    fn queries(&self) -> Result<Vec<WeatherQuery>, BrightSkyError> {
+        self.chunks()
+            .into_iter()
+            .map(|chunk| {
+                let mut query = WeatherQueryBuilder::new()
+                    .with_date(chunk.start)
+                    .with_last_date(chunk.end)
+                    .with_lat_lon(self.location);
+                if let Some(tz) = &self.tz {
+                    query = query.with_tz(tz);
+                }
+                if let Some(units) = self.units {
+                    query = query.with_units(units);
+                }
+                query.build()
+            })
+            .collect()
+    }
+}
+
+impl<C: HttpClient> BrightSkyClient<C> {
+    /// Start a request for the hourly records of `range`, see the
+    /// [`history`](crate::history) module.
+    ///
+    /// The range is half-open: `start..end` covers `start` up to, but not
+    /// including, midnight of `end`.
+    pub fn get_history(
+        &self,
+        location: impl Into<(f64, f64)>,
+        range: Range<NaiveDate>,
+    ) -> HistoryRequest<'_, C> {
+        HistoryRequest {
+            client: self,
+            location: location.into(),
+            range,
+            chunk_days: DEFAULT_HISTORY_CHUNK_DAYS,
+            concurrency: DEFAULT_HISTORY_CONCURRENCY,
+            tz: None,
+            units: None,
+        }
+    }
+}
+
+/// Concatenate responses, dropping duplicate records and sources.
+fn merge(responses: Vec<WeatherResponse>) -> WeatherResponse {
+    let mut merged = WeatherResponse {
+        weather: Vec::new(),
+        sources: Vec::new(),
+    };
+
+    for response in responses {
+        merged.weather.extend(response.weather);
+        for source in response.sources {
+            if !merged.sources.iter().any(|known| known.id == source.id) {
+                merged.sources.push(source);
+            }
+        }
+    }
+
+    merged
+        .weather
+        .sort_by_key(|record| (record.timestamp, record.source_id));
+    merged
+        .weather
+        .dedup_by_key(|record| (record.timestamp, record.source_id));
+    merged
+}
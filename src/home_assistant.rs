@@ -0,0 +1,514 @@
+//! Home Assistant sensors over MQTT.
+//!
+//! [`HomeAssistant`] maps responses to the messages of Home Assistant's
+//! [MQTT discovery](https://www.home-assistant.io/integrations/mqtt/#mqtt-discovery):
+//!
+//! - a sensor per current weather parameter (temperature, humidity, wind, ...),
+//!   all reading the [`CurrentWeather`](crate::types::CurrentWeather) record
+//!   published as JSON to one state topic
+//! - a binary sensor per active alert, with the alert as attributes, removed
+//!   again when the alert is lifted
+//! - a binary sensor that is on while any alert is active
+//!
+//! [`HomeAssistantPublisher`] fetches the current weather and alerts of one
+//! location on an interval and publishes these messages through an
+//! [`MqttPublisher`], implemented for `rumqttc::AsyncClient`:
+//!
+//! ```rust,no_run
+//! # #[cfg(all(feature = "home-assistant", feature = "reqwest"))]
+//! # async fn run() {
+//! use brightsky::BrightSkyClient;
+//! use brightsky::home_assistant::{HomeAssistant, HomeAssistantPublisher};
+//! use rumqttc::{AsyncClient, MqttOptions};
+//! use std::time::Duration;
+//!
+//! let (mqtt, mut eventloop) = AsyncClient::new(MqttOptions::new("brightsky", "localhost", 1883), 64);
+//! // rumqttc sends messages while its event loop is polled
+//! tokio::spawn(async move { while eventloop.poll().await.is_ok() {} });
+//!
+//! let client = BrightSkyClient::new(reqwest::Client::new());
+//! let home_assistant = HomeAssistant::new("berlin").with_name("Weather Berlin");
+//! HomeAssistantPublisher::new(client, mqtt, home_assistant, (52.52, 13.4))
+//!     .with_interval(Duration::from_secs(600))
+//!     .run_until(async {
+//!         tokio::signal::ctrl_c().await.ok();
+//!     })
+//!     .await;
+//! # }
+//! ```
+//!
+//! Only available with the `home-assistant` feature.
+
+use std::{future::Future, pin::pin, time::Duration};
+
+use futures_util::{
+    FutureExt,
+    future::{Either, select},
+};
+pub use rumqttc;
+use serde_json::{Value, json};
+
+use crate::{
+    AlertsQueryBuilder, BrightSkyClient, ClientError, CurrentWeatherQueryBuilder, HttpClient,
+    Language,
+    formatting::FormatOptions,
+    poll::{AlertEvent, AlertsWatcher},
+    types::{Alert, AlertsResponse, CurrentWeatherResponse, UnitType},
+};
+
+/// Topic prefix Home Assistant listens to for discovery messages by default.
+pub const DEFAULT_DISCOVERY_PREFIX: &str = "homeassistant";
+
+/// How often [`HomeAssistantPublisher`] fetches new data by default.
+///
+/// Bright Sky updates current weather every 10 minutes.
+pub const DEFAULT_PUBLISH_INTERVAL: Duration = Duration::from_secs(600);
+
+/// Current weather fields exposed as sensors, with their name and Home
+/// Assistant device class.
+const SENSORS: &[(&str, &str, Option<&str>)] = &[
+    ("temperature", "Temperature", Some("temperature")),
+    ("dew_point", "Dew point", Some("temperature")),
+    ("relative_humidity", "Humidity", Some("humidity")),
+    ("pressure_msl", "Pressure", Some("atmospheric_pressure")),
+    ("wind_speed_10", "Wind speed", Some("wind_speed")),
+    ("wind_gust_speed_10", "Wind gust speed", Some("wind_speed")),
+    (
+        "wind_direction_10",
+        "Wind direction",
+        Some("wind_direction"),
+    ),
+    ("cloud_cover", "Cloud cover", None),
+    ("visibility", "Visibility", Some("distance")),
+    ("precipitation_60", "Precipitation", Some("precipitation")),
+    ("sunshine_60", "Sunshine", Some("duration")),
+    ("solar_60", "Solar irradiation", None),
+    ("condition", "Condition", None),
+];
+
+/// A message to publish to an MQTT broker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MqttMessage {
+    /// Topic of the message
+    pub topic: String,
+    /// Payload, empty to delete a retained message
+    pub payload: String,
+    /// Whether the broker keeps the message for new subscribers
+    pub retain: bool,
+}
+
+impl MqttMessage {
+    fn retained(topic: String, payload: impl Into<String>) -> Self {
+        Self {
+            topic,
+            payload: payload.into(),
+            retain: true,
+        }
+    }
+}
+
+/// Maps responses to Home Assistant discovery and state messages for one
+/// device.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HomeAssistant {
+    node_id: String,
+    name: String,
+    discovery_prefix: String,
+    state_prefix: String,
+    units: UnitType,
+    language: Language,
+}
+
+impl HomeAssistant {
+    /// Create a device with the given ID, which becomes part of the topics and
+    /// entity IDs. Characters other than ASCII letters, digits, `_` and `-` are
+    /// replaced with `_`.
+    pub fn new(node_id: &str) -> Self {
+        let node_id = sanitize(node_id);
+        Self {
+            name: format!("Bright Sky {node_id}"),
+            state_prefix: format!("brightsky/{node_id}"),
+            discovery_prefix: DEFAULT_DISCOVERY_PREFIX.to_string(),
+            node_id,
+            units: UnitType::Dwd,
+            language: Language::En,
+        }
+    }
+
+    /// Set the device name shown in Home Assistant.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Set the discovery prefix configured in Home Assistant.
+    pub fn with_discovery_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.discovery_prefix = prefix.into();
+        self
+    }
+
+    /// Set the prefix of the state topics, `brightsky/<node_id>` by default.
+    pub fn with_state_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.state_prefix = prefix.into();
+        self
+    }
+
+    /// Set the unit system of the published values, which determines the
+    /// units announced to Home Assistant.
+    pub fn with_units(mut self, units: UnitType) -> Self {
+        self.units = units;
+        self
+    }
+
+    /// Set the language of alert entity names.
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// The unit system of the published values.
+    pub fn units(&self) -> UnitType {
+        self.units
+    }
+
+    /// Topic of the current weather record.
+    pub fn weather_topic(&self) -> String {
+        format!("{}/current_weather", self.state_prefix)
+    }
+
+    /// Topic of the alert summary.
+    pub fn alerts_topic(&self) -> String {
+        format!("{}/alerts", self.state_prefix)
+    }
+
+    /// Discovery messages of the current weather sensors and the alert
+    /// summary, published once at startup.
+    pub fn discovery(&self) -> Vec<MqttMessage> {
+        let options = FormatOptions::new().with_units(self.units);
+        let mut messages: Vec<MqttMessage> = SENSORS
+            .iter()
+            .map(|&(field, name, device_class)| {
+                let mut config = self.entity(field, name);
+                config["state_topic"] = self.weather_topic().into();
+                config["value_template"] = format!("{{{{ value_json.{field} }}}}").into();
+                if let Some(device_class) = device_class {
+                    config["device_class"] = device_class.into();
+                }
+                if let Some(unit) = options.unit(field) {
+                    config["unit_of_measurement"] = unit.into();
+                    if field != "wind_direction_10" {
+                        config["state_class"] = "measurement".into();
+                    }
+                }
+                MqttMessage::retained(self.config_topic("sensor", field), config.to_string())
+            })
+            .collect();
+
+        let mut summary = self.entity("alert", "Weather alert");
+        summary["device_class"] = "safety".into();
+        summary["state_topic"] = self.alerts_topic().into();
+        summary["value_template"] = "{{ 'ON' if value_json.count > 0 else 'OFF' }}".into();
+        summary["json_attributes_topic"] = self.alerts_topic().into();
+        messages.push(MqttMessage::retained(
+            self.config_topic("binary_sensor", "alert"),
+            summary.to_string(),
+        ));
+        messages
+    }
+
+    /// State message of a current weather response.
+    pub fn weather_state(&self, response: &CurrentWeatherResponse) -> MqttMessage {
+        let state = serde_json::to_string(&response.weather).unwrap_or_default();
+        MqttMessage::retained(self.weather_topic(), state)
+    }
+
+    /// State message of the alert summary with the currently active alerts.
+    pub fn alerts_state<'a>(&self, active: impl IntoIterator<Item = &'a Alert>) -> MqttMessage {
+        let headlines: Vec<&str> = active
+            .into_iter()
+            .map(|alert| self.headline(alert))
+            .collect();
+        let state = json!({ "count": headlines.len(), "headlines": headlines });
+        MqttMessage::retained(self.alerts_topic(), state.to_string())
+    }
+
+    /// Messages adding, updating or removing the binary sensor of an alert.
+    pub fn alert_messages(&self, event: &AlertEvent) -> Vec<MqttMessage> {
+        let alert = event.alert();
+        let object_id = format!("alert_{}", sanitize(&alert.alert_id));
+        let config_topic = self.config_topic("binary_sensor", &object_id);
+        let state_topic = format!("{}/alert/{}", self.state_prefix, sanitize(&alert.alert_id));
+        let attributes_topic = format!("{state_topic}/attributes");
+
+        match event {
+            AlertEvent::New(_) | AlertEvent::Updated(_) => {
+                let mut config = self.entity(&object_id, self.event(alert));
+                config["device_class"] = "safety".into();
+                config["state_topic"] = state_topic.clone().into();
+                config["json_attributes_topic"] = attributes_topic.clone().into();
+                let attributes = serde_json::to_string(alert).unwrap_or_default();
+                vec![
+                    MqttMessage::retained(config_topic, config.to_string()),
+                    MqttMessage::retained(attributes_topic, attributes),
+                    MqttMessage::retained(state_topic, "ON"),
+                ]
+            }
+            // Empty retained payloads remove the entity and its retained states
+            AlertEvent::Expired(_) => vec![
+                MqttMessage::retained(config_topic, ""),
+                MqttMessage::retained(state_topic, ""),
+                MqttMessage::retained(attributes_topic, ""),
+            ],
+        }
+    }
+
+    fn config_topic(&self, component: &str, object_id: &str) -> String {
+        format!(
+            "{}/{component}/{}/{object_id}/config",
+            self.discovery_prefix, self.node_id
+        )
+    }
+
+    fn entity(&self, object_id: &str, name: &str) -> Value {
+        json!({
+            "name": name,
+            "unique_id": format!("{}_{object_id}", self.node_id),
+            "device": {
+                "identifiers": [format!("brightsky_{}", self.node_id)],
+                "name": self.name,
+                "manufacturer": "Deutscher Wetterdienst",
+                "model": "Bright Sky",
+            },
+        })
+    }
+
+    fn headline<'a>(&self, alert: &'a Alert) -> &'a str {
+        alert.headline(self.language)
+    }
+
+    fn event<'a>(&self, alert: &'a Alert) -> &'a str {
+        alert
+            .event(self.language)
+            .unwrap_or_else(|| self.headline(alert))
+    }
+}
+
+/// Replace characters that are not allowed in topics and entity IDs.
+fn sanitize(id: &str) -> String {
+    id.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// An MQTT client that [`HomeAssistantPublisher`] publishes through.
+pub trait MqttPublisher {
+    /// Error of a publish
+    type Error;
+
+    /// Publish a message.
+    fn publish(&self, message: MqttMessage) -> impl Future<Output = Result<(), Self::Error>>;
+}
+
+impl MqttPublisher for rumqttc::AsyncClient {
+    type Error = rumqttc::ClientError;
+
+    /// Publish with QoS 1 (at least once).
+    async fn publish(&self, message: MqttMessage) -> Result<(), Self::Error> {
+        rumqttc::AsyncClient::publish(
+            self,
+            message.topic,
+            rumqttc::QoS::AtLeastOnce,
+            message.retain,
+            message.payload,
+        )
+        .await
+    }
+}
+
+/// Publishes the current weather and alerts of one location to Home
+/// Assistant on an interval.
+#[derive(Debug)]
+pub struct HomeAssistantPublisher<C, P> {
+    client: BrightSkyClient<C>,
+    mqtt: P,
+    home_assistant: HomeAssistant,
+    location: (f64, f64),
+    interval: Duration,
+    watcher: AlertsWatcher,
+    discovered: bool,
+}
+
+impl<C: HttpClient, P: MqttPublisher> HomeAssistantPublisher<C, P> {
+    /// Publish the weather at `location`, every [`DEFAULT_PUBLISH_INTERVAL`].
+    pub fn new(
+        client: BrightSkyClient<C>,
+        mqtt: P,
+        home_assistant: HomeAssistant,
+        location: impl Into<(f64, f64)>,
+    ) -> Self {
+        Self {
+            client,
+            mqtt,
+            home_assistant,
+            location: location.into(),
+            interval: DEFAULT_PUBLISH_INTERVAL,
+            watcher: AlertsWatcher::new(),
+            discovered: false,
+        }
+    }
+
+    /// Set the time between two updates.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Fetch the current weather and alerts once and publish what changed.
+    ///
+    /// The discovery messages are published with the first update. Both
+    /// endpoints are handled separately: if fetching or publishing one fails,
+    /// the other is still published and the first error is returned.
+    pub async fn update(&mut self) -> Result<(), PublishError<C::Error, P::Error>> {
+        if !self.discovered {
+            for message in self.home_assistant.discovery() {
+                self.mqtt
+                    .publish(message)
+                    .await
+                    .map_err(PublishError::Mqtt)?;
+            }
+            self.discovered = true;
+        }
+
+        let weather = self.update_weather().await;
+        let alerts = self.update_alerts().await;
+        weather.and(alerts)
+    }
+
+    async fn update_weather(&mut self) -> Result<(), PublishError<C::Error, P::Error>> {
+        let query = CurrentWeatherQueryBuilder::new()
+            .with_lat_lon(self.location)
+            .with_units(self.home_assistant.units())
+            .build()
+            .map_err(ClientError::Query)?;
+        let weather: CurrentWeatherResponse = self.client.get(query).await?;
+        self.mqtt
+            .publish(self.home_assistant.weather_state(&weather))
+            .await
+            .map_err(PublishError::Mqtt)
+    }
+
+    async fn update_alerts(&mut self) -> Result<(), PublishError<C::Error, P::Error>> {
+        let query = AlertsQueryBuilder::new()
+            .with_lat_lon(self.location)
+            .build()
+            .map_err(ClientError::Query)?;
+        let alerts: AlertsResponse = self.client.get(query).await?;
+        // Record each change only once it was published, so a failed change
+        // is published again by the next update
+        for event in self.watcher.changes(&alerts) {
+            for message in self.home_assistant.alert_messages(&event) {
+                self.mqtt
+                    .publish(message)
+                    .await
+                    .map_err(PublishError::Mqtt)?;
+            }
+            self.watcher.apply(&event);
+        }
+        self.mqtt
+            .publish(self.home_assistant.alerts_state(self.watcher.active()))
+            .await
+            .map_err(PublishError::Mqtt)
+    }
+
+    /// [`update`](Self::update) forever.
+    ///
+    /// A failed update is retried at the next interval and, with the `tracing`
+    /// feature, reported as a warning. To handle errors yourself, call
+    /// [`update`](Self::update) in your own loop.
+    pub async fn run(&mut self)
+    where
+        C::Error: core::fmt::Display,
+        P::Error: core::fmt::Display,
+    {
+        self.run_until(core::future::pending()).await
+    }
+
+    /// Like [`run`](Self::run), returning once `shutdown` completes.
+    ///
+    /// `shutdown` can be any future, e.g. `tokio::signal::ctrl_c()`. While
+    /// waiting for the next update, the publisher stops immediately; a running
+    /// update is finished first.
+    pub async fn run_until(&mut self, shutdown: impl Future<Output = ()>)
+    where
+        C::Error: core::fmt::Display,
+        P::Error: core::fmt::Display,
+    {
+        let mut shutdown = pin!(shutdown);
+        let mut delay = Duration::ZERO;
+        loop {
+            if delay.is_zero() {
+                if shutdown.as_mut().now_or_never().is_some() {
+                    return;
+                }
+            } else {
+                let sleep = pin!(tokio::time::sleep(delay));
+                if let Either::Left(_) = select(shutdown.as_mut(), sleep).await {
+                    return;
+                }
+            }
+            if let Err(_err) = self.update().await {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(error = %_err, "Home Assistant update failed");
+            }
+            delay = self.interval;
+        }
+    }
+
+    /// The mapping of responses to messages.
+    pub fn home_assistant(&self) -> &HomeAssistant {
+        &self.home_assistant
+    }
+}
+
+/// Error returned by [`HomeAssistantPublisher`].
+#[derive(Debug)]
+pub enum PublishError<E, M> {
+    /// Fetching from Bright Sky failed
+    Client(ClientError<E>),
+    /// Publishing a message failed
+    Mqtt(M),
+}
+
+impl<E, M> From<ClientError<E>> for PublishError<E, M> {
+    fn from(error: ClientError<E>) -> Self {
+        Self::Client(error)
+    }
+}
+
+impl<E: core::fmt::Display, M: core::fmt::Display> core::fmt::Display for PublishError<E, M> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Client(e) => write!(f, "{}", e),
+            Self::Mqtt(e) => write!(f, "MQTT error: {}", e),
+        }
+    }
+}
+
+impl<E, M> std::error::Error for PublishError<E, M>
+where
+    E: std::error::Error + 'static,
+    M: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Client(e) => Some(e),
+            Self::Mqtt(e) => Some(e),
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! Dew point and relative humidity from each other.
+//!
+//! Records often contain only one of `dew_point` and `relative_humidity`, e.g.
+//! when a station does not report humidity and a fallback source is missing.
+//! Together with the air temperature, either one determines the other through
+//! the Magnus formula for saturation vapour pressure over water, with the
+//! coefficients of Sonntag (1990) used by the DWD:
+//!
+//! ```text
+//! E(t) = 6.112 hPa · exp(17.62 · t / (243.12 °C + t))
+//! relative_humidity = 100 % · E(dew_point) / E(temperature)
+//! ```
+//!
+//! The formula is accurate to within 0.1 °C between -45 °C and 60 °C.
+//!
+//! ```rust
+//! use brightsky::humidity::{dew_point, relative_humidity};
+//! use brightsky::types::UnitType;
+//! use brightsky::units::Temperature;
+//!
+//! let temperature = Temperature::new(20.0, UnitType::Dwd);
+//! let dew_point = dew_point(temperature, 50.0).unwrap();
+//! assert!((dew_point.to_celsius() - 9.26).abs() < 0.01);
+//! assert!((relative_humidity(temperature, dew_point) - 50.0).abs() < 1e-9);
+//! ```
+//!
+//! `fill_derived` fills the missing value of a record in place:
+//!
+//! ```rust
+//! use brightsky::types::{UnitType, Weather};
+//!
+//! let mut record: Weather = serde_json::from_str(r#"{
+//!     "timestamp": "2023-08-07T12:00:00+00:00",
+//!     "source_id": 1,
+//!     "temperature": 20.0,
+//!     "dew_point": 9.26
+//! }"#).unwrap();
+//!
+//! record.fill_derived(UnitType::Dwd);
+//! assert_eq!(record.relative_humidity, Some(50));
+//! ```
+//!
+//! Only available with the `std` feature.
+
+use crate::{
+    types::{CurrentWeather, UnitType, Weather, WeatherResponse},
+    units::Temperature,
+};
+
+/// Magnus coefficient over water.
+const A: f64 = 17.62;
+/// Magnus coefficient over water in °C.
+const B: f64 = 243.12;
+
+/// `ln(E(t) / 6.112 hPa)` of the Magnus formula.
+fn magnus(celsius: f64) -> f64 {
+    A * celsius / (B + celsius)
+}
+
+/// Dew point at the given air temperature and relative humidity in percent,
+/// in the unit system of `temperature`.
+///
+/// Returns `None` if the humidity is not positive. Humidity above 100 % is
+/// treated as 100 %.
+pub fn dew_point(temperature: Temperature, relative_humidity: f64) -> Option<Temperature> {
+    if relative_humidity.is_nan() || relative_humidity <= 0.0 {
+        return None;
+    }
+    let gamma = (relative_humidity.min(100.0) / 100.0).ln() + magnus(temperature.to_celsius());
+    let celsius = B * gamma / (A - gamma);
+    Some(Temperature::new(celsius, UnitType::Dwd).convert(temperature.units()))
+}
+
+/// Relative humidity in percent at the given air temperature and dew point.
+///
+/// A dew point above the air temperature yields more than 100 %.
+pub fn relative_humidity(temperature: Temperature, dew_point: Temperature) -> f64 {
+    100.0 * (magnus(dew_point.to_celsius()) - magnus(temperature.to_celsius())).exp()
+}
+
+/// Fill whichever of dew point and relative humidity is missing.
+fn fill(
+    temperature: Option<Temperature>,
+    dew_point_value: &mut Option<f64>,
+    humidity: &mut Option<i64>,
+    units: UnitType,
+) {
+    let Some(temperature) = temperature else {
+        return;
+    };
+    match (*dew_point_value, *humidity) {
+        (Some(dew_point), None) => {
+            let humidity_value =
+                relative_humidity(temperature, Temperature::new(dew_point, units)).round();
+            *humidity = Some(humidity_value.clamp(0.0, 100.0) as i64);
+        }
+        (None, Some(humidity)) => {
+            *dew_point_value = dew_point(temperature, humidity as f64).map(|dew_point| {
+                let celsius = (dew_point.to_celsius() * 10.0).round() / 10.0;
+                Temperature::new(celsius, UnitType::Dwd)
+                    .convert(units)
+                    .value()
+            });
+        }
+        _ => {}
+    }
+}
+
+impl Weather {
+    /// Compute a missing `dew_point` or `relative_humidity` from the other
+    /// and `temperature`, given the unit system of the query.
+    ///
+    /// Computed dew points are rounded to 0.1 °C and humidity to whole
+    /// percent, like the values reported by the DWD. Records without
+    /// temperature or with neither value are left unchanged.
+    pub fn fill_derived(&mut self, units: UnitType) {
+        fill(
+            self.typed_temperature(units),
+            &mut self.dew_point,
+            &mut self.relative_humidity,
+            units,
+        );
+    }
+}
+
+impl CurrentWeather {
+    /// Compute a missing `dew_point` or `relative_humidity`, see
+    /// [`Weather::fill_derived`].
+    pub fn fill_derived(&mut self, units: UnitType) {
+        fill(
+            self.typed_temperature(units),
+            &mut self.dew_point,
+            &mut self.relative_humidity,
+            units,
+        );
+    }
+}
+
+impl WeatherResponse {
+    /// [`Weather::fill_derived`] for every record.
+    pub fn fill_derived(&mut self, units: UnitType) {
+        for record in &mut self.weather {
+            record.fill_derived(units);
+        }
+    }
+}
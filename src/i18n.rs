@@ -0,0 +1,25 @@
+//! Languages for human-readable output.
+//!
+//! Bright Sky itself returns alert texts in German and English (`*_de` and
+//! `*_en` fields). [`Language`] selects between them and localizes text that this
+//! crate generates, such as the summaries in the `display` module.
+
+/// Language of human-readable text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Language {
+    /// English
+    #[default]
+    En,
+    /// German
+    De,
+}
+
+impl Language {
+    /// ISO 639-1 language code (`"en"` or `"de"`).
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::De => "de",
+        }
+    }
+}
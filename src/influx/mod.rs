@@ -0,0 +1,195 @@
+//! Export to InfluxDB line protocol.
+//!
+//! [`WeatherResponse::to_line_protocol`] and
+//! [`CurrentWeatherResponse::to_line_protocol`] turn records into lines of the
+//! [line protocol](https://docs.influxdata.com/influxdb/v2/reference/syntax/line-protocol/)
+//! understood by InfluxDB, VictoriaMetrics and Telegraf:
+//!
+//! ```rust
+//! use brightsky::types::WeatherResponse;
+//!
+//! let response: WeatherResponse = serde_json::from_str(r#"{
+//!     "weather": [{
+//!         "timestamp": "2023-08-07T12:00:00+00:00", "source_id": 6007,
+//!         "temperature": 21.4, "relative_humidity": 64, "condition": "dry"
+//!     }],
+//!     "sources": [{
+//!         "id": 6007, "dwd_station_id": "01766", "observation_type": "historical",
+//!         "first_record": "2010-01-01T00:00:00+00:00", "last_record": "2023-08-07T12:00:00+00:00",
+//!         "lat": 52.1344, "lon": 7.6969, "height": 47.8, "distance": 0.0
+//!     }]
+//! }"#).unwrap();
+//!
+//! let lines = response.to_line_protocol("weather", &[("units", "dwd")]);
+//! assert_eq!(
+//!     lines,
+//!     "weather,units=dwd,dwd_station_id=01766,observation_type=historical \
+//!      condition=\"dry\",relative_humidity=64i,source_id=6007i,temperature=21.4 \
+//!      1691409600000000000\n"
+//! );
+//! ```
+//!
+//! Every record becomes one line with its timestamp in nanoseconds. Missing
+//! values are left out, integer parameters are written as integers and
+//! `condition`/`icon` as strings. The values are in the unit system of the
+//! query; tag them with it (as above) to keep both apart.
+//!
+#![cfg_attr(
+    feature = "influx",
+    doc = "With the `influx` feature, [`InfluxWriter`]"
+)]
+#![cfg_attr(
+    not(feature = "influx"),
+    doc = "With the `influx` feature, `InfluxWriter`"
+)]
+//! sends records to the write API of InfluxDB 1.x/2.x or VictoriaMetrics.
+//!
+//! Only available with the `std` feature.
+
+use std::fmt::Write as _;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::types::{CurrentWeatherResponse, Source, WeatherResponse};
+
+#[cfg(feature = "influx")]
+mod writer;
+#[cfg(feature = "influx")]
+pub use writer::{InfluxError, InfluxWriter};
+
+/// Record fields that are not written as fields.
+const SKIPPED_FIELDS: [&str; 3] = ["timestamp", "fallback_source_ids", "source_id"];
+
+impl WeatherResponse {
+    /// All records as line protocol, one line per record.
+    ///
+    /// Every line has the given `tags` followed by the `dwd_station_id` and
+    /// `observation_type` of the record's source, if it is part of the
+    /// response.
+    pub fn to_line_protocol(&self, measurement: &str, tags: &[(&str, &str)]) -> String {
+        let mut lines = String::new();
+        for record in &self.weather {
+            let source = self.source_by_id(record.source_id);
+            let line = Line::new(measurement, tags, source.map(source_tags));
+            line.write(&mut lines, record, record.source_id, &record.timestamp);
+        }
+        lines
+    }
+}
+
+impl CurrentWeatherResponse {
+    /// The current weather record as one line of line protocol.
+    ///
+    /// The line has the given `tags` followed by the `dwd_station_id` and
+    /// `observation_type` of the main source.
+    pub fn to_line_protocol(&self, measurement: &str, tags: &[(&str, &str)]) -> String {
+        let record = &self.weather;
+        let source = self.source_by_id(record.source_id);
+        let line = Line::new(
+            measurement,
+            tags,
+            source.map(|source| {
+                vec![
+                    ("dwd_station_id", source.dwd_station_id.clone()),
+                    ("observation_type", source.observation_type.as_str().into()),
+                ]
+            }),
+        );
+        let mut lines = String::new();
+        line.write(&mut lines, record, record.source_id, &record.timestamp);
+        lines
+    }
+}
+
+fn source_tags(source: &Source) -> Vec<(&'static str, String)> {
+    let mut tags = Vec::new();
+    if let Some(id) = &source.dwd_station_id {
+        tags.push(("dwd_station_id", id.clone()));
+    }
+    tags.push(("observation_type", source.observation_type.as_str().into()));
+    tags
+}
+
+/// Measurement and tags shared by the lines of a response.
+struct Line {
+    prefix: String,
+}
+
+impl Line {
+    fn new(
+        measurement: &str,
+        tags: &[(&str, &str)],
+        source_tags: Option<Vec<(&str, String)>>,
+    ) -> Self {
+        let mut prefix = escape(measurement, &[',', ' ']);
+        let source_tags = source_tags.unwrap_or_default();
+        let tags = tags.iter().copied().chain(
+            source_tags
+                .iter()
+                .map(|(key, value)| (*key, value.as_str())),
+        );
+        for (key, value) in tags {
+            if value.is_empty() {
+                continue;
+            }
+            let _ = write!(
+                prefix,
+                ",{}={}",
+                escape(key, &[',', '=', ' ']),
+                escape(value, &[',', '=', ' '])
+            );
+        }
+        Self { prefix }
+    }
+
+    /// Append the line of a record, skipping records without values.
+    fn write(
+        &self,
+        out: &mut String,
+        record: &impl Serialize,
+        source_id: i64,
+        timestamp: &chrono::DateTime<chrono::FixedOffset>,
+    ) {
+        let Ok(Value::Object(values)) = serde_json::to_value(record) else {
+            return;
+        };
+        let mut fields: Vec<String> = values
+            .iter()
+            .filter(|(name, _)| !SKIPPED_FIELDS.contains(&name.as_str()))
+            .filter_map(|(name, value)| Some(format!("{}={}", name, field_value(value)?)))
+            .collect();
+        if fields.is_empty() {
+            return;
+        }
+        fields.push(format!("source_id={}i", source_id));
+        fields.sort();
+        let Some(nanos) = timestamp.timestamp_nanos_opt() else {
+            return;
+        };
+        let _ = writeln!(out, "{} {} {}", self.prefix, fields.join(","), nanos);
+    }
+}
+
+/// A JSON value as line protocol field value, `None` for missing values.
+fn field_value(value: &Value) -> Option<String> {
+    match value {
+        Value::Number(number) if number.is_f64() => Some(number.to_string()),
+        Value::Number(number) => Some(format!("{}i", number)),
+        Value::String(text) => Some(format!("\"{}\"", escape(text, &['"', '\\']))),
+        Value::Bool(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+/// Escape `special` characters with a backslash.
+fn escape(text: &str, special: &[char]) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if special.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
@@ -0,0 +1,191 @@
+use url::Url;
+
+use crate::types::{CurrentWeatherResponse, UnitType, WeatherResponse};
+
+/// Writes records to the line protocol write API of InfluxDB or
+/// VictoriaMetrics.
+///
+/// Lines are tagged with `units` (`dwd` or `si`) and the tags added with
+/// [`with_tag`](Self::with_tag). Hourly records are written to the `weather`
+/// measurement and current weather to `current_weather`, unless set otherwise
+/// with [`with_measurements`](Self::with_measurements).
+///
+/// ```rust,no_run
+/// use brightsky::influx::InfluxWriter;
+/// use brightsky::{BrightSkyClient, CurrentWeatherQueryBuilder};
+/// use brightsky::types::CurrentWeatherResponse;
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let writer = InfluxWriter::v2(reqwest::Client::new(), "http://localhost:8086", "home", "weather")?
+///     .with_token("my-token")
+///     .with_tag("location", "berlin");
+///
+/// let client = BrightSkyClient::new(reqwest::Client::new());
+/// let query = CurrentWeatherQueryBuilder::new().with_lat_lon((52.52, 13.4)).build()?;
+/// let response: CurrentWeatherResponse = client.get(query).await?;
+/// writer.write_current_weather(&response).await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Only available with the `influx` feature.
+#[derive(Debug, Clone)]
+pub struct InfluxWriter {
+    http: reqwest::Client,
+    url: Url,
+    token: Option<String>,
+    tags: Vec<(String, String)>,
+    units: UnitType,
+    weather_measurement: String,
+    current_weather_measurement: String,
+}
+
+impl InfluxWriter {
+    /// Write to a bucket of InfluxDB 2.x or 3.x (`/api/v2/write`).
+    pub fn v2(
+        http: reqwest::Client,
+        host: &str,
+        org: &str,
+        bucket: &str,
+    ) -> Result<Self, url::ParseError> {
+        let mut url = Url::parse(&format!("{}/api/v2/write", host.trim_end_matches('/')))?;
+        url.query_pairs_mut()
+            .append_pair("org", org)
+            .append_pair("bucket", bucket)
+            .append_pair("precision", "ns");
+        Ok(Self::new(http, url))
+    }
+
+    /// Write to a database of InfluxDB 1.x or VictoriaMetrics (`/write`).
+    pub fn v1(http: reqwest::Client, host: &str, database: &str) -> Result<Self, url::ParseError> {
+        let mut url = Url::parse(&format!("{}/write", host.trim_end_matches('/')))?;
+        url.query_pairs_mut()
+            .append_pair("db", database)
+            .append_pair("precision", "ns");
+        Ok(Self::new(http, url))
+    }
+
+    fn new(http: reqwest::Client, url: Url) -> Self {
+        Self {
+            http,
+            url,
+            token: None,
+            tags: Vec::new(),
+            units: UnitType::Dwd,
+            weather_measurement: "weather".to_string(),
+            current_weather_measurement: "current_weather".to_string(),
+        }
+    }
+
+    /// Authenticate with an API token (`Authorization: Token ...`).
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Add a tag to every line, e.g. the name of the location.
+    pub fn with_tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.push((key.into(), value.into()));
+        self
+    }
+
+    /// Set the unit system the written responses were requested in, `dwd` by
+    /// default.
+    pub fn with_units(mut self, units: UnitType) -> Self {
+        self.units = units;
+        self
+    }
+
+    /// Set the measurements of hourly records and current weather.
+    pub fn with_measurements(
+        mut self,
+        weather: impl Into<String>,
+        current_weather: impl Into<String>,
+    ) -> Self {
+        self.weather_measurement = weather.into();
+        self.current_weather_measurement = current_weather.into();
+        self
+    }
+
+    /// Write all records of a `/weather` response.
+    pub async fn write_weather(&self, response: &WeatherResponse) -> Result<(), InfluxError> {
+        let lines = response.to_line_protocol(&self.weather_measurement, &self.tags());
+        self.write_lines(lines).await
+    }
+
+    /// Write the record of a `/current_weather` response.
+    pub async fn write_current_weather(
+        &self,
+        response: &CurrentWeatherResponse,
+    ) -> Result<(), InfluxError> {
+        let lines = response.to_line_protocol(&self.current_weather_measurement, &self.tags());
+        self.write_lines(lines).await
+    }
+
+    /// Write lines of line protocol with nanosecond timestamps. Nothing is
+    /// sent if `lines` is empty.
+    pub async fn write_lines(&self, lines: String) -> Result<(), InfluxError> {
+        if lines.is_empty() {
+            return Ok(());
+        }
+        let mut request = self
+            .http
+            .post(self.url.clone())
+            .header("Content-Type", "text/plain; charset=utf-8")
+            .body(lines);
+        if let Some(token) = &self.token {
+            request = request.header("Authorization", format!("Token {}", token));
+        }
+
+        let response = request.send().await.map_err(InfluxError::Http)?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(InfluxError::Status {
+                status: status.as_u16(),
+                body,
+            });
+        }
+        Ok(())
+    }
+
+    fn tags(&self) -> Vec<(&str, &str)> {
+        core::iter::once(("units", self.units.as_str()))
+            .chain(self.tags.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+            .collect()
+    }
+}
+
+/// Error returned by [`InfluxWriter`].
+#[derive(Debug)]
+pub enum InfluxError {
+    /// The request could not be sent
+    Http(reqwest::Error),
+    /// The write API responded with a non-`2xx` status code
+    Status {
+        /// HTTP status code
+        status: u16,
+        /// Error message of the response
+        body: String,
+    },
+}
+
+impl core::fmt::Display for InfluxError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "Request error: {}", e),
+            Self::Status { status, body } => {
+                write!(f, "Write API responded with status {}: {}", status, body)
+            }
+        }
+    }
+}
+
+impl std::error::Error for InfluxError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Http(e) => Some(e),
+            Self::Status { .. } => None,
+        }
+    }
+}
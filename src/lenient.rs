@@ -0,0 +1,220 @@
+//! Deserialization that survives changes to the API schema.
+//!
+//! The typed responses fail as a whole on a single value they do not expect.
+//! [`from_slice`] instead repairs the response and reports what it changed:
+//!
+//! - Invalid values are ignored, i.e. treated as `null`. If the field is
+//!   required, the record (array element) containing it is dropped.
+//! - Keys the type does not know are kept in [`Lenient::extra`].
+//! - Every repair is reported as a [`Warning`].
+//!
+//! Enum values added to the API after this version, such as a new
+//! [`ObservationType`](crate::types::ObservationType), are kept in the
+//! `Other` variant of the enum and do not need repairs.
+//!
+//! ```rust
+//! use brightsky::lenient;
+//! use brightsky::types::WeatherResponse;
+//!
+//! let body = br#"{
+//!     "weather": [
+//!         {"timestamp": "2023-08-07T13:00:00+02:00", "source_id": 1, "temperature": "warm"},
+//!         {"timestamp": "not a timestamp", "source_id": 1},
+//!         {"timestamp": "2023-08-07T15:00:00+02:00", "source_id": 1, "ozone": 42}
+//!     ],
+//!     "sources": []
+//! }"#;
+//!
+//! let parsed = lenient::from_slice::<WeatherResponse>(body).unwrap();
+//! assert_eq!(parsed.value.weather.len(), 2);
+//! assert_eq!(parsed.value.weather[0].temperature, None);
+//! assert_eq!(parsed.extra["weather[1].ozone"], 42);
+//! assert_eq!(parsed.warnings.len(), 2);
+//! assert_eq!(parsed.warnings[0].path, "weather[0].temperature");
+//! assert_eq!(parsed.warnings[1].path, "weather[1]");
+//! ```
+//!
+//! Only available with the `std` feature.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use serde_path_to_error::{Path, Segment};
+
+/// A leniently deserialized value, see the [`lenient`](crate::lenient) module.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lenient<T> {
+    /// The deserialized value
+    pub value: T,
+    /// Unknown keys by their path in `value`, e.g. `weather[0].ozone`
+    pub extra: HashMap<String, Value>,
+    /// Repairs made to deserialize the value, in the order they were made
+    pub warnings: Vec<Warning>,
+}
+
+/// A repair made by lenient deserialization.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    /// Path of the ignored value or dropped record, e.g. `weather[3].timestamp`
+    pub path: String,
+    /// Why the value could not be deserialized and what was done about it
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Leniently deserialize a response body.
+///
+/// Fails only if the body is not JSON or the response cannot be repaired,
+/// e.g. if a required top-level field is missing.
+pub fn from_slice<T>(body: &[u8]) -> Result<Lenient<T>, serde_json::Error>
+where
+    T: DeserializeOwned + Serialize,
+{
+    from_value(serde_json::from_slice(body)?)
+}
+
+/// Leniently deserialize a parsed response.
+pub fn from_value<T>(mut value: Value) -> Result<Lenient<T>, serde_json::Error>
+where
+    T: DeserializeOwned + Serialize,
+{
+    let mut warnings: Vec<Warning> = Vec::new();
+    // Path and error of the value set to `null` in the previous step
+    let mut ignored: Option<(String, String)> = None;
+    let parsed = loop {
+        let err = match serde_path_to_error::deserialize::<_, T>(&value) {
+            Ok(parsed) => break parsed,
+            Err(err) => err,
+        };
+        let path = err.path().to_string();
+        let Some((changed, dropped)) = repair(&mut value, err.path()) else {
+            return Err(err.into_inner());
+        };
+        let mut message = err.into_inner().to_string();
+
+        if dropped {
+            // A required value is reported once, with the error of its original value
+            if let Some((ignored_path, ignored_message)) = ignored.take()
+                && ignored_path == path
+            {
+                warnings.pop();
+                message = ignored_message;
+            }
+            warnings.push(Warning {
+                message: format!("{} at {}, record dropped", message, path),
+                path: changed,
+            });
+        } else {
+            ignored = Some((path, message.clone()));
+            warnings.push(Warning {
+                message: format!("{}, ignored", message),
+                path: changed,
+            });
+        }
+    };
+
+    let mut extra = HashMap::new();
+    collect_extra(&value, &serde_json::to_value(&parsed)?, "", &mut extra);
+    Ok(Lenient {
+        value: parsed,
+        extra,
+        warnings,
+    })
+}
+
+/// Make the value at `path` deserializable by setting it to `null` or, for
+/// required values, by removing the innermost record containing it.
+///
+/// Returns the path of the changed value and whether a record was removed,
+/// `None` if nothing can be changed.
+fn repair(value: &mut Value, path: &Path) -> Option<(String, bool)> {
+    let segments: Vec<&Segment> = path
+        .iter()
+        .take_while(|segment| matches!(segment, Segment::Seq { .. } | Segment::Map { .. }))
+        .collect();
+
+    // Missing fields are reported at their record, which is dropped right away
+    if let Some(Segment::Map { .. }) = segments.last()
+        && let Some(target) = lookup(value, &segments)
+        && !target.is_null()
+    {
+        *target = Value::Null;
+        return Some((format_path(&segments), false));
+    }
+
+    let (record, index) = segments
+        .iter()
+        .enumerate()
+        .rev()
+        .find_map(|(position, segment)| match segment {
+            Segment::Seq { index } => Some((position, *index)),
+            _ => None,
+        })?;
+    let records = lookup(value, &segments[..record])?.as_array_mut()?;
+    if index >= records.len() {
+        return None;
+    }
+    records.remove(index);
+    Some((format_path(&segments[..=record]), true))
+}
+
+fn lookup<'v>(value: &'v mut Value, segments: &[&Segment]) -> Option<&'v mut Value> {
+    segments
+        .iter()
+        .try_fold(value, |value, segment| match segment {
+            Segment::Seq { index } => value.get_mut(*index),
+            Segment::Map { key } => value.get_mut(key.as_str()),
+            _ => None,
+        })
+}
+
+fn format_path(segments: &[&Segment]) -> String {
+    let mut path = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Seq { index } => path.push_str(&format!("[{}]", index)),
+            Segment::Map { key } => push_key(&mut path, key),
+            _ => {}
+        }
+    }
+    path
+}
+
+fn push_key(path: &mut String, key: &str) {
+    if !path.is_empty() {
+        path.push('.');
+    }
+    path.push_str(key);
+}
+
+/// Collect the members of `input` that do not appear in `known`, the
+/// deserialized value serialized again.
+fn collect_extra(input: &Value, known: &Value, path: &str, extra: &mut HashMap<String, Value>) {
+    match (input, known) {
+        (Value::Object(input), Value::Object(known)) => {
+            for (key, value) in input {
+                let mut member = path.to_string();
+                push_key(&mut member, key);
+                match known.get(key) {
+                    Some(known) => collect_extra(value, known, &member, extra),
+                    None => {
+                        extra.insert(member, value.clone());
+                    }
+                }
+            }
+        }
+        (Value::Array(input), Value::Array(known)) => {
+            for (index, (value, known)) in input.iter().zip(known).enumerate() {
+                collect_extra(value, known, &format!("{}[{}]", path, index), extra);
+            }
+        }
+        _ => {}
+    }
+}
@@ -6,8 +6,17 @@
 //!
 //! ## Design Philosophy
 //!
-//! This crate focuses on **query building** and **response types** only.
-//! You bring your own HTTP client (reqwest, reqwless, ureq, etc.).
+//! The core of this crate is **query building** and **response types**: the
+//! query builders turn parameters into Bright Sky URLs, and the response types
+//! deserialize what the API returns. They work without `std` and with any HTTP
+//! client (reqwest, reqwless, ureq, etc.).
+//!
+//! On top of that, [`BrightSkyClient`] sends queries through the [`HttpClient`]
+//! trait, with implementations for reqwest and a mock for tests, and handles
+//! headers, compression, response size limits, concurrent batches and
+//! provenance. Optional features build on the client: long histories, polling
+//! streams and pipelines, sinks for InfluxDB, SQLite and MQTT, Home Assistant
+//! sensors, an axum service and the `brightsky` command line tool.
 //!
 //! ## Features
 //!
@@ -15,6 +24,15 @@
 //! - **Historical & Forecast Weather**: Access hourly weather records and forecasts
 //! - **Radar Data**: Retrieve precipitation radar with 1km spatial resolution
 //! - **Weather Alerts**: Access official weather warnings from DWD
+//! - **Long Histories**: Fetch arbitrary date ranges in concurrent, merged chunks
+//! - **Daily Summaries**: Aggregate hourly records into per-day min/max/mean/sum
+//! - **Threshold Rules**: Evaluate configurable warning conditions against responses
+//! - **Incremental Polling**: Only process records and alert changes since the last fetch
+//! - **Location Privacy**: Optionally snap or jitter coordinates to a coarse grid
+//! - **Export**: Write hourly records as CSV or GeoJSON, optionally anonymized
+//! - **Formatting**: Decimal places, units and decimal separators for text outputs
+//! - **Lenient Parsing**: Keep unknown fields and skip invalid values instead of failing
+//! - **Attribution**: Source notices for the DWD data as text or HTML
 //! - **No API Key Required**: The public API at `https://api.brightsky.dev/` is free
 //! - **no_std Compatible**: Works in embedded environments
 //!
@@ -53,12 +71,43 @@
 //!
 //! // Use your HTTP client to fetch, then deserialize
 //! let response: CurrentWeatherResponse = serde_json::from_slice(&body)?;
+//!
+//! // Or, with the `reqwless` feature, in one step
+//! let mut rx_buf = [0; 8192];
+//! let response: CurrentWeatherResponse =
+//!     brightsky::ext::reqwless_get(&mut client, query, "https://api.brightsky.dev", &mut rx_buf).await?;
 //! ```
 //!
 //! ## Feature Flags
 //!
 //! - `std` (default): Enable std library support and `url::Url` generation
 //! - `reqwest`: Enable `BrightSkyReqwestExt` trait for ergonomic reqwest usage
+//! - `stream`: Enable polling streams such as `poll::weather_stream` and the
+//!   `pipeline` module (uses tokio timers)
+//! - `sqlite`: Archive pipeline records in SQLite with `pipeline::SqliteSink`
+//! - `compression` (default): Request gzip/deflate responses and inflate them on
+//!   every HTTP client, also without `std`
+//! - `rayon`: Decompress and reshape radar frames in parallel in
+//!   `RadarResponse::decode_all`
+//! - `warn-cells`: Look up warn cell IDs from coordinates offline with `warn_cells::WarnCells`
+//! - `cap`: Parse DWD CAP 1.2 XML into `types::Alert` with `Alert::from_cap`
+//! - `geocoding`: Resolve place names to coordinates with the `geocoding::Geocoder` trait
+//! - `nominatim`: Enable `geocoding::Nominatim`, a geocoder for the OpenStreetMap Nominatim API
+//! - `cli`: Build the `brightsky` command line tool
+//! - `display`: Render responses as aligned terminal tables
+//! - `influx`: Write records to InfluxDB or VictoriaMetrics with `influx::InfluxWriter`
+//! - `home-assistant`: Publish current weather and alerts as Home Assistant MQTT sensors
+//! - `tracing`: Emit debug events for responses and deserialization errors, and
+//!   warnings for failed Home Assistant updates
+//! - `metrics`: Record request counts, latencies, errors and cache lookups via
+//!   the `metrics` facade
+//! - `chrono-tz`: Accept `chrono_tz::Tz` in `with_tz`, validate timezone names and
+//!   convert record timestamps with `timestamp_in`
+//! - `wasm`: Enable `reqwest` for browsers (`wasm32-unknown-unknown`)
+//! - `reqwless`: Enable `ext::reqwless_get` for reqwless clients, also without `std`
+//! - `heapless`: Enable the `bounded` module, a fixed-capacity current weather
+//!   response parsed with `serde-json-core`
+//! - `testing`: Enable `testing::MockHttpClient` and sample responses for unit tests
 //! - Without `std`: Only string URL generation available (no_std compatible)
 //!
 //! ## With reqwest Extension Trait
@@ -89,31 +138,143 @@ use url::Url;
 
 pub mod types;
 
-#[cfg(feature = "reqwest")]
+pub mod aggregate;
+
+pub mod rules;
+
+#[cfg(feature = "std")]
+pub mod export;
+
+#[cfg(feature = "std")]
+pub mod influx;
+
+pub mod poll;
+
+pub mod sink;
+
+#[cfg(feature = "stream")]
+pub mod pipeline;
+
+pub mod units;
+
+#[cfg(feature = "std")]
+pub mod humidity;
+
+#[cfg(feature = "std")]
+pub mod solar;
+
+pub mod wind;
+
+pub mod precipitation;
+
+pub mod sources;
+
+pub mod formatting;
+
+pub mod client;
+pub use client::{
+    BrightSkyClient, BrightSkyClientBuilder, ClientError, DEFAULT_BATCH_CONCURRENCY,
+    DEFAULT_USER_AGENT, FromHttpOptions, HttpClient, HttpOptions, HttpResponse,
+};
+
+#[cfg(feature = "std")]
+pub mod forecast;
+
+#[cfg(feature = "std")]
+pub mod provenance;
+
+#[cfg(feature = "std")]
+pub mod lenient;
+
+pub mod history;
+
+#[cfg(feature = "heapless")]
+pub mod bounded;
+
+#[cfg(feature = "geojson")]
+pub mod geojson;
+
+#[cfg(feature = "warn-cells")]
+pub mod warn_cells;
+
+#[cfg(feature = "cap")]
+pub mod cap;
+
+#[cfg(feature = "geocoding")]
+pub mod geocoding;
+
+#[cfg(feature = "config")]
+pub mod config;
+
+pub mod nowcast;
+
+#[cfg(feature = "display")]
+pub mod display;
+
+#[cfg(any(feature = "reqwest", feature = "reqwless"))]
 pub mod ext;
 
+#[cfg(feature = "axum")]
+pub mod service;
+
+#[cfg(feature = "home-assistant")]
+pub mod home_assistant;
+
+#[cfg(feature = "metrics")]
+pub mod metrics;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
 mod weather;
-pub use weather::WeatherQueryBuilder;
+pub use weather::{
+    DATA_START, MAX_DATE_RANGE_DAYS, WeatherQuery, WeatherQueryBuilder, WeatherRecords,
+};
 
 mod current_weather;
-pub use current_weather::CurrentWeatherQueryBuilder;
+pub use current_weather::{
+    CurrentWeatherQuery, CurrentWeatherQueryBuilder, IntervalValue, ObservationInterval,
+};
 
 mod radar;
-pub use radar::RadarWeatherQueryBuilder;
+#[cfg(feature = "std")]
+pub use radar::projection;
+#[cfg(feature = "render")]
+pub use radar::render;
+pub use radar::{
+    RadarBbox, RadarFrame, RadarGrid, RadarStats, RadarWeatherQuery, RadarWeatherQueryBuilder,
+};
 
 mod alerts;
-pub use alerts::AlertsQueryBuilder;
+pub use alerts::{AlertsQuery, AlertsQueryBuilder};
 
 mod errors;
 pub use errors::*;
 
+#[cfg(feature = "compression")]
+mod content_encoding;
+
+mod i18n;
+pub use i18n::Language;
+
+mod attribution;
+pub use attribution::{BRIGHT_SKY_URL, DWD_URL, attribution, attribution_html};
+
+mod tz;
+pub use tz::TzName;
+
+mod coordinates;
+pub use coordinates::{
+    CoordinateFuzzing, Coordinates, DEFAULT_COORDINATE_PRECISION, QueryLocation,
+};
+
 /// Base URL for the Bright Sky API
 pub const BRIGHT_SKY_API: &str = "https://api.brightsky.dev";
 
-/// Trait for converting query builders into Bright Sky API URLs.
+/// Trait for converting built queries into Bright Sky API URLs.
 ///
-/// This trait is implemented by all query builder types to convert
-/// their parameters into properly formatted API URLs.
+/// This trait is implemented by the query types returned by the builders'
+/// `build()` to convert their parameters into properly formatted API URLs.
 ///
 /// # Examples
 ///
@@ -169,6 +330,30 @@ pub trait ToBrightSkyClientUrl: ToBrightSkyUrl {}
 #[allow(deprecated)]
 impl<T: ToBrightSkyUrl> ToBrightSkyClientUrl for T {}
 
+/// `Send` on native targets, no bound on `wasm32`.
+///
+/// Futures and values that cross an `.await` in the reqwest integration and in
+/// alert sinks are bound by this trait so that the same code compiles for
+/// native targets, where it can be spawned on multi-threaded runtimes, and for
+/// the browser, where nothing is `Send`.
+#[cfg(not(target_arch = "wasm32"))]
+pub trait MaybeSend: Send {}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: Send> MaybeSend for T {}
+
+/// `Send` on native targets, no bound on `wasm32`.
+///
+/// Futures and values that cross an `.await` in the reqwest integration and in
+/// alert sinks are bound by this trait so that the same code compiles for
+/// native targets, where it can be spawned on multi-threaded runtimes, and for
+/// the browser, where nothing is `Send`.
+#[cfg(target_arch = "wasm32")]
+pub trait MaybeSend {}
+
+#[cfg(target_arch = "wasm32")]
+impl<T> MaybeSend for T {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -238,6 +423,33 @@ mod tests {
         assert!(url.query().unwrap().contains("lon=13.4"));
     }
 
+    #[cfg(not(feature = "std"))]
+    #[test]
+    fn test_radar_and_alerts_url_strings_without_std() {
+        let radar = RadarWeatherQueryBuilder::new()
+            .with_bbox([100, 100, 299, 299])
+            .with_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap())
+            .with_last_date(NaiveDate::from_ymd_opt(2023, 8, 8).unwrap())
+            .with_compression_format(types::RadarCompressionFormat::Plain)
+            .with_tz("Europe/Berlin")
+            .build()
+            .unwrap();
+        assert_eq!(
+            radar.to_url_string("https://api.brightsky.dev/").unwrap(),
+            "https://api.brightsky.dev/radar?bbox=100,100,299,299&date=2023-08-07&last_date=2023-08-08&format=plain&tz=Europe/Berlin"
+        );
+
+        let alerts = AlertsQueryBuilder::new()
+            .with_warn_cell_id(803159016)
+            .with_tz("UTC")
+            .build()
+            .unwrap();
+        assert_eq!(
+            alerts.to_url_string(BRIGHT_SKY_API).unwrap(),
+            "https://api.brightsky.dev/alerts?warn_cell_id=803159016&tz=UTC"
+        );
+    }
+
     #[test]
     fn test_url_string_generation() {
         let query = CurrentWeatherQueryBuilder::new()
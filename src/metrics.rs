@@ -0,0 +1,89 @@
+//! Operational metrics via the [`metrics`](https://docs.rs/metrics) facade.
+//!
+//! With the `metrics` feature, [`BrightSkyClient`](crate::BrightSkyClient)
+//! records every request and [`WeatherService`](crate::service::WeatherService)
+//! (with the `axum` feature) its cache lookups. Install any recorder, e.g.
+//! `metrics-exporter-prometheus`, to expose them:
+//!
+//! ```rust,ignore
+//! metrics_exporter_prometheus::PrometheusBuilder::new().install()?;
+//! brightsky::metrics::describe();
+//! ```
+//!
+//! | Metric | Type | Labels |
+//! |--------|------|--------|
+//! | [`REQUESTS_TOTAL`] | counter | `endpoint` |
+//! | [`REQUEST_DURATION_SECONDS`] | histogram | `endpoint` |
+//! | [`ERRORS_TOTAL`] | counter | `endpoint`, `code` |
+//! | [`CACHE_LOOKUPS_TOTAL`] | counter | `endpoint`, `result` |
+//!
+//! `endpoint` is the API path without the leading slash, e.g.
+//! `current_weather`. `code` is the HTTP status of API errors, `transport` for
+//! failed requests and `decode` for response bodies that could not be
+//! deserialized. `result` is `hit` or `miss`.
+//!
+//! Without a recorder, recording is a no-op.
+
+use std::time::Duration;
+
+use ::metrics::{Unit, counter, describe_counter, describe_histogram, histogram};
+
+use crate::ClientError;
+
+/// Requests sent by `BrightSkyClient`.
+pub const REQUESTS_TOTAL: &str = "brightsky_requests_total";
+
+/// Time from sending a request to the deserialized response or error.
+pub const REQUEST_DURATION_SECONDS: &str = "brightsky_request_duration_seconds";
+
+/// Failed requests of `BrightSkyClient`.
+pub const ERRORS_TOTAL: &str = "brightsky_errors_total";
+
+/// Cache lookups of `WeatherService`.
+pub const CACHE_LOOKUPS_TOTAL: &str = "brightsky_cache_lookups_total";
+
+/// Register units and descriptions of all metrics with the installed recorder.
+///
+/// Optional, but exporters use the descriptions as help texts.
+pub fn describe() {
+    describe_counter!(REQUESTS_TOTAL, "Requests sent to the Bright Sky API");
+    describe_histogram!(
+        REQUEST_DURATION_SECONDS,
+        Unit::Seconds,
+        "Duration of Bright Sky API requests"
+    );
+    describe_counter!(ERRORS_TOTAL, "Failed Bright Sky API requests");
+    describe_counter!(CACHE_LOOKUPS_TOTAL, "Cache lookups of the weather service");
+}
+
+/// Record a finished request to `url`.
+pub(crate) fn record_request<T, E>(
+    url: &str,
+    elapsed: Duration,
+    result: &Result<T, ClientError<E>>,
+) {
+    let endpoint = endpoint(url);
+    counter!(REQUESTS_TOTAL, "endpoint" => endpoint.clone()).increment(1);
+    histogram!(REQUEST_DURATION_SECONDS, "endpoint" => endpoint.clone()).record(elapsed);
+
+    let code = match result {
+        Ok(_) | Err(ClientError::Query(_)) => return,
+        Err(ClientError::Api(err)) => err.status.to_string(),
+        Err(ClientError::Http(_)) => "transport".to_string(),
+        Err(ClientError::Json(_) | ClientError::Decode(_)) => "decode".to_string(),
+    };
+    counter!(ERRORS_TOTAL, "endpoint" => endpoint, "code" => code).increment(1);
+}
+
+/// Record a cache lookup of the `endpoint` responses.
+#[cfg(feature = "axum")]
+pub(crate) fn record_cache_lookup(endpoint: &'static str, hit: bool) {
+    let result = if hit { "hit" } else { "miss" };
+    counter!(CACHE_LOOKUPS_TOTAL, "endpoint" => endpoint, "result" => result).increment(1);
+}
+
+/// Last path segment of a request URL.
+fn endpoint(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    path.rsplit('/').next().unwrap_or_default().to_string()
+}
@@ -0,0 +1,253 @@
+//! Precipitation nowcast for a single location.
+//!
+//! [`BrightSkyClient::nowcast`] answers "will it rain in the next two hours
+//! here?": it requests a few radar pixels around a coordinate and returns the
+//! precipitation of the pixel containing it, for the latest radar observation
+//! and every forecast frame after it:
+//!
+//! ```rust,no_run
+//! # #[cfg(feature = "reqwest")]
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! use brightsky::BrightSkyClient;
+//!
+//! let client = BrightSkyClient::new(reqwest::Client::new());
+//!
+//! for (time, mm) in client.nowcast((52.52, 13.4)).await? {
+//!     println!("{}: {:.2} mm", time.format("%H:%M"), mm);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Values are in mm per 5 minutes. [`RadarResponse::precipitation_at_position`]
+//! extracts the same series from a response of a custom radar query.
+//!
+//! ## Outlook
+//!
+//! [`BrightSkyClient::precipitation_outlook`] combines the nowcast with the
+//! hourly `/weather` forecast into 5-minute steps for the next two hours.
+//! Radar is most reliable shortly after the observation, so the first hour uses
+//! the radar value alone and the second hour fades linearly to the hourly
+//! forecast, spread evenly over its hour. Steps without a radar frame use the
+//! forecast alone. [`blend_outlook`] does the same for already fetched data.
+//!
+//! With the `std` feature, `nowcast_with_provenance` and
+//! `precipitation_outlook_with_provenance` also return the `Provenance` of
+//! their requests.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use chrono::{DateTime, FixedOffset, TimeDelta};
+
+use crate::{
+    BrightSkyClient, BrightSkyError, ClientError, HttpClient, RadarWeatherQuery,
+    RadarWeatherQueryBuilder, WeatherQuery, WeatherQueryBuilder,
+    types::{RadarCompressionFormat, RadarResponse, Weather, WeatherResponse},
+};
+
+#[cfg(feature = "std")]
+use crate::provenance::Provenance;
+
+/// Radius in meters of the radar area requested by [`BrightSkyClient::nowcast`].
+pub const DEFAULT_NOWCAST_DISTANCE: u64 = 2_000;
+
+/// Minutes after the radar observation covered by [`blend_outlook`].
+pub const OUTLOOK_MINUTES: i64 = 120;
+
+/// One 5-minute step of a precipitation outlook.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OutlookStep {
+    /// Timestamp of the step
+    pub time: DateTime<FixedOffset>,
+    /// Blended precipitation (mm / 5 min)
+    pub precipitation: f64,
+    /// Radar precipitation of this step, if there is a frame for it (mm / 5 min)
+    pub radar: Option<f64>,
+    /// Hourly forecast precipitation spread over the hour (mm / 5 min)
+    pub forecast: Option<f64>,
+    /// Probability of >0.1mm precipitation in the hour of this step (percentage)
+    pub probability: Option<i64>,
+}
+
+/// Blend a nowcast series with hourly records into a 5-minute outlook, see the
+/// [`nowcast`](crate::nowcast) module.
+///
+/// The outlook starts at the first nowcast frame and covers
+/// [`OUTLOOK_MINUTES`]. Steps with neither a radar frame nor an hourly
+/// precipitation value are left out, so it is empty without a nowcast.
+pub fn blend_outlook(
+    nowcast: &[(DateTime<FixedOffset>, f64)],
+    hourly: &[Weather],
+) -> Vec<OutlookStep> {
+    let Some(&(start, _)) = nowcast.first() else {
+        return Vec::new();
+    };
+
+    (0..=OUTLOOK_MINUTES)
+        .step_by(5)
+        .filter_map(|lead| {
+            let time = start + TimeDelta::minutes(lead);
+            let radar = nowcast
+                .iter()
+                .find(|(frame, _)| *frame == time)
+                .map(|(_, mm)| *mm);
+            // Hourly records describe the hour before their timestamp
+            let record = hourly
+                .iter()
+                .find(|r| r.timestamp - TimeDelta::hours(1) <= time && time < r.timestamp);
+            let forecast = record.and_then(|r| r.precipitation).map(|mm| mm / 12.0);
+
+            let precipitation = match (radar, forecast) {
+                (Some(radar), Some(forecast)) => {
+                    let weight = ((OUTLOOK_MINUTES - lead) as f64 / 60.0).min(1.0);
+                    weight * radar + (1.0 - weight) * forecast
+                }
+                (Some(value), None) | (None, Some(value)) => value,
+                (None, None) => return None,
+            };
+            Some(OutlookStep {
+                time,
+                precipitation,
+                radar,
+                forecast,
+                probability: record.and_then(|r| r.precipitation_probability),
+            })
+        })
+        .collect()
+}
+
+impl RadarResponse {
+    /// Precipitation in mm / 5 min at `latlon_position`, per frame in
+    /// chronological order.
+    ///
+    /// Empty if the response has no `latlon_position` (i.e. the query did not
+    /// use `lat`/`lon`) or the position lies outside the bounding box.
+    pub fn precipitation_at_position(&self) -> Vec<(DateTime<FixedOffset>, f64)> {
+        let (Some(position), Some(&[top, left, ..])) =
+            (&self.latlon_position, self.bbox.as_deref())
+        else {
+            return Vec::new();
+        };
+        // Positions are pixel centers relative to the full grid
+        let x = position.x - left as f64 + 0.5;
+        let y = position.y - top as f64 + 0.5;
+        if x < 0.0 || y < 0.0 {
+            return Vec::new();
+        }
+
+        self.frames()
+            .filter_map(|frame| {
+                let value = frame.grid.get(x as usize, y as usize)?;
+                Some((frame.time, f64::from(value) / 100.0))
+            })
+            .collect()
+    }
+}
+
+impl<C: HttpClient> BrightSkyClient<C> {
+    /// Precipitation series at a location, see the [`nowcast`](crate::nowcast)
+    /// module.
+    ///
+    /// Starts with the latest radar observation, followed by the forecast
+    /// frames of the next two hours.
+    pub async fn nowcast(
+        &self,
+        location: impl Into<(f64, f64)>,
+    ) -> Result<Vec<(DateTime<FixedOffset>, f64)>, ClientError<C::Error>> {
+        let response = self.get_radar(nowcast_query(location)?).await?;
+        Ok(nowcast_series(&response))
+    }
+
+    /// Like [`nowcast`](Self::nowcast), also returning where the radar data
+    /// came from.
+    ///
+    /// Only available with the `std` feature.
+    #[cfg(feature = "std")]
+    pub async fn nowcast_with_provenance(
+        &self,
+        location: impl Into<(f64, f64)>,
+    ) -> Result<(Vec<(DateTime<FixedOffset>, f64)>, Provenance), ClientError<C::Error>> {
+        let query = nowcast_query(location)?;
+        let format = query.response_format();
+        let (response, provenance): (RadarResponse, _) = self.get_with_provenance(query).await?;
+        let response = response.into_format(format).map_err(ClientError::Decode)?;
+        Ok((nowcast_series(&response), provenance))
+    }
+
+    /// Precipitation outlook in 5-minute steps, see the
+    /// [`nowcast`](crate::nowcast) module.
+    ///
+    /// Sends a radar and a `/weather` request. Empty if there is no radar
+    /// data for the location.
+    pub async fn precipitation_outlook(
+        &self,
+        location: impl Into<(f64, f64)>,
+    ) -> Result<Vec<OutlookStep>, ClientError<C::Error>> {
+        let location = location.into();
+        let nowcast = self.nowcast(location).await?;
+        let Some(&(start, _)) = nowcast.first() else {
+            return Ok(Vec::new());
+        };
+
+        let hourly: WeatherResponse = self.get(outlook_query(location, start)?).await?;
+        Ok(blend_outlook(&nowcast, &hourly.weather))
+    }
+
+    /// Like [`precipitation_outlook`](Self::precipitation_outlook), also
+    /// returning where the data came from.
+    ///
+    /// The provenance lists the radar request followed by the `/weather`
+    /// request, which is only sent if there is radar data.
+    ///
+    /// Only available with the `std` feature.
+    #[cfg(feature = "std")]
+    pub async fn precipitation_outlook_with_provenance(
+        &self,
+        location: impl Into<(f64, f64)>,
+    ) -> Result<(Vec<OutlookStep>, Provenance), ClientError<C::Error>> {
+        let location = location.into();
+        let (nowcast, provenance) = self.nowcast_with_provenance(location).await?;
+        let Some(&(start, _)) = nowcast.first() else {
+            return Ok((Vec::new(), provenance));
+        };
+
+        let (hourly, hourly_provenance): (WeatherResponse, _) = self
+            .get_with_provenance(outlook_query(location, start)?)
+            .await?;
+        Ok((
+            blend_outlook(&nowcast, &hourly.weather),
+            provenance.combine(hourly_provenance),
+        ))
+    }
+}
+
+/// The radar query of [`BrightSkyClient::nowcast`].
+fn nowcast_query(location: impl Into<(f64, f64)>) -> Result<RadarWeatherQuery, BrightSkyError> {
+    RadarWeatherQueryBuilder::new()
+        .with_lat_lon(location)
+        .with_distance(DEFAULT_NOWCAST_DISTANCE)
+        .with_compression_format(RadarCompressionFormat::Compressed)
+        .build()
+}
+
+/// The series at the location from the latest radar observation on.
+fn nowcast_series(response: &RadarResponse) -> Vec<(DateTime<FixedOffset>, f64)> {
+    let mut series = response.precipitation_at_position();
+    let latest_observation = response.radar.iter().filter_map(|r| r.source_time()).max();
+    if let Some(latest_observation) = latest_observation {
+        series.retain(|(time, _)| *time >= latest_observation);
+    }
+    series
+}
+
+/// The hourly records blended into an outlook starting at `start`.
+fn outlook_query(
+    location: (f64, f64),
+    start: DateTime<FixedOffset>,
+) -> Result<WeatherQuery, BrightSkyError> {
+    WeatherQueryBuilder::new()
+        .with_lat_lon(location)
+        .with_datetime_range(start, start + TimeDelta::minutes(OUTLOOK_MINUTES + 60))
+        .build()
+}
@@ -0,0 +1,541 @@
+//! Building blocks for long-running ingestion services.
+//!
+//! A [`Pipeline`] periodically fetches the `/weather` records of a few stations,
+//! keeps only the records it has not seen before and hands them to a
+//! [`WeatherSink`]. Continuously archiving five stations into a CSV file looks
+//! like this:
+//!
+//! ```rust,no_run
+//! # #[cfg(feature = "reqwest")]
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! use brightsky::{BrightSkyClient, export::ExportProfile};
+//! use brightsky::pipeline::{CsvAppender, Pipeline, PipelineConfig};
+//! use std::time::Duration;
+//!
+//! let client = BrightSkyClient::new(reqwest::Client::new());
+//! let config = PipelineConfig::new(
+//!     ["01766", "10315", "00433", "01975", "03379"],
+//!     Duration::from_secs(3600),
+//! );
+//! let sink = CsvAppender::open("archive.csv", ExportProfile::Full)?;
+//!
+//! let mut pipeline = Pipeline::new(client, config, sink);
+//! loop {
+//!     // Fetch and sink errors stop `run`; its state is kept, so just resume
+//!     if let Err(err) = pipeline.run().await {
+//!         eprintln!("{}", err);
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! Each tick requests the records from `lookback_days` days before today up to
+//! tomorrow. Records are deduplicated by source ID and timestamp, so forecasts
+//! are written once and observations for the same hours are written again when
+//! they arrive (with the source ID of the observing station).
+//!
+//! Besides [`CsvAppender`], batches can go to SQLite (`SqliteSink`, with the
+//! `sqlite` feature), an MQTT broker (`MqttSink`, with the `home-assistant`
+//! feature) and InfluxDB or VictoriaMetrics (`InfluxWriter` implements
+//! [`WeatherSink`] with the `influx` feature). Sinks for other stores implement
+//! [`WeatherSink`] themselves. `HomeAssistantPublisher` is not a sink: it
+//! publishes the current weather of a location rather than hourly records.
+//!
+//! ## Shutdown and Backpressure
+//!
+//! [`Pipeline::run_until`] stops when a shutdown future completes, such as
+//! `token.cancelled()` of a `tokio_util::sync::CancellationToken`. It stops
+//! right away while waiting for the next tick and finishes a running tick
+//! first, so no batch is half-written.
+//!
+//! Sinks are awaited before the pipeline continues, so a slow sink delays the
+//! following fetches rather than piling up records in memory. [`ChannelSink`]
+//! hands batches to another task through a bounded channel with the same
+//! behavior.
+//!
+//! Only available with the `stream` feature.
+
+use std::{collections::BTreeMap, fs::File, future::Future, io, path::Path, pin::pin};
+
+use chrono::{Days, Utc};
+use futures_util::{
+    FutureExt,
+    future::{Either, select},
+};
+use tokio::sync::mpsc;
+
+use crate::{
+    BrightSkyClient, ClientError, HttpClient, TzName, WeatherQueryBuilder,
+    export::ExportProfile,
+    formatting::FormatOptions,
+    poll::{PollSchedule, WeatherDeduplicator},
+    types::{UnitType, WeatherResponse},
+};
+
+#[cfg(feature = "home-assistant")]
+use crate::home_assistant::{MqttMessage, MqttPublisher};
+#[cfg(feature = "influx")]
+use crate::influx::{InfluxError, InfluxWriter};
+
+/// Destination for the new records of a [`Pipeline`].
+pub trait WeatherSink {
+    /// Error writing to the sink
+    type Error;
+
+    /// Store new records of one station.
+    ///
+    /// `batch` only contains records that were not written before and the
+    /// sources they reference.
+    fn write(
+        &mut self,
+        station: &str,
+        batch: &WeatherResponse,
+    ) -> impl Future<Output = Result<(), Self::Error>>;
+}
+
+/// A [`WeatherSink`] appending CSV rows to a file.
+///
+/// The header row is written with the first rows if the file was empty. Rows
+/// are written with blocking I/O, which is fine for the few records of a tick.
+#[derive(Debug)]
+pub struct CsvAppender {
+    file: File,
+    profile: ExportProfile,
+    format: FormatOptions,
+    needs_header: bool,
+}
+
+impl CsvAppender {
+    /// Open or create `path` for appending.
+    pub fn open(path: impl AsRef<Path>, profile: ExportProfile) -> io::Result<Self> {
+        let file = File::options().create(true).append(true).open(path)?;
+        let needs_header = file.metadata()?.len() == 0;
+        Ok(Self {
+            file,
+            profile,
+            format: FormatOptions::default(),
+            needs_header,
+        })
+    }
+
+    /// Set the formatting of weather values.
+    pub fn with_format(mut self, format: FormatOptions) -> Self {
+        self.format = format;
+        self
+    }
+}
+
+impl WeatherSink for CsvAppender {
+    type Error = io::Error;
+
+    async fn write(&mut self, _station: &str, batch: &WeatherResponse) -> io::Result<()> {
+        if self.needs_header {
+            batch.write_csv_with(&mut self.file, self.profile, &self.format)?;
+            self.needs_header = false;
+            Ok(())
+        } else {
+            batch.write_csv_rows(&mut self.file, self.profile, &self.format)
+        }
+    }
+}
+
+/// A [`WeatherSink`] sending batches into a bounded channel.
+///
+/// When the channel is full, writing waits until the receiver catches up, which
+/// delays the pipeline's ticks instead of buffering an unbounded backlog. The
+/// sink fails once the receiver is dropped.
+#[derive(Debug, Clone)]
+pub struct ChannelSink {
+    sender: mpsc::Sender<(String, WeatherResponse)>,
+}
+
+impl ChannelSink {
+    /// Create a sink and the receiving end of its channel, buffering up to
+    /// `capacity` batches (at least 1).
+    pub fn new(capacity: usize) -> (Self, mpsc::Receiver<(String, WeatherResponse)>) {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        (Self { sender }, receiver)
+    }
+}
+
+impl WeatherSink for ChannelSink {
+    type Error = mpsc::error::SendError<(String, WeatherResponse)>;
+
+    async fn write(&mut self, station: &str, batch: &WeatherResponse) -> Result<(), Self::Error> {
+        self.sender.send((station.to_string(), batch.clone())).await
+    }
+}
+
+/// A [`WeatherSink`] storing records in an SQLite table.
+///
+/// Records are kept as JSON in a table with one row per source ID and
+/// timestamp:
+///
+/// ```sql
+/// CREATE TABLE weather (
+///     station TEXT NOT NULL,
+///     source_id INTEGER NOT NULL,
+///     timestamp TEXT NOT NULL,
+///     record TEXT NOT NULL,
+///     PRIMARY KEY (source_id, timestamp)
+/// )
+/// ```
+///
+/// Timestamps are RFC 3339 strings, and single values can be queried with
+/// `json_extract(record, '$.temperature')`. A record written again replaces
+/// the stored one, so restarting a pipeline does not duplicate rows. Each batch
+/// is written in one transaction with blocking I/O.
+///
+/// Only available with the `sqlite` feature.
+#[cfg(feature = "sqlite")]
+#[derive(Debug)]
+pub struct SqliteSink {
+    connection: rusqlite::Connection,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteSink {
+    /// Open or create the database at `path`.
+    pub fn open(path: impl AsRef<Path>) -> rusqlite::Result<Self> {
+        Self::new(rusqlite::Connection::open(path)?)
+    }
+
+    /// Store records through an open connection, creating the `weather` table
+    /// if needed.
+    pub fn new(connection: rusqlite::Connection) -> rusqlite::Result<Self> {
+        connection.execute(
+            "CREATE TABLE IF NOT EXISTS weather (
+                station TEXT NOT NULL,
+                source_id INTEGER NOT NULL,
+                timestamp TEXT NOT NULL,
+                record TEXT NOT NULL,
+                PRIMARY KEY (source_id, timestamp)
+            )",
+            (),
+        )?;
+        Ok(Self { connection })
+    }
+
+    /// The connection, e.g. to query the stored records.
+    pub fn connection(&self) -> &rusqlite::Connection {
+        &self.connection
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl WeatherSink for SqliteSink {
+    type Error = rusqlite::Error;
+
+    async fn write(&mut self, station: &str, batch: &WeatherResponse) -> rusqlite::Result<()> {
+        let transaction = self.connection.transaction()?;
+        {
+            let mut insert = transaction.prepare_cached(
+                "INSERT OR REPLACE INTO weather (station, source_id, timestamp, record)
+                 VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            for record in &batch.weather {
+                let json = serde_json::to_string(record)
+                    .map_err(|err| rusqlite::Error::ToSqlConversionFailure(Box::new(err)))?;
+                insert.execute((
+                    station,
+                    record.source_id,
+                    record.timestamp.to_rfc3339(),
+                    json,
+                ))?;
+            }
+        }
+        transaction.commit()
+    }
+}
+
+/// A [`WeatherSink`] publishing each batch to an MQTT broker.
+///
+/// The batch is published as a [`WeatherResponse`] JSON document to
+/// `{prefix}/{station}`, without the retain flag. Works with any
+/// [`MqttPublisher`], such as `rumqttc::AsyncClient`.
+///
+/// Only available with the `home-assistant` feature.
+#[cfg(feature = "home-assistant")]
+#[derive(Debug, Clone)]
+pub struct MqttSink<P> {
+    mqtt: P,
+    prefix: String,
+}
+
+#[cfg(feature = "home-assistant")]
+impl<P: MqttPublisher> MqttSink<P> {
+    /// Publish below the topic `prefix`, such as `brightsky/weather`.
+    pub fn new(mqtt: P, prefix: impl Into<String>) -> Self {
+        Self {
+            mqtt,
+            prefix: prefix.into(),
+        }
+    }
+
+    /// The topic batches of `station` are published to.
+    pub fn topic(&self, station: &str) -> String {
+        format!("{}/{}", self.prefix.trim_end_matches('/'), station)
+    }
+}
+
+#[cfg(feature = "home-assistant")]
+impl<P: MqttPublisher> WeatherSink for MqttSink<P> {
+    type Error = P::Error;
+
+    async fn write(&mut self, station: &str, batch: &WeatherResponse) -> Result<(), P::Error> {
+        let message = MqttMessage {
+            topic: self.topic(station),
+            payload: serde_json::to_string(batch).unwrap_or_default(),
+            retain: false,
+        };
+        self.mqtt.publish(message).await
+    }
+}
+
+/// Writes the records of each batch with [`InfluxWriter::write_weather`].
+#[cfg(feature = "influx")]
+impl WeatherSink for InfluxWriter {
+    type Error = InfluxError;
+
+    async fn write(&mut self, _station: &str, batch: &WeatherResponse) -> Result<(), InfluxError> {
+        self.write_weather(batch).await
+    }
+}
+
+/// What a [`Pipeline`] fetches and how often.
+#[derive(Debug, Clone)]
+pub struct PipelineConfig {
+    /// DWD station IDs to fetch
+    pub stations: Vec<String>,
+    /// When to fetch
+    pub schedule: PollSchedule,
+    /// Days before today to request on every tick, to pick up late observations
+    pub lookback_days: u32,
+    /// Timezone for timestamps in the response (tz database format)
+    pub tz: Option<String>,
+    /// Physical units system
+    pub units: Option<UnitType>,
+}
+
+impl PipelineConfig {
+    /// Fetch the given DWD stations according to `schedule`, with one day of lookback.
+    pub fn new(
+        stations: impl IntoIterator<Item = impl Into<String>>,
+        schedule: impl Into<PollSchedule>,
+    ) -> Self {
+        Self {
+            stations: stations.into_iter().map(Into::into).collect(),
+            schedule: schedule.into(),
+            lookback_days: 1,
+            tz: None,
+            units: None,
+        }
+    }
+
+    /// Set the days before today to request on every tick.
+    pub fn with_lookback_days(mut self, days: u32) -> Self {
+        self.lookback_days = days;
+        self
+    }
+
+    /// Set the timezone for timestamps in the response (tz database format).
+    pub fn with_tz(mut self, tz: impl TzName) -> Self {
+        self.tz = Some(tz.tz_name().into());
+        self
+    }
+
+    /// Set the physical units system.
+    pub fn with_units(mut self, units: UnitType) -> Self {
+        self.units = Some(units);
+        self
+    }
+}
+
+/// Error of a [`Pipeline`] tick.
+#[derive(Debug)]
+pub enum PipelineError<H, S> {
+    /// Fetching the records of a station failed
+    Fetch {
+        /// DWD station ID
+        station: String,
+        /// The client error
+        error: ClientError<H>,
+    },
+    /// Writing the records of a station to the sink failed
+    Sink {
+        /// DWD station ID
+        station: String,
+        /// The sink error
+        error: S,
+    },
+}
+
+impl<H: core::fmt::Display, S: core::fmt::Display> core::fmt::Display for PipelineError<H, S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Fetch { station, error } => {
+                write!(f, "Fetching station {} failed: {}", station, error)
+            }
+            Self::Sink { station, error } => {
+                write!(f, "Writing station {} failed: {}", station, error)
+            }
+        }
+    }
+}
+
+impl<H, S> std::error::Error for PipelineError<H, S>
+where
+    H: std::error::Error + 'static,
+    S: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Fetch { error, .. } => Some(error),
+            Self::Sink { error, .. } => Some(error),
+        }
+    }
+}
+
+/// Scheduler, incremental fetch and sink wired together, see the
+/// [`pipeline`](crate::pipeline) module.
+#[derive(Debug)]
+pub struct Pipeline<C, S> {
+    client: BrightSkyClient<C>,
+    config: PipelineConfig,
+    sink: S,
+    seen: BTreeMap<String, WeatherDeduplicator>,
+    ticks: u64,
+}
+
+impl<C: HttpClient, S: WeatherSink> Pipeline<C, S> {
+    /// Create a pipeline that has not seen any records yet.
+    pub fn new(client: BrightSkyClient<C>, config: PipelineConfig, sink: S) -> Self {
+        Self {
+            client,
+            config,
+            sink,
+            seen: BTreeMap::new(),
+            ticks: 0,
+        }
+    }
+
+    /// The configuration.
+    pub fn config(&self) -> &PipelineConfig {
+        &self.config
+    }
+
+    /// The sink.
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    /// Mutable access to the sink, e.g. to flush it.
+    pub fn sink_mut(&mut self) -> &mut S {
+        &mut self.sink
+    }
+
+    /// Run ticks according to the schedule until an error occurs.
+    ///
+    /// The first call waits for the schedule offset, later calls (e.g. after an
+    /// error) wait for the interval. Records written before the error are not
+    /// written again, records that failed to be written are retried.
+    pub async fn run(&mut self) -> Result<(), PipelineError<C::Error, S::Error>> {
+        self.run_until(core::future::pending()).await
+    }
+
+    /// Like [`Pipeline::run`], returning `Ok(())` once `shutdown` completes.
+    ///
+    /// `shutdown` can be any future, e.g. `token.cancelled()` of a
+    /// `tokio_util::sync::CancellationToken` or `tokio::signal::ctrl_c()`. While
+    /// waiting for the next tick, the pipeline stops immediately. A running tick
+    /// is finished first, so every fetched batch is either written completely or
+    /// retried by the next run.
+    pub async fn run_until(
+        &mut self,
+        shutdown: impl Future<Output = ()>,
+    ) -> Result<(), PipelineError<C::Error, S::Error>> {
+        let mut shutdown = pin!(shutdown);
+        loop {
+            let delay = if self.ticks == 0 {
+                self.config.schedule.offset()
+            } else {
+                self.config.schedule.interval()
+            };
+            if delay.is_zero() {
+                if shutdown.as_mut().now_or_never().is_some() {
+                    return Ok(());
+                }
+            } else {
+                let sleep = pin!(tokio::time::sleep(delay));
+                if let Either::Left(_) = select(shutdown.as_mut(), sleep).await {
+                    return Ok(());
+                }
+            }
+            self.tick().await?;
+        }
+    }
+
+    /// Fetch every station once and write new records to the sink.
+    ///
+    /// Returns the number of records written. Stops at the first failing station.
+    pub async fn tick(&mut self) -> Result<usize, PipelineError<C::Error, S::Error>> {
+        self.ticks += 1;
+
+        let today = Utc::now().date_naive();
+        let date = today - Days::new(u64::from(self.config.lookback_days));
+        let last_date = today + Days::new(1);
+        // Keep a day of margin for timezone offsets of the query date
+        let cutoff = (date - Days::new(1))
+            .and_time(chrono::NaiveTime::MIN)
+            .and_utc()
+            .fixed_offset();
+
+        let mut written = 0;
+        for station in &self.config.stations {
+            let mut query = WeatherQueryBuilder::new()
+                .with_date(date)
+                .with_last_date(last_date)
+                .with_dwd_station_id(vec![station.as_str()]);
+            if let Some(tz) = &self.config.tz {
+                query = query.with_tz(tz);
+            }
+            if let Some(units) = self.config.units {
+                query = query.with_units(units);
+            }
+
+            let fetched = match query.build() {
+                Ok(query) => self.client.get::<_, WeatherResponse>(query).await,
+                Err(err) => Err(err.into()),
+            };
+            let response = fetched.map_err(|error| PipelineError::Fetch {
+                station: station.clone(),
+                error,
+            })?;
+
+            let seen = self.seen.entry(station.clone()).or_default();
+            seen.forget_before(cutoff);
+            // Restored if the sink fails, so the records are retried next tick
+            let before = seen.clone();
+            let weather = seen.filter_new(response.weather);
+            if weather.is_empty() {
+                continue;
+            }
+
+            let sources = response
+                .sources
+                .into_iter()
+                .filter(|source| weather.iter().any(|record| record.source_id == source.id))
+                .collect();
+            let batch = WeatherResponse { weather, sources };
+            if let Err(error) = self.sink.write(station, &batch).await {
+                *seen = before;
+                return Err(PipelineError::Sink {
+                    station: station.clone(),
+                    error,
+                });
+            }
+            written += batch.weather.len();
+        }
+        Ok(written)
+    }
+}
@@ -0,0 +1,909 @@
+//! Incremental polling helpers for the weather (`/weather`) and alerts
+//! (`/alerts`) endpoints.
+//!
+//! Applications that periodically re-fetch the same `/weather` query (for example
+//! to archive observations into a database) usually only care about records they
+//! have not seen before. [`WeatherPoller`] remembers the newest record timestamp
+//! seen for each query and trims subsequent responses down to the new records.
+//!
+//! The poller does not perform any HTTP requests itself. You keep using your own
+//! HTTP client and hand the query and response to the poller around each fetch.
+//!
+//! ## Usage
+//!
+//! ```rust,no_run
+//! use brightsky::{WeatherQueryBuilder, ToBrightSkyUrl, BRIGHT_SKY_API, poll::WeatherPoller, types::WeatherResponse};
+//! use chrono::NaiveDate;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let mut poller = WeatherPoller::new();
+//!
+//!     loop {
+//!         let query = poller.prepare(
+//!             WeatherQueryBuilder::new()
+//!                 .with_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap())
+//!                 .with_lat_lon((52.52, 13.4))
+//!                 .build()?,
+//!         );
+//!
+//!         let url = query.clone().to_url(BRIGHT_SKY_API)?;
+//!         let response: WeatherResponse = reqwest::get(url).await?.json().await?;
+//!
+//!         // `None` means nothing new arrived since the last poll
+//!         if let Some(new) = poller.ingest(&query, response) {
+//!             println!("{} new records", new.weather.len());
+//!         }
+//!
+//!         tokio::time::sleep(std::time::Duration::from_secs(600)).await;
+//!     }
+//! }
+//! ```
+//!
+//! ## Alerts
+//!
+//! [`AlertsWatcher`] compares consecutive `/alerts` responses and reports which
+//! alerts were issued, updated or lifted in between, identified by `alert_id`.
+//! With the `stream` feature, `alerts_stream` polls a query on an interval and
+//! yields these changes as [`AlertEvent`]s.
+//! [`AlertsDiff::between`] compares two responses directly, without keeping
+//! state between polls.
+//!
+//! [`AlertsWatcher::forward`] sends the changes to an [`AlertSink`], e.g. a
+//! webhook.
+//!
+//! ## Scans
+//!
+//! With the `stream` feature, `alerts_scan` fetches a long list of `/alerts`
+//! queries (e.g. every warn cell of a state) one by one within a [`RateLimit`]
+//! and yields the responses as they arrive.
+//!
+//! ## Shutdown and Backpressure
+//!
+//! The streams are pull-based: the next fetch only happens when the consumer
+//! asks for the next item after the buffered ones, so a slow consumer delays
+//! polling instead of accumulating unbounded buffers. A fetch is never issued
+//! earlier than the schedule allows. With the `stream` feature,
+//! `until_shutdown` ends a stream when a cancellation future completes.
+//!
+//! ## Fleets
+//!
+//! The streams accept a [`PollSchedule`] instead of a plain interval. Its
+//! per-device jitter keeps many devices polling the same endpoint from sending
+//! their requests at the same moment.
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    format,
+    string::String,
+    vec::Vec,
+};
+
+#[cfg(feature = "std")]
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{DateTime, FixedOffset};
+
+use crate::{
+    WeatherQuery,
+    sink::AlertSink,
+    types::{Alert, AlertsResponse, Weather, WeatherResponse},
+};
+
+/// Remembers the newest record timestamp per `/weather` query.
+///
+/// Queries are identified by their location, timezone and unit parameters; the
+/// `date` and `last_date` parameters are ignored so that a query whose start date
+/// was moved forward by [`WeatherPoller::prepare`] is still recognized.
+#[derive(Debug, Clone)]
+pub struct WeatherPoller {
+    last_seen: BTreeMap<String, DateTime<FixedOffset>>,
+    adjust_date: bool,
+}
+
+impl WeatherPoller {
+    /// Create a new poller with no history.
+    ///
+    /// Date adjustment is enabled by default, see [`WeatherPoller::with_date_adjustment`].
+    pub fn new() -> Self {
+        Self {
+            last_seen: BTreeMap::new(),
+            adjust_date: true,
+        }
+    }
+
+    /// Enable or disable moving the query `date` forward to the newest known record.
+    ///
+    /// When enabled, [`WeatherPoller::prepare`] replaces the query's `date` with the
+    /// day of the newest record already seen, so days that were fully ingested are
+    /// not downloaded again.
+    pub fn with_date_adjustment(mut self, enabled: bool) -> Self {
+        self.adjust_date = enabled;
+        self
+    }
+
+    /// Newest record timestamp seen for the given query, if any.
+    pub fn last_seen(&self, query: &WeatherQuery) -> Option<DateTime<FixedOffset>> {
+        self.last_seen.get(&poll_key(query)).copied()
+    }
+
+    /// Forget all remembered timestamps.
+    pub fn reset(&mut self) {
+        self.last_seen.clear();
+    }
+
+    /// Prepare a query for the next fetch.
+    ///
+    /// If date adjustment is enabled and a record was already seen for this query,
+    /// the `date` parameter is moved forward to the day of that record. It is never
+    /// moved backwards and never past `last_date`.
+    pub fn prepare(&self, mut query: WeatherQuery) -> WeatherQuery {
+        if !self.adjust_date {
+            return query;
+        }
+        if let Some(last_seen) = self.last_seen(&query) {
+            let mut date = last_seen.date_naive();
+            if let Some(last_date) = query.last_date {
+                date = date.min(last_date.date_naive());
+            }
+            if query.date.is_none_or(|current| current.date_naive() < date) {
+                // Moving the start forward within the range keeps the query valid
+                query.builder.date = Some(date.into());
+            }
+        }
+        query
+    }
+
+    /// Trim a response to the records newer than anything seen before for this query.
+    ///
+    /// Returns `None` if the response contains no new records. Otherwise the returned
+    /// response only contains the new records and the sources they reference, and the
+    /// newest timestamp is remembered for the next poll.
+    pub fn ingest(
+        &mut self,
+        query: &WeatherQuery,
+        response: WeatherResponse,
+    ) -> Option<WeatherResponse> {
+        let previous = self.last_seen(query);
+
+        let weather: Vec<_> = response
+            .weather
+            .into_iter()
+            .filter(|record| previous.is_none_or(|previous| record.timestamp > previous))
+            .collect();
+
+        if weather.is_empty() {
+            return None;
+        }
+
+        let sources = response
+            .sources
+            .into_iter()
+            .filter(|source| {
+                weather.iter().any(|record| {
+                    record.source_id == source.id
+                        || record
+                            .fallback_source_ids
+                            .as_ref()
+                            .is_some_and(|ids| ids.values().any(|id| *id == source.id))
+                })
+            })
+            .collect();
+
+        let response = WeatherResponse { weather, sources };
+        self.observe(query, &response);
+        Some(response)
+    }
+
+    /// Remember the newest record of a response without trimming it.
+    ///
+    /// Useful when the records are filtered by other means (e.g. a
+    /// [`WeatherDeduplicator`]) but the date adjustment should still advance.
+    pub fn observe(&mut self, query: &WeatherQuery, response: &WeatherResponse) {
+        let newest = response.weather.iter().map(|record| record.timestamp).max();
+
+        if let Some(newest) = newest {
+            let entry = self.last_seen.entry(poll_key(query)).or_insert(newest);
+            if newest > *entry {
+                *entry = newest;
+            }
+        }
+    }
+}
+
+impl Default for WeatherPoller {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Tracks which hourly records were already emitted, keyed by source ID and timestamp.
+///
+/// Unlike [`WeatherPoller`], which only looks at the newest timestamp, the
+/// deduplicator remembers every record it has let through, so records that show up
+/// again in overlapping responses are emitted exactly once.
+#[derive(Debug, Clone, Default)]
+pub struct WeatherDeduplicator {
+    seen: BTreeSet<(DateTime<FixedOffset>, i64)>,
+}
+
+impl WeatherDeduplicator {
+    /// Create an empty deduplicator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return only the records that were not emitted before, and remember them.
+    pub fn filter_new(&mut self, records: Vec<Weather>) -> Vec<Weather> {
+        records
+            .into_iter()
+            .filter(|record| self.seen.insert((record.timestamp, record.source_id)))
+            .collect()
+    }
+
+    /// Forget all records older than `cutoff` to bound memory usage.
+    pub fn forget_before(&mut self, cutoff: DateTime<FixedOffset>) {
+        self.seen = self.seen.split_off(&(cutoff, i64::MIN));
+    }
+
+    /// Number of remembered records.
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    /// Whether no records are remembered.
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+}
+
+/// When and how often a polling stream fetches.
+///
+/// By default the first fetch happens immediately and then every `interval`.
+/// Fleets of devices that start at the same time (e.g. after a power outage or a
+/// deployment) would then hit the public API in the same second forever.
+/// [`PollSchedule::with_device_jitter`] delays the first fetch by an offset
+/// derived from a device ID, which spreads the fleet evenly over the jitter
+/// window while every device keeps a fixed, reproducible slot.
+///
+/// # Examples
+///
+/// ```rust
+/// use brightsky::poll::PollSchedule;
+/// use std::time::Duration;
+///
+/// let schedule = PollSchedule::new(Duration::from_secs(600))
+///     .with_device_jitter("sensor-0042", Duration::from_secs(600));
+///
+/// assert!(schedule.offset() < Duration::from_secs(600));
+/// // The same device always gets the same offset
+/// assert_eq!(
+///     schedule.offset(),
+///     PollSchedule::new(Duration::from_secs(600))
+///         .with_device_jitter("sensor-0042", Duration::from_secs(600))
+///         .offset()
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PollSchedule {
+    interval: core::time::Duration,
+    offset: core::time::Duration,
+}
+
+impl PollSchedule {
+    /// Fetch immediately and then every `interval`.
+    pub fn new(interval: core::time::Duration) -> Self {
+        Self {
+            interval,
+            offset: core::time::Duration::ZERO,
+        }
+    }
+
+    /// Delay the first fetch by a deterministic offset in `0..spread` derived from
+    /// `device_id`.
+    ///
+    /// Use a stable identifier such as a serial number or MAC address, and a
+    /// `spread` equal to the interval to distribute devices over the whole
+    /// interval. The offset only depends on the ID and the spread, not on the
+    /// platform or crate version.
+    pub fn with_device_jitter(mut self, device_id: &str, spread: core::time::Duration) -> Self {
+        let spread_nanos = spread.as_nanos();
+        self.offset = if spread_nanos == 0 {
+            core::time::Duration::ZERO
+        } else {
+            let nanos = u128::from(device_hash(device_id)) % spread_nanos;
+            core::time::Duration::from_nanos(nanos as u64)
+        };
+        self
+    }
+
+    /// Time between two fetches.
+    pub fn interval(&self) -> core::time::Duration {
+        self.interval
+    }
+
+    /// Delay before the first fetch.
+    pub fn offset(&self) -> core::time::Duration {
+        self.offset
+    }
+}
+
+impl From<core::time::Duration> for PollSchedule {
+    fn from(interval: core::time::Duration) -> Self {
+        Self::new(interval)
+    }
+}
+
+/// FNV-1a over the device ID, finished with a SplitMix64 round so that similar
+/// IDs (`sensor-1`, `sensor-2`) end up far apart.
+fn device_hash(device_id: &str) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in device_id.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
+
+/// Poll a `/weather` query forever and yield every hourly record exactly once.
+///
+/// The query is fetched according to `schedule` (a [`PollSchedule`] or just a
+/// `Duration` interval), using `fetch` to perform the HTTP request. Records are deduplicated by source ID and timestamp
+/// with a [`WeatherDeduplicator`], and the query date is moved forward with a
+/// [`WeatherPoller`] so already ingested days are not downloaded again.
+///
+/// Fetch errors are yielded as `Err` items; the stream keeps polling afterwards.
+///
+/// Only available with the `stream` feature.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use brightsky::{WeatherQueryBuilder, ToBrightSkyUrl, BRIGHT_SKY_API, poll, types::WeatherResponse};
+/// use chrono::NaiveDate;
+/// use futures_util::StreamExt;
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let query = WeatherQueryBuilder::new()
+///         .with_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap())
+///         .with_dwd_station_id(vec!["01766"])
+///         .build()?;
+///
+///     let records = poll::weather_stream(query, Duration::from_secs(600), |query| async move {
+///         let url = query.to_url(BRIGHT_SKY_API)?;
+///         let response: WeatherResponse = reqwest::get(url).await?.json().await?;
+///         Ok::<_, Box<dyn std::error::Error>>(response)
+///     });
+///     let mut records = std::pin::pin!(records);
+///
+///     while let Some(record) = records.next().await {
+///         let record = record?;
+///         println!("{}: {:?}", record.timestamp, record.temperature);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "stream")]
+pub fn weather_stream<F, Fut, E>(
+    query: WeatherQuery,
+    schedule: impl Into<PollSchedule>,
+    fetch: F,
+) -> impl futures_util::Stream<Item = Result<Weather, E>>
+where
+    F: FnMut(WeatherQuery) -> Fut,
+    Fut: core::future::Future<Output = Result<WeatherResponse, E>>,
+{
+    use std::collections::VecDeque;
+
+    struct State<F> {
+        query: WeatherQuery,
+        fetch: F,
+        schedule: PollSchedule,
+        poller: WeatherPoller,
+        dedup: WeatherDeduplicator,
+        buffer: VecDeque<Weather>,
+        first: bool,
+    }
+
+    let state = State {
+        query,
+        fetch,
+        schedule: schedule.into(),
+        poller: WeatherPoller::new(),
+        dedup: WeatherDeduplicator::new(),
+        buffer: VecDeque::new(),
+        first: true,
+    };
+
+    futures_util::stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(record) = state.buffer.pop_front() {
+                return Some((Ok(record), state));
+            }
+            let delay = if state.first {
+                state.schedule.offset()
+            } else {
+                state.schedule.interval()
+            };
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+            state.first = false;
+
+            let query = state.poller.prepare(state.query.clone());
+            if let Some(date) = query.date {
+                // Keep a day of margin for timezone offsets of the query date
+                let cutoff = (date.date_naive() - chrono::Days::new(1))
+                    .and_time(chrono::NaiveTime::MIN)
+                    .and_utc()
+                    .fixed_offset();
+                state.dedup.forget_before(cutoff);
+            }
+
+            match (state.fetch)(query.clone()).await {
+                Ok(response) => {
+                    state.poller.observe(&query, &response);
+                    state.buffer.extend(state.dedup.filter_new(response.weather));
+                }
+                Err(err) => return Some((Err(err), state)),
+            }
+        }
+    })
+}
+
+/// A change in the set of active alerts, see [`AlertsWatcher`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlertEvent {
+    /// An alert that was not active before
+    New(Alert),
+    /// An active alert whose content changed (e.g. a new `expires` time)
+    Updated(Alert),
+    /// An alert that is no longer part of the response, with its last known content
+    Expired(Alert),
+}
+
+impl AlertEvent {
+    /// The alert this event refers to.
+    pub fn alert(&self) -> &Alert {
+        match self {
+            Self::New(alert) | Self::Updated(alert) | Self::Expired(alert) => alert,
+        }
+    }
+
+    /// `new`, `updated` or `expired`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::New(_) => "new",
+            Self::Updated(_) => "updated",
+            Self::Expired(_) => "expired",
+        }
+    }
+}
+
+/// Alerts that were added, updated or removed between two `/alerts` responses.
+///
+/// Alerts are identified by their `alert_id`, the CAP message identifier, as
+/// in [`AlertsWatcher`]. An alert with a known identifier and different
+/// content (e.g. a new `expires` time) counts as updated. The API does not
+/// include the CAP `references` of a message, so an update issued under a new
+/// identifier shows up as an added alert, and as a removed one once the old
+/// alert is gone. An alert listed more than once in a response counts once.
+///
+/// ```rust
+/// use brightsky::{poll::AlertsDiff, types::AlertsResponse};
+///
+/// # fn poll() -> AlertsResponse { AlertsResponse { alerts: vec![], location: None } }
+/// let old = poll();
+/// let new = poll();
+///
+/// let diff = AlertsDiff::between(&old, &new);
+/// for alert in &diff.added {
+///     println!("New: {}", alert.headline_en);
+/// }
+/// for alert in &diff.removed {
+///     println!("Lifted: {}", alert.headline_en);
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AlertsDiff {
+    /// Alerts of the new response with an unknown `alert_id`, in response order
+    pub added: Vec<Alert>,
+    /// Alerts of the new response whose content changed, in response order
+    pub updated: Vec<Alert>,
+    /// Alerts of the old response that are missing from the new one, with their
+    /// last known content, ordered by `alert_id`
+    pub removed: Vec<Alert>,
+}
+
+impl AlertsDiff {
+    /// Compare an older and a newer response for the same query.
+    pub fn between(old: &AlertsResponse, new: &AlertsResponse) -> Self {
+        Self::of(&old.alerts, &new.alerts)
+    }
+
+    fn of<'a>(old: impl IntoIterator<Item = &'a Alert>, new: &[Alert]) -> Self {
+        let mut previous = BTreeMap::new();
+        for alert in old {
+            previous.entry(alert.alert_id.as_str()).or_insert(alert);
+        }
+
+        let mut seen = BTreeSet::new();
+        let mut diff = Self::default();
+        for alert in new {
+            if !seen.insert(alert.alert_id.as_str()) {
+                continue;
+            }
+            match previous.remove(alert.alert_id.as_str()) {
+                None => diff.added.push(alert.clone()),
+                Some(old) if old != alert => diff.updated.push(alert.clone()),
+                Some(_) => {}
+            }
+        }
+        diff.removed = previous.into_values().cloned().collect();
+        diff
+    }
+
+    /// Whether nothing changed between the two responses.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+
+    /// The changes as [`AlertEvent`]s: added, then updated, then removed alerts.
+    pub fn into_events(self) -> Vec<AlertEvent> {
+        let added = self.added.into_iter().map(AlertEvent::New);
+        let updated = self.updated.into_iter().map(AlertEvent::Updated);
+        let removed = self.removed.into_iter().map(AlertEvent::Expired);
+        added.chain(updated).chain(removed).collect()
+    }
+}
+
+/// Tracks the active alerts of one `/alerts` query across polls.
+///
+/// Alerts are identified by their `alert_id`. An alert that disappears from the
+/// response is reported as expired, whether it ran out or was cancelled by the
+/// DWD.
+#[derive(Debug, Clone, Default)]
+pub struct AlertsWatcher {
+    active: BTreeMap<String, Alert>,
+}
+
+impl AlertsWatcher {
+    /// Create a watcher with no known alerts.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Currently active alerts, ordered by `alert_id`.
+    pub fn active(&self) -> impl Iterator<Item = &Alert> {
+        self.active.values()
+    }
+
+    /// Forget all known alerts, so the next update reports every alert as new.
+    pub fn reset(&mut self) {
+        self.active.clear();
+    }
+
+    /// Replace the known alerts with a new response and return what changed.
+    ///
+    /// The changes are those of [`AlertsDiff::between`] the previous and the
+    /// new response, in the order of [`AlertsDiff::into_events`].
+    pub fn update(&mut self, response: AlertsResponse) -> Vec<AlertEvent> {
+        let events = self.changes(&response);
+        for event in &events {
+            self.apply(event);
+        }
+        events
+    }
+
+    /// What [`update`](Self::update) would report, without recording it.
+    pub(crate) fn changes(&self, response: &AlertsResponse) -> Vec<AlertEvent> {
+        AlertsDiff::of(self.active.values(), &response.alerts).into_events()
+    }
+
+    /// Record a change, e.g. once it was delivered.
+    pub(crate) fn apply(&mut self, event: &AlertEvent) {
+        match event {
+            AlertEvent::New(alert) | AlertEvent::Updated(alert) => {
+                self.active.insert(alert.alert_id.clone(), alert.clone());
+            }
+            AlertEvent::Expired(alert) => {
+                self.active.remove(&alert.alert_id);
+            }
+        }
+    }
+
+    /// [`update`](Self::update) with a new response and send every change to
+    /// `sink` with [`AlertSink::notify_event`], in the same order.
+    ///
+    /// Returns the number of changes. Stops at the first failed notification;
+    /// the watcher only records the changes that were sent, so the next call
+    /// sends the remaining ones again if they still apply.
+    pub async fn forward<S: AlertSink>(
+        &mut self,
+        response: AlertsResponse,
+        sink: &S,
+    ) -> Result<usize, S::Error> {
+        let events = self.changes(&response);
+        for event in &events {
+            sink.notify_event(event).await?;
+            self.apply(event);
+        }
+        Ok(events.len())
+    }
+}
+
+/// Poll an `/alerts` query forever and yield every change as an [`AlertEvent`].
+///
+/// The query is fetched according to `schedule` (a [`PollSchedule`] or just a
+/// `Duration` interval), using `fetch` to perform the HTTP request. Alerts active at the first fetch are yielded as
+/// [`AlertEvent::New`]. Use `with_lat_lon` or `with_warn_cell_id` on the query to
+/// watch a single location.
+///
+/// Fetch errors are yielded as `Err` items; the stream keeps polling afterwards
+/// and does not report alerts as expired because of a failed fetch.
+///
+/// Only available with the `stream` feature.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use brightsky::{AlertsQueryBuilder, ToBrightSkyUrl, BRIGHT_SKY_API, poll::{self, AlertEvent}, types::AlertsResponse};
+/// use futures_util::StreamExt;
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let query = AlertsQueryBuilder::new()
+///         .with_warn_cell_id(803159016)
+///         .build()?;
+///
+///     let events = poll::alerts_stream(query, Duration::from_secs(300), |query| async move {
+///         let url = query.to_url(BRIGHT_SKY_API)?;
+///         let response: AlertsResponse = reqwest::get(url).await?.json().await?;
+///         Ok::<_, Box<dyn std::error::Error>>(response)
+///     });
+///     let mut events = std::pin::pin!(events);
+///
+///     while let Some(event) = events.next().await {
+///         match event? {
+///             AlertEvent::New(alert) => println!("New: {}", alert.headline_en),
+///             AlertEvent::Updated(alert) => println!("Updated: {}", alert.headline_en),
+///             AlertEvent::Expired(alert) => println!("Lifted: {}", alert.headline_en),
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "stream")]
+pub fn alerts_stream<F, Fut, E>(
+    query: crate::AlertsQuery,
+    schedule: impl Into<PollSchedule>,
+    fetch: F,
+) -> impl futures_util::Stream<Item = Result<AlertEvent, E>>
+where
+    F: FnMut(crate::AlertsQuery) -> Fut,
+    Fut: core::future::Future<Output = Result<AlertsResponse, E>>,
+{
+    use std::collections::VecDeque;
+
+    struct State<F> {
+        query: crate::AlertsQuery,
+        fetch: F,
+        schedule: PollSchedule,
+        watcher: AlertsWatcher,
+        buffer: VecDeque<AlertEvent>,
+        first: bool,
+    }
+
+    let state = State {
+        query,
+        fetch,
+        schedule: schedule.into(),
+        watcher: AlertsWatcher::new(),
+        buffer: VecDeque::new(),
+        first: true,
+    };
+
+    futures_util::stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(event) = state.buffer.pop_front() {
+                return Some((Ok(event), state));
+            }
+            let delay = if state.first {
+                state.schedule.offset()
+            } else {
+                state.schedule.interval()
+            };
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+            state.first = false;
+
+            match (state.fetch)(state.query.clone()).await {
+                Ok(response) => state.buffer.extend(state.watcher.update(response)),
+                Err(err) => return Some((Err(err), state)),
+            }
+        }
+    })
+}
+
+/// Maximum request rate of `alerts_scan`.
+///
+/// Requests are spread evenly: a limit of 10 requests per second sends one
+/// request every 100 ms rather than bursts of 10.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    requests: u32,
+    per: core::time::Duration,
+}
+
+impl RateLimit {
+    /// Allow `requests` requests (at least 1) per `per`.
+    pub fn new(requests: u32, per: core::time::Duration) -> Self {
+        Self {
+            requests: requests.max(1),
+            per,
+        }
+    }
+
+    /// Allow `requests` requests per second.
+    pub fn per_second(requests: u32) -> Self {
+        Self::new(requests, core::time::Duration::from_secs(1))
+    }
+
+    /// Allow `requests` requests per minute.
+    pub fn per_minute(requests: u32) -> Self {
+        Self::new(requests, core::time::Duration::from_secs(60))
+    }
+
+    /// Minimum time between the starts of two requests.
+    pub fn min_interval(&self) -> core::time::Duration {
+        self.per / self.requests
+    }
+}
+
+/// Fetch many `/alerts` queries one after another, within a [`RateLimit`].
+///
+/// Meant for nationwide scans over many warn cells or locations. Queries are
+/// taken from `queries` lazily and each response is yielded together with its
+/// query before the next request is sent, so neither the list of queries nor
+/// the responses need to be kept in memory. Stopping to poll the stream pauses
+/// the scan; a slow consumer never causes more than the allowed rate.
+///
+/// Fetch errors are yielded with their query; the scan continues afterwards.
+///
+/// Only available with the `stream` feature.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use brightsky::{AlertsQueryBuilder, BrightSkyClient, poll::{self, RateLimit}, types::AlertsResponse};
+/// use futures_util::StreamExt;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = BrightSkyClient::new(reqwest::Client::new());
+///     let cells = [803159016, 805314000, 809162000];
+///
+///     let queries = cells
+///         .into_iter()
+///         .map(|cell| AlertsQueryBuilder::new().with_warn_cell_id(cell).build())
+///         .collect::<Result<Vec<_>, _>>()?;
+///     let scan = poll::alerts_scan(queries, RateLimit::per_second(2), |query| {
+///         client.get::<_, AlertsResponse>(query)
+///     });
+///     let mut scan = std::pin::pin!(scan);
+///
+///     while let Some((query, result)) = scan.next().await {
+///         match result {
+///             Ok(response) => println!("{:?}: {} alerts", query.warn_cell_id, response.alerts.len()),
+///             Err(err) => println!("{:?}: {}", query.warn_cell_id, err),
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "stream")]
+pub fn alerts_scan<I, F, Fut, E>(
+    queries: I,
+    rate: RateLimit,
+    fetch: F,
+) -> impl futures_util::Stream<Item = (crate::AlertsQuery, Result<AlertsResponse, E>)>
+where
+    I: IntoIterator<Item = crate::AlertsQuery>,
+    F: FnMut(crate::AlertsQuery) -> Fut,
+    Fut: core::future::Future<Output = Result<AlertsResponse, E>>,
+{
+    struct State<I, F> {
+        queries: I,
+        fetch: F,
+        interval: core::time::Duration,
+        last_request: Option<tokio::time::Instant>,
+    }
+
+    let state = State {
+        queries: queries.into_iter(),
+        fetch,
+        interval: rate.min_interval(),
+        last_request: None,
+    };
+
+    futures_util::stream::unfold(state, |mut state| async move {
+        let query = state.queries.next()?;
+        if let Some(last_request) = state.last_request {
+            tokio::time::sleep_until(last_request + state.interval).await;
+        }
+        state.last_request = Some(tokio::time::Instant::now());
+
+        let result = (state.fetch)(query.clone()).await;
+        Some(((query, result), state))
+    })
+}
+
+/// End a polling stream once `shutdown` completes.
+///
+/// `shutdown` can be any future, e.g. `token.cancelled()` of a
+/// `tokio_util::sync::CancellationToken`. The stream ends at the next item
+/// boundary: a pending wait or fetch is dropped and records or events that
+/// were fetched but not yet yielded are discarded. Since the polling state is
+/// dropped with the stream, a new stream starts from scratch.
+///
+/// Only available with the `stream` feature.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use brightsky::{AlertsQueryBuilder, ToBrightSkyUrl, BRIGHT_SKY_API, poll, types::AlertsResponse};
+/// use futures_util::StreamExt;
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let query = AlertsQueryBuilder::new().with_warn_cell_id(803159016).build()?;
+///     let events = poll::alerts_stream(query, Duration::from_secs(300), |query| async move {
+///         let url = query.to_url(BRIGHT_SKY_API)?;
+///         let response: AlertsResponse = reqwest::get(url).await?.json().await?;
+///         Ok::<_, Box<dyn std::error::Error>>(response)
+///     });
+///
+///     // Stop after an hour
+///     let shutdown = tokio::time::sleep(Duration::from_secs(3600));
+///     let mut events = std::pin::pin!(poll::until_shutdown(events, shutdown));
+///
+///     while let Some(event) = events.next().await {
+///         println!("{:?}", event?);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "stream")]
+pub fn until_shutdown<S, F>(stream: S, shutdown: F) -> impl futures_util::Stream<Item = S::Item>
+where
+    S: futures_util::Stream,
+    F: core::future::Future<Output = ()>,
+{
+    futures_util::StreamExt::take_until(stream, shutdown)
+}
+
+/// Identify a query by everything except its date range.
+fn poll_key(query: &WeatherQuery) -> String {
+    format!(
+        "{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}",
+        query.lat,
+        query.lon,
+        query.max_dist,
+        query.dwd_station_id,
+        query.wmo_station_id,
+        query.source_id,
+        query.tz,
+        query.units,
+    )
+}
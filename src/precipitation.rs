@@ -0,0 +1,249 @@
+//! Precipitation accumulation over longer periods and recent intervals.
+//!
+//! Hourly records report the precipitation of the *previous* hour. Forecast
+//! products and charts usually want 3-hourly, 6-hourly or daily totals
+//! instead, which [`WeatherResponse::precipitation_accumulation`] computes:
+//!
+//! ```rust
+//! use brightsky::precipitation::AccumulationPeriod;
+//! use brightsky::types::WeatherResponse;
+//!
+//! let response: WeatherResponse = serde_json::from_str(r#"{
+//!     "weather": [
+//!         {"timestamp": "2023-08-07T01:00:00+02:00", "source_id": 1, "precipitation": 0.5},
+//!         {"timestamp": "2023-08-07T02:00:00+02:00", "source_id": 1, "precipitation": 1.0},
+//!         {"timestamp": "2023-08-07T03:00:00+02:00", "source_id": 1, "precipitation": 0.0}
+//!     ],
+//!     "sources": []
+//! }"#).unwrap();
+//!
+//! let accumulations = response.precipitation_accumulation(AccumulationPeriod::Hours3);
+//! assert_eq!(accumulations.len(), 1);
+//! assert_eq!(accumulations[0].total, Some(1.5));
+//! assert!(accumulations[0].is_complete());
+//! ```
+//!
+//! Periods are aligned to midnight in the UTC offset of the record timestamps,
+//! like the days of [`daily_summary`](WeatherResponse::daily_summary). Hours
+//! without a record or without a precipitation value are not guessed but
+//! counted in [`Accumulation::missing_hours`], so callers decide whether a
+//! partial total is good enough.
+//!
+//! The `precipitation_10`, `precipitation_30` and `precipitation_60` fields of
+//! [`CurrentWeather`] cover nested intervals ending at the same time and can
+//! be combined with [`CurrentWeather::recent_precipitation`].
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{collections::BTreeMap, vec::Vec};
+
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, FixedOffset, NaiveDateTime, NaiveTime, TimeDelta, Timelike};
+
+use crate::{
+    current_weather::{IntervalValue, ObservationInterval},
+    types::{CurrentWeather, WeatherResponse},
+};
+
+/// Length of the periods precipitation is accumulated over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccumulationPeriod {
+    /// 00-03, 03-06, ... local time
+    Hours3,
+    /// 00-06, 06-12, 12-18 and 18-24 local time
+    Hours6,
+    /// Calendar days
+    Daily,
+}
+
+impl AccumulationPeriod {
+    /// Length of the period in hours.
+    pub fn hours(&self) -> u32 {
+        match self {
+            Self::Hours3 => 3,
+            Self::Hours6 => 6,
+            Self::Daily => 24,
+        }
+    }
+
+    /// Start of the period containing `time`, in local time.
+    fn start(&self, time: NaiveDateTime) -> NaiveDateTime {
+        let hour = time.hour() - time.hour() % self.hours();
+        time.date()
+            .and_time(NaiveTime::from_hms_opt(hour, 0, 0).unwrap_or_default())
+    }
+}
+
+/// Precipitation total of one period, see
+/// [`WeatherResponse::precipitation_accumulation`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Accumulation {
+    /// Start of the period
+    pub start: DateTime<FixedOffset>,
+    /// End of the period
+    pub end: DateTime<FixedOffset>,
+    /// Sum of the hourly precipitation (mm), `None` if no hour had a value
+    pub total: Option<f64>,
+    /// Number of hours with a precipitation value
+    pub hours: u32,
+    /// Number of hours without a record or without a precipitation value
+    pub missing_hours: u32,
+}
+
+impl Accumulation {
+    /// Whether every hour of the period had a precipitation value.
+    pub fn is_complete(&self) -> bool {
+        self.missing_hours == 0
+    }
+}
+
+impl WeatherResponse {
+    /// Sum the hourly precipitation into periods of 3 or 6 hours or calendar
+    /// days, in chronological order.
+    ///
+    /// One accumulation is returned for every period containing at least one
+    /// record, including the partial periods at the start and end of the
+    /// response. Each record counts towards the period in which its hour
+    /// started. Days on which the UTC offset changes are assumed to have 24
+    /// hours.
+    pub fn precipitation_accumulation(&self, period: AccumulationPeriod) -> Vec<Accumulation> {
+        let mut periods: BTreeMap<NaiveDateTime, Accumulation> = BTreeMap::new();
+
+        for record in &self.weather {
+            let hour_start = record.timestamp - TimeDelta::hours(1);
+            let local_start = period.start(hour_start.naive_local());
+            let accumulation = periods.entry(local_start).or_insert_with(|| {
+                let start = hour_start - (hour_start.naive_local() - local_start);
+                Accumulation {
+                    start,
+                    end: start + TimeDelta::hours(period.hours().into()),
+                    total: None,
+                    hours: 0,
+                    missing_hours: 0,
+                }
+            });
+            if let Some(precipitation) = record.precipitation {
+                accumulation.total = Some(accumulation.total.unwrap_or(0.0) + precipitation);
+                accumulation.hours += 1;
+            }
+        }
+
+        periods
+            .into_values()
+            .map(|mut accumulation| {
+                accumulation.missing_hours = period.hours().saturating_sub(accumulation.hours);
+                accumulation
+            })
+            .collect()
+    }
+}
+
+/// Precipitation of the nested intervals of a [`CurrentWeather`] record.
+///
+/// Values are made consistent: as the 10 minutes are part of the 30 minutes,
+/// which are part of the 60 minutes, a longer interval reports at least the
+/// precipitation of any shorter one. Intervals the record has no value for
+/// stay `None`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RecentPrecipitation {
+    /// Precipitation of the previous 10 minutes (mm)
+    pub minutes_10: Option<f64>,
+    /// Precipitation of the previous 30 minutes (mm)
+    pub minutes_30: Option<f64>,
+    /// Precipitation of the previous 60 minutes (mm)
+    pub minutes_60: Option<f64>,
+}
+
+impl RecentPrecipitation {
+    /// Precipitation of one interval.
+    pub fn get(&self, interval: ObservationInterval) -> Option<f64> {
+        match interval {
+            ObservationInterval::Minutes10 => self.minutes_10,
+            ObservationInterval::Minutes30 => self.minutes_30,
+            ObservationInterval::Minutes60 => self.minutes_60,
+        }
+    }
+
+    /// Precipitation of the longest available interval.
+    pub fn total(&self) -> Option<IntervalValue<f64>> {
+        self.available().last()
+    }
+
+    /// Precipitation rate of the shortest available interval (mm/h).
+    pub fn rate(&self) -> Option<IntervalValue<f64>> {
+        self.available().next().map(|amount| IntervalValue {
+            value: amount.value * 60.0 / f64::from(amount.interval.minutes()),
+            interval: amount.interval,
+        })
+    }
+
+    /// Intervals without a value.
+    pub fn missing(&self) -> impl Iterator<Item = ObservationInterval> + '_ {
+        INTERVALS
+            .into_iter()
+            .filter(|&interval| self.get(interval).is_none())
+    }
+
+    /// Whether all three intervals have a value.
+    pub fn is_complete(&self) -> bool {
+        self.missing().next().is_none()
+    }
+
+    /// Available values from the shortest to the longest interval.
+    fn available(&self) -> impl Iterator<Item = IntervalValue<f64>> + '_ {
+        INTERVALS.into_iter().filter_map(|interval| {
+            Some(IntervalValue {
+                value: self.get(interval)?,
+                interval,
+            })
+        })
+    }
+}
+
+const INTERVALS: [ObservationInterval; 3] = [
+    ObservationInterval::Minutes10,
+    ObservationInterval::Minutes30,
+    ObservationInterval::Minutes60,
+];
+
+impl CurrentWeather {
+    /// Precipitation of the previous 10, 30 and 60 minutes, made consistent
+    /// with each other.
+    ///
+    /// ```rust
+    /// use brightsky::ObservationInterval;
+    /// use brightsky::types::CurrentWeather;
+    ///
+    /// let current: CurrentWeather = serde_json::from_str(r#"{
+    ///     "timestamp": "2023-08-07T12:30:00+00:00",
+    ///     "source_id": 1,
+    ///     "precipitation_10": 0.4,
+    ///     "precipitation_60": 0.3
+    /// }"#).unwrap();
+    ///
+    /// let recent = current.recent_precipitation();
+    /// assert_eq!(recent.minutes_60, Some(0.4));
+    /// assert_eq!(recent.missing().collect::<Vec<_>>(), [ObservationInterval::Minutes30]);
+    /// assert_eq!(recent.rate().unwrap().value, 2.4);
+    /// ```
+    pub fn recent_precipitation(&self) -> RecentPrecipitation {
+        let minutes_10 = self.precipitation_10;
+        let minutes_30 = self.precipitation_30.map(|value| max(value, minutes_10));
+        let minutes_60 = self
+            .precipitation_60
+            .map(|value| max(max(value, minutes_30), minutes_10));
+        RecentPrecipitation {
+            minutes_10,
+            minutes_30,
+            minutes_60,
+        }
+    }
+}
+
+fn max(value: f64, shorter: Option<f64>) -> f64 {
+    shorter.map_or(value, |shorter| value.max(shorter))
+}
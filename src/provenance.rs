@@ -0,0 +1,109 @@
+//! Where the data of a result came from.
+//!
+//! High-level helpers attach a [`Provenance`] to their results, listing the
+//! API host, the request URLs, when they were fetched, whether the data came
+//! from a cache and the unit system of the values. It is serializable, so it
+//! can be stored next to derived data for auditing:
+//!
+//! ```rust,no_run
+//! # #[cfg(feature = "reqwest")]
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! use brightsky::BrightSkyClient;
+//!
+//! let client = BrightSkyClient::new(reqwest::Client::new());
+//! let forecast = client.forecast((52.52, 13.4)).days(3).await?;
+//!
+//! if let Some(provenance) = &forecast.provenance {
+//!     println!("{}", serde_json::to_string(provenance)?);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`BrightSkyClient::get_with_provenance`](crate::BrightSkyClient::get_with_provenance)
+//! returns the provenance of a single request. Helpers that send several
+//! requests, such as
+//! [`HistoryRequest::fetch_with_provenance`](crate::history::HistoryRequest::fetch_with_provenance)
+//! and
+//! [`BrightSkyClient::precipitation_outlook_with_provenance`](crate::BrightSkyClient::precipitation_outlook_with_provenance),
+//! list all of their request URLs.
+//!
+//! Only available with the `std` feature, which provides the system clock.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::types::UnitType;
+
+/// Origin of a result, see the [`provenance`](crate::provenance) module.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// API host the requests were sent to
+    pub host: String,
+    /// Full URLs of the requests, in the order they were sent
+    pub requests: Vec<String>,
+    /// When the (first) request was sent
+    pub fetched_at: DateTime<Utc>,
+    /// Whether the result was served from a cache instead of a live request
+    pub cached: bool,
+    /// Unit system of the values
+    pub units: UnitType,
+}
+
+impl Provenance {
+    /// Provenance of a live request to `url`, sent at `fetched_at`.
+    ///
+    /// The unit system is read from the `units` parameter of the URL, DWD units
+    /// if it is not set.
+    pub fn live(
+        host: impl Into<String>,
+        url: impl Into<String>,
+        fetched_at: DateTime<Utc>,
+    ) -> Self {
+        let url = url.into();
+        let si = Url::parse(&url).is_ok_and(|url| {
+            url.query_pairs()
+                .any(|(name, value)| name == "units" && value == "si")
+        });
+
+        Self {
+            host: host.into(),
+            requests: vec![url],
+            fetched_at,
+            cached: false,
+            units: if si { UnitType::Si } else { UnitType::Dwd },
+        }
+    }
+
+    /// Combine with the provenance of another request of the same result.
+    ///
+    /// The requests of `other` are appended, `fetched_at` is the earlier of
+    /// both and the result only counts as cached if both parts were.
+    pub fn combine(mut self, other: Self) -> Self {
+        self.requests.extend(other.requests);
+        self.fetched_at = self.fetched_at.min(other.fetched_at);
+        self.cached &= other.cached;
+        self
+    }
+
+    /// Mark the result as served from a cache.
+    pub fn with_cached(mut self, cached: bool) -> Self {
+        self.cached = cached;
+        self
+    }
+
+    /// URL paths of the requests without duplicates, e.g. `/weather`.
+    pub fn endpoints(&self) -> Vec<String> {
+        let mut endpoints: Vec<String> = Vec::new();
+        for request in &self.requests {
+            let path = Url::parse(request)
+                .map(|url| url.path().to_string())
+                .unwrap_or_default();
+            if !endpoints.contains(&path) {
+                endpoints.push(path);
+            }
+        }
+        endpoints
+    }
+}
@@ -0,0 +1,88 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+
+use crate::radar::RadarGrid;
+
+/// Lower bounds in 0.01 mm / 5 min with the block character and ANSI color
+/// code of each intensity, following the default PNG color ramp.
+const LEVELS: [(u16, char, &str); 7] = [
+    (1, '░', "36"),
+    (10, '▒', "34"),
+    (50, '▒', "32"),
+    (100, '▓', "33"),
+    (250, '▓', "31"),
+    (500, '█', "31"),
+    (1000, '█', "35"),
+];
+
+impl RadarGrid {
+    /// Render the grid as unicode block characters with ANSI colors, at most
+    /// `width` characters wide.
+    ///
+    /// Each character covers a block of pixels twice as high as wide, so the
+    /// map keeps its proportions in a terminal, and shows the heaviest
+    /// precipitation of its block so small rain cells stay visible. Dry
+    /// blocks are blank. Every line ends with a newline.
+    ///
+    /// ```rust
+    /// use brightsky::RadarGrid;
+    ///
+    /// let grid = RadarGrid::new(4, 2, vec![0, 0, 5, 0, 0, 0, 0, 1200]).unwrap();
+    /// print!("{}", grid.render_ascii(80));
+    /// assert_eq!(grid.render_ascii_plain(2), " █\n");
+    /// assert_eq!(grid.render_ascii_plain(4), "  ░█\n");
+    /// ```
+    pub fn render_ascii(&self, width: usize) -> String {
+        self.ascii(width, true)
+    }
+
+    /// Like [`render_ascii`](Self::render_ascii), without ANSI colors.
+    pub fn render_ascii_plain(&self, width: usize) -> String {
+        self.ascii(width, false)
+    }
+
+    fn ascii(&self, width: usize, color: bool) -> String {
+        let (w, h) = (self.width(), self.height());
+        if w == 0 || h == 0 || width == 0 {
+            return String::new();
+        }
+        let columns = width.min(w);
+        // Terminal cells are about twice as high as wide
+        let rows = (h * columns).div_ceil(2 * w);
+
+        let mut out = String::new();
+        for row in 0..rows {
+            let y0 = row * 2 * w / columns;
+            let y1 = ((row + 1) * 2 * w / columns).clamp(y0 + 1, h);
+            let mut current = None;
+            for column in 0..columns {
+                let x0 = column * w / columns;
+                let x1 = ((column + 1) * w / columns).max(x0 + 1);
+                let value = (y0..y1)
+                    .flat_map(|y| (x0..x1).filter_map(move |x| self.get(x, y)))
+                    .max()
+                    .unwrap_or(0);
+                let level = LEVELS.iter().rev().find(|(min, _, _)| value >= *min);
+
+                if color {
+                    let code = level.map(|(_, _, code)| *code);
+                    if code != current {
+                        out.push_str("\x1b[");
+                        out.push_str(code.unwrap_or("0"));
+                        out.push('m');
+                        current = code;
+                    }
+                }
+                out.push(level.map_or(' ', |(_, block, _)| *block));
+            }
+            if current.is_some() {
+                out.push_str("\x1b[0m");
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
@@ -0,0 +1,162 @@
+use core::fmt;
+
+use crate::QueryError;
+#[cfg(feature = "std")]
+use crate::{Coordinates, coordinates::EARTH_RADIUS, projection::latlon_to_pixel};
+
+/// Number of pixels from left to right in the full radar grid.
+pub const GRID_WIDTH: usize = 1100;
+
+/// Number of pixels from top to bottom in the full radar grid.
+pub const GRID_HEIGHT: usize = 1200;
+
+/// Pixel bounding box of a radar query, with both edges included.
+///
+/// Pixels are counted from the top left corner of the 1200×1100 radar grid,
+/// so `0 <= top <= bottom < 1200` and `0 <= left <= right < 1100`.
+/// [`RadarWeatherQueryBuilder::build`](crate::RadarWeatherQueryBuilder::build)
+/// rejects bounding boxes outside the grid.
+///
+/// ```rust
+/// use brightsky::RadarBbox;
+///
+/// let bbox = RadarBbox { top: 100, left: 100, bottom: 299, right: 299 };
+/// assert_eq!((bbox.width(), bbox.height()), (200, 200));
+/// assert_eq!(bbox.to_string(), "100,100,299,299");
+/// assert_eq!(RadarBbox::from([100, 100, 299, 299]), bbox);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RadarBbox {
+    /// First row
+    pub top: i64,
+    /// First column
+    pub left: i64,
+    /// Last row
+    pub bottom: i64,
+    /// Last column
+    pub right: i64,
+}
+
+impl RadarBbox {
+    /// The full radar grid.
+    pub const FULL: Self = Self {
+        top: 0,
+        left: 0,
+        bottom: GRID_HEIGHT as i64 - 1,
+        right: GRID_WIDTH as i64 - 1,
+    };
+
+    /// Number of columns, zero if `right` is left of `left`.
+    pub fn width(&self) -> usize {
+        usize::try_from(self.right - self.left + 1).unwrap_or(0)
+    }
+
+    /// Number of rows, zero if `bottom` is above `top`.
+    pub fn height(&self) -> usize {
+        usize::try_from(self.bottom - self.top + 1).unwrap_or(0)
+    }
+
+    /// `[top, left, bottom, right]`, the layout of `RadarResponse::bbox`.
+    pub const fn to_array(&self) -> [i64; 4] {
+        [self.top, self.left, self.bottom, self.right]
+    }
+
+    /// Check that the bounding box is not empty and lies within the grid.
+    pub fn validate(&self) -> Result<(), QueryError> {
+        let rows = 0..GRID_HEIGHT as i64;
+        let columns = 0..GRID_WIDTH as i64;
+        if rows.contains(&self.top)
+            && rows.contains(&self.bottom)
+            && columns.contains(&self.left)
+            && columns.contains(&self.right)
+            && self.top <= self.bottom
+            && self.left <= self.right
+        {
+            Ok(())
+        } else {
+            Err(QueryError::InvalidBbox(*self))
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl RadarBbox {
+    /// The smallest bounding box containing all pixels within `km` kilometers
+    /// of `location`, cut to the radar grid.
+    ///
+    /// Use it with `with_bbox` instead of `with_lat_lon` and `with_distance`
+    /// to request exactly the pixels around a location. Returns `None` if the
+    /// location is invalid, the area lies completely outside the grid or `km`
+    /// is not finite.
+    ///
+    /// ```rust
+    /// use brightsky::RadarBbox;
+    ///
+    /// let bbox = RadarBbox::around((52.52, 13.4), 30.0).unwrap();
+    /// // Pixels are about 1 km wide
+    /// assert!((60..=66).contains(&bbox.width()));
+    /// assert!(bbox.validate().is_ok());
+    ///
+    /// assert_eq!(RadarBbox::around((40.0, -70.0), 30.0), None);
+    /// ```
+    ///
+    /// Only available with the `std` feature.
+    pub fn around(location: impl TryInto<Coordinates>, km: f64) -> Option<Self> {
+        /// Points sampled on the circle around the location
+        const BEARINGS: u32 = 72;
+
+        if !km.is_finite() {
+            return None;
+        }
+        let location: Coordinates = location.try_into().ok()?;
+        let (lat, lon) = (location.lat.to_radians(), location.lon.to_radians());
+        let angle = km.max(0.0) * 1000.0 / EARTH_RADIUS;
+
+        let (x, y) = latlon_to_pixel(location.lat, location.lon);
+        let (mut min_x, mut max_x, mut min_y, mut max_y) = (x, x, y, y);
+        for step in 0..BEARINGS {
+            let bearing = f64::from(step) * core::f64::consts::TAU / f64::from(BEARINGS);
+            let point_lat =
+                (lat.sin() * angle.cos() + lat.cos() * angle.sin() * bearing.cos()).asin();
+            let point_lon = lon
+                + (bearing.sin() * angle.sin() * lat.cos())
+                    .atan2(angle.cos() - lat.sin() * point_lat.sin());
+            let (x, y) = latlon_to_pixel(point_lat.to_degrees(), point_lon.to_degrees());
+            min_x = min_x.min(x);
+            max_x = max_x.max(x);
+            min_y = min_y.min(y);
+            max_y = max_y.max(y);
+        }
+
+        let bbox = Self {
+            top: (min_y.round() as i64).max(0),
+            left: (min_x.round() as i64).max(0),
+            bottom: (max_y.round() as i64).min(GRID_HEIGHT as i64 - 1),
+            right: (max_x.round() as i64).min(GRID_WIDTH as i64 - 1),
+        };
+        bbox.validate().is_ok().then_some(bbox)
+    }
+}
+
+impl From<[i64; 4]> for RadarBbox {
+    /// From `[top, left, bottom, right]`.
+    fn from([top, left, bottom, right]: [i64; 4]) -> Self {
+        Self {
+            top,
+            left,
+            bottom,
+            right,
+        }
+    }
+}
+
+/// Formats as the `bbox` query parameter, `top,left,bottom,right`.
+impl fmt::Display for RadarBbox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{},{},{},{}",
+            self.top, self.left, self.bottom, self.right
+        )
+    }
+}
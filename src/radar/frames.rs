@@ -0,0 +1,252 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use chrono::{DateTime, FixedOffset, TimeDelta, TimeZone};
+
+use crate::{
+    DecodeError,
+    radar::{RadarBbox, RadarGrid},
+    types::{Radar, RadarResponse},
+};
+
+/// Pixel bounding box of the full radar grid, used by responses without `bbox`.
+const FULL_GRID_BBOX: [i64; 4] = RadarBbox::FULL.to_array();
+
+/// A decoded radar frame, see [`RadarResponse::frames`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RadarFrame {
+    /// Timestamp of the frame
+    pub time: DateTime<FixedOffset>,
+    /// Precipitation in 0.01 mm / 5 min
+    pub grid: RadarGrid,
+}
+
+impl RadarFrame {
+    /// Per-pixel maximum across frames, e.g. the heaviest rain expected anywhere
+    /// in the next two hours.
+    ///
+    /// Returns `None` if there are no frames or their grids differ in size.
+    pub fn max<'a>(frames: impl IntoIterator<Item = &'a RadarFrame>) -> Option<RadarGrid> {
+        Self::combine(frames, u16::max)
+    }
+
+    /// Per-pixel sum across frames in 0.01 mm, e.g. the total rainfall of a
+    /// sequence. Sums saturate at `u16::MAX`.
+    ///
+    /// Returns `None` if there are no frames or their grids differ in size.
+    pub fn accumulate<'a>(frames: impl IntoIterator<Item = &'a RadarFrame>) -> Option<RadarGrid> {
+        Self::combine(frames, u16::saturating_add)
+    }
+
+    fn combine<'a>(
+        frames: impl IntoIterator<Item = &'a RadarFrame>,
+        op: impl Fn(u16, u16) -> u16,
+    ) -> Option<RadarGrid> {
+        let mut frames = frames.into_iter();
+        let first = &frames.next()?.grid;
+        let mut data = first.data().to_vec();
+        for frame in frames {
+            let grid = &frame.grid;
+            if (grid.width(), grid.height()) != (first.width(), first.height()) {
+                return None;
+            }
+            for (total, &value) in data.iter_mut().zip(grid.data()) {
+                *total = op(*total, value);
+            }
+        }
+        RadarGrid::new(first.width(), first.height(), data)
+    }
+}
+
+impl Radar {
+    /// Time of the radar product this frame belongs to.
+    ///
+    /// Parsed from the end of the `source` identifier (e.g.
+    /// `RADOLAN::RV::2023-08-08T11:45:00+00:00`). All frames of one nowcast share
+    /// the time of the latest observation they were computed from.
+    pub fn source_time(&self) -> Option<DateTime<FixedOffset>> {
+        let (_, time) = self.source.rsplit_once("::")?;
+        DateTime::parse_from_rfc3339(time).ok()
+    }
+
+    /// Whether this frame is a nowcast prediction rather than an observation.
+    ///
+    /// A frame is a forecast if its timestamp lies after the time of the radar
+    /// product it belongs to. Frames with an unparseable `source` are treated as
+    /// observations.
+    pub fn is_forecast(&self) -> bool {
+        self.source_time()
+            .is_some_and(|source_time| self.timestamp > source_time)
+    }
+}
+
+impl RadarResponse {
+    /// Nominal time between two consecutive radar frames (5 minutes).
+    pub const FRAME_INTERVAL: TimeDelta = TimeDelta::minutes(5);
+
+    /// Decoded frames in chronological order, for animations and accumulations.
+    ///
+    /// Records are reshaped with [`Radar::to_grid`] using the response `bbox`,
+    /// or the full radar grid for responses without one. Records that do not
+    /// match the bounding box or fail to decode are skipped, see
+    /// [`RadarResponse::decode_all`].
+    ///
+    /// ```rust
+    /// use brightsky::{RadarFrame, types::RadarResponse};
+    ///
+    /// let response: RadarResponse = serde_json::from_str(r#"{
+    ///     "radar": [
+    ///         {"timestamp": "2023-08-07T12:05:00+00:00", "source": "RADOLAN::RV::2023-08-07T12:00:00+00:00", "precipitation_5": [[0, 30]]},
+    ///         {"timestamp": "2023-08-07T12:00:00+00:00", "source": "RADOLAN::RV::2023-08-07T12:00:00+00:00", "precipitation_5": [[10, 20]]}
+    ///     ],
+    ///     "bbox": [100, 200, 100, 201]
+    /// }"#).unwrap();
+    ///
+    /// let frames: Vec<RadarFrame> = response.frames().collect();
+    /// assert!(frames[0].time < frames[1].time);
+    /// assert_eq!(RadarFrame::max(&frames).unwrap().data(), [10, 30]);
+    /// assert_eq!(RadarFrame::accumulate(&frames).unwrap().data(), [10, 50]);
+    /// ```
+    pub fn frames(&self) -> impl Iterator<Item = RadarFrame> {
+        let frames = self.decode_each().into_iter();
+        chronological(frames.filter_map(|frame| frame.ok().flatten()).collect()).into_iter()
+    }
+
+    /// Decoded frames in chronological order, failing on the first record
+    /// whose precipitation data does not decode.
+    ///
+    /// Unlike [`RadarResponse::frames`], corrupt payloads are reported instead
+    /// of skipped; records that do not match the bounding box are still
+    /// skipped. With the `rayon` feature, records are decompressed and
+    /// reshaped on rayon's thread pool, which pays off for long sequences of
+    /// large grids.
+    pub fn decode_all(&self) -> Result<Vec<RadarFrame>, DecodeError> {
+        let frames = self.decode_each().into_iter().filter_map(Result::transpose);
+        Ok(chronological(frames.collect::<Result<_, _>>()?))
+    }
+
+    /// Per-pixel sum of all frames in 0.01 mm, e.g. the total rainfall over
+    /// the observed and forecast period of the response.
+    ///
+    /// Shorthand for [`RadarFrame::accumulate`] over
+    /// [`RadarResponse::decode_all`]; sums saturate at `u16::MAX`. Returns
+    /// `Ok(None)` if the response has no frames matching its bounding box.
+    ///
+    /// ```rust
+    /// use brightsky::types::RadarResponse;
+    ///
+    /// let response: RadarResponse = serde_json::from_str(r#"{
+    ///     "radar": [
+    ///         {"timestamp": "2023-08-07T12:00:00+00:00", "source": "RADOLAN::RV::2023-08-07T12:00:00+00:00", "precipitation_5": [[10, 0]]},
+    ///         {"timestamp": "2023-08-07T12:05:00+00:00", "source": "RADOLAN::RV::2023-08-07T12:00:00+00:00", "precipitation_5": [[25, 0]]}
+    ///     ],
+    ///     "bbox": [100, 200, 100, 201]
+    /// }"#).unwrap();
+    ///
+    /// let total = response.total_accumulation_grid().unwrap().unwrap();
+    /// assert_eq!(total.data(), [35, 0]);
+    /// assert_eq!(total.stats().rainy_pixels, 1);
+    /// ```
+    pub fn total_accumulation_grid(&self) -> Result<Option<RadarGrid>, DecodeError> {
+        Ok(RadarFrame::accumulate(&self.decode_all()?))
+    }
+
+    /// Decode every record in response order, in parallel with `rayon`.
+    fn decode_each(&self) -> Vec<Result<Option<RadarFrame>, DecodeError>> {
+        let bbox = self.bbox.as_deref().unwrap_or(&FULL_GRID_BBOX);
+        let decode = |record: &Radar| {
+            let grid = record.decode_grid(bbox)?;
+            Ok(grid.map(|grid| RadarFrame {
+                time: record.timestamp,
+                grid,
+            }))
+        };
+
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            self.radar.par_iter().map(decode).collect()
+        }
+        #[cfg(not(feature = "rayon"))]
+        self.radar.iter().map(decode).collect()
+    }
+
+    /// Radar frame timestamps in chronological order.
+    pub fn timestamps(&self) -> Vec<DateTime<FixedOffset>> {
+        let mut timestamps: Vec<_> = self.radar.iter().map(|r| r.timestamp).collect();
+        timestamps.sort();
+        timestamps.dedup();
+        timestamps
+    }
+
+    /// The most common time difference between consecutive frames.
+    ///
+    /// Returns `None` if the response contains fewer than two distinct frames.
+    /// For regular responses this is [`RadarResponse::FRAME_INTERVAL`]; when
+    /// differences are equally common, the shortest one is returned.
+    pub fn frame_interval(&self) -> Option<TimeDelta> {
+        let mut deltas: Vec<TimeDelta> = self
+            .timestamps()
+            .windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .collect();
+        deltas.sort();
+
+        let mut best: Option<(TimeDelta, usize)> = None;
+        for chunk in deltas.chunk_by(|a, b| a == b) {
+            if best.is_none_or(|(_, count)| chunk.len() > count) {
+                best = Some((chunk[0], chunk.len()));
+            }
+        }
+        best.map(|(delta, _)| delta)
+    }
+
+    /// Timestamps of frames missing from the regular 5-minute cadence.
+    ///
+    /// Looks at the span between the first and the last frame and returns every
+    /// expected 5-minute step that has no frame, in chronological order.
+    pub fn missing_frames(&self) -> Vec<DateTime<FixedOffset>> {
+        let timestamps = self.timestamps();
+        let mut missing = Vec::new();
+
+        for pair in timestamps.windows(2) {
+            let mut expected = pair[0] + Self::FRAME_INTERVAL;
+            while expected < pair[1] {
+                missing.push(expected);
+                expected += Self::FRAME_INTERVAL;
+            }
+        }
+        missing
+    }
+
+    /// Whether any frame is missing from the regular 5-minute cadence.
+    pub fn has_gaps(&self) -> bool {
+        !self.missing_frames().is_empty()
+    }
+
+    /// Frames containing measured precipitation, see [`Radar::is_forecast`].
+    pub fn observed(&self) -> Vec<&Radar> {
+        self.radar.iter().filter(|r| !r.is_forecast()).collect()
+    }
+
+    /// Frames containing nowcast predictions, see [`Radar::is_forecast`].
+    pub fn forecast(&self) -> Vec<&Radar> {
+        self.radar.iter().filter(|r| r.is_forecast()).collect()
+    }
+
+    /// Split frames into those at or before `now` and those after it.
+    ///
+    /// Unlike [`RadarResponse::observed`] and [`RadarResponse::forecast`] this
+    /// uses a clock instead of the radar product time, e.g. `Utc::now()`.
+    pub fn split_at<Tz: TimeZone>(&self, now: &DateTime<Tz>) -> (Vec<&Radar>, Vec<&Radar>) {
+        self.radar.iter().partition(|r| r.timestamp <= *now)
+    }
+}
+
+fn chronological(mut frames: Vec<RadarFrame>) -> Vec<RadarFrame> {
+    frames.sort_by_key(|frame| frame.time);
+    frames
+}
@@ -0,0 +1,136 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::{
+    DecodeError,
+    types::{MaybeCompressedPrecipitation, Radar, RadarCompressionFormat, RadarResponse},
+};
+
+/// Radar precipitation values reshaped into a two-dimensional grid.
+///
+/// Values are stored row by row, starting with the top row of the bounding box.
+/// Each value represents 0.01 mm / 5 min.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RadarGrid {
+    width: usize,
+    height: usize,
+    data: Vec<u16>,
+}
+
+impl RadarGrid {
+    /// Create a grid from row-major values.
+    ///
+    /// Returns `None` if `data` does not contain exactly `width * height` values.
+    pub fn new(width: usize, height: usize, data: Vec<u16>) -> Option<Self> {
+        if width.checked_mul(height)? != data.len() {
+            return None;
+        }
+        Some(Self {
+            width,
+            height,
+            data,
+        })
+    }
+
+    /// Number of columns (pixels from left to right).
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Number of rows (pixels from top to bottom).
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Value at column `x` and row `y`, relative to the top left corner.
+    pub fn get(&self, x: usize, y: usize) -> Option<u16> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        Some(self.data[y * self.width + x])
+    }
+
+    /// Iterate over the rows from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[u16]> {
+        // `chunks_exact` panics on a zero chunk size
+        self.data.chunks_exact(self.width.max(1))
+    }
+
+    /// All values in row-major order.
+    pub fn data(&self) -> &[u16] {
+        &self.data
+    }
+
+    /// Consume the grid, returning the row-major values.
+    pub fn into_data(self) -> Vec<u16> {
+        self.data
+    }
+}
+
+impl Radar {
+    /// Reshape the precipitation data into a [`RadarGrid`].
+    ///
+    /// `bbox` is the `(top, left, bottom, right)` pixel bounding box of the
+    /// response (`RadarResponse::bbox`), with both edges included. Plain data is
+    /// already two-dimensional and only checked against the bounding box.
+    ///
+    /// Returns `None` if the bounding box is malformed, does not match the
+    /// number of values in this record, or the data fails to decode. Use
+    /// [`Radar::decode_grid`] to tell decoding errors apart.
+    pub fn to_grid(&self, bbox: &[i64]) -> Option<RadarGrid> {
+        self.decode_grid(bbox).ok().flatten()
+    }
+
+    /// Like [`Radar::to_grid`], but returns an error if the precipitation data
+    /// fails to decode.
+    ///
+    /// `Ok(None)` means the bounding box is malformed or does not match.
+    pub fn decode_grid(&self, bbox: &[i64]) -> Result<Option<RadarGrid>, DecodeError> {
+        let Some((width, height)) = bbox_dimensions(bbox) else {
+            return Ok(None);
+        };
+
+        if let MaybeCompressedPrecipitation::Plain(rows) = &self.precipitation_5
+            && (rows.len() != height || rows.iter().any(|row| row.len() != width))
+        {
+            return Ok(None);
+        }
+        Ok(RadarGrid::new(
+            width,
+            height,
+            self.precipitation_5.decode()?,
+        ))
+    }
+}
+
+impl RadarResponse {
+    /// Treat every record as the format the query requested, see
+    /// [`MaybeCompressedPrecipitation::into_format`].
+    ///
+    /// Without it, compressed and raw payloads are told apart by their zlib
+    /// header, so a damaged header silently reads as raw bytes. Use
+    /// `RadarWeatherQuery::response_format` for the format of a query.
+    pub fn into_format(mut self, format: RadarCompressionFormat) -> Result<Self, DecodeError> {
+        for record in &mut self.radar {
+            let precipitation = core::mem::replace(
+                &mut record.precipitation_5,
+                MaybeCompressedPrecipitation::Plain(Vec::new()),
+            );
+            record.precipitation_5 = precipitation.into_format(format)?;
+        }
+        Ok(self)
+    }
+}
+
+/// Width and height of an inclusive `(top, left, bottom, right)` bounding box.
+fn bbox_dimensions(bbox: &[i64]) -> Option<(usize, usize)> {
+    let &[top, left, bottom, right] = bbox else {
+        return None;
+    };
+    let width = usize::try_from(right.checked_sub(left)?.checked_add(1)?).ok()?;
+    let height = usize::try_from(bottom.checked_sub(top)?.checked_add(1)?).ok()?;
+    Some((width, height))
+}
@@ -27,6 +27,14 @@
 //! - **Y range**: 500 to -1,199,500 (top to bottom)
 //! - **Pixel size**: 1000×1000 meters (1 km²)
 //!
+//! With the `std` feature, `brightsky::projection` converts between pixel
+//! positions and latitude/longitude without depending on PROJ.
+//!
+//! With the `render` feature, `brightsky::render` turns decoded grids into
+//! grayscale or color-mapped PNG images. Without it,
+//! `RadarGrid::render_ascii(width)` draws a grid with unicode block characters
+//! and ANSI colors for a quick look in the terminal.
+//!
 //! ## Usage Examples
 //!
 //! ### Basic radar query with compressed data (recommended)
@@ -45,15 +53,9 @@
 //!     let response: RadarResponse = reqwest::get(url).await?.json().await?;
 //!
 //!     for record in response.radar {
-//!         println!("Radar data at {}: {} pixels",
-//!             record.timestamp,
-//!             match &record.precipitation_5 {
-//!                 brightsky::types::MaybeCompressedPrecipitation::Compressed(data) => data.len(),
-//!                 brightsky::types::MaybeCompressedPrecipitation::Bytes(data) => data.len(),
-//!                 brightsky::types::MaybeCompressedPrecipitation::Plain(data) =>
-//!                     data.iter().map(|row| row.len()).sum::<usize>(),
-//!             }
-//!         );
+//!         // Compressed payloads are only decompressed on demand
+//!         let values = record.precipitation_5.decode()?;
+//!         println!("Radar data at {}: {} pixels", record.timestamp, values.len());
 //!     }
 //!
 //!     Ok(())
@@ -67,7 +69,7 @@
 //! #[tokio::main]
 //! async fn main() -> Result<(), Box<dyn std::error::Error>> {
 //!     let query = RadarWeatherQueryBuilder::new()
-//!         .with_bbox(vec![100, 100, 300, 300])  // 200x200 pixel area
+//!         .with_bbox([100, 100, 299, 299])  // 200x200 pixel area
 //!         .with_compression_format(RadarCompressionFormat::Plain)  // For easy processing
 //!         .build()?;
 //!
@@ -96,6 +98,21 @@
 //! }
 //! ```
 //!
+//! ## Frame Cadence
+//!
+//! Radar frames are nominally 5 minutes apart. `RadarResponse::frame_interval()`
+//! reports the observed cadence and `RadarResponse::missing_frames()` lists the
+//! timestamps of frames absent from the sequence, so animation and accumulation
+//! code can handle gaps explicitly.
+//!
+//! Frames after the time of the radar product are nowcast predictions.
+//! `RadarResponse::observed()` and `RadarResponse::forecast()` split the frames
+//! accordingly, `RadarResponse::split_at(now)` does the same against a clock.
+//!
+//! `RadarFrame::stats()` summarizes a frame (maximum, mean, rainy pixels and
+//! percentiles), `RadarResponse::total_accumulation_grid()` sums all frames
+//! into a single grid for rainfall totals.
+//!
 //! ## Data Processing Notes
 //!
 //! When working with compressed radar data, you'll need to:
@@ -103,6 +120,46 @@
 //! 2. Decompress using zlib (for compressed format)
 //! 3. Convert bytes to 16-bit integers (little-endian)
 //! 4. Reshape into 2D grid based on your bounding box dimensions
+//!
+//! Deserialization keeps base64 payloads as they are and tells compressed
+//! from raw data by the zlib header; `BrightSkyClient::get_radar` (or
+//! `RadarResponse::into_format`) uses the requested format instead, so corrupt
+//! data is an error rather than garbage values. Steps 1-3 happen on
+//! demand in `MaybeCompressedPrecipitation::decode()` (or `decode_into()` to
+//! reuse a buffer), so frames you never look at are never decompressed. For
+//! step 4, `Radar::to_grid(&bbox)` decodes and reshapes a record into a
+//! [`RadarGrid`] using the `bbox` of the response:
+//!
+//! ```rust
+//! use brightsky::types::RadarResponse;
+//!
+//! let response: RadarResponse = serde_json::from_str(r#"{
+//!     "radar": [{
+//!         "timestamp": "2023-08-07T12:00:00+00:00",
+//!         "source": "RADOLAN::RV::2023-08-07T12:00:00+00:00",
+//!         "precipitation_5": [[0, 1, 2], [3, 4, 5]]
+//!     }],
+//!     "bbox": [100, 200, 101, 202]
+//! }"#).unwrap();
+//!
+//! let bbox = response.bbox.as_deref().unwrap();
+//! let grid = response.radar[0].to_grid(bbox).unwrap();
+//! assert_eq!((grid.width(), grid.height()), (3, 2));
+//! assert_eq!(grid.get(2, 1), Some(5));
+//! ```
 
+mod ascii;
+mod bbox;
+mod frames;
+mod grid;
+#[cfg(feature = "std")]
+pub mod projection;
 mod query_builder;
+#[cfg(feature = "render")]
+pub mod render;
+mod stats;
+pub use bbox::RadarBbox;
+pub use frames::RadarFrame;
+pub use grid::RadarGrid;
 pub use query_builder::*;
+pub use stats::RadarStats;
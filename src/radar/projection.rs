@@ -0,0 +1,106 @@
+//! Conversion between radar pixel positions and geographic coordinates.
+//!
+//! The radar grid uses a polar stereographic projection with these parameters:
+//!
+//! ```text
+//! +proj=stere +lat_0=90 +lat_ts=60 +lon_0=10 +a=6378137 +b=6356752.3142451802
+//! +no_defs +x_0=543196.83521776402 +y_0=3622588.8619310018
+//! ```
+//!
+//! Pixels are 1 km wide. Pixel `(0, 0)` is the top left corner of the full grid
+//! and `x` grows to the right, `y` downwards, matching `RadarResponse::bbox`
+//! and `RadarResponse::latlon_position`. Whole numbers refer to pixel centers.
+//!
+//! Only available with the `std` feature, which provides the floating point
+//! functions the projection needs.
+//!
+//! ```rust
+//! use brightsky::projection::{latlon_to_pixel, pixel_to_latlon};
+//!
+//! let (x, y) = latlon_to_pixel(52.52, 13.4);
+//! let (lat, lon) = pixel_to_latlon(x, y);
+//!
+//! assert!((lat - 52.52).abs() < 1e-9);
+//! assert!((lon - 13.4).abs() < 1e-9);
+//! ```
+
+use core::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+pub use super::bbox::{GRID_HEIGHT, GRID_WIDTH};
+
+/// Edge length of one pixel in meters.
+const PIXEL_SIZE: f64 = 1000.0;
+
+/// Semi-major axis of the ellipsoid (`+a`).
+const SEMI_MAJOR: f64 = 6_378_137.0;
+/// Semi-minor axis of the ellipsoid (`+b`).
+const SEMI_MINOR: f64 = 6_356_752.314_245_18;
+/// Latitude of true scale (`+lat_ts`).
+const LAT_TS: f64 = 60.0;
+/// Central meridian (`+lon_0`).
+const LON_0: f64 = 10.0;
+/// False easting (`+x_0`).
+const FALSE_EASTING: f64 = 543_196.835_217_764;
+/// False northing (`+y_0`).
+const FALSE_NORTHING: f64 = 3_622_588.861_931_002;
+
+/// Convert a pixel position into `(lat, lon)` in decimal degrees.
+///
+/// Fractional positions are supported; positions outside of the grid are
+/// extrapolated.
+pub fn pixel_to_latlon(x: f64, y: f64) -> (f64, f64) {
+    let e = eccentricity();
+    let easting = x * PIXEL_SIZE - FALSE_EASTING;
+    let northing = -y * PIXEL_SIZE - FALSE_NORTHING;
+
+    let rho = easting.hypot(northing);
+    let t =
+        rho * t_factor(LAT_TS.to_radians(), e) / (SEMI_MAJOR * m_factor(LAT_TS.to_radians(), e));
+
+    // Iterate the conformal latitude until it converges (usually 4-5 steps)
+    let mut lat = FRAC_PI_2 - 2.0 * t.atan();
+    for _ in 0..15 {
+        let e_sin = e * lat.sin();
+        let next = FRAC_PI_2 - 2.0 * (t * ((1.0 - e_sin) / (1.0 + e_sin)).powf(e / 2.0)).atan();
+        let converged = (next - lat).abs() < 1e-12;
+        lat = next;
+        if converged {
+            break;
+        }
+    }
+    let lon = LON_0.to_radians() + easting.atan2(-northing);
+
+    (lat.to_degrees(), lon.to_degrees())
+}
+
+/// Convert `(lat, lon)` in decimal degrees into a pixel position.
+///
+/// The result is fractional; round it to get the pixel containing the point.
+/// Points outside of the radar coverage yield positions outside of
+/// `0..GRID_WIDTH` and `0..GRID_HEIGHT`.
+pub fn latlon_to_pixel(lat: f64, lon: f64) -> (f64, f64) {
+    let e = eccentricity();
+    let lat = lat.to_radians();
+    let lat_ts = LAT_TS.to_radians();
+    let delta_lon = lon.to_radians() - LON_0.to_radians();
+
+    let rho = SEMI_MAJOR * m_factor(lat_ts, e) * t_factor(lat, e) / t_factor(lat_ts, e);
+    let easting = FALSE_EASTING + rho * delta_lon.sin();
+    let northing = FALSE_NORTHING - rho * delta_lon.cos();
+
+    (easting / PIXEL_SIZE, -northing / PIXEL_SIZE)
+}
+
+fn eccentricity() -> f64 {
+    (1.0 - (SEMI_MINOR * SEMI_MINOR) / (SEMI_MAJOR * SEMI_MAJOR)).sqrt()
+}
+
+fn t_factor(lat: f64, e: f64) -> f64 {
+    let e_sin = e * lat.sin();
+    (FRAC_PI_4 - lat / 2.0).tan() / ((1.0 - e_sin) / (1.0 + e_sin)).powf(e / 2.0)
+}
+
+fn m_factor(lat: f64, e: f64) -> f64 {
+    let e_sin = e * lat.sin();
+    lat.cos() / (1.0 - e_sin * e_sin).sqrt()
+}
@@ -2,10 +2,19 @@
 extern crate alloc;
 
 #[cfg(not(feature = "std"))]
-use alloc::{format, string::String, string::ToString, vec::Vec};
+use alloc::{format, string::String};
 
-use crate::{BrightSkyError, ToBrightSkyUrl, types::RadarCompressionFormat};
-use chrono::NaiveDate;
+use core::ops::Deref;
+
+use crate::{
+    BrightSkyError, DEFAULT_COORDINATE_PRECISION, QueryError, RadarBbox, ToBrightSkyUrl, TzName,
+    coordinates::{
+        CoordinateFuzzing, QueryLocation, check_coordinates, check_location, url_coordinates,
+    },
+    types::{QueryDate, RadarCompressionFormat},
+    tz::check_tz,
+};
+use chrono::{DateTime, FixedOffset, NaiveDate};
 
 #[cfg(feature = "std")]
 use url::Url;
@@ -46,34 +55,42 @@ use url::Url;
 ///
 /// ### Custom bounding box for smaller area
 /// ```rust
-/// use brightsky::RadarWeatherQueryBuilder;
+/// use brightsky::{RadarBbox, RadarWeatherQueryBuilder};
 ///
 /// #[tokio::main]
 /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
 ///     let query = RadarWeatherQueryBuilder::new()
-///         .with_bbox(vec![100, 100, 300, 300])  // Custom 200x200 pixel area
+///         .with_bbox(RadarBbox { top: 100, left: 100, bottom: 299, right: 299 })  // 200x200 pixels
 ///         .build()?;
 ///     Ok(())
 /// }
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct RadarWeatherQueryBuilder {
     /// Bounding box in pixels (top, left, bottom, right)
-    pub bbox: Option<Vec<i64>>,
+    pub bbox: Option<RadarBbox>,
     /// Distance in meters around lat/lon (used with lat/lon, default: 200,000)
     pub distance: Option<u64>,
     /// Latitude in decimal degrees (-90.0 to 90.0)
-    pub lat: Option<String>,
+    pub lat: Option<f64>,
     /// Longitude in decimal degrees (-180.0 to 180.0)
-    pub lon: Option<String>,
+    pub lon: Option<f64>,
+    /// Number of decimal places used for `lat` and `lon` in URLs
+    pub coordinate_precision: u8,
+    /// Privacy grid applied to `lat` and `lon`, if any
+    pub coordinate_fuzzing: Option<CoordinateFuzzing>,
     /// First timestamp to retrieve (defaults to 1 hour before latest)
-    pub date: Option<NaiveDate>,
+    pub date: Option<QueryDate>,
     /// Last timestamp to retrieve (defaults to 2 hours after date)
-    pub last_date: Option<NaiveDate>,
+    pub last_date: Option<QueryDate>,
     /// Precipitation data encoding format
     pub compression_format: Option<RadarCompressionFormat>,
     /// Timezone for timestamp presentation (tz database format)
     pub tz: Option<String>,
+    /// Parameter of a [`QueryLocation`] that `/radar` does not support
+    unsupported_location: Option<&'static str>,
+    /// Whether `build` refuses queries without `lat`/`lon` or `bbox`
+    require_location: bool,
 }
 
 impl RadarWeatherQueryBuilder {
@@ -85,12 +102,16 @@ impl RadarWeatherQueryBuilder {
         Self {
             lat: None,
             lon: None,
+            coordinate_precision: DEFAULT_COORDINATE_PRECISION,
+            coordinate_fuzzing: None,
             bbox: None,
             distance: None,
             date: None,
             last_date: None,
             compression_format: None,
             tz: None,
+            unsupported_location: None,
+            require_location: false,
         }
     }
 
@@ -101,7 +122,8 @@ impl RadarWeatherQueryBuilder {
     ///
     /// # Parameters
     ///
-    /// * `lat_lon` - Tuple of (latitude, longitude) in decimal degrees
+    /// * `lat_lon` - Tuple of (latitude, longitude) in decimal degrees, or
+    ///   [`Coordinates`](crate::Coordinates)
     ///
     /// # Constraints
     ///
@@ -116,28 +138,35 @@ impl RadarWeatherQueryBuilder {
     /// let query = RadarWeatherQueryBuilder::new()
     ///     .with_lat_lon((52.0, 7.6));  // Near Münster
     /// ```
-    pub fn with_lat_lon(mut self, lat_lon: (f64, f64)) -> Self {
-        let lat_str = format!("{}", lat_lon.0);
-        let lon_str = format!("{}", lat_lon.1);
-
-        self.lat = Some(if !lat_str.contains('.') {
-            format!("{}.0", lat_str)
-        } else {
-            lat_str
-        });
-
-        self.lon = Some(if !lon_str.contains('.') {
-            format!("{}.0", lon_str)
-        } else {
-            lon_str
-        });
+    pub fn with_lat_lon(mut self, lat_lon: impl Into<(f64, f64)>) -> Self {
+        let (lat, lon) = lat_lon.into();
+        self.lat = Some(lat);
+        self.lon = Some(lon);
+        self
+    }
 
+    /// Set the number of decimal places used for `lat` and `lon` in the URL,
+    /// see [`DEFAULT_COORDINATE_PRECISION`].
+    pub fn with_coordinate_precision(mut self, decimals: u8) -> Self {
+        self.coordinate_precision = decimals;
+        self
+    }
+
+    /// Move `lat` and `lon` onto a coarse grid before they are sent to the API.
+    ///
+    /// Applies to coordinates set before or after this call. See
+    /// [`CoordinateFuzzing`] for the available strategies.
+    pub fn with_coordinate_fuzzing(mut self, fuzzing: CoordinateFuzzing) -> Self {
+        self.coordinate_fuzzing = Some(fuzzing);
         self
     }
 
     /// Set a custom bounding box for radar data in pixel coordinates.
-    pub fn with_bbox(mut self, bbox: Vec<i64>) -> Self {
-        self.bbox = Some(bbox);
+    ///
+    /// Takes a [`RadarBbox`] or a `[top, left, bottom, right]` array. `build()`
+    /// checks that it lies within the 1200×1100 pixel grid.
+    pub fn with_bbox(mut self, bbox: impl Into<RadarBbox>) -> Self {
+        self.bbox = Some(bbox.into());
         self
     }
 
@@ -147,21 +176,60 @@ impl RadarWeatherQueryBuilder {
         self
     }
 
+    /// Set the location from a [`QueryLocation`], replacing any location set before.
+    ///
+    /// `LatLon` sets `lat`/`lon` and uses `max_dist` as `distance`. Station and
+    /// source locations make `build()` fail with `UnsupportedLocation`.
+    pub fn with_location(mut self, location: impl Into<QueryLocation>) -> Self {
+        self.lat = None;
+        self.lon = None;
+        self.bbox = None;
+        self.distance = None;
+        self.unsupported_location = None;
+        match location.into() {
+            QueryLocation::LatLon { lat, lon, max_dist } => {
+                self.distance = max_dist.map(u64::from);
+                self.with_lat_lon((lat, lon))
+            }
+            location => {
+                self.unsupported_location = Some(location.parameter());
+                self
+            }
+        }
+    }
+
     /// Set the start date for radar data retrieval (optional).
     pub fn with_date(mut self, date: NaiveDate) -> Self {
-        self.date = Some(date);
+        self.date = Some(date.into());
         self
     }
 
     /// Set the end date for radar data retrieval (optional).
     pub fn with_last_date(mut self, last_date: NaiveDate) -> Self {
-        self.last_date = Some(last_date);
+        self.last_date = Some(last_date.into());
+        self
+    }
+
+    /// Set the first timestamp to retrieve, including time of day and UTC offset.
+    pub fn with_datetime(mut self, datetime: impl Into<DateTime<FixedOffset>>) -> Self {
+        self.date = Some(QueryDate::DateTime(datetime.into()));
+        self
+    }
+
+    /// Set the first and last timestamp to retrieve.
+    pub fn with_datetime_range(
+        mut self,
+        datetime: impl Into<DateTime<FixedOffset>>,
+        last_datetime: impl Into<DateTime<FixedOffset>>,
+    ) -> Self {
+        self.date = Some(QueryDate::DateTime(datetime.into()));
+        self.last_date = Some(QueryDate::DateTime(last_datetime.into()));
         self
     }
 
     /// Set the timezone for timestamp presentation.
-    pub fn with_tz(mut self, tz: &str) -> Self {
-        self.tz = Some(tz.to_string());
+    pub fn with_tz(mut self, tz: impl TzName) -> Self {
+        self.tz = Some(tz.tz_name().into());
         self
     }
 
@@ -171,80 +239,138 @@ impl RadarWeatherQueryBuilder {
         self
     }
 
+    /// Refuse to build the query without `lat`/`lon` or a bounding box.
+    ///
+    /// Without a spatial restriction the API returns the full 1200×1100 pixel
+    /// grid for every frame, which can exhaust the memory of small services.
+    /// To request the full grid anyway, say so explicitly with
+    /// `with_bbox(RadarBbox::FULL)`.
+    ///
+    /// ```rust
+    /// use brightsky::{BrightSkyError, QueryError, RadarBbox, RadarWeatherQueryBuilder};
+    ///
+    /// let result = RadarWeatherQueryBuilder::new().require_location().build();
+    /// assert!(matches!(result, Err(BrightSkyError::Query(QueryError::MissingLocation))));
+    ///
+    /// let full_grid = RadarWeatherQueryBuilder::new()
+    ///     .require_location()
+    ///     .with_bbox(RadarBbox::FULL)
+    ///     .build();
+    /// assert!(full_grid.is_ok());
+    /// ```
+    pub fn require_location(mut self) -> Self {
+        self.require_location = true;
+        self
+    }
+
     /// Build and validate the query.
-    pub fn build(self) -> Result<Self, BrightSkyError> {
-        if let Some(lat_str) = &self.lat {
-            lat_str
-                .parse::<f64>()
-                .map_err(BrightSkyError::ParseFloatError)
-                .and_then(|lat| -> Result<(), BrightSkyError> {
-                    if !(-90.0..=90.0).contains(&lat) {
-                        Err(BrightSkyError::InvalidLongitude(lat))
-                    } else {
-                        Ok(())
-                    }
-                })?;
+    ///
+    /// # Errors
+    ///
+    /// - `InvalidLatitude`/`InvalidLongitude` - Coordinates out of valid range
+    /// - `InvalidBbox` - Bounding box empty or outside the radar grid
+    /// - `ConflictingLocationParameters` - Both `lat`/`lon` and `bbox` are set
+    /// - `UnsupportedLocation` - A station or source [`QueryLocation`] was set
+    /// - `MissingLocation` - Neither `lat`/`lon` nor `bbox` is set, but
+    ///   [`require_location`](Self::require_location) was
+    /// - `InvalidTimezone` - Unknown timezone (with the `chrono-tz` feature)
+    pub fn build(self) -> Result<RadarWeatherQuery, BrightSkyError> {
+        if let Some(parameter) = self.unsupported_location {
+            return Err(QueryError::UnsupportedLocation(parameter).into());
         }
-        if let Some(lon_str) = &self.lon {
-            lon_str
-                .parse::<f64>()
-                .map_err(BrightSkyError::ParseFloatError)
-                .and_then(|lon| -> Result<(), BrightSkyError> {
-                    if !(-180.0..=180.0).contains(&lon) {
-                        Err(BrightSkyError::InvalidLongitude(lon))
-                    } else {
-                        Ok(())
-                    }
-                })?;
+        check_coordinates(self.lat, self.lon)?;
+        if let Some(bbox) = &self.bbox {
+            bbox.validate()?;
         }
+        check_location(
+            &[
+                ("lat/lon", self.lat.is_some() || self.lon.is_some()),
+                ("bbox", self.bbox.is_some()),
+            ],
+            self.require_location,
+        )?;
+        check_tz(self.tz.as_deref())?;
+
+        Ok(RadarWeatherQuery { builder: self })
+    }
+}
+
+/// A validated `/radar` query, returned by [`RadarWeatherQueryBuilder::build`].
+///
+/// Only validated queries implement [`ToBrightSkyUrl`] and can be sent with a
+/// client. The parameters can be read through [`Deref`] to the builder; use
+/// [`into_builder`](Self::into_builder) to change them and build again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RadarWeatherQuery {
+    builder: RadarWeatherQueryBuilder,
+}
+
+impl RadarWeatherQuery {
+    /// Turn the query back into a builder, e.g. to change a parameter.
+    pub fn into_builder(self) -> RadarWeatherQueryBuilder {
+        self.builder
+    }
+
+    /// Format of the precipitation data in the response; the API sends
+    /// [`RadarCompressionFormat::Compressed`] unless another format is set.
+    ///
+    /// Pass it to `RadarResponse::into_format` to decode the response as
+    /// requested, as `BrightSkyClient::get_radar` does.
+    pub fn response_format(&self) -> RadarCompressionFormat {
+        self.builder
+            .compression_format
+            .unwrap_or(RadarCompressionFormat::Compressed)
+    }
+}
+
+impl Deref for RadarWeatherQuery {
+    type Target = RadarWeatherQueryBuilder;
 
-        Ok(self)
+    fn deref(&self) -> &Self::Target {
+        &self.builder
     }
 }
 
-impl ToBrightSkyUrl for RadarWeatherQueryBuilder {
+impl ToBrightSkyUrl for RadarWeatherQuery {
     #[cfg(feature = "std")]
     fn to_url(self, host: &str) -> Result<Url, BrightSkyError> {
+        let builder = self.builder;
+        let (lat, lon) = url_coordinates(
+            builder.lat,
+            builder.lon,
+            builder.coordinate_precision,
+            builder.coordinate_fuzzing,
+        );
         let base = Url::parse(host)?;
         let mut url = base.join("radar")?;
 
         let mut query = url.query_pairs_mut();
 
-        if let Some(lat) = self.lat {
+        if let Some(lat) = lat {
             query.append_pair("lat", &lat);
         }
-        if let Some(lon) = self.lon {
+        if let Some(lon) = lon {
             query.append_pair("lon", &lon);
         }
 
-        if let Some(bbox) = self.bbox {
-            let bbox_str = bbox
-                .iter()
-                .map(|v| v.to_string())
-                .collect::<Vec<String>>()
-                .join(",");
-            query.append_pair("bbox", &bbox_str);
+        if let Some(bbox) = builder.bbox {
+            query.append_pair("bbox", &bbox.to_string());
         }
 
-        if let Some(distance) = self.distance {
+        if let Some(distance) = builder.distance {
             query.append_pair("distance", &distance.to_string());
         }
-        if let Some(date) = self.date {
+        if let Some(date) = builder.date {
             query.append_pair("date", &date.to_string());
         }
-        if let Some(last_date) = self.last_date {
+        if let Some(last_date) = builder.last_date {
             query.append_pair("last_date", &last_date.to_string());
         }
-        if let Some(format) = self.compression_format {
-            let format_str = match format {
-                RadarCompressionFormat::Compressed => "compressed",
-                RadarCompressionFormat::Bytes => "bytes",
-                RadarCompressionFormat::Plain => "plain",
-            };
-            query.append_pair("format", format_str);
+        if let Some(format) = builder.compression_format {
+            query.append_pair("format", format.as_str());
         }
 
-        if let Some(tz) = self.tz {
+        if let Some(tz) = builder.tz {
             query.append_pair("tz", &tz);
         }
         drop(query);
@@ -252,46 +378,43 @@ impl ToBrightSkyUrl for RadarWeatherQueryBuilder {
     }
 
     fn to_url_string(self, host: &str) -> Result<String, BrightSkyError> {
+        let builder = self.builder;
         #[cfg(not(feature = "std"))]
         use alloc::vec::Vec;
         #[cfg(feature = "std")]
         use std::vec::Vec;
 
+        let (lat, lon) = url_coordinates(
+            builder.lat,
+            builder.lon,
+            builder.coordinate_precision,
+            builder.coordinate_fuzzing,
+        );
         let mut url = format!("{}/radar", host.trim_end_matches('/'));
         let mut params = Vec::new();
 
-        if let Some(lat) = self.lat {
+        if let Some(lat) = lat {
             params.push(format!("lat={}", lat));
         }
-        if let Some(lon) = self.lon {
+        if let Some(lon) = lon {
             params.push(format!("lon={}", lon));
         }
-        if let Some(bbox) = self.bbox {
-            let bbox_str = bbox
-                .iter()
-                .map(|v| v.to_string())
-                .collect::<Vec<String>>()
-                .join(",");
-            params.push(format!("bbox={}", bbox_str));
+        if let Some(bbox) = builder.bbox {
+            params.push(format!("bbox={}", bbox));
         }
-        if let Some(distance) = self.distance {
+        if let Some(distance) = builder.distance {
             params.push(format!("distance={}", distance));
         }
-        if let Some(date) = self.date {
-            params.push(format!("date={}", date));
+        if let Some(date) = builder.date {
+            params.push(format!("date={}", date.to_url_param()));
         }
-        if let Some(last_date) = self.last_date {
-            params.push(format!("last_date={}", last_date));
+        if let Some(last_date) = builder.last_date {
+            params.push(format!("last_date={}", last_date.to_url_param()));
         }
-        if let Some(format) = self.compression_format {
-            let format_str = match format {
-                RadarCompressionFormat::Compressed => "compressed",
-                RadarCompressionFormat::Bytes => "bytes",
-                RadarCompressionFormat::Plain => "plain",
-            };
-            params.push(format!("format={}", format_str));
+        if let Some(format) = builder.compression_format {
+            params.push(format!("format={}", format.as_str()));
         }
-        if let Some(tz) = self.tz {
+        if let Some(tz) = builder.tz {
             params.push(format!("tz={}", tz));
         }
 
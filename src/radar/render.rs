@@ -0,0 +1,141 @@
+//! PNG rendering of radar grids.
+//!
+//! [`RadarGrid::to_image`] turns precipitation values into an image, either as
+//! grayscale intensities or through a [`ColorRamp`] of mm / 5 min bins. The
+//! images can be saved as rain maps or used as frames of an animation:
+//!
+//! ```rust
+//! use brightsky::{
+//!     RadarGrid,
+//!     render::{ColorRamp, RenderStyle},
+//! };
+//!
+//! let grid = RadarGrid::new(2, 1, vec![0, 120]).unwrap();
+//!
+//! let ramp = ColorRamp::new()
+//!     .with_bin(0.1, [120, 180, 255, 255])
+//!     .with_bin(1.0, [0, 0, 200, 255]);
+//! let image = grid.to_image(&RenderStyle::Color(ramp)).into_rgba8();
+//! assert_eq!(image.get_pixel(0, 0).0, [0, 0, 0, 0]);
+//! assert_eq!(image.get_pixel(1, 0).0, [0, 0, 200, 255]);
+//!
+//! let png = grid.to_png(&RenderStyle::default()).unwrap();
+//! assert!(png.starts_with(b"\x89PNG"));
+//! ```
+//!
+//! Only available with the `render` feature.
+
+use std::{io::Cursor, path::Path};
+
+pub use image;
+use image::{DynamicImage, GrayImage, ImageFormat, ImageResult, Luma, Rgba, RgbaImage};
+
+use crate::radar::RadarGrid;
+
+/// Colors for ranges of precipitation.
+///
+/// Each bin starts at a precipitation amount in mm / 5 min and reaches up to
+/// the next bin. Pixels below the first bin are transparent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorRamp {
+    /// Lower bounds in 0.01 mm / 5 min and RGBA colors, sorted by bound
+    bins: Vec<(u16, [u8; 4])>,
+}
+
+impl Default for ColorRamp {
+    /// Blue for light rain over green and yellow to red and purple for
+    /// cloudbursts, starting at 0.01 mm / 5 min.
+    fn default() -> Self {
+        Self::new()
+            .with_bin(0.01, [166, 206, 227, 255])
+            .with_bin(0.1, [31, 120, 180, 255])
+            .with_bin(0.5, [51, 160, 44, 255])
+            .with_bin(1.0, [255, 237, 0, 255])
+            .with_bin(2.5, [255, 127, 0, 255])
+            .with_bin(5.0, [227, 26, 28, 255])
+            .with_bin(10.0, [148, 0, 211, 255])
+    }
+}
+
+impl ColorRamp {
+    /// A ramp without bins, rendering every pixel transparent.
+    pub fn new() -> Self {
+        Self { bins: Vec::new() }
+    }
+
+    /// Add a bin starting at `min_mm` mm / 5 min, replacing a bin with the same
+    /// start.
+    pub fn with_bin(mut self, min_mm: f64, color: [u8; 4]) -> Self {
+        let min = (min_mm * 100.0).round().clamp(0.0, f64::from(u16::MAX)) as u16;
+        match self.bins.binary_search_by_key(&min, |(bound, _)| *bound) {
+            Ok(index) => self.bins[index].1 = color,
+            Err(index) => self.bins.insert(index, (min, color)),
+        }
+        self
+    }
+
+    /// Color of a value in 0.01 mm / 5 min.
+    pub fn color(&self, value: u16) -> [u8; 4] {
+        self.bins
+            .iter()
+            .rev()
+            .find(|(bound, _)| value >= *bound)
+            .map_or([0; 4], |(_, color)| *color)
+    }
+}
+
+/// How precipitation values are turned into pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenderStyle {
+    /// Single-channel intensity from black (dry) to white (`max_mm` mm / 5 min
+    /// or more)
+    Grayscale {
+        /// Precipitation rendered as white
+        max_mm: f64,
+    },
+    /// RGBA colors of a [`ColorRamp`]
+    Color(ColorRamp),
+}
+
+impl Default for RenderStyle {
+    /// The default [`ColorRamp`].
+    fn default() -> Self {
+        Self::Color(ColorRamp::default())
+    }
+}
+
+impl RadarGrid {
+    /// Render the grid with one pixel per radar pixel, top row first.
+    pub fn to_image(&self, style: &RenderStyle) -> DynamicImage {
+        let (width, height) = (self.width() as u32, self.height() as u32);
+        let value = |x: u32, y: u32| self.get(x as usize, y as usize).unwrap_or(0);
+
+        match style {
+            RenderStyle::Grayscale { max_mm } => {
+                let max = (max_mm * 100.0).max(1.0);
+                let image = GrayImage::from_fn(width, height, |x, y| {
+                    let intensity = f64::from(value(x, y)) / max * 255.0;
+                    Luma([intensity.round().min(255.0) as u8])
+                });
+                DynamicImage::ImageLuma8(image)
+            }
+            RenderStyle::Color(ramp) => {
+                let image = RgbaImage::from_fn(width, height, |x, y| Rgba(ramp.color(value(x, y))));
+                DynamicImage::ImageRgba8(image)
+            }
+        }
+    }
+
+    /// Render the grid and encode it as PNG.
+    pub fn to_png(&self, style: &RenderStyle) -> ImageResult<Vec<u8>> {
+        let mut png = Cursor::new(Vec::new());
+        self.to_image(style).write_to(&mut png, ImageFormat::Png)?;
+        Ok(png.into_inner())
+    }
+
+    /// Render the grid and write it to a PNG file.
+    pub fn save_png(&self, path: impl AsRef<Path>, style: &RenderStyle) -> ImageResult<()> {
+        self.to_image(style)
+            .save_with_format(path, ImageFormat::Png)
+    }
+}
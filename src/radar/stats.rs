@@ -0,0 +1,152 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+use crate::radar::{RadarFrame, RadarGrid};
+
+/// Summary statistics of a radar grid, see [`RadarGrid::stats`].
+///
+/// Values are in 0.01 mm / 5 min for a single frame, or 0.01 mm for an
+/// accumulated grid. Percentiles use the nearest-rank method over all pixels,
+/// dry ones included.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RadarStats {
+    /// Number of pixels in the grid
+    pub pixels: usize,
+    /// Number of pixels with any precipitation (value above zero)
+    pub rainy_pixels: usize,
+    /// Highest value
+    pub max: u16,
+    /// Mean value over all pixels
+    pub mean: f64,
+    /// 50th percentile (median)
+    pub p50: u16,
+    /// 90th percentile
+    pub p90: u16,
+    /// 95th percentile
+    pub p95: u16,
+    /// 99th percentile
+    pub p99: u16,
+}
+
+impl RadarStats {
+    /// Share of pixels with precipitation, between 0 and 1.
+    pub fn rainy_fraction(&self) -> f64 {
+        if self.pixels == 0 {
+            return 0.0;
+        }
+        self.rainy_pixels as f64 / self.pixels as f64
+    }
+}
+
+impl RadarGrid {
+    /// Maximum, mean, rainy-pixel count and percentiles, computed from a
+    /// single ranking of the values.
+    ///
+    /// An empty grid yields all zeros.
+    ///
+    /// ```rust
+    /// use brightsky::RadarGrid;
+    ///
+    /// let grid = RadarGrid::new(5, 2, vec![0, 0, 0, 0, 0, 10, 20, 30, 40, 400]).unwrap();
+    /// let stats = grid.stats();
+    /// assert_eq!((stats.max, stats.rainy_pixels), (400, 5));
+    /// assert_eq!(stats.mean, 50.0);
+    /// assert_eq!((stats.p50, stats.p90), (0, 40));
+    /// ```
+    pub fn stats(&self) -> RadarStats {
+        let ranking = Ranking::new(self.data());
+        let pixels = self.data().len();
+
+        let sum: u64 = self.data().iter().map(|&value| u64::from(value)).sum();
+        let mean = if pixels == 0 {
+            0.0
+        } else {
+            sum as f64 / pixels as f64
+        };
+        let rank = |p| ranking.nearest_rank(p).unwrap_or(0);
+
+        RadarStats {
+            pixels,
+            rainy_pixels: self.data().iter().filter(|&&value| value > 0).count(),
+            max: self.data().iter().copied().max().unwrap_or(0),
+            mean,
+            p50: rank(50),
+            p90: rank(90),
+            p95: rank(95),
+            p99: rank(99),
+        }
+    }
+
+    /// The `p`th percentile (0 to 100) of all values, using the nearest-rank
+    /// method.
+    ///
+    /// Returns `None` for an empty grid or `p` above 100. For several
+    /// percentiles of the same grid, [`RadarGrid::stats`] ranks the values
+    /// only once.
+    pub fn percentile(&self, p: u8) -> Option<u16> {
+        Ranking::new(self.data()).nearest_rank(p)
+    }
+}
+
+impl RadarFrame {
+    /// Summary statistics of this frame, see [`RadarGrid::stats`].
+    pub fn stats(&self) -> RadarStats {
+        self.grid.stats()
+    }
+}
+
+/// Grids with more values than this are ranked with a histogram, smaller
+/// ones by sorting a copy.
+///
+/// The histogram has a count for every `u16` (256 KiB) and takes linear time,
+/// which only pays off for grids of a similar size, e.g. a whole radar frame.
+const HISTOGRAM_MIN_LEN: usize = 1 << 16;
+
+/// The values of a grid in a form that answers percentile queries.
+enum Ranking {
+    /// The values in ascending order
+    Sorted(Vec<u16>),
+    /// Number of values equal to each `u16`, and the number of values
+    Histogram(Vec<u32>, usize),
+}
+
+impl Ranking {
+    fn new(values: &[u16]) -> Self {
+        // Counts are `u32`, so larger grids are sorted as well
+        if values.len() < HISTOGRAM_MIN_LEN || u32::try_from(values.len()).is_err() {
+            let mut sorted = values.to_vec();
+            sorted.sort_unstable();
+            return Self::Sorted(sorted);
+        }
+        let mut counts = vec![0; usize::from(u16::MAX) + 1];
+        for &value in values {
+            counts[usize::from(value)] += 1;
+        }
+        Self::Histogram(counts, values.len())
+    }
+
+    /// Smallest value with at least `p` percent of all values at or below it.
+    fn nearest_rank(&self, p: u8) -> Option<u16> {
+        let len = match self {
+            Self::Sorted(sorted) => sorted.len(),
+            Self::Histogram(_, len) => *len,
+        };
+        if p > 100 || len == 0 {
+            return None;
+        }
+        let rank = (usize::from(p) * len).div_ceil(100).max(1);
+        match self {
+            Self::Sorted(sorted) => sorted.get(rank - 1).copied(),
+            Self::Histogram(counts, _) => {
+                let mut seen = 0;
+                (0..=u16::MAX).zip(counts).find_map(|(value, &count)| {
+                    seen += count as usize;
+                    (seen >= rank).then_some(value)
+                })
+            }
+        }
+    }
+}
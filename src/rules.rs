@@ -0,0 +1,381 @@
+//! Threshold rules for custom warning conditions.
+//!
+//! A [`Rule`] compares one weather parameter against a threshold, optionally
+//! for a number of consecutive hours. Rules are serializable, so they can be
+//! kept in configuration files next to the monitored locations:
+//!
+//! ```rust
+//! use brightsky::rules::{self, Rule};
+//! use brightsky::types::WeatherResponse;
+//!
+//! let rules: Vec<Rule> = serde_json::from_str(r#"[
+//!     {"field": "temperature", "op": ">=", "value": 30.0, "duration": 2},
+//!     {"field": "wind_gust_speed", "op": ">", "value": 60.0}
+//! ]"#).unwrap();
+//!
+//! let response: WeatherResponse = serde_json::from_str(r#"{
+//!     "weather": [
+//!         {"timestamp": "2023-08-07T13:00:00+02:00", "source_id": 1, "temperature": 29.0},
+//!         {"timestamp": "2023-08-07T14:00:00+02:00", "source_id": 1, "temperature": 30.5},
+//!         {"timestamp": "2023-08-07T15:00:00+02:00", "source_id": 1, "temperature": 31.0}
+//!     ],
+//!     "sources": []
+//! }"#).unwrap();
+//!
+//! let matches = rules::evaluate(&response, &rules);
+//! assert_eq!(matches.len(), 1);
+//! assert_eq!(matches[0].rule, 0);
+//! assert_eq!(matches[0].start.to_rfc3339(), "2023-08-07T14:00:00+02:00");
+//! assert_eq!(matches[0].end.to_rfc3339(), "2023-08-07T15:00:00+02:00");
+//! ```
+//!
+//! ## Fields
+//!
+//! `field` is a numerical [`WeatherParameter`], named as returned by the API,
+//! see [`FIELDS`]. Against [`CurrentWeatherResponse`], the interval fields such
+//! as `wind_speed_10` can be used as well, and the hourly names such as
+//! `wind_speed` refer to the shortest available interval. Records without a
+//! value for the field never match. Unknown and non-numerical fields such as
+//! `condition` are rejected when a rule is created or deserialized:
+//!
+//! ```rust
+//! use brightsky::rules::Rule;
+//!
+//! let typo = serde_json::from_str::<Rule>(r#"{"field": "temprature", "op": ">", "value": 30.0}"#);
+//! assert!(typo.is_err());
+//! ```
+//!
+//! ## Duration
+//!
+//! `duration` is the number of consecutive hourly records the condition has to
+//! hold for, 1 if omitted. Records count as consecutive if they are at most an
+//! hour apart. Current weather is a single observation, so its rules match
+//! regardless of their duration.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use core::borrow::Borrow;
+
+use chrono::{DateTime, FixedOffset, TimeDelta};
+use serde::{Deserialize, Deserializer, Serialize, de::Error as _};
+
+use crate::types::{
+    CurrentWeather, CurrentWeatherResponse, Weather, WeatherParameter, WeatherResponse,
+};
+
+/// Parameters of hourly records rules can refer to.
+pub const FIELDS: &[WeatherParameter] = &[
+    WeatherParameter::CloudCover,
+    WeatherParameter::DewPoint,
+    WeatherParameter::PressureMsl,
+    WeatherParameter::RelativeHumidity,
+    WeatherParameter::Temperature,
+    WeatherParameter::Visibility,
+    WeatherParameter::Precipitation,
+    WeatherParameter::Solar,
+    WeatherParameter::Sunshine,
+    WeatherParameter::WindDirection,
+    WeatherParameter::WindSpeed,
+    WeatherParameter::WindGustDirection,
+    WeatherParameter::WindGustSpeed,
+    WeatherParameter::PrecipitationProbability,
+    WeatherParameter::PrecipitationProbability6h,
+];
+
+/// Comparison of a [`Rule`].
+///
+/// Serialized as `>`, `>=`, `<`, `<=`, `==` and `!=`; `gt`, `ge`, `lt`, `le`,
+/// `eq` and `ne` are accepted as well.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Op {
+    /// Greater than the threshold
+    #[serde(rename = ">", alias = "gt")]
+    Gt,
+    /// Greater than or equal to the threshold
+    #[serde(rename = ">=", alias = "ge")]
+    Ge,
+    /// Less than the threshold
+    #[serde(rename = "<", alias = "lt")]
+    Lt,
+    /// Less than or equal to the threshold
+    #[serde(rename = "<=", alias = "le")]
+    Le,
+    /// Equal to the threshold
+    #[serde(rename = "==", alias = "eq")]
+    Eq,
+    /// Not equal to the threshold
+    #[serde(rename = "!=", alias = "ne")]
+    Ne,
+}
+
+impl Op {
+    /// Whether `value` compares to `threshold` as required.
+    pub fn compare(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            Self::Gt => value > threshold,
+            Self::Ge => value >= threshold,
+            Self::Lt => value < threshold,
+            Self::Le => value <= threshold,
+            Self::Eq => value == threshold,
+            Self::Ne => value != threshold,
+        }
+    }
+}
+
+impl core::fmt::Display for Op {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let symbol = match self {
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Eq => "==",
+            Self::Ne => "!=",
+        };
+        f.write_str(symbol)
+    }
+}
+
+/// A threshold condition on one weather parameter, see the
+/// [`rules`](crate::rules) module.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rule {
+    /// Parameter compared, e.g. `temperature`
+    #[serde(deserialize_with = "deserialize_field")]
+    pub field: WeatherParameter,
+    /// How the parameter is compared to `value`
+    pub op: Op,
+    /// Threshold in the units of the response
+    pub value: f64,
+    /// Consecutive hours the condition has to hold for (1 if `None`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<u32>,
+}
+
+impl Rule {
+    /// Create a rule that matches a single record.
+    ///
+    /// Fails for parameters that are not numerical, such as `condition` and
+    /// names unknown to this version.
+    pub fn new(field: WeatherParameter, op: Op, value: f64) -> Result<Self, UnknownField> {
+        Ok(Self {
+            field: check_field(field)?,
+            op,
+            value,
+            duration: None,
+        })
+    }
+
+    /// Set the consecutive hours the condition has to hold for.
+    pub fn with_duration(mut self, hours: u32) -> Self {
+        self.duration = Some(hours);
+        self
+    }
+
+    /// Whether `value` satisfies the condition, ignoring the duration.
+    pub fn matches(&self, value: f64) -> bool {
+        self.op.compare(value, self.value)
+    }
+}
+
+impl core::fmt::Display for Rule {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} {} {}", self.field, self.op, self.value)?;
+        match self.duration {
+            Some(hours) if hours > 1 => write!(f, " for {}h", hours),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Error for a [`Rule`] on a parameter that is not numerical.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownField(pub WeatherParameter);
+
+impl core::fmt::Display for UnknownField {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Unknown rule field: {}", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnknownField {}
+
+fn check_field(field: WeatherParameter) -> Result<WeatherParameter, UnknownField> {
+    match field {
+        WeatherParameter::Condition | WeatherParameter::Icon | WeatherParameter::Other(_) => {
+            Err(UnknownField(field))
+        }
+        field => Ok(field),
+    }
+}
+
+fn deserialize_field<'de, D>(deserializer: D) -> Result<WeatherParameter, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    check_field(WeatherParameter::deserialize(deserializer)?).map_err(D::Error::custom)
+}
+
+/// A period in which a rule held.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleMatch {
+    /// Index of the rule in the evaluated rules
+    pub rule: usize,
+    /// Timestamp of the first matching record
+    pub start: DateTime<FixedOffset>,
+    /// Timestamp of the last matching record
+    pub end: DateTime<FixedOffset>,
+}
+
+/// Responses rules can be evaluated against.
+pub trait Evaluate {
+    /// The periods in which each rule held, ordered by rule and start.
+    fn evaluate(&self, rules: &[Rule]) -> Vec<RuleMatch>;
+}
+
+/// Evaluate `rules` against a weather or current weather response.
+pub fn evaluate<R: Evaluate + ?Sized>(response: &R, rules: &[Rule]) -> Vec<RuleMatch> {
+    response.evaluate(rules)
+}
+
+impl Evaluate for WeatherResponse {
+    /// Each maximal run of consecutive matching records that is at least
+    /// `duration` records long is one match.
+    fn evaluate(&self, rules: &[Rule]) -> Vec<RuleMatch> {
+        let mut matches = Vec::new();
+        for (index, rule) in rules.iter().enumerate() {
+            let required = rule.duration.unwrap_or(1).max(1) as usize;
+            let mut run: Option<(DateTime<FixedOffset>, DateTime<FixedOffset>, usize)> = None;
+
+            for record in &self.weather {
+                let holds = record
+                    .field_value(&rule.field)
+                    .is_some_and(|value| rule.matches(value));
+                run = match run {
+                    Some((start, end, length))
+                        if holds && record.timestamp - end <= TimeDelta::hours(1) =>
+                    {
+                        Some((start, record.timestamp, length + 1))
+                    }
+                    _ => {
+                        close_run(&mut matches, index, run, required);
+                        holds.then_some((record.timestamp, record.timestamp, 1))
+                    }
+                };
+            }
+            close_run(&mut matches, index, run, required);
+        }
+        matches
+    }
+}
+
+impl Evaluate for CurrentWeatherResponse {
+    /// Rules match the observation if its value satisfies the condition.
+    fn evaluate(&self, rules: &[Rule]) -> Vec<RuleMatch> {
+        let weather = &self.weather;
+        rules
+            .iter()
+            .enumerate()
+            .filter(|(_, rule)| {
+                weather
+                    .field_value(&rule.field)
+                    .is_some_and(|value| rule.matches(value))
+            })
+            .map(|(rule, _)| RuleMatch {
+                rule,
+                start: weather.timestamp,
+                end: weather.timestamp,
+            })
+            .collect()
+    }
+}
+
+fn close_run(
+    matches: &mut Vec<RuleMatch>,
+    rule: usize,
+    run: Option<(DateTime<FixedOffset>, DateTime<FixedOffset>, usize)>,
+    required: usize,
+) {
+    if let Some((start, end, length)) = run
+        && length >= required
+    {
+        matches.push(RuleMatch { rule, start, end });
+    }
+}
+
+impl Weather {
+    /// Numerical value of `field`, see [`FIELDS`].
+    pub fn field_value(&self, field: impl Borrow<WeatherParameter>) -> Option<f64> {
+        match field.borrow() {
+            WeatherParameter::CloudCover => self.cloud_cover,
+            WeatherParameter::DewPoint => self.dew_point,
+            WeatherParameter::PressureMsl => self.pressure_msl,
+            WeatherParameter::RelativeHumidity => self.relative_humidity.map(|v| v as f64),
+            WeatherParameter::Temperature => self.temperature,
+            WeatherParameter::Visibility => self.visibility.map(|v| v as f64),
+            WeatherParameter::Precipitation => self.precipitation,
+            WeatherParameter::Solar => self.solar,
+            WeatherParameter::Sunshine => self.sunshine,
+            WeatherParameter::WindDirection => self.wind_direction.map(|v| v as f64),
+            WeatherParameter::WindSpeed => self.wind_speed,
+            WeatherParameter::WindGustDirection => self.wind_gust_direction.map(|v| v as f64),
+            WeatherParameter::WindGustSpeed => self.wind_gust_speed,
+            WeatherParameter::PrecipitationProbability => {
+                self.precipitation_probability.map(|v| v as f64)
+            }
+            WeatherParameter::PrecipitationProbability6h => {
+                self.precipitation_probability_6h.map(|v| v as f64)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl CurrentWeather {
+    /// Numerical value of `field`.
+    ///
+    /// Accepts the interval fields such as `wind_speed_10` and the fields of
+    /// [`FIELDS`], which refer to the shortest available interval.
+    pub fn field_value(&self, field: impl Borrow<WeatherParameter>) -> Option<f64> {
+        match field.borrow() {
+            WeatherParameter::CloudCover => self.cloud_cover,
+            WeatherParameter::DewPoint => self.dew_point,
+            WeatherParameter::PressureMsl => self.pressure_msl,
+            WeatherParameter::RelativeHumidity => self.relative_humidity.map(|v| v as f64),
+            WeatherParameter::Temperature => self.temperature,
+            WeatherParameter::Visibility => self.visibility.map(|v| v as f64),
+            WeatherParameter::Precipitation => self.best_precipitation().map(|v| v.value),
+            WeatherParameter::Precipitation10 => self.precipitation_10,
+            WeatherParameter::Precipitation30 => self.precipitation_30,
+            WeatherParameter::Precipitation60 => self.precipitation_60,
+            WeatherParameter::Solar => self.best_solar().map(|v| v.value),
+            WeatherParameter::Solar10 => self.solar_10,
+            WeatherParameter::Solar30 => self.solar_30,
+            WeatherParameter::Solar60 => self.solar_60,
+            WeatherParameter::Sunshine => self.best_sunshine().map(|v| v.value),
+            WeatherParameter::Sunshine30 => self.sunshine_30,
+            WeatherParameter::Sunshine60 => self.sunshine_60,
+            WeatherParameter::WindDirection => self.best_wind_direction().map(|v| v.value as f64),
+            WeatherParameter::WindDirection10 => self.wind_direction_10.map(|v| v as f64),
+            WeatherParameter::WindDirection30 => self.wind_direction_30.map(|v| v as f64),
+            WeatherParameter::WindDirection60 => self.wind_direction_60.map(|v| v as f64),
+            WeatherParameter::WindSpeed => self.best_wind_speed().map(|v| v.value),
+            WeatherParameter::WindSpeed10 => self.wind_speed_10,
+            WeatherParameter::WindSpeed30 => self.wind_speed_30,
+            WeatherParameter::WindSpeed60 => self.wind_speed_60,
+            WeatherParameter::WindGustDirection => {
+                self.best_wind_gust_direction().map(|v| v.value as f64)
+            }
+            WeatherParameter::WindGustDirection10 => self.wind_gust_direction_10.map(|v| v as f64),
+            WeatherParameter::WindGustDirection30 => self.wind_gust_direction_30.map(|v| v as f64),
+            WeatherParameter::WindGustDirection60 => self.wind_gust_direction_60.map(|v| v as f64),
+            WeatherParameter::WindGustSpeed => self.best_wind_gust_speed().map(|v| v.value),
+            WeatherParameter::WindGustSpeed10 => self.wind_gust_speed_10,
+            WeatherParameter::WindGustSpeed30 => self.wind_gust_speed_30,
+            WeatherParameter::WindGustSpeed60 => self.wind_gust_speed_60,
+            _ => None,
+        }
+    }
+}
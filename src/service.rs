@@ -0,0 +1,260 @@
+//! Caching weather endpoints for [axum](https://docs.rs/axum) services.
+//!
+//! [`WeatherService`] wraps a [`BrightSkyClient`] for one configured location
+//! and caches its current weather and alerts for a while, so a small web
+//! service built on top of Bright Sky sends at most one upstream request per
+//! endpoint and cache period, however many clients it serves. Requests that
+//! miss the cache while a fetch is in flight wait for its response instead of
+//! sending their own; after a failed fetch, the next waiting request retries.
+//!
+//! [`WeatherService::router`] returns a ready-made router with two JSON routes:
+//!
+//! - `GET /current_weather`: the [`CurrentWeatherResponse`] of the location
+//! - `GET /alerts`: the [`AlertsResponse`] of the location
+//!
+//! ```rust
+//! use brightsky::{BrightSkyClient, service::WeatherService};
+//! use std::time::Duration;
+//!
+//! let client = BrightSkyClient::new(reqwest::Client::new());
+//! let weather = WeatherService::new(client, (52.52, 13.4)).with_ttl(Duration::from_secs(600));
+//!
+//! let app: axum::Router = axum::Router::new().nest("/weather", weather.router());
+//! // axum::serve(listener, app).await?;
+//! ```
+//!
+//! To mount the handlers in an existing router, share the service as
+//! `Arc<WeatherService<reqwest::Client>>` state and route to
+//! [`current_weather`] and [`alerts`].
+//!
+//! Only available with the `axum` feature.
+
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+pub use axum;
+use axum::{
+    Json, Router,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+
+use crate::{
+    AlertsQueryBuilder, BrightSkyClient, ClientError, CurrentWeatherQueryBuilder, HttpClient,
+    provenance::Provenance,
+    types::{AlertsResponse, CurrentWeatherResponse},
+};
+
+/// How long [`WeatherService`] reuses a response by default.
+///
+/// Bright Sky updates current weather every 10 minutes and alerts every 5.
+pub const DEFAULT_SERVICE_TTL: Duration = Duration::from_secs(300);
+
+/// A response, when it was fetched and where it came from.
+type Entry<T> = Option<(Instant, T, Provenance)>;
+
+/// The cached response of one endpoint.
+///
+/// `fetching` is held while an upstream request is in flight, so concurrent
+/// cache misses wait for it and then read its response.
+#[derive(Debug)]
+struct Cached<T> {
+    entry: Mutex<Entry<T>>,
+    fetching: tokio::sync::Mutex<()>,
+}
+
+impl<T> Cached<T> {
+    fn new() -> Self {
+        Self {
+            entry: Mutex::new(None),
+            fetching: tokio::sync::Mutex::new(()),
+        }
+    }
+}
+
+/// Current weather and alerts of one location, cached for a configurable time.
+#[derive(Debug)]
+pub struct WeatherService<C> {
+    client: BrightSkyClient<C>,
+    location: (f64, f64),
+    ttl: Duration,
+    current_weather: Cached<CurrentWeatherResponse>,
+    alerts: Cached<AlertsResponse>,
+}
+
+impl<C: HttpClient> WeatherService<C> {
+    /// Serve the weather at `location`, caching responses for
+    /// [`DEFAULT_SERVICE_TTL`].
+    pub fn new(client: BrightSkyClient<C>, location: impl Into<(f64, f64)>) -> Self {
+        Self {
+            client,
+            location: location.into(),
+            ttl: DEFAULT_SERVICE_TTL,
+            current_weather: Cached::new(),
+            alerts: Cached::new(),
+        }
+    }
+
+    /// Set how long responses are reused, `Duration::ZERO` to disable caching.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// The configured location.
+    pub fn location(&self) -> (f64, f64) {
+        self.location
+    }
+
+    /// The client used for upstream requests.
+    pub fn client(&self) -> &BrightSkyClient<C> {
+        &self.client
+    }
+
+    /// Current weather at the location, from the cache if it is fresh.
+    pub async fn current_weather(&self) -> Result<CurrentWeatherResponse, ClientError<C::Error>> {
+        self.current_weather_with_provenance()
+            .await
+            .map(|(response, _)| response)
+    }
+
+    /// Like [`current_weather`](Self::current_weather), also returning where
+    /// the response came from and whether it was cached.
+    pub async fn current_weather_with_provenance(
+        &self,
+    ) -> Result<(CurrentWeatherResponse, Provenance), ClientError<C::Error>> {
+        self.cached_or_fetch(&self.current_weather, "current_weather", async {
+            let query = CurrentWeatherQueryBuilder::new()
+                .with_lat_lon(self.location)
+                .build()?;
+            self.client.get_with_provenance(query).await
+        })
+        .await
+    }
+
+    /// Alerts at the location, from the cache if they are fresh.
+    pub async fn alerts(&self) -> Result<AlertsResponse, ClientError<C::Error>> {
+        self.alerts_with_provenance()
+            .await
+            .map(|(response, _)| response)
+    }
+
+    /// Like [`alerts`](Self::alerts), also returning where the response came
+    /// from and whether it was cached.
+    pub async fn alerts_with_provenance(
+        &self,
+    ) -> Result<(AlertsResponse, Provenance), ClientError<C::Error>> {
+        self.cached_or_fetch(&self.alerts, "alerts", async {
+            let query = AlertsQueryBuilder::new()
+                .with_lat_lon(self.location)
+                .build()?;
+            self.client.get_with_provenance(query).await
+        })
+        .await
+    }
+
+    /// Drop cached responses, so the next calls fetch fresh data.
+    pub fn clear_cache(&self) {
+        *lock(&self.current_weather) = None;
+        *lock(&self.alerts) = None;
+    }
+
+    /// The cached response if it is fresh, otherwise the response of `fetch`,
+    /// which runs for one caller at a time.
+    async fn cached_or_fetch<T: Clone>(
+        &self,
+        cache: &Cached<T>,
+        _endpoint: &'static str,
+        fetch: impl Future<Output = Result<(T, Provenance), ClientError<C::Error>>>,
+    ) -> Result<(T, Provenance), ClientError<C::Error>> {
+        let cached = match self.cached(cache) {
+            Some(cached) => Ok(cached),
+            None => {
+                let fetching = cache.fetching.lock().await;
+                // Filled by the request this one waited for
+                self.cached(cache).ok_or(fetching)
+            }
+        };
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_cache_lookup(_endpoint, cached.is_ok());
+        match cached {
+            Ok(cached) => Ok(cached),
+            Err(_fetching) => {
+                let fetched = fetch.await?;
+                store(cache, &fetched);
+                Ok(fetched)
+            }
+        }
+    }
+
+    fn cached<T: Clone>(&self, cache: &Cached<T>) -> Option<(T, Provenance)> {
+        match &*lock(cache) {
+            Some((fetched, response, provenance)) if fetched.elapsed() < self.ttl => {
+                Some((response.clone(), provenance.clone().with_cached(true)))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl WeatherService<reqwest::Client> {
+    /// Router serving `/current_weather` and `/alerts` from this service.
+    pub fn router<S>(self) -> Router<S> {
+        Router::new()
+            .route("/current_weather", get(current_weather))
+            .route("/alerts", get(alerts))
+            .with_state(Arc::new(self))
+    }
+}
+
+/// Handler returning the current weather of the shared [`WeatherService`].
+pub async fn current_weather(
+    State(service): State<Arc<WeatherService<reqwest::Client>>>,
+) -> Result<Json<CurrentWeatherResponse>, ServiceError> {
+    service
+        .current_weather()
+        .await
+        .map(Json)
+        .map_err(ServiceError)
+}
+
+/// Handler returning the alerts of the shared [`WeatherService`].
+pub async fn alerts(
+    State(service): State<Arc<WeatherService<reqwest::Client>>>,
+) -> Result<Json<AlertsResponse>, ServiceError> {
+    service.alerts().await.map(Json).map_err(ServiceError)
+}
+
+/// Upstream failure of a handler.
+///
+/// Responds with `502 Bad Gateway` and the error message, or `500 Internal
+/// Server Error` if the query could not be built.
+#[derive(Debug)]
+pub struct ServiceError(pub ClientError<reqwest::Error>);
+
+impl IntoResponse for ServiceError {
+    fn into_response(self) -> Response {
+        let status = match self.0 {
+            ClientError::Query(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::BAD_GATEWAY,
+        };
+        (status, self.0.to_string()).into_response()
+    }
+}
+
+fn store<T: Clone>(cache: &Cached<T>, (response, provenance): &(T, Provenance)) {
+    *lock(cache) = Some((Instant::now(), response.clone(), provenance.clone()));
+}
+
+/// Lock a cache entry, ignoring poisoning: entries are replaced as a whole.
+fn lock<T>(cache: &Cached<T>) -> std::sync::MutexGuard<'_, Entry<T>> {
+    cache
+        .entry
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
@@ -0,0 +1,184 @@
+//! Destinations for alert notifications.
+//!
+//! An [`AlertSink`] receives the alerts that [`AlertsWatcher`] reports as
+//! issued, updated or lifted. [`AlertsWatcher::forward`] compares a new
+//! response with the known alerts and hands every change to a sink, so a
+//! service forwarding alerts combines the two:
+//!
+//! ```rust,ignore
+//! use brightsky::{AlertsQueryBuilder, BrightSkyClient, poll::AlertsWatcher, sink::WebhookSink, types::AlertsResponse};
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let http = reqwest::Client::new();
+//! let client = BrightSkyClient::new(http.clone());
+//! let sink = WebhookSink::new(http, "https://example.com/hook");
+//! let mut watcher = AlertsWatcher::new();
+//!
+//! let query = AlertsQueryBuilder::new().with_warn_cell_id(803159016).build()?;
+//! loop {
+//!     let response: AlertsResponse = client.get(query.clone()).await?;
+//!     watcher.forward(response, &sink).await?;
+//!     tokio::time::sleep(std::time::Duration::from_secs(300)).await;
+//! }
+//! # }
+//! ```
+//!
+//! Ready-made sinks:
+//!
+#![cfg_attr(
+    feature = "std",
+    doc = "- [`StdoutSink`]: prints one line per change (`std` feature)"
+)]
+#![cfg_attr(
+    not(feature = "std"),
+    doc = "- `StdoutSink`: prints one line per change (`std` feature)"
+)]
+#![cfg_attr(
+    feature = "reqwest",
+    doc = "- [`WebhookSink`]: posts every change as JSON (`reqwest` feature)"
+)]
+#![cfg_attr(
+    not(feature = "reqwest"),
+    doc = "- `WebhookSink`: posts every change as JSON (`reqwest` feature)"
+)]
+//!
+//! [`AlertsWatcher`]: crate::poll::AlertsWatcher
+//! [`AlertsWatcher::forward`]: crate::poll::AlertsWatcher::forward
+
+use core::future::Future;
+
+use crate::{MaybeSend, poll::AlertEvent, types::Alert};
+
+/// A destination for alert notifications, e.g. a chat, a push service or a
+/// log.
+///
+/// Only [`notify`](Self::notify) is required. It is called for issued and
+/// updated alerts by the default [`notify_event`](Self::notify_event);
+/// override that as well to report lifted alerts.
+///
+/// Notifications are `Send` except on `wasm32`, see [`MaybeSend`], so a sink
+/// can be used from a spawned task.
+pub trait AlertSink: Sync {
+    /// Error of a notification
+    type Error;
+
+    /// Send one alert.
+    fn notify(&self, alert: &Alert) -> impl Future<Output = Result<(), Self::Error>> + MaybeSend;
+
+    /// Send a change reported by [`AlertsWatcher`](crate::poll::AlertsWatcher).
+    ///
+    /// Calls [`notify`](Self::notify) for new and updated alerts and ignores
+    /// expired ones.
+    fn notify_event(
+        &self,
+        event: &AlertEvent,
+    ) -> impl Future<Output = Result<(), Self::Error>> + MaybeSend {
+        async move {
+            match event {
+                AlertEvent::New(alert) | AlertEvent::Updated(alert) => self.notify(alert).await,
+                AlertEvent::Expired(_) => Ok(()),
+            }
+        }
+    }
+}
+
+/// Prints a line per alert to stdout, e.g. `new: [severe] Official WARNING of
+/// GALE-FORCE GUSTS`.
+///
+/// Only available with the `std` feature.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdoutSink;
+
+#[cfg(feature = "std")]
+impl StdoutSink {
+    fn print(&self, kind: &str, alert: &Alert) -> std::io::Result<()> {
+        use std::io::Write;
+
+        let severity = alert
+            .severity
+            .map_or("unknown", |severity| severity.as_str());
+        writeln!(
+            std::io::stdout().lock(),
+            "{}: [{}] {}",
+            kind,
+            severity,
+            alert.headline_en
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl AlertSink for StdoutSink {
+    type Error = std::io::Error;
+
+    async fn notify(&self, alert: &Alert) -> Result<(), Self::Error> {
+        self.print("alert", alert)
+    }
+
+    async fn notify_event(&self, event: &AlertEvent) -> Result<(), Self::Error> {
+        self.print(event.kind(), event.alert())
+    }
+}
+
+/// Posts alerts as JSON to a webhook URL.
+///
+/// [`notify`](AlertSink::notify) posts the alert as it is returned by the
+/// `/alerts` endpoint. For changes reported by the watcher, the body names
+/// the kind of change, including lifted alerts:
+///
+/// ```json
+/// {"event": "updated", "alert": {"id": 1, "alert_id": "...", ...}}
+/// ```
+///
+/// A non-`2xx` response is an error.
+///
+/// Only available with the `reqwest` feature.
+#[cfg(feature = "reqwest")]
+#[derive(Debug, Clone)]
+pub struct WebhookSink {
+    http: reqwest::Client,
+    url: String,
+    headers: Vec<(String, String)>,
+}
+
+#[cfg(feature = "reqwest")]
+impl WebhookSink {
+    /// Post to `url` with the given client.
+    pub fn new(http: reqwest::Client, url: impl Into<String>) -> Self {
+        Self {
+            http,
+            url: url.into(),
+            headers: Vec::new(),
+        }
+    }
+
+    /// Send a header with every request, e.g. `Authorization`.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    async fn post<T: serde::Serialize>(&self, body: &T) -> Result<(), reqwest::Error> {
+        let mut request = self.http.post(&self.url).json(body);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        request.send().await?.error_for_status()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl AlertSink for WebhookSink {
+    type Error = reqwest::Error;
+
+    async fn notify(&self, alert: &Alert) -> Result<(), Self::Error> {
+        self.post(alert).await
+    }
+
+    async fn notify_event(&self, event: &AlertEvent) -> Result<(), Self::Error> {
+        self.post(&serde_json::json!({ "event": event.kind(), "alert": event.alert() }))
+            .await
+    }
+}
@@ -0,0 +1,214 @@
+//! Sunrise, sunset and solar elevation, computed locally.
+//!
+//! The position of the sun follows the NOAA solar calculator (after Jean
+//! Meeus, *Astronomical Algorithms*), accurate to about a minute for sunrise
+//! and sunset between ±72° latitude. Sunrise and sunset are the moments the
+//! upper edge of the sun crosses the horizon, taking atmospheric refraction
+//! into account (a solar elevation of -0.833°).
+//!
+//! ```rust
+//! use brightsky::{Coordinates, solar::sun_times};
+//! use chrono::{NaiveDate, Timelike};
+//!
+//! let berlin = Coordinates::new(52.52, 13.405)?;
+//! let times = sun_times(berlin, NaiveDate::from_ymd_opt(2023, 6, 21).unwrap());
+//!
+//! let sunrise = times.sunrise().unwrap();
+//! assert_eq!((sunrise.hour(), sunrise.minute()), (2, 43)); // UTC
+//! assert_eq!(times.daylight().num_minutes() / 60, 16);
+//! # Ok::<(), brightsky::BrightSkyError>(())
+//! ```
+//!
+//! Bright Sky picks day or night icons from its own notion of daylight, which
+//! can disagree with the sunrise shown next to a forecast. `harmonize_icons`
+//! makes the icons of a response follow [`is_daylight`] at the station of each
+//! record, so UI code can rely on
+//! [`WeatherIcon::is_night`](crate::types::WeatherIcon::is_night).
+//!
+//! Only available with the `std` feature.
+
+use chrono::{DateTime, NaiveDate, TimeDelta, TimeZone, Timelike, Utc};
+
+use crate::{
+    Coordinates,
+    types::{CurrentWeatherResponse, WeatherResponse},
+};
+
+/// Solar elevation at sunrise and sunset in degrees, for refraction and the
+/// radius of the sun.
+pub const SUNRISE_ELEVATION: f64 = -0.833;
+
+/// Sunrise, sunset and solar noon of one day, see [`sun_times`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SunTimes {
+    solar_noon: DateTime<Utc>,
+    sunrise: Option<DateTime<Utc>>,
+    sunset: Option<DateTime<Utc>>,
+    /// Whether the sun stays above the horizon, if it neither rises nor sets
+    polar_day: bool,
+}
+
+impl SunTimes {
+    /// When the sun is highest.
+    pub fn solar_noon(&self) -> DateTime<Utc> {
+        self.solar_noon
+    }
+
+    /// When the sun rises, `None` during polar day or night.
+    pub fn sunrise(&self) -> Option<DateTime<Utc>> {
+        self.sunrise
+    }
+
+    /// When the sun sets, `None` during polar day or night.
+    pub fn sunset(&self) -> Option<DateTime<Utc>> {
+        self.sunset
+    }
+
+    /// Time between sunrise and sunset, 24 hours during polar day and zero
+    /// during polar night.
+    pub fn daylight(&self) -> TimeDelta {
+        match (self.sunrise, self.sunset) {
+            (Some(sunrise), Some(sunset)) => sunset - sunrise,
+            _ if self.polar_day => TimeDelta::days(1),
+            _ => TimeDelta::zero(),
+        }
+    }
+}
+
+/// Sunrise, sunset and solar noon at `coordinates` on `date`.
+///
+/// The times belong to the solar day around noon UTC of `date`, which is the
+/// local date everywhere in Europe.
+pub fn sun_times(coordinates: Coordinates, date: NaiveDate) -> SunTimes {
+    let Coordinates { lat, lon } = coordinates;
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+    let sun = Sun::at(midnight + TimeDelta::hours(12));
+
+    let noon_minutes = 720.0 - 4.0 * lon - sun.equation_of_time;
+    let at = |minutes: f64| midnight + TimeDelta::milliseconds((minutes * 60_000.0).round() as i64);
+
+    let (lat, declination) = (lat.to_radians(), sun.declination);
+    let cos_hour_angle = (90.0 - SUNRISE_ELEVATION).to_radians().cos()
+        / (lat.cos() * declination.cos())
+        - lat.tan() * declination.tan();
+    let (sunrise, sunset) = if (-1.0..=1.0).contains(&cos_hour_angle) {
+        let hour_angle = cos_hour_angle.acos().to_degrees();
+        (
+            Some(at(noon_minutes - 4.0 * hour_angle)),
+            Some(at(noon_minutes + 4.0 * hour_angle)),
+        )
+    } else {
+        (None, None)
+    };
+
+    SunTimes {
+        solar_noon: at(noon_minutes),
+        sunrise,
+        sunset,
+        polar_day: cos_hour_angle < -1.0,
+    }
+}
+
+/// Elevation of the center of the sun above the horizon in degrees, without
+/// refraction. Negative while the sun is below the horizon.
+pub fn solar_elevation<Tz: TimeZone>(coordinates: Coordinates, time: &DateTime<Tz>) -> f64 {
+    let Coordinates { lat, lon } = coordinates;
+    let time = time.with_timezone(&Utc);
+    let sun = Sun::at(time);
+
+    let minutes = f64::from(time.num_seconds_from_midnight()) / 60.0;
+    let true_solar_time = (minutes + sun.equation_of_time + 4.0 * lon).rem_euclid(1440.0);
+    let hour_angle = (true_solar_time / 4.0 - 180.0).to_radians();
+
+    let lat = lat.to_radians();
+    let cos_zenith =
+        lat.sin() * sun.declination.sin() + lat.cos() * sun.declination.cos() * hour_angle.cos();
+    90.0 - cos_zenith.clamp(-1.0, 1.0).acos().to_degrees()
+}
+
+/// Whether the sun is up at `time`, by the same definition as sunrise and
+/// sunset.
+pub fn is_daylight<Tz: TimeZone>(coordinates: Coordinates, time: &DateTime<Tz>) -> bool {
+    solar_elevation(coordinates, time) > SUNRISE_ELEVATION
+}
+
+/// Declination and equation of time at an instant.
+struct Sun {
+    /// Declination in radians
+    declination: f64,
+    /// Equation of time in minutes
+    equation_of_time: f64,
+}
+
+impl Sun {
+    fn at(time: DateTime<Utc>) -> Self {
+        let julian_day = time.timestamp_millis() as f64 / 86_400_000.0 + 2_440_587.5;
+        let t = (julian_day - 2_451_545.0) / 36_525.0;
+
+        let mean_longitude = (280.46646 + t * (36000.76983 + t * 0.0003032)).rem_euclid(360.0);
+        let mean_anomaly = (357.52911 + t * (35999.05029 - 0.0001537 * t)).to_radians();
+        let eccentricity = 0.016708634 - t * (0.000042037 + 0.0000001267 * t);
+        let center = mean_anomaly.sin() * (1.914602 - t * (0.004817 + 0.000014 * t))
+            + (2.0 * mean_anomaly).sin() * (0.019993 - 0.000101 * t)
+            + (3.0 * mean_anomaly).sin() * 0.000289;
+        let omega = (125.04 - 1934.136 * t).to_radians();
+        let apparent_longitude =
+            (mean_longitude + center - 0.00569 - 0.00478 * omega.sin()).to_radians();
+        let mean_obliquity =
+            23.0 + (26.0 + (21.448 - t * (46.815 + t * (0.00059 - t * 0.001813))) / 60.0) / 60.0;
+        let obliquity = (mean_obliquity + 0.00256 * omega.cos()).to_radians();
+
+        let declination = (obliquity.sin() * apparent_longitude.sin()).asin();
+        let y = (obliquity / 2.0).tan().powi(2);
+        let l0 = mean_longitude.to_radians();
+        let equation_of_time = 4.0
+            * (y * (2.0 * l0).sin() - 2.0 * eccentricity * mean_anomaly.sin()
+                + 4.0 * eccentricity * y * mean_anomaly.sin() * (2.0 * l0).cos()
+                - 0.5 * y * y * (4.0 * l0).sin()
+                - 1.25 * eccentricity * eccentricity * (2.0 * mean_anomaly).sin())
+            .to_degrees();
+
+        Self {
+            declination,
+            equation_of_time,
+        }
+    }
+}
+
+impl WeatherResponse {
+    /// Switch the day and night icons of all records to match [`is_daylight`]
+    /// at their source station. Records whose source is not part of the
+    /// response, or has invalid coordinates, keep their icon.
+    pub fn harmonize_icons(&mut self) {
+        for record in &mut self.weather {
+            let Some(source) = self.sources.iter().find(|s| s.id == record.source_id) else {
+                continue;
+            };
+            let Ok(station) = Coordinates::new(source.lat, source.lon) else {
+                continue;
+            };
+            if let Some(icon) = record.icon.take() {
+                let daylight = is_daylight(station, &record.timestamp);
+                record.icon = Some(icon.with_daylight(daylight));
+            }
+        }
+    }
+}
+
+impl CurrentWeatherResponse {
+    /// Switch a day or night icon to match [`is_daylight`] at the main
+    /// source station.
+    pub fn harmonize_icons(&mut self) {
+        let record = &mut self.weather;
+        let Some(source) = self.sources.iter().find(|s| s.id == record.source_id) else {
+            return;
+        };
+        let Ok(station) = Coordinates::new(source.lat, source.lon) else {
+            return;
+        };
+        if let Some(icon) = record.icon.take() {
+            let daylight = is_daylight(station, &record.timestamp);
+            record.icon = Some(icon.with_daylight(daylight));
+        }
+    }
+}
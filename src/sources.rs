@@ -0,0 +1,355 @@
+//! Which source provided a value.
+//!
+//! Every record names its main source in `source_id`. Parameters the main
+//! source could not provide are filled from other sources, listed per
+//! parameter in `fallback_source_ids`. [`Weather::source_for`] and
+//! [`CurrentWeather::source_for`] resolve the source of one parameter against
+//! the `sources` of the response; [`Weather::resolve`] annotates every
+//! parameter of a record with its source:
+//!
+//! ```rust
+//! use brightsky::types::{WeatherParameter, WeatherResponse};
+//!
+//! let response: WeatherResponse = serde_json::from_str(r#"{
+//!     "weather": [{
+//!         "timestamp": "2023-08-07T12:00:00+00:00", "source_id": 1,
+//!         "temperature": 21.5, "wind_speed": 12.0,
+//!         "fallback_source_ids": {"wind_speed": 2}
+//!     }],
+//!     "sources": [
+//!         {"id": 1, "station_name": "Berlin-Tempelhof", "observation_type": "historical",
+//!          "first_record": "2010-01-01T00:00:00+00:00", "last_record": "2023-08-07T12:00:00+00:00",
+//!          "lat": 52.47, "lon": 13.40, "height": 48.0},
+//!         {"id": 2, "station_name": "Berlin-Dahlem", "observation_type": "historical",
+//!          "first_record": "2010-01-01T00:00:00+00:00", "last_record": "2023-08-07T12:00:00+00:00",
+//!          "lat": 52.45, "lon": 13.30, "height": 51.0}
+//!     ]
+//! }"#).unwrap();
+//!
+//! let record = &response.weather[0];
+//! let source = record.source_for(&WeatherParameter::WindSpeed, &response.sources).unwrap();
+//! assert_eq!(source.station_name.as_deref(), Some("Berlin-Dahlem"));
+//!
+//! let resolved = record.resolve(&response.sources);
+//! let temperature = resolved.temperature.unwrap();
+//! assert_eq!(temperature.value, 21.5);
+//! assert_eq!(temperature.source_id, 1);
+//! ```
+//!
+//! ## Looking Up Sources
+//!
+//! [`WeatherResponse::source_by_id`] and [`Weather::primary_source`] join a
+//! record to its station metadata. Responses list only a few sources, so these
+//! scan the list. To look up many IDs, e.g. while exporting a long time series
+//! of merged responses, build a [`SourceIndex`] once with
+//! [`WeatherResponse::source_index`].
+//!
+#![cfg_attr(
+    feature = "std",
+    doc = "With the `std` feature, [`nearest_stations`] sorts"
+)]
+#![cfg_attr(
+    not(feature = "std"),
+    doc = "With the `std` feature, `nearest_stations` sorts"
+)]
+//! sources by their distance to a location, e.g. to let users pick a specific
+//! station.
+
+#[cfg(not(feature = "std"))]
+use alloc::collections::BTreeMap;
+
+use core::borrow::Borrow;
+
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+use crate::types::{
+    CurrentWeather, CurrentWeatherResponse, CurrentWeatherSource, Source, Weather,
+    WeatherCondition, WeatherIcon, WeatherParameter, WeatherResponse,
+};
+
+/// A value together with the source it was taken from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sourced<'a, T> {
+    /// The value
+    pub value: T,
+    /// Bright Sky ID of the source that provided the value
+    pub source_id: i64,
+    /// The source, `None` if it is not part of the response's `sources`
+    pub source: Option<&'a Source>,
+}
+
+/// A [`Weather`] record with every parameter annotated with its source.
+///
+/// Parameters without a value are `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedWeather<'a> {
+    /// The underlying record
+    pub record: &'a Weather,
+    /// The main source of the record
+    pub source: Option<&'a Source>,
+    /// Total cloud cover (percentage)
+    pub cloud_cover: Option<Sourced<'a, f64>>,
+    /// Current weather conditions (derived field)
+    pub condition: Option<Sourced<'a, &'a WeatherCondition>>,
+    /// Dew point (°C or K)
+    pub dew_point: Option<Sourced<'a, f64>>,
+    /// Icon alias (derived field)
+    pub icon: Option<Sourced<'a, &'a WeatherIcon>>,
+    /// Pressure reduced to mean sea level (hPa or Pa)
+    pub pressure_msl: Option<Sourced<'a, f64>>,
+    /// Relative humidity (percentage)
+    pub relative_humidity: Option<Sourced<'a, i64>>,
+    /// Air temperature (°C or K)
+    pub temperature: Option<Sourced<'a, f64>>,
+    /// Visibility (meters)
+    pub visibility: Option<Sourced<'a, i64>>,
+    /// Precipitation during the previous hour (mm)
+    pub precipitation: Option<Sourced<'a, f64>>,
+    /// Solar irradiation during the previous hour (kWh/m² or J/m²)
+    pub solar: Option<Sourced<'a, f64>>,
+    /// Sunshine duration during the previous hour (minutes or seconds)
+    pub sunshine: Option<Sourced<'a, f64>>,
+    /// Mean wind direction during the previous hour (degrees)
+    pub wind_direction: Option<Sourced<'a, i64>>,
+    /// Mean wind speed during the previous hour (km/h or m/s)
+    pub wind_speed: Option<Sourced<'a, f64>>,
+    /// Direction of the maximum wind gust during the previous hour (degrees)
+    pub wind_gust_direction: Option<Sourced<'a, i64>>,
+    /// Speed of the maximum wind gust during the previous hour (km/h or m/s)
+    pub wind_gust_speed: Option<Sourced<'a, f64>>,
+    /// Probability of >0.1mm precipitation in the previous hour (percentage)
+    pub precipitation_probability: Option<Sourced<'a, i64>>,
+    /// Probability of >0.2mm precipitation in the previous 6 hours (percentage)
+    pub precipitation_probability_6h: Option<Sourced<'a, i64>>,
+}
+
+impl Weather {
+    /// The record's main source in `response`, `None` if it is not listed.
+    pub fn primary_source<'a>(&self, response: &'a WeatherResponse) -> Option<&'a Source> {
+        response.source_by_id(self.source_id)
+    }
+
+    /// ID of the source that provided `parameter`: its fallback source if
+    /// there is one, otherwise the record's main source.
+    pub fn source_id_for(&self, parameter: impl Borrow<WeatherParameter>) -> i64 {
+        self.fallback_source_ids
+            .as_ref()
+            .and_then(|ids| ids.get(parameter.borrow()).copied())
+            .unwrap_or(self.source_id)
+    }
+
+    /// The source in `sources` that provided `parameter`, see
+    /// [`source_id_for`](Self::source_id_for).
+    pub fn source_for<'a>(
+        &self,
+        parameter: impl Borrow<WeatherParameter>,
+        sources: &'a [Source],
+    ) -> Option<&'a Source> {
+        let id = self.source_id_for(parameter);
+        sources.iter().find(|source| source.id == id)
+    }
+
+    /// This record with every parameter annotated with its source in `sources`.
+    pub fn resolve<'a>(&'a self, sources: &'a [Source]) -> ResolvedWeather<'a> {
+        macro_rules! resolve {
+            (&$field:ident, $parameter:ident) => {
+                self.$field
+                    .as_ref()
+                    .map(|value| sourced(self, WeatherParameter::$parameter, sources, value))
+            };
+            ($field:ident, $parameter:ident) => {
+                self.$field
+                    .map(|value| sourced(self, WeatherParameter::$parameter, sources, value))
+            };
+        }
+
+        ResolvedWeather {
+            record: self,
+            source: sources.iter().find(|source| source.id == self.source_id),
+            cloud_cover: resolve!(cloud_cover, CloudCover),
+            condition: resolve!(&condition, Condition),
+            dew_point: resolve!(dew_point, DewPoint),
+            icon: resolve!(&icon, Icon),
+            pressure_msl: resolve!(pressure_msl, PressureMsl),
+            relative_humidity: resolve!(relative_humidity, RelativeHumidity),
+            temperature: resolve!(temperature, Temperature),
+            visibility: resolve!(visibility, Visibility),
+            precipitation: resolve!(precipitation, Precipitation),
+            solar: resolve!(solar, Solar),
+            sunshine: resolve!(sunshine, Sunshine),
+            wind_direction: resolve!(wind_direction, WindDirection),
+            wind_speed: resolve!(wind_speed, WindSpeed),
+            wind_gust_direction: resolve!(wind_gust_direction, WindGustDirection),
+            wind_gust_speed: resolve!(wind_gust_speed, WindGustSpeed),
+            precipitation_probability: resolve!(
+                precipitation_probability,
+                PrecipitationProbability
+            ),
+            precipitation_probability_6h: resolve!(
+                precipitation_probability_6h,
+                PrecipitationProbability6h
+            ),
+        }
+    }
+}
+
+impl CurrentWeather {
+    /// The record's main source in `response`, `None` if it is not listed.
+    pub fn primary_source<'a>(
+        &self,
+        response: &'a CurrentWeatherResponse,
+    ) -> Option<&'a CurrentWeatherSource> {
+        response.source_by_id(self.source_id)
+    }
+
+    /// ID of the source that provided `parameter`: its fallback source if
+    /// there is one, otherwise the record's main source.
+    pub fn source_id_for(&self, parameter: impl Borrow<WeatherParameter>) -> i64 {
+        self.fallback_source_ids
+            .as_ref()
+            .and_then(|ids| ids.get(parameter.borrow()).copied())
+            .unwrap_or(self.source_id)
+    }
+
+    /// The source in `sources` that provided `parameter`, see
+    /// [`source_id_for`](Self::source_id_for).
+    pub fn source_for<'a>(
+        &self,
+        parameter: impl Borrow<WeatherParameter>,
+        sources: &'a [CurrentWeatherSource],
+    ) -> Option<&'a CurrentWeatherSource> {
+        let id = self.source_id_for(parameter);
+        sources.iter().find(|source| source.id == id)
+    }
+}
+
+impl WeatherResponse {
+    /// The source with the given Bright Sky ID.
+    ///
+    /// Scans the sources; for many lookups, build a
+    /// [`source_index`](Self::source_index) once instead.
+    pub fn source_by_id(&self, id: i64) -> Option<&Source> {
+        self.sources.iter().find(|source| source.id == id)
+    }
+
+    /// An index of the sources by ID for repeated lookups.
+    pub fn source_index(&self) -> SourceIndex<'_> {
+        SourceIndex::new(&self.sources)
+    }
+
+    /// All records with every parameter annotated with its source, in order.
+    pub fn resolved(&self) -> impl Iterator<Item = ResolvedWeather<'_>> {
+        self.weather
+            .iter()
+            .map(|record| record.resolve(&self.sources))
+    }
+}
+
+impl CurrentWeatherResponse {
+    /// The source with the given Bright Sky ID.
+    pub fn source_by_id(&self, id: i64) -> Option<&CurrentWeatherSource> {
+        self.sources.iter().find(|source| source.id == id)
+    }
+}
+
+/// Sources by Bright Sky ID, see [`WeatherResponse::source_index`].
+///
+/// If IDs repeat, the first source with an ID wins, like in
+/// [`WeatherResponse::source_by_id`].
+#[derive(Debug, Clone, Default)]
+pub struct SourceIndex<'a> {
+    sources: BTreeMap<i64, &'a Source>,
+}
+
+impl<'a> SourceIndex<'a> {
+    /// Index the given sources.
+    pub fn new(sources: &'a [Source]) -> Self {
+        let mut index = BTreeMap::new();
+        for source in sources {
+            index.entry(source.id).or_insert(source);
+        }
+        Self { sources: index }
+    }
+
+    /// The source with the given Bright Sky ID.
+    pub fn get(&self, id: i64) -> Option<&'a Source> {
+        self.sources.get(&id).copied()
+    }
+
+    /// The main source of `record`.
+    pub fn primary_source(&self, record: &Weather) -> Option<&'a Source> {
+        self.get(record.source_id)
+    }
+
+    /// Number of distinct source IDs.
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    /// Whether no sources are indexed.
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+}
+
+/// The `n` sources closest to `location`, nearest first, with their distance
+/// in meters.
+///
+/// The distance is computed from the station coordinates, so it does not
+/// depend on the location the sources were requested for. Sources listed more
+/// than once (by ID) are returned once, sources with invalid coordinates not
+/// at all.
+///
+/// ```rust
+/// use brightsky::{Coordinates, sources::nearest_stations, types::WeatherResponse};
+///
+/// let response: WeatherResponse = serde_json::from_str(r#"{
+///     "weather": [],
+///     "sources": [
+///         {"id": 1, "station_name": "Berlin-Tempelhof", "observation_type": "historical",
+///          "first_record": "2010-01-01T00:00:00+00:00", "last_record": "2023-08-07T12:00:00+00:00",
+///          "lat": 52.47, "lon": 13.40, "height": 48.0},
+///         {"id": 2, "station_name": "Potsdam", "observation_type": "historical",
+///          "first_record": "2010-01-01T00:00:00+00:00", "last_record": "2023-08-07T12:00:00+00:00",
+///          "lat": 52.38, "lon": 13.06, "height": 81.0}
+///     ]
+/// }"#).unwrap();
+///
+/// let potsdam = Coordinates::new(52.40, 13.05).unwrap();
+/// let nearest = nearest_stations(&response.sources, potsdam, 1);
+/// assert_eq!(nearest[0].0.station_name.as_deref(), Some("Potsdam"));
+/// assert!(nearest[0].1 < 2_500.0);
+/// ```
+#[cfg(feature = "std")]
+pub fn nearest_stations(
+    sources: &[Source],
+    location: crate::Coordinates,
+    n: usize,
+) -> Vec<(&Source, f64)> {
+    let mut stations: Vec<(&Source, f64)> = SourceIndex::new(sources)
+        .sources
+        .into_values()
+        .filter_map(|source| {
+            let station = crate::Coordinates::new(source.lat, source.lon).ok()?;
+            Some((source, location.distance_to(station)))
+        })
+        .collect();
+    stations.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.id.cmp(&b.0.id)));
+    stations.truncate(n);
+    stations
+}
+
+fn sourced<'a, T>(
+    record: &Weather,
+    parameter: WeatherParameter,
+    sources: &'a [Source],
+    value: T,
+) -> Sourced<'a, T> {
+    let source_id = record.source_id_for(parameter);
+    Sourced {
+        value,
+        source_id,
+        source: sources.iter().find(|source| source.id == source_id),
+    }
+}
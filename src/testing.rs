@@ -0,0 +1,279 @@
+//! Test helpers for code using this crate.
+//!
+//! The [`fixtures`] module bundles sample responses for all endpoints, shaped
+//! like real responses of the public API but trimmed to a few records and
+//! pixels. They are available as raw
+//! JSON and as parsed responses through constructors like
+//! `WeatherResponse::sample()`.
+//!
+//! [`MockHttpClient`] implements [`HttpClient`] with canned responses per
+//! endpoint and records every request, so application logic built on
+//! [`BrightSkyClient`](crate::BrightSkyClient) can be unit-tested without a mock
+//! HTTP server and without hitting the live API.
+//!
+//! Only available with the `testing` feature.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use brightsky::{BrightSkyClient, CurrentWeatherQueryBuilder, testing::MockHttpClient, types::CurrentWeatherResponse};
+//!
+//! # tokio_test::block_on(async {
+//! let mock = MockHttpClient::new().with_fixture(
+//!     "/current_weather",
+//!     r#"{"weather": {"timestamp": "2023-08-07T12:30:00+00:00", "source_id": 1, "temperature": 22.3}, "sources": []}"#,
+//! );
+//! let client = BrightSkyClient::new(mock);
+//!
+//! let query = CurrentWeatherQueryBuilder::new().with_lat_lon((52.52, 13.4)).build().unwrap();
+//! let response: CurrentWeatherResponse = client.get(query).await.unwrap();
+//! assert_eq!(response.weather.temperature, Some(22.3));
+//!
+//! let requests = client.http_client().requests();
+//! assert_eq!(requests[0].path, "/current_weather");
+//! assert_eq!(requests[0].param("lat"), Some("52.52"));
+//! # });
+//! ```
+//!
+//! ### Sample responses
+//!
+//! ```rust
+//! use brightsky::types::{MaybeCompressedPrecipitation, RadarResponse, WeatherResponse};
+//!
+//! let weather = WeatherResponse::sample();
+//! assert_eq!(weather.weather.len(), 8);
+//!
+//! let radar = RadarResponse::sample_plain();
+//! assert!(matches!(radar.radar[0].precipitation_5, MaybeCompressedPrecipitation::Plain(_)));
+//! ```
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    client::{FromHttpOptions, HttpClient, HttpOptions, HttpResponse},
+    types::{AlertsResponse, CurrentWeatherResponse, RadarResponse, WeatherResponse},
+};
+
+/// Raw JSON of the bundled sample responses.
+pub mod fixtures {
+    /// `/current_weather` near Berlin (two sources, one used as fallback).
+    pub const CURRENT_WEATHER: &str = include_str!("../fixtures/current_weather.json");
+    /// `/weather` for station 01766 (Münster/Osnabrück), 8 hourly records with rain.
+    pub const WEATHER: &str = include_str!("../fixtures/weather.json");
+    /// `/radar` with `format=compressed`, three frames on a 6×4 pixel bounding box.
+    pub const RADAR_COMPRESSED: &str = include_str!("../fixtures/radar_compressed.json");
+    /// `/radar` with `format=bytes`, same frames as [`RADAR_COMPRESSED`].
+    pub const RADAR_BYTES: &str = include_str!("../fixtures/radar_bytes.json");
+    /// `/radar` with `format=plain`, same frames as [`RADAR_COMPRESSED`].
+    pub const RADAR_PLAIN: &str = include_str!("../fixtures/radar_plain.json");
+    /// `/alerts` for warn cell 803159016 (Göttingen) with two active alerts.
+    pub const ALERTS: &str = include_str!("../fixtures/alerts.json");
+}
+
+impl CurrentWeatherResponse {
+    /// The bundled [`fixtures::CURRENT_WEATHER`] sample response.
+    pub fn sample() -> Self {
+        serde_json::from_str(fixtures::CURRENT_WEATHER).expect("valid fixture")
+    }
+}
+
+impl WeatherResponse {
+    /// The bundled [`fixtures::WEATHER`] sample response.
+    pub fn sample() -> Self {
+        serde_json::from_str(fixtures::WEATHER).expect("valid fixture")
+    }
+}
+
+impl RadarResponse {
+    /// The bundled [`fixtures::RADAR_COMPRESSED`] sample response.
+    pub fn sample() -> Self {
+        serde_json::from_str(fixtures::RADAR_COMPRESSED).expect("valid fixture")
+    }
+
+    /// The bundled [`fixtures::RADAR_BYTES`] sample response.
+    pub fn sample_bytes() -> Self {
+        serde_json::from_str(fixtures::RADAR_BYTES).expect("valid fixture")
+    }
+
+    /// The bundled [`fixtures::RADAR_PLAIN`] sample response.
+    pub fn sample_plain() -> Self {
+        serde_json::from_str(fixtures::RADAR_PLAIN).expect("valid fixture")
+    }
+}
+
+impl AlertsResponse {
+    /// The bundled [`fixtures::ALERTS`] sample response.
+    pub fn sample() -> Self {
+        serde_json::from_str(fixtures::ALERTS).expect("valid fixture")
+    }
+}
+
+/// A request recorded by [`MockHttpClient`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockRequest {
+    /// The full request URL
+    pub url: String,
+    /// Path of the URL, e.g. `/weather`
+    pub path: String,
+    /// Decoded query parameters in order of appearance
+    pub query: Vec<(String, String)>,
+    /// Headers sent with the request
+    pub headers: Vec<(String, String)>,
+}
+
+impl MockRequest {
+    fn parse(url: &str, headers: &[(String, String)]) -> Self {
+        let (path, query) = match url::Url::parse(url) {
+            Ok(parsed) => (
+                parsed.path().to_string(),
+                parsed
+                    .query_pairs()
+                    .map(|(k, v)| (k.into_owned(), v.into_owned()))
+                    .collect(),
+            ),
+            Err(_) => (url.to_string(), Vec::new()),
+        };
+        Self {
+            url: url.to_string(),
+            path,
+            query,
+            headers: headers.to_vec(),
+        }
+    }
+
+    /// Value of the first query parameter with the given name.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Value of the header with the given (case-insensitive) name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Error returned by [`MockHttpClient`] when no response is configured for a path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockError {
+    /// The request URL without a configured response
+    pub url: String,
+}
+
+impl core::fmt::Display for MockError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "No mock response configured for {}", self.url)
+    }
+}
+
+impl std::error::Error for MockError {}
+
+/// An [`HttpClient`] returning canned responses per endpoint path.
+///
+/// Clones share the recorded requests, so a clone can be kept for assertions
+/// after the client was moved into a [`BrightSkyClient`](crate::BrightSkyClient).
+#[derive(Debug, Clone, Default)]
+pub struct MockHttpClient {
+    responses: HashMap<String, HttpResponse>,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+    options: HttpOptions,
+}
+
+impl MockHttpClient {
+    /// Create a client without any configured responses.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Respond to requests for `path` (e.g. `/weather`) with status 200 and `json`.
+    pub fn with_fixture(self, path: &str, json: impl Into<String>) -> Self {
+        self.with_response(path, 200, json)
+    }
+
+    /// Respond to every endpoint with the bundled [`fixtures`].
+    ///
+    /// `/radar` responds with the compressed format.
+    pub fn with_sample_fixtures(self) -> Self {
+        self.with_fixture("/current_weather", fixtures::CURRENT_WEATHER)
+            .with_fixture("/weather", fixtures::WEATHER)
+            .with_fixture("/radar", fixtures::RADAR_COMPRESSED)
+            .with_fixture("/alerts", fixtures::ALERTS)
+    }
+
+    /// Respond to requests for `path` with the given status code and body.
+    pub fn with_response(mut self, path: &str, status: u16, body: impl Into<String>) -> Self {
+        self.responses.insert(
+            normalize_path(path),
+            HttpResponse {
+                status,
+                body: body.into().into_bytes(),
+            },
+        );
+        self
+    }
+
+    /// All requests made so far, in order.
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// The most recent request, if any.
+    pub fn last_request(&self) -> Option<MockRequest> {
+        self.requests.lock().unwrap().last().cloned()
+    }
+
+    /// Forget all recorded requests.
+    pub fn clear_requests(&self) {
+        self.requests.lock().unwrap().clear();
+    }
+
+    /// The options the client was created with by a
+    /// [`BrightSkyClientBuilder`](crate::BrightSkyClientBuilder).
+    pub fn options(&self) -> &HttpOptions {
+        &self.options
+    }
+}
+
+impl FromHttpOptions for MockHttpClient {
+    /// Create a client responding with the bundled [`fixtures`], keeping
+    /// `options` for assertions.
+    fn from_http_options(options: &HttpOptions) -> Result<Self, Self::Error> {
+        let mut client = Self::new().with_sample_fixtures();
+        client.options = options.clone();
+        Ok(client)
+    }
+}
+
+impl HttpClient for MockHttpClient {
+    type Error = MockError;
+
+    async fn get(&self, url: &str) -> Result<HttpResponse, Self::Error> {
+        self.get_with_headers(url, &[]).await
+    }
+
+    async fn get_with_headers(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+    ) -> Result<HttpResponse, Self::Error> {
+        let request = MockRequest::parse(url, headers);
+        let response = self.responses.get(&request.path).cloned();
+        self.requests.lock().unwrap().push(request);
+
+        response.ok_or_else(|| MockError {
+            url: url.to_string(),
+        })
+    }
+}
+
+fn normalize_path(path: &str) -> String {
+    format!("/{}", path.trim_start_matches('/'))
+}
@@ -3,18 +3,26 @@
 //! This module contains all the data structures used for communicating with
 //! the Bright Sky API, including request parameters, response types, and
 //! various enumerations for weather data.
+//!
+//! Timestamps returned by the API (ISO 8601 with UTC offset) are parsed into
+//! `chrono::DateTime<FixedOffset>`, keeping the offset of the requested `tz`.
 
 #[cfg(not(feature = "std"))]
 extern crate alloc;
 
 #[cfg(not(feature = "std"))]
-use alloc::{collections::BTreeMap, format, string::String, vec::Vec};
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
 
 #[cfg(feature = "std")]
 use std::collections::HashMap;
 
+use core::cmp::Ordering;
+
 use base64::{Engine as _, engine::general_purpose};
-use serde::{Deserialize, Deserializer, Serialize};
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::DecodeError;
 
 #[cfg(not(feature = "std"))]
 use alloc::string::ToString;
@@ -69,15 +77,12 @@ where
     deserializer.deserialize_any(StringOrIntVisitor)
 }
 
-#[cfg(feature = "std")]
-use std::io::Read;
-
 /// Format options for radar precipitation data encoding.
 ///
 /// Determines how the precipitation data is encoded in the `precipitation_5` field
 /// of radar responses. Different formats offer trade-offs between response size
 /// and processing complexity.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RadarCompressionFormat {
     /// Base64-encoded, zlib-compressed bytestring of 2-byte integers.
     /// This is the most efficient format in terms of response size and should
@@ -91,17 +96,44 @@ pub enum RadarCompressionFormat {
     Plain,
 }
 
-/// Represents precipitation data that may be in different compressed formats.
+impl RadarCompressionFormat {
+    /// The value of the `format` query parameter.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Compressed => "compressed",
+            Self::Bytes => "bytes",
+            Self::Plain => "plain",
+        }
+    }
+}
+
+/// Radar precipitation data in one of the formats of [`RadarCompressionFormat`].
+///
+/// Base64 payloads are kept as received and only expanded on demand with
+/// [`decode`](Self::decode) or [`decode_into`](Self::decode_into), so parsing a
+/// long sequence of frames stays cheap and only the frames in use are
+/// decompressed. Values represent 0.01 mm / 5 min precipitation amounts.
 ///
-/// This enum handles the different ways radar precipitation data can be encoded
-/// in API responses, automatically detecting and parsing the appropriate format.
-/// Values represent 0.01 mm / 5 min precipitation amounts.
+/// Deserialization tells compressed and raw payloads apart by the zlib header.
+/// It rejects payloads that do not start with valid base64 and raw payloads
+/// with an odd number of bytes; corruption further into a payload is reported
+/// as a [`DecodeError`] when decoding. When the requested format is known, use
+/// [`into_format`](Self::into_format) (or `RadarResponse::into_format`) so a
+/// damaged zlib header is an error rather than raw bytes, and raw data that
+/// happens to look like a zlib header is not decompressed. Without the `std`
+/// feature, compressed data cannot be decompressed; request
+/// [`RadarCompressionFormat::Bytes`] or [`RadarCompressionFormat::Plain`]
+/// instead.
+///
+/// Equality compares payloads as received: the same values in another format,
+/// or compressed differently, are not equal. Compare the results of
+/// [`decode`](Self::decode) to compare values.
 #[derive(Debug, Clone, PartialEq)]
 pub enum MaybeCompressedPrecipitation {
-    /// Zlib-compressed precipitation data as 16-bit integers
-    Compressed(Vec<u16>),
-    /// Uncompressed precipitation data as 16-bit integers
-    Bytes(Vec<u16>),
+    /// Base64-encoded, zlib-compressed little-endian 16-bit integers
+    Compressed(String),
+    /// Base64-encoded little-endian 16-bit integers
+    Bytes(String),
     /// Plain 2D array of precipitation values
     Plain(Vec<Vec<u16>>),
 }
@@ -111,61 +143,319 @@ impl<'de> Deserialize<'de> for MaybeCompressedPrecipitation {
     where
         D: Deserializer<'de>,
     {
-        let value: serde_json::Value = Deserialize::deserialize(deserializer)?;
+        use serde::de::{self, SeqAccess, Visitor};
 
-        match value {
-            // If it is a nested array, treat it as plain 2D array
-            serde_json::Value::Array(outer) => {
-                let mut result = Vec::new();
-                for inner in outer {
-                    if let serde_json::Value::Array(inner_array) = inner {
-                        let mut row = Vec::new();
-                        for v in inner_array {
-                            let val = v
-                                .as_u64()
-                                .ok_or_else(|| serde::de::Error::custom("Invalid array element"))?
-                                as u16;
-                            row.push(val);
-                        }
-                        result.push(row);
-                    } else {
-                        return Err(serde::de::Error::custom("Expected nested array"));
-                    }
+        // Decodes straight from the input instead of buffering the whole field
+        // in a `serde_json::Value` first, which doubled memory for plain grids.
+        struct PrecipitationVisitor;
+
+        impl<'de> Visitor<'de> for PrecipitationVisitor {
+            type Value = MaybeCompressedPrecipitation;
+
+            fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+                formatter.write_str("a base64 string or a nested array of integers")
+            }
+
+            // A nested array is the plain 2D format
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut rows = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(row) = seq.next_element::<Vec<u16>>()? {
+                    rows.push(row);
                 }
-                Ok(MaybeCompressedPrecipitation::Plain(result))
+                Ok(MaybeCompressedPrecipitation::Plain(rows))
+            }
+
+            // A string is base64, either zlib-compressed (`compressed`) or raw (`bytes`)
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                self.visit_string(value.to_string())
+            }
+
+            fn visit_string<E>(self, value: String) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                MaybeCompressedPrecipitation::from_base64(value).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(PrecipitationVisitor)
+    }
+}
+
+/// Serializes to the representation the API uses for the variant, so responses
+/// round-trip through caches: base64 payloads as received and plain data as
+/// nested arrays.
+impl Serialize for MaybeCompressedPrecipitation {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Compressed(data) | Self::Bytes(data) => serializer.serialize_str(data),
+            Self::Plain(rows) => rows.serialize(serializer),
+        }
+    }
+}
+
+impl MaybeCompressedPrecipitation {
+    /// Encode values as a compressed payload, as sent for
+    /// [`RadarCompressionFormat::Compressed`].
+    #[cfg(feature = "std")]
+    pub fn compressed(values: &[u16]) -> Self {
+        Self::Compressed(general_purpose::STANDARD.encode(deflate(&u16_to_le_bytes(values))))
+    }
+
+    /// Encode values as a raw payload, as sent for [`RadarCompressionFormat::Bytes`].
+    pub fn bytes(values: &[u16]) -> Self {
+        Self::Bytes(general_purpose::STANDARD.encode(u16_to_le_bytes(values)))
+    }
+
+    /// The format this payload is decoded as.
+    pub fn format(&self) -> RadarCompressionFormat {
+        match self {
+            Self::Compressed(_) => RadarCompressionFormat::Compressed,
+            Self::Bytes(_) => RadarCompressionFormat::Bytes,
+            Self::Plain(_) => RadarCompressionFormat::Plain,
+        }
+    }
+
+    /// Treat the payload as the format it was requested in, instead of the
+    /// format guessed during deserialization.
+    ///
+    /// # Errors
+    ///
+    /// - [`DecodeError::InvalidZlibHeader`] if compressed data was requested
+    ///   but the payload does not start with a zlib header
+    /// - [`DecodeError::UnexpectedRadarFormat`] if a base64 format was
+    ///   requested but the payload is a plain array, or the other way around
+    ///
+    /// ```rust
+    /// use brightsky::types::{MaybeCompressedPrecipitation, RadarCompressionFormat};
+    ///
+    /// // Raw bytes whose first value happens to look like a zlib header
+    /// let raw = MaybeCompressedPrecipitation::bytes(&[0x9c78, 0]);
+    /// let guessed: MaybeCompressedPrecipitation =
+    ///     serde_json::from_value(serde_json::to_value(&raw).unwrap()).unwrap();
+    /// assert_eq!(guessed.format(), RadarCompressionFormat::Compressed);
+    ///
+    /// let requested = guessed.into_format(RadarCompressionFormat::Bytes).unwrap();
+    /// assert_eq!(requested.decode().unwrap(), [0x9c78, 0]);
+    /// ```
+    pub fn into_format(self, format: RadarCompressionFormat) -> Result<Self, DecodeError> {
+        let unexpected = |received| DecodeError::UnexpectedRadarFormat {
+            requested: format,
+            received,
+        };
+        match (self, format) {
+            (Self::Compressed(data) | Self::Bytes(data), RadarCompressionFormat::Compressed) => {
+                check_zlib_header(&data)?;
+                Ok(Self::Compressed(data))
+            }
+            (Self::Compressed(data) | Self::Bytes(data), RadarCompressionFormat::Bytes) => {
+                Ok(Self::Bytes(data))
+            }
+            (plain @ Self::Plain(_), RadarCompressionFormat::Plain) => Ok(plain),
+            (Self::Plain(_), _) => Err(unexpected(RadarCompressionFormat::Plain)),
+            (payload, RadarCompressionFormat::Plain) => Err(unexpected(payload.format())),
+        }
+    }
+
+    /// Decode all values in row-major order.
+    ///
+    /// ```rust
+    /// use brightsky::types::MaybeCompressedPrecipitation;
+    ///
+    /// let precipitation: MaybeCompressedPrecipitation =
+    ///     serde_json::from_str("\"eJzjYhBhAAAAVAAf\"").unwrap();
+    /// assert!(matches!(precipitation, MaybeCompressedPrecipitation::Compressed(_)));
+    /// assert_eq!(precipitation.decode().unwrap(), [10, 20]);
+    /// ```
+    pub fn decode(&self) -> Result<Vec<u16>, DecodeError> {
+        let mut values = Vec::new();
+        self.decode_into(&mut values)?;
+        Ok(values)
+    }
+
+    /// Decode all values in row-major order into `values`, replacing its
+    /// contents.
+    ///
+    /// Reuses the allocation of `values`, e.g. when stepping through the frames
+    /// of an animation one at a time.
+    pub fn decode_into(&self, values: &mut Vec<u16>) -> Result<(), DecodeError> {
+        values.clear();
+        match self {
+            Self::Compressed(data) => {
+                check_zlib_header(data)?;
+                inflate_into(&general_purpose::STANDARD.decode(data)?, values)
             }
-            // Otherwise treat it as base64 string (compressed or bytes format)
-            serde_json::Value::String(s) => {
-                let decoded = general_purpose::STANDARD
-                    .decode(&s)
-                    .map_err(|e| serde::de::Error::custom(format!("Base64 decode error: {}", e)))?;
-
-                // Attempt to decompress using zlib (requires std feature for flate2)
-                #[cfg(feature = "std")]
-                {
-                    let mut decoder = flate2::read::ZlibDecoder::new(&decoded[..]);
-                    let mut decompressed = Vec::new();
-                    if decoder.read_to_end(&mut decompressed).is_ok() {
-                        let values: Vec<u16> = decompressed
-                            .chunks_exact(2)
-                            .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]))
-                            .collect();
-                        return Ok(MaybeCompressedPrecipitation::Compressed(values));
-                    }
+            Self::Bytes(data) => base64_into(data, values),
+            Self::Plain(rows) => {
+                values.reserve(rows.iter().map(Vec::len).sum());
+                for row in rows {
+                    values.extend_from_slice(row);
                 }
+                Ok(())
+            }
+        }
+    }
+
+    /// Decode a `precipitation_5` string of a response requested with
+    /// [`RadarCompressionFormat::Compressed`].
+    ///
+    /// Deserialization tells the formats apart by the zlib header. Use this to
+    /// decode a raw field when the requested format is known.
+    pub fn decode_compressed(data: &str) -> Result<Vec<u16>, DecodeError> {
+        Self::Compressed(data.to_string()).decode()
+    }
+
+    /// Decode a `precipitation_5` string of a response requested with
+    /// [`RadarCompressionFormat::Bytes`].
+    pub fn decode_bytes(data: &str) -> Result<Vec<u16>, DecodeError> {
+        Self::Bytes(data.to_string()).decode()
+    }
+
+    /// Classify a base64 payload by its zlib header without decoding it.
+    fn from_base64(data: String) -> Result<Self, DecodeError> {
+        if base64_has_zlib_header(&data)? {
+            return Ok(Self::Compressed(data));
+        }
+
+        if data.len().is_multiple_of(4) {
+            let padding = data.bytes().rev().take_while(|&b| b == b'=').count();
+            let len = (data.len() / 4 * 3).saturating_sub(padding);
+            if !len.is_multiple_of(2) {
+                return Err(DecodeError::OddByteLength(len));
+            }
+        }
+        Ok(Self::Bytes(data))
+    }
+}
+
+/// Whether `data` starts with a zlib header (RFC 1950) as written by the API:
+/// deflate method, window size of at most 32 KiB and no preset dictionary.
+///
+/// Raw `bytes` payloads only pass this check if the first pixel has a value
+/// far outside of the radar's range.
+fn has_zlib_header(data: &[u8]) -> bool {
+    match data {
+        [cmf, flg, ..] => {
+            cmf & 0x0f == 8
+                && cmf >> 4 <= 7
+                && flg & 0x20 == 0
+                && (u16::from(*cmf) << 8 | u16::from(*flg)) % 31 == 0
+        }
+        _ => false,
+    }
+}
 
-                // If decompression fails (or not available in no_std), treat it as raw bytes
-                let values: Vec<u16> = decoded
-                    .chunks_exact(2)
-                    .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]))
-                    .collect();
-                Ok(MaybeCompressedPrecipitation::Bytes(values))
+/// Whether base64 `data` starts with a zlib header, decoding only the first
+/// four characters, which hold the two header bytes.
+fn base64_has_zlib_header(data: &str) -> Result<bool, DecodeError> {
+    let prefix = general_purpose::STANDARD.decode(&data.as_bytes()[..data.len().min(4)])?;
+    Ok(has_zlib_header(&prefix))
+}
+
+fn check_zlib_header(data: &str) -> Result<(), DecodeError> {
+    match base64_has_zlib_header(data)? {
+        true => Ok(()),
+        false => Err(DecodeError::InvalidZlibHeader),
+    }
+}
+
+/// Decompress a complete zlib stream of little-endian `u16` values into
+/// `values`, without an intermediate byte buffer.
+#[cfg(feature = "std")]
+fn inflate_into(data: &[u8], values: &mut Vec<u16>) -> Result<(), DecodeError> {
+    use flate2::{Decompress, FlushDecompress, Status};
+
+    let mut decompress = Decompress::new(true);
+    // Radar values compress well; reserve for a typical ratio up front
+    values.resize((data.len() * 4).max(512), 0);
+    loop {
+        let (total_in, total_out) = (decompress.total_in(), decompress.total_out());
+        let output = bytemuck::cast_slice_mut::<u16, u8>(values);
+        if total_out as usize == output.len() {
+            values.resize(values.len() * 2, 0);
+            continue;
+        }
+        let status = decompress.decompress(
+            &data[total_in as usize..],
+            &mut output[total_out as usize..],
+            FlushDecompress::None,
+        )?;
+        if status == Status::StreamEnd {
+            let len = decompress.total_out() as usize;
+            if !len.is_multiple_of(2) {
+                return Err(DecodeError::OddByteLength(len));
             }
-            _ => Err(serde::de::Error::custom("Expected string or array")),
+            values.truncate(len / 2);
+            values_from_le(values);
+            return Ok(());
+        }
+        // No progress with free output space means the input ended early
+        if decompress.total_in() == total_in
+            && decompress.total_out() == total_out
+            && (total_out as usize) < output.len()
+        {
+            return Err(DecodeError::TruncatedZlibStream);
         }
     }
 }
 
+#[cfg(not(feature = "std"))]
+fn inflate_into(_data: &[u8], _values: &mut Vec<u16>) -> Result<(), DecodeError> {
+    Err(DecodeError::DecompressionUnavailable)
+}
+
+/// Decode base64 of little-endian `u16` values straight into `values`.
+fn base64_into(data: &str, values: &mut Vec<u16>) -> Result<(), DecodeError> {
+    let estimate = base64::decoded_len_estimate(data.len());
+    values.resize(estimate.div_ceil(2), 0);
+    let len = general_purpose::STANDARD
+        .decode_slice_unchecked(data, bytemuck::cast_slice_mut::<u16, u8>(values))?;
+    if !len.is_multiple_of(2) {
+        return Err(DecodeError::OddByteLength(len));
+    }
+    values.truncate(len / 2);
+    values_from_le(values);
+    Ok(())
+}
+
+/// Convert values read as little-endian bytes to native endianness.
+fn values_from_le(values: &mut [u16]) {
+    if cfg!(target_endian = "big") {
+        for value in values {
+            *value = u16::from_le(*value);
+        }
+    }
+}
+
+/// Compress bytes into a zlib stream.
+#[cfg(feature = "std")]
+fn deflate(data: &[u8]) -> Vec<u8> {
+    use flate2::{Compression, write::ZlibEncoder};
+    use std::io::Write;
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    // Writing to a `Vec` cannot fail
+    let _ = encoder.write_all(data);
+    encoder.finish().unwrap_or_default()
+}
+
+/// Little-endian bytes of `u16` values, as sent by the API.
+fn u16_to_le_bytes(values: &[u16]) -> Vec<u8> {
+    values
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect()
+}
+
 /// Weather condition icons suitable for display in weather applications.
 ///
 /// Unlike numerical parameters, this field is calculated from different fields
@@ -202,28 +492,187 @@ pub enum WeatherIcon {
     Unknown,
 }
 
+impl WeatherIcon {
+    /// Whether this is a night icon (`clear-night`, `partly-cloudy-night`).
+    ///
+    /// Icons without a day and night variant, such as `rain`, are neither.
+    pub fn is_night(&self) -> bool {
+        matches!(self, Self::ClearNight | Self::PartlyCloudyNight)
+    }
+
+    /// Whether this is a day icon (`clear-day`, `partly-cloudy-day`).
+    pub fn is_day(&self) -> bool {
+        matches!(self, Self::ClearDay | Self::PartlyCloudyDay)
+    }
+
+    /// The day or night variant of this icon. Icons without variants are
+    /// returned unchanged.
+    ///
+    /// ```rust
+    /// use brightsky::types::WeatherIcon;
+    ///
+    /// assert_eq!(WeatherIcon::ClearDay.with_daylight(false), WeatherIcon::ClearNight);
+    /// assert_eq!(WeatherIcon::Rain.with_daylight(false), WeatherIcon::Rain);
+    /// ```
+    pub fn with_daylight(self, daylight: bool) -> Self {
+        match (self, daylight) {
+            (Self::ClearDay | Self::ClearNight, true) => Self::ClearDay,
+            (Self::ClearDay | Self::ClearNight, false) => Self::ClearNight,
+            (Self::PartlyCloudyDay | Self::PartlyCloudyNight, true) => Self::PartlyCloudyDay,
+            (Self::PartlyCloudyDay | Self::PartlyCloudyNight, false) => Self::PartlyCloudyNight,
+            (icon, _) => icon,
+        }
+    }
+
+    /// Emoji for this icon, e.g. `"🌧"` for `rain`.
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            Self::ClearDay => "☀",
+            Self::ClearNight => "🌙",
+            Self::PartlyCloudyDay => "⛅",
+            Self::PartlyCloudyNight => "☁",
+            Self::Cloudy => "☁",
+            Self::Fog => "🌫",
+            Self::Wind => "💨",
+            Self::Rain => "🌧",
+            Self::Sleet => "🌨",
+            Self::Snow => "❄",
+            Self::Hail => "🌨",
+            Self::Thunderstorm => "⛈",
+            Self::Unknown => "?",
+        }
+    }
+
+    /// Short English description, e.g. `"Partly cloudy"`.
+    pub fn description_en(&self) -> &'static str {
+        match self {
+            Self::ClearDay | Self::ClearNight => "Clear",
+            Self::PartlyCloudyDay | Self::PartlyCloudyNight => "Partly cloudy",
+            Self::Cloudy => "Cloudy",
+            Self::Fog => "Fog",
+            Self::Wind => "Windy",
+            Self::Rain => "Rain",
+            Self::Sleet => "Sleet",
+            Self::Snow => "Snow",
+            Self::Hail => "Hail",
+            Self::Thunderstorm => "Thunderstorm",
+            Self::Unknown => "Unknown",
+        }
+    }
+
+    /// Short German description, e.g. `"Teilweise bewölkt"`.
+    pub fn description_de(&self) -> &'static str {
+        match self {
+            Self::ClearDay | Self::ClearNight => "Klar",
+            Self::PartlyCloudyDay | Self::PartlyCloudyNight => "Teilweise bewölkt",
+            Self::Cloudy => "Bewölkt",
+            Self::Fog => "Nebel",
+            Self::Wind => "Windig",
+            Self::Rain => "Regen",
+            Self::Sleet => "Schneeregen",
+            Self::Snow => "Schnee",
+            Self::Hail => "Hagel",
+            Self::Thunderstorm => "Gewitter",
+            Self::Unknown => "Unbekannt",
+        }
+    }
+
+    /// Short description in the given language.
+    pub fn description(&self, language: crate::Language) -> &'static str {
+        match language {
+            crate::Language::En => self.description_en(),
+            crate::Language::De => self.description_de(),
+        }
+    }
+
+    /// Class name in the [Weather Icons](https://erikflowers.github.io/weather-icons/)
+    /// font, e.g. `"wi-day-sunny"`.
+    pub fn weather_icons_class(&self) -> &'static str {
+        match self {
+            Self::ClearDay => "wi-day-sunny",
+            Self::ClearNight => "wi-night-clear",
+            Self::PartlyCloudyDay => "wi-day-cloudy",
+            Self::PartlyCloudyNight => "wi-night-alt-cloudy",
+            Self::Cloudy => "wi-cloudy",
+            Self::Fog => "wi-fog",
+            Self::Wind => "wi-strong-wind",
+            Self::Rain => "wi-rain",
+            Self::Sleet => "wi-sleet",
+            Self::Snow => "wi-snow",
+            Self::Hail => "wi-hail",
+            Self::Thunderstorm => "wi-thunderstorm",
+            Self::Unknown => "wi-na",
+        }
+    }
+
+    /// Name in Google's [Material Symbols](https://fonts.google.com/icons),
+    /// e.g. `"partly_cloudy_day"`.
+    pub fn material_symbol(&self) -> &'static str {
+        match self {
+            Self::ClearDay => "clear_day",
+            Self::ClearNight => "clear_night",
+            Self::PartlyCloudyDay => "partly_cloudy_day",
+            Self::PartlyCloudyNight => "partly_cloudy_night",
+            Self::Cloudy => "cloud",
+            Self::Fog => "foggy",
+            Self::Wind => "air",
+            Self::Rain => "rainy",
+            Self::Sleet => "weather_mix",
+            Self::Snow => "weather_snowy",
+            Self::Hail => "weather_hail",
+            Self::Thunderstorm => "thunderstorm",
+            Self::Unknown => "question_mark",
+        }
+    }
+
+    /// Name in [Material Design Icons](https://pictogrammers.com/library/mdi/),
+    /// as used by Home Assistant, e.g. `"mdi:weather-partly-cloudy"`.
+    pub fn mdi_name(&self) -> &'static str {
+        match self {
+            Self::ClearDay => "mdi:weather-sunny",
+            Self::ClearNight => "mdi:weather-night",
+            Self::PartlyCloudyDay => "mdi:weather-partly-cloudy",
+            Self::PartlyCloudyNight => "mdi:weather-night-partly-cloudy",
+            Self::Cloudy => "mdi:weather-cloudy",
+            Self::Fog => "mdi:weather-fog",
+            Self::Wind => "mdi:weather-windy",
+            Self::Rain => "mdi:weather-rainy",
+            Self::Sleet => "mdi:weather-snowy-rainy",
+            Self::Snow => "mdi:weather-snowy",
+            Self::Hail => "mdi:weather-hail",
+            Self::Thunderstorm => "mdi:weather-lightning",
+            Self::Unknown => "mdi:help-circle-outline",
+        }
+    }
+
+    /// Parse the API value, mapping unknown values to `Unknown`.
+    pub(crate) fn from_api(value: &str) -> Self {
+        match value {
+            "clear-day" => WeatherIcon::ClearDay,
+            "clear-night" => WeatherIcon::ClearNight,
+            "partly-cloudy-day" => WeatherIcon::PartlyCloudyDay,
+            "partly-cloudy-night" => WeatherIcon::PartlyCloudyNight,
+            "cloudy" => WeatherIcon::Cloudy,
+            "fog" => WeatherIcon::Fog,
+            "wind" => WeatherIcon::Wind,
+            "rain" => WeatherIcon::Rain,
+            "sleet" => WeatherIcon::Sleet,
+            "snow" => WeatherIcon::Snow,
+            "hail" => WeatherIcon::Hail,
+            "thunderstorm" => WeatherIcon::Thunderstorm,
+            // For null or unknown values
+            _ => WeatherIcon::Unknown,
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for WeatherIcon {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
         let value: String = String::deserialize(deserializer)?;
-        match value.as_str() {
-            "clear-day" => Ok(WeatherIcon::ClearDay),
-            "clear-night" => Ok(WeatherIcon::ClearNight),
-            "partly-cloudy-day" => Ok(WeatherIcon::PartlyCloudyDay),
-            "partly-cloudy-night" => Ok(WeatherIcon::PartlyCloudyNight),
-            "cloudy" => Ok(WeatherIcon::Cloudy),
-            "fog" => Ok(WeatherIcon::Fog),
-            "wind" => Ok(WeatherIcon::Wind),
-            "rain" => Ok(WeatherIcon::Rain),
-            "sleet" => Ok(WeatherIcon::Sleet),
-            "snow" => Ok(WeatherIcon::Snow),
-            "hail" => Ok(WeatherIcon::Hail),
-            "thunderstorm" => Ok(WeatherIcon::Thunderstorm),
-            // For null or unknown values
-            _ => Ok(WeatherIcon::Unknown),
-        }
+        Ok(WeatherIcon::from_api(&value))
     }
 }
 
@@ -253,30 +702,342 @@ pub enum WeatherCondition {
     Unknown,
 }
 
+impl WeatherCondition {
+    /// Parse the API value, mapping unknown values to `Unknown`.
+    pub(crate) fn from_api(value: &str) -> Self {
+        match value {
+            "dry" => WeatherCondition::Dry,
+            "fog" => WeatherCondition::Fog,
+            "rain" => WeatherCondition::Rain,
+            "sleet" => WeatherCondition::Sleet,
+            "snow" => WeatherCondition::Snow,
+            "hail" => WeatherCondition::Hail,
+            "thunderstorm" => WeatherCondition::Thunderstorm,
+            _ => WeatherCondition::Unknown,
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for WeatherCondition {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
         let value: String = String::deserialize(deserializer)?;
-        match value.as_str() {
-            "dry" => Ok(WeatherCondition::Dry),
-            "fog" => Ok(WeatherCondition::Fog),
-            "rain" => Ok(WeatherCondition::Rain),
-            "sleet" => Ok(WeatherCondition::Sleet),
-            "snow" => Ok(WeatherCondition::Snow),
-            "hail" => Ok(WeatherCondition::Hail),
-            "thunderstorm" => Ok(WeatherCondition::Thunderstorm),
-            _ => Ok(WeatherCondition::Unknown),
+        Ok(WeatherCondition::from_api(&value))
+    }
+}
+
+/// A weather parameter, named like the fields of [`Weather`] and
+/// [`CurrentWeather`].
+///
+/// Used as key of `fallback_source_ids`, and as a typed list of columns, e.g.
+/// [`WeatherParameter::HOURLY`] for the hourly record fields. Serialized as the
+/// API's snake_case name.
+///
+/// ```rust
+/// use brightsky::types::WeatherParameter;
+///
+/// let parameter: WeatherParameter = serde_json::from_str("\"wind_speed_10\"").unwrap();
+/// assert_eq!(parameter, WeatherParameter::WindSpeed10);
+/// assert_eq!(parameter.as_str(), "wind_speed_10");
+/// assert_eq!(parameter.hourly(), None);
+/// assert_eq!(WeatherParameter::WindSpeed60.hourly(), Some(WeatherParameter::WindSpeed));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum WeatherParameter {
+    /// Total cloud cover (`cloud_cover`)
+    CloudCover,
+    /// Current weather conditions (`condition`)
+    Condition,
+    /// Dew point (`dew_point`)
+    DewPoint,
+    /// Icon alias (`icon`)
+    Icon,
+    /// Pressure reduced to mean sea level (`pressure_msl`)
+    PressureMsl,
+    /// Relative humidity (`relative_humidity`)
+    RelativeHumidity,
+    /// Air temperature (`temperature`)
+    Temperature,
+    /// Visibility (`visibility`)
+    Visibility,
+    /// Precipitation during the previous hour (`precipitation`)
+    Precipitation,
+    /// Precipitation during the previous 10 minutes (`precipitation_10`)
+    Precipitation10,
+    /// Precipitation during the previous 30 minutes (`precipitation_30`)
+    Precipitation30,
+    /// Precipitation during the previous 60 minutes (`precipitation_60`)
+    Precipitation60,
+    /// Solar irradiation during the previous hour (`solar`)
+    Solar,
+    /// Solar irradiation during the previous 10 minutes (`solar_10`)
+    Solar10,
+    /// Solar irradiation during the previous 30 minutes (`solar_30`)
+    Solar30,
+    /// Solar irradiation during the previous 60 minutes (`solar_60`)
+    Solar60,
+    /// Sunshine duration during the previous hour (`sunshine`)
+    Sunshine,
+    /// Sunshine duration during the previous 30 minutes (`sunshine_30`)
+    Sunshine30,
+    /// Sunshine duration during the previous 60 minutes (`sunshine_60`)
+    Sunshine60,
+    /// Mean wind direction during the previous hour (`wind_direction`)
+    WindDirection,
+    /// Mean wind direction during the previous 10 minutes (`wind_direction_10`)
+    WindDirection10,
+    /// Mean wind direction during the previous 30 minutes (`wind_direction_30`)
+    WindDirection30,
+    /// Mean wind direction during the previous 60 minutes (`wind_direction_60`)
+    WindDirection60,
+    /// Mean wind speed during the previous hour (`wind_speed`)
+    WindSpeed,
+    /// Mean wind speed during the previous 10 minutes (`wind_speed_10`)
+    WindSpeed10,
+    /// Mean wind speed during the previous 30 minutes (`wind_speed_30`)
+    WindSpeed30,
+    /// Mean wind speed during the previous 60 minutes (`wind_speed_60`)
+    WindSpeed60,
+    /// Direction of the maximum wind gust during the previous hour (`wind_gust_direction`)
+    WindGustDirection,
+    /// Direction of the maximum wind gust during the previous 10 minutes (`wind_gust_direction_10`)
+    WindGustDirection10,
+    /// Direction of the maximum wind gust during the previous 30 minutes (`wind_gust_direction_30`)
+    WindGustDirection30,
+    /// Direction of the maximum wind gust during the previous 60 minutes (`wind_gust_direction_60`)
+    WindGustDirection60,
+    /// Speed of the maximum wind gust during the previous hour (`wind_gust_speed`)
+    WindGustSpeed,
+    /// Speed of the maximum wind gust during the previous 10 minutes (`wind_gust_speed_10`)
+    WindGustSpeed10,
+    /// Speed of the maximum wind gust during the previous 30 minutes (`wind_gust_speed_30`)
+    WindGustSpeed30,
+    /// Speed of the maximum wind gust during the previous 60 minutes (`wind_gust_speed_60`)
+    WindGustSpeed60,
+    /// Probability of >0.1mm precipitation in the previous hour (`precipitation_probability`)
+    PrecipitationProbability,
+    /// Probability of >0.2mm precipitation in the previous 6 hours (`precipitation_probability_6h`)
+    PrecipitationProbability6h,
+    /// A parameter added to the API after this version, as sent
+    Other(String),
+}
+
+impl WeatherParameter {
+    /// Parameters of hourly [`Weather`] records, in field order.
+    pub const HOURLY: [WeatherParameter; 17] = [
+        WeatherParameter::CloudCover,
+        WeatherParameter::Condition,
+        WeatherParameter::DewPoint,
+        WeatherParameter::Icon,
+        WeatherParameter::PressureMsl,
+        WeatherParameter::RelativeHumidity,
+        WeatherParameter::Temperature,
+        WeatherParameter::Visibility,
+        WeatherParameter::Precipitation,
+        WeatherParameter::Solar,
+        WeatherParameter::Sunshine,
+        WeatherParameter::WindDirection,
+        WeatherParameter::WindSpeed,
+        WeatherParameter::WindGustDirection,
+        WeatherParameter::WindGustSpeed,
+        WeatherParameter::PrecipitationProbability,
+        WeatherParameter::PrecipitationProbability6h,
+    ];
+
+    /// Parameters of [`CurrentWeather`], in field order.
+    pub const CURRENT: [WeatherParameter; 28] = [
+        WeatherParameter::CloudCover,
+        WeatherParameter::Condition,
+        WeatherParameter::DewPoint,
+        WeatherParameter::Icon,
+        WeatherParameter::PressureMsl,
+        WeatherParameter::RelativeHumidity,
+        WeatherParameter::Temperature,
+        WeatherParameter::Visibility,
+        WeatherParameter::Precipitation10,
+        WeatherParameter::Precipitation30,
+        WeatherParameter::Precipitation60,
+        WeatherParameter::Solar10,
+        WeatherParameter::Solar30,
+        WeatherParameter::Solar60,
+        WeatherParameter::Sunshine30,
+        WeatherParameter::Sunshine60,
+        WeatherParameter::WindDirection10,
+        WeatherParameter::WindDirection30,
+        WeatherParameter::WindDirection60,
+        WeatherParameter::WindSpeed10,
+        WeatherParameter::WindSpeed30,
+        WeatherParameter::WindSpeed60,
+        WeatherParameter::WindGustDirection10,
+        WeatherParameter::WindGustDirection30,
+        WeatherParameter::WindGustDirection60,
+        WeatherParameter::WindGustSpeed10,
+        WeatherParameter::WindGustSpeed30,
+        WeatherParameter::WindGustSpeed60,
+    ];
+
+    /// The API name, e.g. `pressure_msl`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::CloudCover => "cloud_cover",
+            Self::Condition => "condition",
+            Self::DewPoint => "dew_point",
+            Self::Icon => "icon",
+            Self::PressureMsl => "pressure_msl",
+            Self::RelativeHumidity => "relative_humidity",
+            Self::Temperature => "temperature",
+            Self::Visibility => "visibility",
+            Self::Precipitation => "precipitation",
+            Self::Precipitation10 => "precipitation_10",
+            Self::Precipitation30 => "precipitation_30",
+            Self::Precipitation60 => "precipitation_60",
+            Self::Solar => "solar",
+            Self::Solar10 => "solar_10",
+            Self::Solar30 => "solar_30",
+            Self::Solar60 => "solar_60",
+            Self::Sunshine => "sunshine",
+            Self::Sunshine30 => "sunshine_30",
+            Self::Sunshine60 => "sunshine_60",
+            Self::WindDirection => "wind_direction",
+            Self::WindDirection10 => "wind_direction_10",
+            Self::WindDirection30 => "wind_direction_30",
+            Self::WindDirection60 => "wind_direction_60",
+            Self::WindSpeed => "wind_speed",
+            Self::WindSpeed10 => "wind_speed_10",
+            Self::WindSpeed30 => "wind_speed_30",
+            Self::WindSpeed60 => "wind_speed_60",
+            Self::WindGustDirection => "wind_gust_direction",
+            Self::WindGustDirection10 => "wind_gust_direction_10",
+            Self::WindGustDirection30 => "wind_gust_direction_30",
+            Self::WindGustDirection60 => "wind_gust_direction_60",
+            Self::WindGustSpeed => "wind_gust_speed",
+            Self::WindGustSpeed10 => "wind_gust_speed_10",
+            Self::WindGustSpeed30 => "wind_gust_speed_30",
+            Self::WindGustSpeed60 => "wind_gust_speed_60",
+            Self::PrecipitationProbability => "precipitation_probability",
+            Self::PrecipitationProbability6h => "precipitation_probability_6h",
+            Self::Other(value) => value,
+        }
+    }
+
+    /// The hourly parameter describing the same quantity, for mapping
+    /// [`CurrentWeather`] onto [`Weather`].
+    ///
+    /// 60-minute parameters map to their hourly counterpart, 10- and 30-minute
+    /// parameters to `None`. All others, including `Other` names without an
+    /// interval suffix, are returned unchanged.
+    pub fn hourly(&self) -> Option<Self> {
+        let parameter = match self {
+            Self::Precipitation60 => Self::Precipitation,
+            Self::Solar60 => Self::Solar,
+            Self::Sunshine60 => Self::Sunshine,
+            Self::WindDirection60 => Self::WindDirection,
+            Self::WindSpeed60 => Self::WindSpeed,
+            Self::WindGustDirection60 => Self::WindGustDirection,
+            Self::WindGustSpeed60 => Self::WindGustSpeed,
+            Self::Precipitation10
+            | Self::Precipitation30
+            | Self::Solar10
+            | Self::Solar30
+            | Self::Sunshine30
+            | Self::WindDirection10
+            | Self::WindDirection30
+            | Self::WindSpeed10
+            | Self::WindSpeed30
+            | Self::WindGustDirection10
+            | Self::WindGustDirection30
+            | Self::WindGustSpeed10
+            | Self::WindGustSpeed30 => return None,
+            Self::Other(value) => {
+                if let Some(parameter) = value.strip_suffix("_60") {
+                    return Some(Self::from_api(parameter));
+                }
+                if value.ends_with("_10") || value.ends_with("_30") {
+                    return None;
+                }
+                return Some(self.clone());
+            }
+            other => other.clone(),
+        };
+        Some(parameter)
+    }
+
+    /// Parse the API name, keeping unknown names as `Other`.
+    pub(crate) fn from_api(value: &str) -> Self {
+        match value {
+            "cloud_cover" => Self::CloudCover,
+            "condition" => Self::Condition,
+            "dew_point" => Self::DewPoint,
+            "icon" => Self::Icon,
+            "pressure_msl" => Self::PressureMsl,
+            "relative_humidity" => Self::RelativeHumidity,
+            "temperature" => Self::Temperature,
+            "visibility" => Self::Visibility,
+            "precipitation" => Self::Precipitation,
+            "precipitation_10" => Self::Precipitation10,
+            "precipitation_30" => Self::Precipitation30,
+            "precipitation_60" => Self::Precipitation60,
+            "solar" => Self::Solar,
+            "solar_10" => Self::Solar10,
+            "solar_30" => Self::Solar30,
+            "solar_60" => Self::Solar60,
+            "sunshine" => Self::Sunshine,
+            "sunshine_30" => Self::Sunshine30,
+            "sunshine_60" => Self::Sunshine60,
+            "wind_direction" => Self::WindDirection,
+            "wind_direction_10" => Self::WindDirection10,
+            "wind_direction_30" => Self::WindDirection30,
+            "wind_direction_60" => Self::WindDirection60,
+            "wind_speed" => Self::WindSpeed,
+            "wind_speed_10" => Self::WindSpeed10,
+            "wind_speed_30" => Self::WindSpeed30,
+            "wind_speed_60" => Self::WindSpeed60,
+            "wind_gust_direction" => Self::WindGustDirection,
+            "wind_gust_direction_10" => Self::WindGustDirection10,
+            "wind_gust_direction_30" => Self::WindGustDirection30,
+            "wind_gust_direction_60" => Self::WindGustDirection60,
+            "wind_gust_speed" => Self::WindGustSpeed,
+            "wind_gust_speed_10" => Self::WindGustSpeed10,
+            "wind_gust_speed_30" => Self::WindGustSpeed30,
+            "wind_gust_speed_60" => Self::WindGustSpeed60,
+            "precipitation_probability" => Self::PrecipitationProbability,
+            "precipitation_probability_6h" => Self::PrecipitationProbability6h,
+            _ => Self::Other(value.to_string()),
         }
     }
 }
 
+impl core::fmt::Display for WeatherParameter {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for WeatherParameter {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for WeatherParameter {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value: String = String::deserialize(deserializer)?;
+        Ok(WeatherParameter::from_api(&value))
+    }
+}
+
 /// Type of meteorological observation or data source.
 ///
 /// Indicates the nature and time characteristics of the weather data source.
-#[derive(Debug, Clone, Serialize, PartialEq)]
-#[serde(rename_all = "kebab-case")]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ObservationType {
     /// Historical weather observations from past measurements
     Historical,
@@ -286,6 +1047,30 @@ pub enum ObservationType {
     Synop,
     /// Weather forecast data
     Forecast,
+    /// A type added to the API after this version, as sent
+    Other(String),
+}
+
+impl ObservationType {
+    /// The value as sent by the API, e.g. `synop`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            ObservationType::Historical => "historical",
+            ObservationType::Current => "current",
+            ObservationType::Synop => "synop",
+            ObservationType::Forecast => "forecast",
+            ObservationType::Other(value) => value,
+        }
+    }
+}
+
+impl Serialize for ObservationType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
 }
 
 impl<'de> Deserialize<'de> for ObservationType {
@@ -299,9 +1084,69 @@ impl<'de> Deserialize<'de> for ObservationType {
             "current" => Ok(ObservationType::Current),
             "synop" => Ok(ObservationType::Synop),
             "forecast" => Ok(ObservationType::Forecast),
-            _ => Err(serde::de::Error::custom("Invalid observation type")),
+            _ => Ok(ObservationType::Other(value)),
+        }
+    }
+}
+
+/// Value of a `date` or `last_date` query parameter.
+///
+/// The API accepts plain dates as well as full ISO 8601 timestamps with a UTC
+/// offset, e.g. to request records "from 14:00 today".
+///
+/// ```rust
+/// use brightsky::types::QueryDate;
+/// use chrono::{DateTime, NaiveDate};
+///
+/// let date = QueryDate::from(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap());
+/// assert_eq!(date.to_string(), "2023-08-07");
+///
+/// let datetime = QueryDate::from(DateTime::parse_from_rfc3339("2023-08-07T14:00:00+02:00").unwrap());
+/// assert_eq!(datetime.to_string(), "2023-08-07T14:00:00+02:00");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryDate {
+    /// A calendar date, interpreted by the API in the query's timezone
+    Date(NaiveDate),
+    /// A point in time with UTC offset
+    DateTime(DateTime<FixedOffset>),
+}
+
+impl QueryDate {
+    /// The calendar date, in the timestamp's own offset for `DateTime`.
+    pub fn date_naive(&self) -> NaiveDate {
+        match self {
+            Self::Date(date) => *date,
+            Self::DateTime(datetime) => datetime.date_naive(),
         }
     }
+
+    /// Percent-encoded value for hand-built query strings (`+` in offsets
+    /// would otherwise be decoded as a space).
+    pub(crate) fn to_url_param(self) -> String {
+        self.to_string().replace('+', "%2B")
+    }
+}
+
+impl core::fmt::Display for QueryDate {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Date(date) => write!(f, "{}", date),
+            Self::DateTime(datetime) => write!(f, "{}", datetime.to_rfc3339()),
+        }
+    }
+}
+
+impl From<NaiveDate> for QueryDate {
+    fn from(date: NaiveDate) -> Self {
+        Self::Date(date)
+    }
+}
+
+impl From<DateTime<FixedOffset>> for QueryDate {
+    fn from(datetime: DateTime<FixedOffset>) -> Self {
+        Self::DateTime(datetime)
+    }
 }
 
 /// Physical units system for meteorological parameters.
@@ -309,7 +1154,7 @@ impl<'de> Deserialize<'de> for ObservationType {
 /// Determines the unit system used for returned meteorological data.
 /// The `dwd` system uses units common in meteorological applications,
 /// while `si` uses International System of Units (with precipitation always in mm).
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub enum UnitType {
     /// International System of Units (SI)
@@ -344,6 +1189,16 @@ impl<'de> Deserialize<'de> for UnitType {
     }
 }
 
+impl UnitType {
+    /// The value of the `units` query parameter, `dwd` or `si`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UnitType::Si => "si",
+            UnitType::Dwd => "dwd",
+        }
+    }
+}
+
 /// Response structure for data returned by the `/weather` endpoint.
 ///
 /// Contains hourly weather records and/or forecasts for the requested time range,
@@ -388,8 +1243,8 @@ pub struct WeatherResponse {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct Weather {
-    /// ISO 8601 formatted timestamp of this weather record
-    pub timestamp: String,
+    /// Timestamp of this weather record
+    pub timestamp: DateTime<FixedOffset>,
     /// Bright Sky source ID for this record
     pub source_id: i64,
     /// Total cloud cover at timestamp (percentage)
@@ -410,10 +1265,10 @@ pub struct Weather {
     pub visibility: Option<i64>,
     /// Mapping of parameters to alternative source IDs used for missing values
     #[cfg(feature = "std")]
-    pub fallback_source_ids: Option<HashMap<String, i64>>,
+    pub fallback_source_ids: Option<HashMap<WeatherParameter, i64>>,
     /// Mapping of parameters to alternative source IDs used for missing values
     #[cfg(not(feature = "std"))]
-    pub fallback_source_ids: Option<BTreeMap<String, i64>>,
+    pub fallback_source_ids: Option<BTreeMap<WeatherParameter, i64>>,
     /// Total precipitation during previous 60 minutes (mm)
     pub precipitation: Option<f64>,
     /// Solar irradiation during previous 60 minutes (kWh/m² or J/m²)
@@ -451,10 +1306,10 @@ pub struct Source {
     pub station_name: Option<String>,
     /// Type of observations provided by this source
     pub observation_type: ObservationType,
-    /// ISO 8601 timestamp of first available record for this source
-    pub first_record: String,
-    /// ISO 8601 timestamp of latest available record for this source
-    pub last_record: String,
+    /// Timestamp of first available record for this source
+    pub first_record: DateTime<FixedOffset>,
+    /// Timestamp of latest available record for this source
+    pub last_record: DateTime<FixedOffset>,
     /// Station latitude in decimal degrees
     pub lat: f64,
     /// Station longitude in decimal degrees
@@ -507,8 +1362,8 @@ pub struct CurrentWeatherResponse {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct CurrentWeather {
-    /// ISO 8601 formatted timestamp of this weather record
-    pub timestamp: String,
+    /// Timestamp of this weather record
+    pub timestamp: DateTime<FixedOffset>,
     /// Bright Sky source ID for this record
     pub source_id: i64,
     /// Total cloud cover at timestamp (percentage)
@@ -530,11 +1385,11 @@ pub struct CurrentWeather {
     /// Mapping of meteorological parameters to alternative source IDs
     /// used to fill missing values in the main source
     #[cfg(feature = "std")]
-    pub fallback_source_ids: Option<HashMap<String, i64>>,
+    pub fallback_source_ids: Option<HashMap<WeatherParameter, i64>>,
     /// Mapping of meteorological parameters to alternative source IDs
     /// used to fill missing values in the main source
     #[cfg(not(feature = "std"))]
-    pub fallback_source_ids: Option<BTreeMap<String, i64>>,
+    pub fallback_source_ids: Option<BTreeMap<WeatherParameter, i64>>,
     /// Total precipitation during previous 10 minutes (mm)
     pub precipitation_10: Option<f64>,
     /// Total precipitation during previous 30 minutes (mm)
@@ -594,10 +1449,10 @@ pub struct CurrentWeatherSource {
     pub station_name: String,
     /// Type of observations provided by this source
     pub observation_type: ObservationType,
-    /// ISO 8601 timestamp of first available record for this source
-    pub first_record: String,
-    /// ISO 8601 timestamp of latest available record for this source
-    pub last_record: String,
+    /// Timestamp of first available record for this source
+    pub first_record: DateTime<FixedOffset>,
+    /// Timestamp of latest available record for this source
+    pub last_record: DateTime<FixedOffset>,
     /// Station latitude in decimal degrees
     pub lat: f64,
     /// Station longitude in decimal degrees
@@ -639,7 +1494,7 @@ pub struct CurrentWeatherSource {
 ///     Ok(())
 /// }
 /// ```
-#[derive(Default, Debug, Clone, PartialEq, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct RadarResponse {
     /// List of radar records with precipitation data
@@ -656,11 +1511,11 @@ pub struct RadarResponse {
 ///
 /// Contains 5-minute precipitation data for a specific timestamp, with values
 /// representing 0.01 mm / 5 min precipitation amounts.
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct Radar {
-    /// ISO 8601 formatted timestamp of this radar record
-    pub timestamp: String,
+    /// Timestamp of this radar record
+    pub timestamp: DateTime<FixedOffset>,
     /// Unique identifier for DWD radar product source (e.g., "RADOLAN::RV::2023-08-08T11:45:00+00:00")
     pub source: String,
     /// 5-minute precipitation data in various possible formats (compressed/bytes/plain)
@@ -697,13 +1552,34 @@ pub struct LatlonPosition {
 /// Status of a weather alert.
 ///
 /// Indicates whether this is a real alert or a test message.
-#[derive(Debug, Clone, Serialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
+#[derive(Debug, Clone, PartialEq)]
 pub enum AlertStatus {
     /// Real, active weather alert
     Actual,
     /// Test alert message
     Test,
+    /// A value added after this version, as sent
+    Other(String),
+}
+
+impl AlertStatus {
+    /// The value as sent by the API, e.g. `actual`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            AlertStatus::Actual => "actual",
+            AlertStatus::Test => "test",
+            AlertStatus::Other(value) => value,
+        }
+    }
+}
+
+impl Serialize for AlertStatus {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
 }
 
 impl<'de> Deserialize<'de> for AlertStatus {
@@ -712,10 +1588,10 @@ impl<'de> Deserialize<'de> for AlertStatus {
         D: Deserializer<'de>,
     {
         let value: String = String::deserialize(deserializer)?;
-        match value.as_str() {
+        match value.to_ascii_lowercase().as_str() {
             "actual" => Ok(AlertStatus::Actual),
             "test" => Ok(AlertStatus::Test),
-            _ => Err(serde::de::Error::custom("Invalid alert status")),
+            _ => Ok(AlertStatus::Other(value)),
         }
     }
 }
@@ -723,13 +1599,34 @@ impl<'de> Deserialize<'de> for AlertStatus {
 /// Category of weather alert.
 ///
 /// Classifies alerts by their primary domain.
-#[derive(Debug, Clone, Serialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
+#[derive(Debug, Clone, PartialEq)]
 pub enum AlertCategory {
     /// Meteorological alert (weather-related)
     Met,
     /// Public health related alert
     Health,
+    /// A value added after this version, as sent
+    Other(String),
+}
+
+impl AlertCategory {
+    /// The value as sent by the API, e.g. `met`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            AlertCategory::Met => "met",
+            AlertCategory::Health => "health",
+            AlertCategory::Other(value) => value,
+        }
+    }
+}
+
+impl Serialize for AlertCategory {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
 }
 
 impl<'de> Deserialize<'de> for AlertCategory {
@@ -738,10 +1635,10 @@ impl<'de> Deserialize<'de> for AlertCategory {
         D: Deserializer<'de>,
     {
         let value: String = String::deserialize(deserializer)?;
-        match value.as_str() {
+        match value.to_ascii_lowercase().as_str() {
             "met" => Ok(AlertCategory::Met),
             "health" => Ok(AlertCategory::Health),
-            _ => Err(serde::de::Error::custom("Invalid alert category")),
+            _ => Ok(AlertCategory::Other(value)),
         }
     }
 }
@@ -749,8 +1646,7 @@ impl<'de> Deserialize<'de> for AlertCategory {
 /// Recommended response type for a weather alert.
 ///
 /// Indicates what type of action is recommended for the target audience.
-#[derive(Debug, Clone, Serialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
+#[derive(Debug, Clone, PartialEq)]
 pub enum AlertResponseType {
     /// Take preparatory action
     Prepare,
@@ -760,6 +1656,30 @@ pub enum AlertResponseType {
     None,
     /// Monitor the situation
     Monitor,
+    /// A value added after this version, as sent
+    Other(String),
+}
+
+impl AlertResponseType {
+    /// The value as sent by the API, e.g. `prepare`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            AlertResponseType::Prepare => "prepare",
+            AlertResponseType::AllClear => "allclear",
+            AlertResponseType::None => "none",
+            AlertResponseType::Monitor => "monitor",
+            AlertResponseType::Other(value) => value,
+        }
+    }
+}
+
+impl Serialize for AlertResponseType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
 }
 
 impl<'de> Deserialize<'de> for AlertResponseType {
@@ -768,26 +1688,71 @@ impl<'de> Deserialize<'de> for AlertResponseType {
         D: Deserializer<'de>,
     {
         let value: String = String::deserialize(deserializer)?;
-        match value.as_str() {
+        match value.to_ascii_lowercase().as_str() {
             "prepare" => Ok(AlertResponseType::Prepare),
             "allclear" => Ok(AlertResponseType::AllClear),
             "none" => Ok(AlertResponseType::None),
             "monitor" => Ok(AlertResponseType::Monitor),
-            _ => Err(serde::de::Error::custom("Invalid alert response type")),
+            _ => Ok(AlertResponseType::Other(value)),
         }
     }
 }
 
 /// Urgency level of a weather alert.
 ///
-/// Indicates the time frame for the expected weather event.
-#[derive(Debug, Clone, Serialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
+/// Indicates the time frame for the expected weather event. Ordered by
+/// urgency: `Future` < `Immediate`. Unknown values sort below `Future`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum AlertUrgency {
     /// Immediate threat or event in progress
     Immediate,
     /// Future threat, advance warning
     Future,
+    /// A value added after this version, as sent
+    Other(String),
+}
+
+impl AlertUrgency {
+    /// The value as sent by the API, e.g. `immediate`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            AlertUrgency::Immediate => "immediate",
+            AlertUrgency::Future => "future",
+            AlertUrgency::Other(value) => value,
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            AlertUrgency::Other(_) => 0,
+            AlertUrgency::Future => 1,
+            AlertUrgency::Immediate => 2,
+        }
+    }
+}
+
+impl PartialOrd for AlertUrgency {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for AlertUrgency {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (AlertUrgency::Other(a), AlertUrgency::Other(b)) => a.cmp(b),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+}
+
+impl Serialize for AlertUrgency {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
 }
 
 impl<'de> Deserialize<'de> for AlertUrgency {
@@ -796,10 +1761,10 @@ impl<'de> Deserialize<'de> for AlertUrgency {
         D: Deserializer<'de>,
     {
         let value: String = String::deserialize(deserializer)?;
-        match value.as_str() {
+        match value.to_ascii_lowercase().as_str() {
             "immediate" => Ok(AlertUrgency::Immediate),
             "future" => Ok(AlertUrgency::Future),
-            _ => Err(serde::de::Error::custom("Invalid alert urgency")),
+            _ => Ok(AlertUrgency::Other(value)),
         }
     }
 }
@@ -807,7 +1772,20 @@ impl<'de> Deserialize<'de> for AlertUrgency {
 /// Severity level of a weather alert.
 ///
 /// Indicates the expected intensity and potential impact of the weather event.
-#[derive(Debug, Clone, Serialize, PartialEq)]
+/// Ordered from `Minor` to `Extreme`, so alerts can be compared and ranked
+/// directly:
+///
+/// ```rust
+/// use brightsky::types::AlertSeverity;
+///
+/// let severities = [Some(AlertSeverity::Moderate), None, Some(AlertSeverity::Severe)];
+/// assert!(AlertSeverity::Severe > AlertSeverity::Moderate);
+/// assert_eq!(severities.iter().flatten().max(), Some(&AlertSeverity::Severe));
+/// // `None` sorts below every severity
+/// let severe = severities.iter().filter(|s| **s >= Some(AlertSeverity::Severe));
+/// assert_eq!(severe.count(), 1);
+/// ```
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum AlertSeverity {
     /// Minor impact expected
@@ -820,6 +1798,57 @@ pub enum AlertSeverity {
     Extreme,
 }
 
+impl AlertSeverity {
+    /// The value as sent by the API, e.g. `severe`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertSeverity::Minor => "minor",
+            AlertSeverity::Moderate => "moderate",
+            AlertSeverity::Severe => "severe",
+            AlertSeverity::Extreme => "extreme",
+        }
+    }
+
+    /// Color of the warning level on DWD's warning maps: yellow, orange, red
+    /// and violet from `Minor` to `Extreme`.
+    ///
+    /// ```rust
+    /// use brightsky::types::AlertSeverity;
+    ///
+    /// let color = AlertSeverity::Severe.dwd_color();
+    /// assert_eq!(color.hex, "#FF0000");
+    /// assert_eq!(color.rgb, [255, 0, 0]);
+    /// ```
+    pub fn dwd_color(&self) -> SeverityColor {
+        let (hex, rgb) = match self {
+            AlertSeverity::Minor => ("#FFFF00", [255, 255, 0]),
+            AlertSeverity::Moderate => ("#FF9900", [255, 153, 0]),
+            AlertSeverity::Severe => ("#FF0000", [255, 0, 0]),
+            AlertSeverity::Extreme => ("#9E46F8", [158, 70, 248]),
+        };
+        SeverityColor { hex, rgb }
+    }
+
+    /// CSS class for styling the severity, e.g. `alert-severity-severe`.
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            AlertSeverity::Minor => "alert-severity-minor",
+            AlertSeverity::Moderate => "alert-severity-moderate",
+            AlertSeverity::Severe => "alert-severity-severe",
+            AlertSeverity::Extreme => "alert-severity-extreme",
+        }
+    }
+}
+
+/// Color of a warning level, see [`AlertSeverity::dwd_color`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SeverityColor {
+    /// Uppercase hex notation with a leading `#`, e.g. `#FF9900`
+    pub hex: &'static str,
+    /// Red, green and blue components
+    pub rgb: [u8; 3],
+}
+
 impl<'de> Deserialize<'de> for AlertSeverity {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -839,13 +1868,34 @@ impl<'de> Deserialize<'de> for AlertSeverity {
 /// Certainty level of a weather alert.
 ///
 /// Indicates the confidence in the occurrence of the forecasted event.
-#[derive(Debug, Clone, Serialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
+#[derive(Debug, Clone, PartialEq)]
 pub enum AlertCertainty {
     /// Event has been observed and is occurring
     Observed,
     /// Event is likely to occur (forecast)
     Likely,
+    /// A value added after this version, as sent
+    Other(String),
+}
+
+impl AlertCertainty {
+    /// The value as sent by the API, e.g. `observed`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            AlertCertainty::Observed => "observed",
+            AlertCertainty::Likely => "likely",
+            AlertCertainty::Other(value) => value,
+        }
+    }
+}
+
+impl Serialize for AlertCertainty {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
 }
 
 impl<'de> Deserialize<'de> for AlertCertainty {
@@ -854,10 +1904,10 @@ impl<'de> Deserialize<'de> for AlertCertainty {
         D: Deserializer<'de>,
     {
         let value: String = String::deserialize(deserializer)?;
-        match value.as_str() {
+        match value.to_ascii_lowercase().as_str() {
             "observed" => Ok(AlertCertainty::Observed),
             "likely" => Ok(AlertCertainty::Likely),
-            _ => Err(serde::de::Error::custom("Invalid alert certainty")),
+            _ => Ok(AlertCertainty::Other(value)),
         }
     }
 }
@@ -878,12 +1928,12 @@ pub struct Alert {
     pub alert_id: String,
     /// Alert status (actual warning or test)
     pub status: AlertStatus,
-    /// ISO 8601 timestamp when alert was issued
-    pub effective: String,
-    /// ISO 8601 timestamp when weather event is expected to begin
-    pub onset: String,
-    /// ISO 8601 timestamp when weather event is expected to end
-    pub expires: Option<String>,
+    /// Timestamp when alert was issued
+    pub effective: DateTime<FixedOffset>,
+    /// Timestamp when weather event is expected to begin
+    pub onset: DateTime<FixedOffset>,
+    /// Timestamp when weather event is expected to end
+    pub expires: Option<DateTime<FixedOffset>>,
     /// Alert category (meteorological or health-related)
     pub category: Option<AlertCategory>,
     /// Recommended response type for the target audience
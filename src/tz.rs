@@ -0,0 +1,122 @@
+//! Timezone parameters.
+//!
+//! The `tz` query parameter takes a name from the tz database. Query builders
+//! accept any [`TzName`]: a string or, with the `chrono-tz` feature, a
+//! `chrono_tz::Tz`. With that feature, `build()` also
+//! rejects names that are not in the tz database, and records can be converted
+//! into a timezone with `timestamp_in`:
+//!
+//! ```rust
+//! # #[cfg(feature = "chrono-tz")]
+//! # fn main() {
+//! use brightsky::{BrightSkyError, QueryError, WeatherQueryBuilder, types::Weather};
+//! use chrono::NaiveDate;
+//! use chrono_tz::Europe::Berlin;
+//!
+//! let query = WeatherQueryBuilder::new()
+//!     .with_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap())
+//!     .with_lat_lon((52.52, 13.4))
+//!     .with_tz(Berlin)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(query.tz.as_deref(), Some("Europe/Berlin"));
+//!
+//! let invalid = WeatherQueryBuilder::new()
+//!     .with_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap())
+//!     .with_tz("Europe/Berlim")
+//!     .build();
+//! assert!(matches!(invalid, Err(BrightSkyError::Query(QueryError::InvalidTimezone(_)))));
+//!
+//! let record: Weather = serde_json::from_str(
+//!     r#"{"timestamp": "2023-08-07T12:00:00+00:00", "source_id": 1}"#,
+//! ).unwrap();
+//! assert_eq!(record.timestamp_in(Berlin).to_rfc3339(), "2023-08-07T14:00:00+02:00");
+//! # }
+//! # #[cfg(not(feature = "chrono-tz"))]
+//! # fn main() {}
+//! ```
+
+#[cfg(not(feature = "std"))]
+use alloc::{borrow::Cow, string::String};
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+
+use crate::QueryError;
+
+/// A timezone for the `tz` parameter of `with_tz`.
+///
+/// Implemented for strings holding a tz database name and, with the
+/// `chrono-tz` feature, for `chrono_tz::Tz`. This is the `AsRef<str>` of
+/// `with_tz`, which `chrono_tz::Tz` cannot implement itself.
+pub trait TzName {
+    /// The tz database name, such as `Europe/Berlin`.
+    fn tz_name(&self) -> &str;
+}
+
+impl TzName for str {
+    fn tz_name(&self) -> &str {
+        self
+    }
+}
+
+impl TzName for String {
+    fn tz_name(&self) -> &str {
+        self
+    }
+}
+
+impl TzName for Cow<'_, str> {
+    fn tz_name(&self) -> &str {
+        self
+    }
+}
+
+impl<T: TzName + ?Sized> TzName for &T {
+    fn tz_name(&self) -> &str {
+        (**self).tz_name()
+    }
+}
+
+#[cfg(feature = "chrono-tz")]
+impl TzName for chrono_tz::Tz {
+    fn tz_name(&self) -> &str {
+        self.name()
+    }
+}
+
+/// Check a `tz` parameter against the tz database.
+///
+/// Always succeeds without the `chrono-tz` feature; the API then reports
+/// unknown timezones.
+pub(crate) fn check_tz(tz: Option<&str>) -> Result<(), QueryError> {
+    #[cfg(feature = "chrono-tz")]
+    if let Some(tz) = tz {
+        tz.parse::<chrono_tz::Tz>()
+            .map_err(|_| QueryError::InvalidTimezone(tz.into()))?;
+    }
+    #[cfg(not(feature = "chrono-tz"))]
+    let _ = tz;
+    Ok(())
+}
+
+#[cfg(feature = "chrono-tz")]
+mod convert {
+    use chrono::DateTime;
+    use chrono_tz::Tz;
+
+    use crate::types::{CurrentWeather, Weather};
+
+    impl Weather {
+        /// The record timestamp in the given timezone.
+        pub fn timestamp_in(&self, tz: Tz) -> DateTime<Tz> {
+            self.timestamp.with_timezone(&tz)
+        }
+    }
+
+    impl CurrentWeather {
+        /// The record timestamp in the given timezone.
+        pub fn timestamp_in(&self, tz: Tz) -> DateTime<Tz> {
+            self.timestamp.with_timezone(&tz)
+        }
+    }
+}
@@ -0,0 +1,409 @@
+//! Unit-aware measurement values.
+//!
+//! Temperatures, pressures and wind speeds in API responses are plain `f64` values
+//! whose meaning depends on the `units` query parameter (`dwd` or `si`). The
+//! newtypes in this module carry the [`UnitType`] alongside the value, so values
+//! from differently configured queries can be converted explicitly instead of
+//! being mixed up silently.
+//!
+//! | Quantity | `UnitType::Dwd` | `UnitType::Si` |
+//! |----------|-----------------|----------------|
+//! | [`Temperature`] | °C | K |
+//! | [`Pressure`] | hPa | Pa |
+//! | [`WindSpeed`] | km/h | m/s |
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use brightsky::{types::UnitType, units::Temperature};
+//!
+//! let dwd = Temperature::new(22.3, UnitType::Dwd);
+//! let si = Temperature::new(295.45, UnitType::Si);
+//!
+//! assert!((dwd.to_kelvin() - si.to_kelvin()).abs() < 1e-9);
+//! assert!((si.to_celsius() - 22.3).abs() < 1e-9);
+//! ```
+//!
+//! Response records expose typed accessors which take the unit system the query
+//! was made with:
+//!
+//! ```rust
+//! use brightsky::types::{UnitType, Weather};
+//!
+//! let record: Weather = serde_json::from_str(r#"{
+//!     "timestamp": "2023-08-07T12:00:00+00:00",
+//!     "source_id": 1234,
+//!     "temperature": 295.45,
+//!     "wind_speed": 5.0
+//! }"#).unwrap();
+//!
+//! let temperature = record.typed_temperature(UnitType::Si).unwrap();
+//! assert!((temperature.to_celsius() - 22.3).abs() < 1e-9);
+//!
+//! let wind = record.typed_wind_speed(UnitType::Si).unwrap();
+//! assert!((wind.to_kmh() - 18.0).abs() < 1e-9);
+//! ```
+//!
+//! `convert_units` rewrites whole records and responses to another unit
+//! system, including solar irradiation (kWh/m² or J/m²) and sunshine duration
+//! (minutes or seconds).
+
+use crate::types::{CurrentWeather, CurrentWeatherResponse, UnitType, Weather, WeatherResponse};
+
+/// Offset between degrees Celsius and Kelvin.
+const KELVIN_OFFSET: f64 = 273.15;
+
+/// Joules per kilowatt hour.
+const JOULES_PER_KWH: f64 = 3_600_000.0;
+
+/// An air or dew point temperature (°C for `UnitType::Dwd`, K for `UnitType::Si`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Temperature {
+    value: f64,
+    units: UnitType,
+}
+
+impl Temperature {
+    /// Create a temperature from a raw API value and the unit system it was requested in.
+    pub fn new(value: f64, units: UnitType) -> Self {
+        Self { value, units }
+    }
+
+    /// The raw value in the unit system it was created with.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// The unit system of the raw value.
+    pub fn units(&self) -> UnitType {
+        self.units
+    }
+
+    /// Temperature in degrees Celsius.
+    pub fn to_celsius(&self) -> f64 {
+        match self.units {
+            UnitType::Dwd => self.value,
+            UnitType::Si => self.value - KELVIN_OFFSET,
+        }
+    }
+
+    /// Temperature in Kelvin.
+    pub fn to_kelvin(&self) -> f64 {
+        match self.units {
+            UnitType::Dwd => self.value + KELVIN_OFFSET,
+            UnitType::Si => self.value,
+        }
+    }
+
+    /// Temperature in degrees Fahrenheit.
+    pub fn to_fahrenheit(&self) -> f64 {
+        self.to_celsius() * 9.0 / 5.0 + 32.0
+    }
+
+    /// Convert to the given unit system.
+    pub fn convert(&self, units: UnitType) -> Self {
+        let value = match units {
+            UnitType::Dwd => self.to_celsius(),
+            UnitType::Si => self.to_kelvin(),
+        };
+        Self { value, units }
+    }
+}
+
+impl core::fmt::Display for Temperature {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.units {
+            UnitType::Dwd => write!(f, "{} °C", self.value),
+            UnitType::Si => write!(f, "{} K", self.value),
+        }
+    }
+}
+
+/// An atmospheric pressure (hPa for `UnitType::Dwd`, Pa for `UnitType::Si`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pressure {
+    value: f64,
+    units: UnitType,
+}
+
+impl Pressure {
+    /// Create a pressure from a raw API value and the unit system it was requested in.
+    pub fn new(value: f64, units: UnitType) -> Self {
+        Self { value, units }
+    }
+
+    /// The raw value in the unit system it was created with.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// The unit system of the raw value.
+    pub fn units(&self) -> UnitType {
+        self.units
+    }
+
+    /// Pressure in hectopascal.
+    pub fn to_hpa(&self) -> f64 {
+        match self.units {
+            UnitType::Dwd => self.value,
+            UnitType::Si => self.value / 100.0,
+        }
+    }
+
+    /// Pressure in pascal.
+    pub fn to_pa(&self) -> f64 {
+        match self.units {
+            UnitType::Dwd => self.value * 100.0,
+            UnitType::Si => self.value,
+        }
+    }
+
+    /// Convert to the given unit system.
+    pub fn convert(&self, units: UnitType) -> Self {
+        let value = match units {
+            UnitType::Dwd => self.to_hpa(),
+            UnitType::Si => self.to_pa(),
+        };
+        Self { value, units }
+    }
+}
+
+impl core::fmt::Display for Pressure {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.units {
+            UnitType::Dwd => write!(f, "{} hPa", self.value),
+            UnitType::Si => write!(f, "{} Pa", self.value),
+        }
+    }
+}
+
+/// A wind or gust speed (km/h for `UnitType::Dwd`, m/s for `UnitType::Si`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindSpeed {
+    value: f64,
+    units: UnitType,
+}
+
+impl WindSpeed {
+    /// Create a wind speed from a raw API value and the unit system it was requested in.
+    pub fn new(value: f64, units: UnitType) -> Self {
+        Self { value, units }
+    }
+
+    /// The raw value in the unit system it was created with.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// The unit system of the raw value.
+    pub fn units(&self) -> UnitType {
+        self.units
+    }
+
+    /// Wind speed in meters per second.
+    pub fn to_ms(&self) -> f64 {
+        match self.units {
+            UnitType::Dwd => self.value / 3.6,
+            UnitType::Si => self.value,
+        }
+    }
+
+    /// Wind speed in kilometers per hour.
+    pub fn to_kmh(&self) -> f64 {
+        match self.units {
+            UnitType::Dwd => self.value,
+            UnitType::Si => self.value * 3.6,
+        }
+    }
+
+    /// Convert to the given unit system.
+    pub fn convert(&self, units: UnitType) -> Self {
+        let value = match units {
+            UnitType::Dwd => self.to_kmh(),
+            UnitType::Si => self.to_ms(),
+        };
+        Self { value, units }
+    }
+}
+
+impl core::fmt::Display for WindSpeed {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.units {
+            UnitType::Dwd => write!(f, "{} km/h", self.value),
+            UnitType::Si => write!(f, "{} m/s", self.value),
+        }
+    }
+}
+
+impl Weather {
+    /// Air temperature as a unit-aware value, given the unit system of the query.
+    pub fn typed_temperature(&self, units: UnitType) -> Option<Temperature> {
+        self.temperature.map(|v| Temperature::new(v, units))
+    }
+
+    /// Dew point as a unit-aware value, given the unit system of the query.
+    pub fn typed_dew_point(&self, units: UnitType) -> Option<Temperature> {
+        self.dew_point.map(|v| Temperature::new(v, units))
+    }
+
+    /// Mean sea level pressure as a unit-aware value, given the unit system of the query.
+    pub fn typed_pressure_msl(&self, units: UnitType) -> Option<Pressure> {
+        self.pressure_msl.map(|v| Pressure::new(v, units))
+    }
+
+    /// Mean wind speed as a unit-aware value, given the unit system of the query.
+    pub fn typed_wind_speed(&self, units: UnitType) -> Option<WindSpeed> {
+        self.wind_speed.map(|v| WindSpeed::new(v, units))
+    }
+
+    /// Maximum wind gust speed as a unit-aware value, given the unit system of the query.
+    pub fn typed_wind_gust_speed(&self, units: UnitType) -> Option<WindSpeed> {
+        self.wind_gust_speed.map(|v| WindSpeed::new(v, units))
+    }
+}
+
+impl CurrentWeather {
+    /// Air temperature as a unit-aware value, given the unit system of the query.
+    pub fn typed_temperature(&self, units: UnitType) -> Option<Temperature> {
+        self.temperature.map(|v| Temperature::new(v, units))
+    }
+
+    /// Dew point as a unit-aware value, given the unit system of the query.
+    pub fn typed_dew_point(&self, units: UnitType) -> Option<Temperature> {
+        self.dew_point.map(|v| Temperature::new(v, units))
+    }
+
+    /// Mean sea level pressure as a unit-aware value, given the unit system of the query.
+    pub fn typed_pressure_msl(&self, units: UnitType) -> Option<Pressure> {
+        self.pressure_msl.map(|v| Pressure::new(v, units))
+    }
+
+    /// Mean wind speed during the previous 10 minutes as a unit-aware value.
+    pub fn typed_wind_speed_10(&self, units: UnitType) -> Option<WindSpeed> {
+        self.wind_speed_10.map(|v| WindSpeed::new(v, units))
+    }
+
+    /// Mean wind speed during the previous 30 minutes as a unit-aware value.
+    pub fn typed_wind_speed_30(&self, units: UnitType) -> Option<WindSpeed> {
+        self.wind_speed_30.map(|v| WindSpeed::new(v, units))
+    }
+
+    /// Mean wind speed during the previous 60 minutes as a unit-aware value.
+    pub fn typed_wind_speed_60(&self, units: UnitType) -> Option<WindSpeed> {
+        self.wind_speed_60.map(|v| WindSpeed::new(v, units))
+    }
+}
+
+/// Convert a value between the unit systems of a quantity, given its value in
+/// the SI system per `UnitType::Dwd` unit.
+fn convert(value: &mut Option<f64>, si_per_dwd: f64, from: UnitType, to: UnitType) {
+    if let Some(value) = value {
+        match (from, to) {
+            (UnitType::Dwd, UnitType::Si) => *value *= si_per_dwd,
+            (UnitType::Si, UnitType::Dwd) => *value /= si_per_dwd,
+            _ => {}
+        }
+    }
+}
+
+fn convert_temperature(value: &mut Option<f64>, from: UnitType, to: UnitType) {
+    *value = value.map(|v| Temperature::new(v, from).convert(to).value());
+}
+
+fn convert_pressure(value: &mut Option<f64>, from: UnitType, to: UnitType) {
+    *value = value.map(|v| Pressure::new(v, from).convert(to).value());
+}
+
+fn convert_wind_speed(value: &mut Option<f64>, from: UnitType, to: UnitType) {
+    *value = value.map(|v| WindSpeed::new(v, from).convert(to).value());
+}
+
+fn convert_solar(value: &mut Option<f64>, from: UnitType, to: UnitType) {
+    convert(value, JOULES_PER_KWH, from, to);
+}
+
+fn convert_sunshine(value: &mut Option<f64>, from: UnitType, to: UnitType) {
+    convert(value, 60.0, from, to);
+}
+
+impl Weather {
+    /// Rewrite every unit-dependent value from the unit system of the query,
+    /// `from`, to `to`.
+    ///
+    /// Temperatures (°C ↔ K), pressure (hPa ↔ Pa), wind speeds (km/h ↔ m/s),
+    /// solar irradiation (kWh/m² ↔ J/m²) and sunshine (minutes ↔ seconds) are
+    /// converted, all other values are the same in both systems. Converted
+    /// values are not rounded.
+    pub fn convert_units(&mut self, from: UnitType, to: UnitType) {
+        convert_temperature(&mut self.temperature, from, to);
+        convert_temperature(&mut self.dew_point, from, to);
+        convert_pressure(&mut self.pressure_msl, from, to);
+        convert_wind_speed(&mut self.wind_speed, from, to);
+        convert_wind_speed(&mut self.wind_gust_speed, from, to);
+        convert_solar(&mut self.solar, from, to);
+        convert_sunshine(&mut self.sunshine, from, to);
+    }
+}
+
+impl CurrentWeather {
+    /// Rewrite every unit-dependent value from the unit system of the query,
+    /// `from`, to `to`, see [`Weather::convert_units`].
+    pub fn convert_units(&mut self, from: UnitType, to: UnitType) {
+        convert_temperature(&mut self.temperature, from, to);
+        convert_temperature(&mut self.dew_point, from, to);
+        convert_pressure(&mut self.pressure_msl, from, to);
+        for speed in [
+            &mut self.wind_speed_10,
+            &mut self.wind_speed_30,
+            &mut self.wind_speed_60,
+            &mut self.wind_gust_speed_10,
+            &mut self.wind_gust_speed_30,
+            &mut self.wind_gust_speed_60,
+        ] {
+            convert_wind_speed(speed, from, to);
+        }
+        for solar in [&mut self.solar_10, &mut self.solar_30, &mut self.solar_60] {
+            convert_solar(solar, from, to);
+        }
+        convert_sunshine(&mut self.sunshine_30, from, to);
+        convert_sunshine(&mut self.sunshine_60, from, to);
+    }
+}
+
+impl WeatherResponse {
+    /// Convert every record from the unit system of the query, `from`, to `to`,
+    /// e.g. to store responses of differently configured queries together.
+    ///
+    /// ```rust
+    /// use brightsky::types::{UnitType, WeatherResponse};
+    ///
+    /// let mut response: WeatherResponse = serde_json::from_str(r#"{
+    ///     "weather": [{
+    ///         "timestamp": "2023-08-07T12:00:00+00:00",
+    ///         "source_id": 1,
+    ///         "pressure_msl": 101325.0,
+    ///         "wind_speed": 5.0,
+    ///         "sunshine": 1800.0
+    ///     }],
+    ///     "sources": []
+    /// }"#).unwrap();
+    ///
+    /// response.convert_units(UnitType::Si, UnitType::Dwd);
+    /// let record = &response.weather[0];
+    /// assert_eq!(record.pressure_msl, Some(1013.25));
+    /// assert_eq!(record.wind_speed, Some(18.0));
+    /// assert_eq!(record.sunshine, Some(30.0));
+    /// ```
+    pub fn convert_units(&mut self, from: UnitType, to: UnitType) {
+        for record in &mut self.weather {
+            record.convert_units(from, to);
+        }
+    }
+}
+
+impl CurrentWeatherResponse {
+    /// Convert the record from the unit system of the query, `from`, to `to`.
+    pub fn convert_units(&mut self, from: UnitType, to: UnitType) {
+        self.weather.convert_units(from, to);
+    }
+}
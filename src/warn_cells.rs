@@ -0,0 +1,254 @@
+//! Offline lookup of DWD warn cells by coordinates.
+//!
+//! Alerts are issued for municipality warn cells. Subscribing by cell ID
+//! usually takes an `/alerts` query with `lat`/`lon` first, just to learn the
+//! ID from its `location`. [`WarnCells`] answers that locally from DWD's
+//! warn cell geometry, a GeoJSON `FeatureCollection` of `Polygon` and
+//! `MultiPolygon` features with a `WARNCELLID` property, as DWD's GeoServer
+//! returns it for the municipality warn cells (WFS with
+//! `outputFormat=application/json`). The geometry is large and changes with
+//! municipal reforms, so it is not bundled with this crate; load it once at
+//! startup:
+//!
+//! ```rust
+//! use brightsky::warn_cells::WarnCells;
+//!
+//! let geojson = br#"{
+//!     "type": "FeatureCollection",
+//!     "features": [{
+//!         "type": "Feature",
+//!         "properties": {"WARNCELLID": 811000000, "NAME": "Stadt Stuttgart"},
+//!         "geometry": {
+//!             "type": "Polygon",
+//!             "coordinates": [[[9.0, 48.7], [9.3, 48.7], [9.3, 48.9], [9.0, 48.9], [9.0, 48.7]]]
+//!         }
+//!     }]
+//! }"#;
+//!
+//! let cells = WarnCells::from_slice(geojson).unwrap();
+//! assert_eq!(cells.warn_cell_id_for((48.78, 9.18)), Some(811000000));
+//! assert_eq!(cells.warn_cell_id_for((52.52, 13.4)), None);
+//! ```
+//!
+//! Coordinates are compared in plain longitude/latitude, which is exact
+//! enough at the scale of municipalities. Points on a shared border belong to
+//! either cell.
+//!
+//! Only available with the `warn-cells` feature.
+
+#[cfg(not(feature = "std"))]
+use alloc::{collections::BTreeMap, string::String, vec, vec::Vec};
+
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, de::Error as _};
+use serde_json::Value;
+
+use crate::{AlertsQueryBuilder, Coordinates};
+
+/// Property names of the warn cell ID, compared case-insensitively.
+const ID_PROPERTIES: &[&str] = &["WARNCELLID", "warn_cell_id"];
+
+/// Property names of the municipality name, compared case-insensitively.
+const NAME_PROPERTIES: &[&str] = &["NAME", "name"];
+
+/// Warn cell geometry, see the [`warn_cells`](crate::warn_cells) module.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WarnCells {
+    cells: Vec<WarnCell>,
+}
+
+/// One warn cell with its outline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WarnCell {
+    /// Warn cell ID, as used by `AlertsQueryBuilder::with_warn_cell_id`
+    pub id: i64,
+    /// Municipality name, if the geometry has one
+    pub name: Option<String>,
+    /// Bounding box as `[min_lon, min_lat, max_lon, max_lat]`
+    pub bbox: [f64; 4],
+    /// Polygons as lists of rings of `(lon, lat)` positions; the first ring of
+    /// each polygon is its outline, the others are holes
+    pub polygons: Vec<Vec<Vec<(f64, f64)>>>,
+}
+
+impl WarnCells {
+    /// Load warn cells from a GeoJSON `FeatureCollection`.
+    ///
+    /// Features without geometry are skipped. A feature without a
+    /// `WARNCELLID` property is an error.
+    pub fn from_slice(geojson: &[u8]) -> Result<Self, serde_json::Error> {
+        let collection: FeatureCollection = serde_json::from_slice(geojson)?;
+        let mut cells = Vec::with_capacity(collection.features.len());
+        for feature in collection.features {
+            let Some(geometry) = feature.geometry else {
+                continue;
+            };
+            let properties = feature.properties.unwrap_or_default();
+            let id = property(&properties, ID_PROPERTIES)
+                .and_then(|id| match id {
+                    Value::Number(id) => id.as_i64(),
+                    Value::String(id) => id.parse().ok(),
+                    _ => None,
+                })
+                .ok_or_else(|| serde_json::Error::custom("feature without a WARNCELLID"))?;
+            let name = property(&properties, NAME_PROPERTIES)
+                .and_then(Value::as_str)
+                .map(String::from);
+            let polygons: Vec<Vec<Vec<(f64, f64)>>> = match geometry {
+                Geometry::Polygon { coordinates } => vec![rings(coordinates)],
+                Geometry::MultiPolygon { coordinates } => {
+                    coordinates.into_iter().map(rings).collect()
+                }
+                Geometry::Other => continue,
+            };
+            cells.push(WarnCell {
+                id,
+                name,
+                bbox: bbox(&polygons),
+                polygons,
+            });
+        }
+        Ok(Self { cells })
+    }
+
+    /// ID of the warn cell containing `coordinates`.
+    pub fn warn_cell_id_for(&self, coordinates: impl TryInto<Coordinates>) -> Option<i64> {
+        self.cell_for(coordinates).map(|cell| cell.id)
+    }
+
+    /// The warn cell containing `coordinates`.
+    pub fn cell_for(&self, coordinates: impl TryInto<Coordinates>) -> Option<&WarnCell> {
+        let Coordinates { lat, lon } = coordinates.try_into().ok()?;
+        self.cells.iter().find(|cell| cell.contains(lon, lat))
+    }
+
+    /// An alerts query for the warn cell containing `coordinates`.
+    pub fn alerts_query_for(
+        &self,
+        coordinates: impl TryInto<Coordinates>,
+    ) -> Option<AlertsQueryBuilder> {
+        self.warn_cell_id_for(coordinates)
+            .map(|id| AlertsQueryBuilder::new().with_warn_cell_id(id))
+    }
+
+    /// The warn cell with the given ID.
+    pub fn get(&self, id: i64) -> Option<&WarnCell> {
+        self.cells.iter().find(|cell| cell.id == id)
+    }
+
+    /// All warn cells, in the order of the GeoJSON features.
+    pub fn cells(&self) -> &[WarnCell] {
+        &self.cells
+    }
+
+    /// Number of warn cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Whether no warn cells were loaded.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+}
+
+impl WarnCell {
+    /// Whether the position lies inside the outline of one of the polygons
+    /// and outside of its holes.
+    pub fn contains(&self, lon: f64, lat: f64) -> bool {
+        let [min_lon, min_lat, max_lon, max_lat] = self.bbox;
+        if lon < min_lon || lon > max_lon || lat < min_lat || lat > max_lat {
+            return false;
+        }
+        self.polygons.iter().any(|polygon| {
+            let mut rings = polygon.iter();
+            rings
+                .next()
+                .is_some_and(|outline| in_ring(outline, lon, lat))
+                && !rings.any(|hole| in_ring(hole, lon, lat))
+        })
+    }
+}
+
+/// Even-odd ray casting test.
+fn in_ring(ring: &[(f64, f64)], lon: f64, lat: f64) -> bool {
+    let mut inside = false;
+    let mut previous = match ring.last() {
+        Some(&position) => position,
+        None => return false,
+    };
+    for &(x, y) in ring {
+        let (px, py) = previous;
+        if (y > lat) != (py > lat) && lon < (px - x) * (lat - y) / (py - y) + x {
+            inside = !inside;
+        }
+        previous = (x, y);
+    }
+    inside
+}
+
+fn rings(coordinates: Vec<Vec<Vec<f64>>>) -> Vec<Vec<(f64, f64)>> {
+    coordinates
+        .into_iter()
+        .map(|ring| {
+            ring.into_iter()
+                .filter_map(|position| Some((*position.first()?, *position.get(1)?)))
+                .collect()
+        })
+        .collect()
+}
+
+fn bbox(polygons: &[Vec<Vec<(f64, f64)>>]) -> [f64; 4] {
+    let mut bbox = [
+        f64::INFINITY,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        f64::NEG_INFINITY,
+    ];
+    for &(lon, lat) in polygons
+        .iter()
+        .flat_map(|polygon| polygon.first())
+        .flatten()
+    {
+        bbox = [
+            bbox[0].min(lon),
+            bbox[1].min(lat),
+            bbox[2].max(lon),
+            bbox[3].max(lat),
+        ];
+    }
+    bbox
+}
+
+fn property<'a>(properties: &'a BTreeMap<String, Value>, names: &[&str]) -> Option<&'a Value> {
+    properties
+        .iter()
+        .find(|(key, _)| names.iter().any(|name| key.eq_ignore_ascii_case(name)))
+        .map(|(_, value)| value)
+}
+
+#[derive(Deserialize)]
+struct FeatureCollection {
+    features: Vec<Feature>,
+}
+
+#[derive(Deserialize)]
+struct Feature {
+    properties: Option<BTreeMap<String, Value>>,
+    geometry: Option<Geometry>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum Geometry {
+    Polygon {
+        coordinates: Vec<Vec<Vec<f64>>>,
+    },
+    MultiPolygon {
+        coordinates: Vec<Vec<Vec<Vec<f64>>>>,
+    },
+    #[serde(other)]
+    Other,
+}
@@ -78,3 +78,6 @@
 
 mod query_builder;
 pub use query_builder::*;
+
+mod records;
+pub use records::WeatherRecords;
@@ -4,12 +4,30 @@ extern crate alloc;
 #[cfg(not(feature = "std"))]
 use alloc::{format, string::String, string::ToString, vec::Vec};
 
-use crate::{BrightSkyError, ToBrightSkyUrl, types::UnitType};
-use chrono::NaiveDate;
+use core::ops::Deref;
+
+use crate::{
+    BrightSkyError, DEFAULT_COORDINATE_PRECISION, QueryError, ToBrightSkyUrl, TzName,
+    coordinates::{
+        CoordinateFuzzing, QueryLocation, check_coordinates, check_location, check_station_ids,
+        has_ids, url_coordinates,
+    },
+    types::{QueryDate, UnitType},
+    tz::check_tz,
+};
+use chrono::{DateTime, FixedOffset, NaiveDate};
 
 #[cfg(feature = "std")]
 use url::Url;
 
+/// First day with weather records in Bright Sky.
+pub const DATA_START: NaiveDate = NaiveDate::from_ymd_opt(2010, 1, 1).expect("valid date");
+
+/// Longest span between `date` and `last_date` the `/weather` endpoint
+/// accepts, in days. Use [`BrightSkyClient::get_history`](crate::BrightSkyClient::get_history)
+/// for longer ranges.
+pub const MAX_DATE_RANGE_DAYS: i64 = 90;
+
 /// Query builder for the weather endpoint (`/weather`).
 ///
 /// This builder constructs queries to retrieve hourly weather records and/or forecasts
@@ -58,22 +76,26 @@ use url::Url;
 ///     Ok(())
 /// }
 /// ```
-#[derive(Debug)]
-pub struct WeatherQueryBuilder<'a> {
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeatherQueryBuilder {
     /// First timestamp to retrieve (required)
-    pub date: Option<NaiveDate>,
+    pub date: Option<QueryDate>,
     /// Last timestamp to retrieve (defaults to date + 1 day)
-    pub last_date: Option<NaiveDate>,
+    pub last_date: Option<QueryDate>,
     /// Latitude in decimal degrees (-90.0 to 90.0)
-    pub lat: Option<String>,
+    pub lat: Option<f64>,
     /// Longitude in decimal degrees (-180.0 to 180.0)
-    pub lon: Option<String>,
+    pub lon: Option<f64>,
+    /// Number of decimal places used for `lat` and `lon` in URLs
+    pub coordinate_precision: u8,
+    /// Privacy grid applied to `lat` and `lon`, if any
+    pub coordinate_fuzzing: Option<CoordinateFuzzing>,
     /// Maximum distance from lat/lon in meters (0 to 500,000)
     pub max_dist: Option<String>,
     /// DWD station IDs (5 alphanumeric characters each)
-    pub dwd_station_id: Option<Vec<&'a str>>,
+    pub dwd_station_id: Option<Vec<String>>,
     /// WMO station IDs (5 alphanumeric characters each)
-    pub wmo_station_id: Option<Vec<&'a str>>,
+    pub wmo_station_id: Option<Vec<String>>,
     /// Bright Sky source IDs
     pub source_id: Option<Vec<String>>,
     /// Timezone for timestamp presentation (tz database format)
@@ -82,7 +104,7 @@ pub struct WeatherQueryBuilder<'a> {
     pub units: Option<UnitType>,
 }
 
-impl<'a> WeatherQueryBuilder<'a> {
+impl WeatherQueryBuilder {
     /// Create a new weather query builder.
     ///
     /// Returns a builder with no parameters set. You must set a date and location
@@ -93,6 +115,8 @@ impl<'a> WeatherQueryBuilder<'a> {
             last_date: None,
             lat: None,
             lon: None,
+            coordinate_precision: DEFAULT_COORDINATE_PRECISION,
+            coordinate_fuzzing: None,
             max_dist: None,
             dwd_station_id: None,
             wmo_station_id: None,
@@ -121,7 +145,7 @@ impl<'a> WeatherQueryBuilder<'a> {
     ///     .with_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap());
     /// ```
     pub fn with_date(mut self, date: NaiveDate) -> Self {
-        self.date = Some(date);
+        self.date = Some(date.into());
         self
     }
 
@@ -145,7 +169,40 @@ impl<'a> WeatherQueryBuilder<'a> {
     ///     .with_last_date(NaiveDate::from_ymd_opt(2023, 8, 10).unwrap());  // 3-day range
     /// ```
     pub fn with_last_date(mut self, last_date: NaiveDate) -> Self {
-        self.last_date = Some(last_date);
+        self.last_date = Some(last_date.into());
+        self
+    }
+
+    /// Set the first timestamp to retrieve, including time of day and UTC offset.
+    ///
+    /// Unlike [`with_date`](Self::with_date), this can request records starting
+    /// at a specific hour.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brightsky::WeatherQueryBuilder;
+    /// use chrono::DateTime;
+    ///
+    /// let query = WeatherQueryBuilder::new()
+    ///     .with_datetime(DateTime::parse_from_rfc3339("2023-08-07T14:00:00+02:00").unwrap())
+    ///     .with_lat_lon((52.52, 13.4))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn with_datetime(mut self, datetime: impl Into<DateTime<FixedOffset>>) -> Self {
+        self.date = Some(QueryDate::DateTime(datetime.into()));
+        self
+    }
+
+    /// Set the first and last timestamp to retrieve.
+    pub fn with_datetime_range(
+        mut self,
+        datetime: impl Into<DateTime<FixedOffset>>,
+        last_datetime: impl Into<DateTime<FixedOffset>>,
+    ) -> Self {
+        self.date = Some(QueryDate::DateTime(datetime.into()));
+        self.last_date = Some(QueryDate::DateTime(last_datetime.into()));
         self
     }
 
@@ -153,7 +210,8 @@ impl<'a> WeatherQueryBuilder<'a> {
     ///
     /// # Parameters
     ///
-    /// * `lat_lon` - Tuple of (latitude, longitude) in decimal degrees
+    /// * `lat_lon` - Tuple of (latitude, longitude) in decimal degrees, or
+    ///   [`Coordinates`](crate::Coordinates)
     ///
     /// # Constraints
     ///
@@ -170,24 +228,26 @@ impl<'a> WeatherQueryBuilder<'a> {
     ///     .with_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap())
     ///     .with_lat_lon((52.52, 13.4));  // Berlin coordinates
     /// ```
-    pub fn with_lat_lon(mut self, lat_lon: (f64, f64)) -> Self {
-        // Format coordinates preserving all decimal precision
-        // For whole numbers, ensure at least one decimal place is shown
-        let lat_str = format!("{}", lat_lon.0);
-        let lon_str = format!("{}", lat_lon.1);
-
-        self.lat = Some(if !lat_str.contains('.') {
-            format!("{}.0", lat_str)
-        } else {
-            lat_str
-        });
-
-        self.lon = Some(if !lon_str.contains('.') {
-            format!("{}.0", lon_str)
-        } else {
-            lon_str
-        });
+    pub fn with_lat_lon(mut self, lat_lon: impl Into<(f64, f64)>) -> Self {
+        let (lat, lon) = lat_lon.into();
+        self.lat = Some(lat);
+        self.lon = Some(lon);
+        self
+    }
+
+    /// Set the number of decimal places used for `lat` and `lon` in the URL,
+    /// see [`DEFAULT_COORDINATE_PRECISION`].
+    pub fn with_coordinate_precision(mut self, decimals: u8) -> Self {
+        self.coordinate_precision = decimals;
+        self
+    }
 
+    /// Move `lat` and `lon` onto a coarse grid before they are sent to the API.
+    ///
+    /// Applies to coordinates set before or after this call. See
+    /// [`CoordinateFuzzing`] for the available strategies.
+    pub fn with_coordinate_fuzzing(mut self, fuzzing: CoordinateFuzzing) -> Self {
+        self.coordinate_fuzzing = Some(fuzzing);
         self
     }
 
@@ -223,7 +283,7 @@ impl<'a> WeatherQueryBuilder<'a> {
     ///
     /// # Parameters
     ///
-    /// * `ids` - Vector of DWD station ID string references
+    /// * `ids` - DWD station IDs (`&str` or `String`), e.g. a `Vec` or array
     ///
     /// # Examples
     ///
@@ -235,8 +295,28 @@ impl<'a> WeatherQueryBuilder<'a> {
     ///     .with_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap())
     ///     .with_dwd_station_id(vec!["01766", "00420"]);  // Multiple stations
     /// ```
-    pub fn with_dwd_station_id(mut self, ids: Vec<&'a str>) -> Self {
-        self.dwd_station_id = Some(ids);
+    pub fn with_dwd_station_id(mut self, ids: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.dwd_station_id = Some(ids.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Add a single DWD station ID after any already set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brightsky::WeatherQueryBuilder;
+    /// use chrono::NaiveDate;
+    ///
+    /// let query = WeatherQueryBuilder::new()
+    ///     .with_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap())
+    ///     .with_dwd_station("01766")
+    ///     .with_dwd_station("00420");  // Fallback station
+    /// ```
+    pub fn with_dwd_station(mut self, id: impl Into<String>) -> Self {
+        self.dwd_station_id
+            .get_or_insert_with(Vec::new)
+            .push(id.into());
         self
     }
 
@@ -247,7 +327,7 @@ impl<'a> WeatherQueryBuilder<'a> {
     ///
     /// # Parameters
     ///
-    /// * `ids` - Vector of WMO station ID string references
+    /// * `ids` - WMO station IDs (`&str` or `String`), e.g. a `Vec` or array
     ///
     /// # Examples
     ///
@@ -259,8 +339,28 @@ impl<'a> WeatherQueryBuilder<'a> {
     ///     .with_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap())
     ///     .with_wmo_station_id(vec!["10315"]);
     /// ```
-    pub fn with_wmo_station_id(mut self, ids: Vec<&'a str>) -> Self {
-        self.wmo_station_id = Some(ids);
+    pub fn with_wmo_station_id(mut self, ids: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.wmo_station_id = Some(ids.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Add a single WMO station ID after any already set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brightsky::WeatherQueryBuilder;
+    /// use chrono::NaiveDate;
+    ///
+    /// let query = WeatherQueryBuilder::new()
+    ///     .with_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap())
+    ///     .with_wmo_station("10315")
+    ///     .with_wmo_station("10147");  // Fallback station
+    /// ```
+    pub fn with_wmo_station(mut self, id: impl Into<String>) -> Self {
+        self.wmo_station_id
+            .get_or_insert_with(Vec::new)
+            .push(id.into());
         self
     }
 
@@ -271,7 +371,7 @@ impl<'a> WeatherQueryBuilder<'a> {
     ///
     /// # Parameters
     ///
-    /// * `ids` - Vector of Bright Sky source IDs
+    /// * `ids` - Bright Sky source IDs, e.g. a `Vec` or array
     ///
     /// # Examples
     ///
@@ -283,11 +383,52 @@ impl<'a> WeatherQueryBuilder<'a> {
     ///     .with_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap())
     ///     .with_source_id(vec![1234, 2345]);
     /// ```
-    pub fn with_source_id(mut self, ids: Vec<i64>) -> Self {
+    pub fn with_source_id(mut self, ids: impl IntoIterator<Item = i64>) -> Self {
         self.source_id = Some(ids.into_iter().map(|id| id.to_string()).collect());
         self
     }
 
+    /// Add a single Bright Sky source ID after any already set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brightsky::WeatherQueryBuilder;
+    /// use chrono::NaiveDate;
+    ///
+    /// let query = WeatherQueryBuilder::new()
+    ///     .with_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap())
+    ///     .with_source(1234);
+    /// ```
+    pub fn with_source(mut self, id: i64) -> Self {
+        self.source_id
+            .get_or_insert_with(Vec::new)
+            .push(id.to_string());
+        self
+    }
+
+    /// Set the location from a [`QueryLocation`], replacing any location set before.
+    ///
+    /// Coordinates are formatted like with [`with_lat_lon`](Self::with_lat_lon),
+    /// so coordinate precision and fuzzing apply.
+    pub fn with_location(mut self, location: impl Into<QueryLocation>) -> Self {
+        self.lat = None;
+        self.lon = None;
+        self.max_dist = None;
+        self.dwd_station_id = None;
+        self.wmo_station_id = None;
+        self.source_id = None;
+        match location.into() {
+            QueryLocation::LatLon { lat, lon, max_dist } => {
+                self.max_dist = max_dist.map(|max_dist| max_dist.to_string());
+                self.with_lat_lon((lat, lon))
+            }
+            QueryLocation::DwdStations(ids) => self.with_dwd_station_id(ids),
+            QueryLocation::WmoStations(ids) => self.with_wmo_station_id(ids),
+            QueryLocation::Sources(ids) => self.with_source_id(ids),
+        }
+    }
+
     /// Set the timezone for timestamp presentation.
     ///
     /// Timestamps in the response will be presented in this timezone.
@@ -296,7 +437,8 @@ impl<'a> WeatherQueryBuilder<'a> {
     ///
     /// # Parameters
     ///
-    /// * `tz` - Timezone name from the tz database
+    /// * `tz` - Timezone name from the tz database, or a `chrono_tz::Tz` with the
+    ///   `chrono-tz` feature
     ///
     /// # Examples
     ///
@@ -309,8 +451,8 @@ impl<'a> WeatherQueryBuilder<'a> {
     ///     .with_lat_lon((52.52, 13.4))
     ///     .with_tz("Europe/Berlin");
     /// ```
-    pub fn with_tz(mut self, tz: &str) -> Self {
-        self.tz = Some(tz.to_string());
+    pub fn with_tz(mut self, tz: impl TzName) -> Self {
+        self.tz = Some(tz.tz_name().into());
         self
     }
 
@@ -348,9 +490,16 @@ impl<'a> WeatherQueryBuilder<'a> {
     /// # Errors
     ///
     /// - `DateNotSet` - No date was provided (required parameter)
+    /// - `InvalidDateRange` - `last_date` is earlier than `date`
+    /// - `DateBeforeDataStart` - The range starts before [`DATA_START`]
+    /// - `RangeTooLong` - The range spans more than [`MAX_DATE_RANGE_DAYS`] days
     /// - `InvalidLatitude`/`InvalidLongitude` - Coordinates out of valid range
     /// - `InvalidMaxDistance` - Distance greater than 500,000 meters
     /// - `ParseFloatError`/`ParseIntError` - Invalid numeric values
+    /// - `InvalidStationId` - A DWD or WMO station ID is not 5 alphanumeric characters
+    /// - `MissingLocation` - Neither coordinates nor station or source IDs were set
+    /// - `ConflictingLocationParameters` - More than one of coordinates, DWD
+    ///   station IDs, WMO station IDs and source IDs were set
     ///
     /// # Examples
     ///
@@ -367,93 +516,138 @@ impl<'a> WeatherQueryBuilder<'a> {
     ///     Ok(())
     /// }
     /// ```
-    pub fn build(self) -> Result<Self, BrightSkyError> {
-        if self.date.is_none() {
-            return Err(BrightSkyError::DateNotSet);
-        }
-        if let Some(lat_str) = &self.lat {
-            lat_str
-                .parse::<f64>()
-                .map_err(BrightSkyError::ParseFloatError)
-                .and_then(|lat| -> Result<(), BrightSkyError> {
-                    if !(-90.0..=90.0).contains(&lat) {
-                        Err(BrightSkyError::InvalidLongitude(lat))
-                    } else {
-                        Ok(())
-                    }
-                })?;
-        }
-        if let Some(lon_str) = &self.lon {
-            lon_str
-                .parse::<f64>()
-                .map_err(BrightSkyError::ParseFloatError)
-                .and_then(|lon| -> Result<(), BrightSkyError> {
-                    if !(-180.0..=180.0).contains(&lon) {
-                        Err(BrightSkyError::InvalidLongitude(lon))
-                    } else {
-                        Ok(())
-                    }
-                })?;
-        }
+    pub fn build(self) -> Result<WeatherQuery, BrightSkyError> {
+        let Some(date) = self.date else {
+            return Err(QueryError::DateNotSet.into());
+        };
+        check_date_range(date, self.last_date)?;
+        check_coordinates(self.lat, self.lon)?;
+        check_tz(self.tz.as_deref())?;
         if let Some(max_dist_str) = &self.max_dist {
             max_dist_str
                 .parse::<u32>()
-                .map_err(BrightSkyError::ParseIntError)
+                .map_err(QueryError::ParseIntError)
                 .and_then(|max_dist| {
                     if max_dist > 500000 {
-                        Err(BrightSkyError::InvalidMaxDistance(max_dist))
+                        Err(QueryError::InvalidMaxDistance(max_dist))
                     } else {
                         Ok(())
                     }
                 })?;
         }
 
-        Ok(self)
+        check_station_ids(self.dwd_station_id.as_deref())?;
+        check_station_ids(self.wmo_station_id.as_deref())?;
+        check_location(
+            &[
+                ("lat/lon", self.lat.is_some() && self.lon.is_some()),
+                ("dwd_station_id", has_ids(self.dwd_station_id.as_deref())),
+                ("wmo_station_id", has_ids(self.wmo_station_id.as_deref())),
+                ("source_id", has_ids(self.source_id.as_deref())),
+            ],
+            true,
+        )?;
+
+        Ok(WeatherQuery { builder: self })
+    }
+}
+
+/// Check the order, start and length of a date range.
+fn check_date_range(date: QueryDate, last_date: Option<QueryDate>) -> Result<(), QueryError> {
+    if date.date_naive() < DATA_START {
+        return Err(QueryError::DateBeforeDataStart(date.date_naive()));
+    }
+    let Some(last_date) = last_date else {
+        return Ok(());
+    };
+    let reversed = match (date, last_date) {
+        (QueryDate::DateTime(date), QueryDate::DateTime(last_date)) => last_date < date,
+        _ => last_date.date_naive() < date.date_naive(),
+    };
+    if reversed {
+        return Err(QueryError::InvalidDateRange { date, last_date });
+    }
+    let days = (last_date.date_naive() - date.date_naive()).num_days();
+    if days > MAX_DATE_RANGE_DAYS {
+        return Err(QueryError::RangeTooLong(days));
+    }
+    Ok(())
+}
+
+/// A validated `/weather` query, returned by [`WeatherQueryBuilder::build`].
+///
+/// Only validated queries implement [`ToBrightSkyUrl`] and can be sent with a
+/// client. The parameters can be read through [`Deref`] to the builder; use
+/// [`into_builder`](Self::into_builder) to change them and build again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeatherQuery {
+    pub(crate) builder: WeatherQueryBuilder,
+}
+
+impl WeatherQuery {
+    /// Turn the query back into a builder, e.g. to change a parameter.
+    pub fn into_builder(self) -> WeatherQueryBuilder {
+        self.builder
+    }
+}
+
+impl Deref for WeatherQuery {
+    type Target = WeatherQueryBuilder;
+
+    fn deref(&self) -> &Self::Target {
+        &self.builder
     }
 }
 
-impl<'a> ToBrightSkyUrl for WeatherQueryBuilder<'a> {
+impl ToBrightSkyUrl for WeatherQuery {
     #[cfg(feature = "std")]
     fn to_url(self, host: &str) -> Result<Url, BrightSkyError> {
+        let builder = self.builder;
+        let (lat, lon) = url_coordinates(
+            builder.lat,
+            builder.lon,
+            builder.coordinate_precision,
+            builder.coordinate_fuzzing,
+        );
         let base = Url::parse(host)?;
         let mut url = base.join("weather")?;
 
         let mut query = url.query_pairs_mut();
 
-        if let Some(date) = self.date {
+        if let Some(date) = builder.date {
             query.append_pair("date", &date.to_string());
         }
-        if let Some(last_date) = self.last_date {
+        if let Some(last_date) = builder.last_date {
             query.append_pair("last_date", &last_date.to_string());
         }
-        if let Some(lat) = self.lat {
+        if let Some(lat) = lat {
             query.append_pair("lat", &lat);
         }
-        if let Some(lon) = self.lon {
+        if let Some(lon) = lon {
             query.append_pair("lon", &lon);
         }
-        if let Some(max_dist) = self.max_dist {
+        if let Some(max_dist) = builder.max_dist {
             query.append_pair("max_dist", &max_dist);
         }
-        if let Some(dwd_station_id) = self.dwd_station_id {
+        if let Some(dwd_station_id) = builder.dwd_station_id {
             for id in dwd_station_id {
-                query.append_pair("dwd_station_id", id);
+                query.append_pair("dwd_station_id", &id);
             }
         }
-        if let Some(wmo_station_id) = self.wmo_station_id {
+        if let Some(wmo_station_id) = builder.wmo_station_id {
             for id in wmo_station_id {
-                query.append_pair("wmo_station_id", id);
+                query.append_pair("wmo_station_id", &id);
             }
         }
-        if let Some(source_id) = self.source_id {
+        if let Some(source_id) = builder.source_id {
             for id in source_id {
                 query.append_pair("source_id", &id);
             }
         }
-        if let Some(tz) = self.tz {
+        if let Some(tz) = builder.tz {
             query.append_pair("tz", &tz);
         }
-        if let Some(units) = self.units {
+        if let Some(units) = builder.units {
             let unit_string = serde_json::to_string(&units).unwrap();
             query.append_pair("units", unit_string.trim_matches('"'));
         }
@@ -462,48 +656,55 @@ impl<'a> ToBrightSkyUrl for WeatherQueryBuilder<'a> {
     }
 
     fn to_url_string(self, host: &str) -> Result<String, BrightSkyError> {
+        let builder = self.builder;
         #[cfg(not(feature = "std"))]
         use alloc::vec::Vec;
         #[cfg(feature = "std")]
         use std::vec::Vec;
 
+        let (lat, lon) = url_coordinates(
+            builder.lat,
+            builder.lon,
+            builder.coordinate_precision,
+            builder.coordinate_fuzzing,
+        );
         let mut url = format!("{}/weather", host.trim_end_matches('/'));
         let mut params = Vec::new();
 
-        if let Some(date) = self.date {
-            params.push(format!("date={}", date));
+        if let Some(date) = builder.date {
+            params.push(format!("date={}", date.to_url_param()));
         }
-        if let Some(last_date) = self.last_date {
-            params.push(format!("last_date={}", last_date));
+        if let Some(last_date) = builder.last_date {
+            params.push(format!("last_date={}", last_date.to_url_param()));
         }
-        if let Some(lat) = self.lat {
+        if let Some(lat) = lat {
             params.push(format!("lat={}", lat));
         }
-        if let Some(lon) = self.lon {
+        if let Some(lon) = lon {
             params.push(format!("lon={}", lon));
         }
-        if let Some(max_dist) = self.max_dist {
+        if let Some(max_dist) = builder.max_dist {
             params.push(format!("max_dist={}", max_dist));
         }
-        if let Some(dwd_station_id) = self.dwd_station_id {
+        if let Some(dwd_station_id) = builder.dwd_station_id {
             for id in dwd_station_id {
                 params.push(format!("dwd_station_id={}", id));
             }
         }
-        if let Some(wmo_station_id) = self.wmo_station_id {
+        if let Some(wmo_station_id) = builder.wmo_station_id {
             for id in wmo_station_id {
                 params.push(format!("wmo_station_id={}", id));
             }
         }
-        if let Some(source_id) = self.source_id {
+        if let Some(source_id) = builder.source_id {
             for id in source_id {
                 params.push(format!("source_id={}", id));
             }
         }
-        if let Some(tz) = self.tz {
+        if let Some(tz) = builder.tz {
             params.push(format!("tz={}", tz));
         }
-        if let Some(units) = self.units {
+        if let Some(units) = builder.units {
             let unit_string = serde_json::to_string(&units).unwrap();
             params.push(format!("units={}", unit_string.trim_matches('"')));
         }
@@ -517,7 +718,7 @@ impl<'a> ToBrightSkyUrl for WeatherQueryBuilder<'a> {
     }
 }
 
-impl<'a> Default for WeatherQueryBuilder<'a> {
+impl Default for WeatherQueryBuilder {
     fn default() -> Self {
         Self::new()
     }
@@ -0,0 +1,181 @@
+#[cfg(not(feature = "std"))]
+use alloc::{borrow::Cow, string::String, vec::Vec};
+
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+
+use serde::de::{DeserializeOwned, Error as _, IgnoredAny};
+
+use crate::types::Weather;
+
+/// Hourly records of a `/weather` response body, parsed one at a time.
+///
+/// Deserializing a [`WeatherResponse`](crate::types::WeatherResponse) keeps
+/// every record in memory at once, which adds up for requests spanning weeks.
+/// This iterator parses the `weather` array of the raw body lazily, so only the
+/// body and the current record are held. `sources` and other fields are
+/// skipped.
+///
+/// ```rust
+/// use brightsky::WeatherRecords;
+///
+/// let body = br#"{
+///     "weather": [
+///         {"timestamp": "2023-08-07T13:00:00+02:00", "source_id": 1, "temperature": 24.0},
+///         {"timestamp": "2023-08-07T14:00:00+02:00", "source_id": 1, "temperature": 26.0}
+///     ],
+///     "sources": []
+/// }"#;
+///
+/// let mut warmest = f64::MIN;
+/// for record in WeatherRecords::from_slice(body) {
+///     warmest = warmest.max(record?.temperature.unwrap_or(f64::MIN));
+/// }
+/// assert_eq!(warmest, 26.0);
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+///
+/// A malformed body yields one error, after which the iterator ends.
+#[derive(Debug, Clone)]
+pub struct WeatherRecords<'a> {
+    body: Cow<'a, [u8]>,
+    pos: usize,
+    state: State,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Before the opening brace of the response object
+    Start,
+    /// Inside the `weather` array, before the first record or a separator
+    Records {
+        first: bool,
+    },
+    Done,
+}
+
+impl<'a> WeatherRecords<'a> {
+    /// Parse records from a borrowed response body.
+    pub fn from_slice(body: &'a [u8]) -> Self {
+        Self {
+            body: Cow::Borrowed(body),
+            pos: 0,
+            state: State::Start,
+        }
+    }
+}
+
+impl WeatherRecords<'static> {
+    /// Parse records from an owned response body.
+    pub fn from_vec(body: Vec<u8>) -> Self {
+        Self {
+            body: Cow::Owned(body),
+            pos: 0,
+            state: State::Start,
+        }
+    }
+}
+
+impl WeatherRecords<'_> {
+    fn next_record(&mut self) -> Result<Option<Weather>, serde_json::Error> {
+        if self.state == State::Start {
+            self.expect(b'{')?;
+            if !self.find_weather_array()? {
+                self.state = State::Done;
+                return Ok(None);
+            }
+            self.state = State::Records { first: true };
+        }
+        let State::Records { first } = self.state else {
+            return Ok(None);
+        };
+
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.state = State::Done;
+            return Ok(None);
+        }
+        if !first {
+            self.expect(b',')?;
+        }
+        let record = self.parse::<Weather>()?;
+        self.state = State::Records { first: false };
+        Ok(Some(record))
+    }
+
+    /// Skip the members of the response object up to the opening bracket of
+    /// `weather`. Returns `false` if there is no such member.
+    fn find_weather_array(&mut self) -> Result<bool, serde_json::Error> {
+        let mut first = true;
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(b'}') {
+                return Ok(false);
+            }
+            if !first {
+                self.expect(b',')?;
+            }
+            first = false;
+
+            self.skip_whitespace();
+            let key = self.parse::<String>()?;
+            self.expect(b':')?;
+            if key == "weather" {
+                self.expect(b'[')?;
+                return Ok(true);
+            }
+            self.parse::<IgnoredAny>()?;
+        }
+    }
+
+    /// Deserialize one value at the current position.
+    fn parse<T: DeserializeOwned>(&mut self) -> Result<T, serde_json::Error> {
+        let rest = self.body.get(self.pos..).unwrap_or_default();
+        let mut values = serde_json::Deserializer::from_slice(rest).into_iter::<T>();
+        match values.next() {
+            Some(Ok(value)) => {
+                self.pos += values.byte_offset();
+                Ok(value)
+            }
+            Some(Err(err)) => Err(err),
+            None => Err(serde_json::Error::custom("unexpected end of response body")),
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), serde_json::Error> {
+        self.skip_whitespace();
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(serde_json::Error::custom(format_args!(
+                "expected `{}` at byte {} of the response body",
+                byte as char, self.pos
+            )))
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\n' | b'\r' | b'\t')) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.body.get(self.pos).copied()
+    }
+}
+
+impl core::iter::FusedIterator for WeatherRecords<'_> {}
+
+impl Iterator for WeatherRecords<'_> {
+    type Item = Result<Weather, serde_json::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = self.next_record();
+        if !matches!(record, Ok(Some(_))) {
+            self.state = State::Done;
+        }
+        record.transpose()
+    }
+}
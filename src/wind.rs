@@ -0,0 +1,256 @@
+//! Wind directions as compass points and vectors.
+//!
+//! Bright Sky reports wind directions in degrees clockwise from north, the
+//! direction the wind is blowing *from*. [`WindDirection`] converts them to
+//! compass points and back:
+//!
+//! ```rust
+//! use brightsky::Language;
+//! use brightsky::wind::{CompassPoints, WindDirection};
+//!
+//! let direction = WindDirection::new(22.0);
+//! assert_eq!(direction.compass_point(), "NNE");
+//! assert_eq!(direction.to_compass(CompassPoints::Eight, Language::De), "N");
+//! assert_eq!("SW".parse::<WindDirection>().unwrap().degrees(), 225.0);
+//! ```
+//!
+//! With the `std` feature, directions convert to and from u/v vector
+//! components and average correctly across north:
+//!
+//! ```rust
+//! # #[cfg(feature = "std")]
+//! # {
+//! use brightsky::wind::WindDirection;
+//!
+//! let directions = [WindDirection::new(350.0), WindDirection::new(10.0)];
+//! let mean = WindDirection::circular_mean(directions).unwrap();
+//! assert!(mean.degrees() < 1e-9 || mean.degrees() > 360.0 - 1e-9);
+//! # }
+//! ```
+
+use core::{fmt, str::FromStr};
+
+use crate::{
+    Language,
+    current_weather::IntervalValue,
+    types::{CurrentWeather, Weather},
+};
+
+/// Number of points of a compass rose.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompassPoints {
+    /// N, NE, E, ...
+    Eight,
+    /// N, NNE, NE, ENE, E, ...
+    Sixteen,
+}
+
+const EN: [&str; 16] = [
+    "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW", "NW",
+    "NNW",
+];
+const DE: [&str; 16] = [
+    "N", "NNO", "NO", "ONO", "O", "OSO", "SO", "SSO", "S", "SSW", "SW", "WSW", "W", "WNW", "NW",
+    "NNW",
+];
+
+/// The direction the wind blows from, in degrees clockwise from north.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct WindDirection {
+    degrees: f64,
+}
+
+impl WindDirection {
+    /// Create a direction, normalized to `0.0..360.0` degrees.
+    pub fn new(degrees: f64) -> Self {
+        // `f64::rem_euclid` needs std
+        let mut degrees = degrees % 360.0;
+        if degrees < 0.0 {
+            degrees += 360.0;
+        }
+        if degrees >= 360.0 {
+            degrees = 0.0;
+        }
+        Self { degrees }
+    }
+
+    /// The direction in degrees, `0.0..360.0`.
+    pub fn degrees(&self) -> f64 {
+        self.degrees
+    }
+
+    /// Sixteen-point English compass point, e.g. `"NNE"`.
+    pub fn compass_point(&self) -> &'static str {
+        self.to_compass(CompassPoints::Sixteen, Language::En)
+    }
+
+    /// The nearest compass point in the given language (`"O"` for east in
+    /// German).
+    pub fn to_compass(&self, points: CompassPoints, language: Language) -> &'static str {
+        let names = match language {
+            Language::En => &EN,
+            Language::De => &DE,
+        };
+        let (count, step) = match points {
+            CompassPoints::Eight => (8, 2),
+            CompassPoints::Sixteen => (16, 1),
+        };
+        let sector = 360.0 / count as f64;
+        let index = ((self.degrees + sector / 2.0) / sector) as usize % count;
+        names[index * step]
+    }
+
+    /// Parse a compass point of up to sixteen points, in English or German,
+    /// ignoring case.
+    pub fn from_compass_point(point: &str) -> Option<Self> {
+        let index = EN
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(point))
+            .or_else(|| DE.iter().position(|name| name.eq_ignore_ascii_case(point)))?;
+        Some(Self::new(index as f64 * 22.5))
+    }
+}
+
+#[cfg(feature = "std")]
+impl WindDirection {
+    /// u (eastward) and v (northward) components of a wind of `speed` from
+    /// this direction, in the unit of `speed`.
+    ///
+    /// A wind from the west (270°) blows eastward and has a positive u.
+    pub fn to_uv(&self, speed: f64) -> (f64, f64) {
+        let radians = self.degrees.to_radians();
+        (-speed * radians.sin(), -speed * radians.cos())
+    }
+
+    /// Direction of a wind with the given u and v components, `None` if both
+    /// are zero.
+    pub fn from_uv(u: f64, v: f64) -> Option<Self> {
+        if u == 0.0 && v == 0.0 {
+            return None;
+        }
+        Some(Self::new((-u).atan2(-v).to_degrees()))
+    }
+
+    /// Mean of directions on the circle, e.g. 0° for 350° and 10°.
+    ///
+    /// Returns `None` without directions or if they cancel out exactly.
+    pub fn circular_mean(directions: impl IntoIterator<Item = WindDirection>) -> Option<Self> {
+        Self::vector_mean(directions.into_iter().map(|direction| (direction, 1.0)))
+    }
+
+    /// Direction of the mean wind vector, weighting each direction with its
+    /// speed, so calm hours count less than stormy ones.
+    ///
+    /// Returns `None` without directions or if they cancel out exactly.
+    pub fn vector_mean(winds: impl IntoIterator<Item = (WindDirection, f64)>) -> Option<Self> {
+        let (u, v) = winds
+            .into_iter()
+            .map(|(direction, speed)| direction.to_uv(speed))
+            .fold((0.0, 0.0), |(u, v), (du, dv)| (u + du, v + dv));
+        if u.abs() < 1e-9 && v.abs() < 1e-9 {
+            return None;
+        }
+        Self::from_uv(u, v)
+    }
+}
+
+impl From<i64> for WindDirection {
+    fn from(degrees: i64) -> Self {
+        Self::new(degrees as f64)
+    }
+}
+
+impl From<f64> for WindDirection {
+    fn from(degrees: f64) -> Self {
+        Self::new(degrees)
+    }
+}
+
+impl FromStr for WindDirection {
+    type Err = UnknownCompassPoint;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_compass_point(s.trim()).ok_or(UnknownCompassPoint)
+    }
+}
+
+/// Displays the sixteen-point English compass point.
+impl fmt::Display for WindDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.compass_point())
+    }
+}
+
+/// Error parsing a [`WindDirection`] from a string that is not a compass point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownCompassPoint;
+
+impl fmt::Display for UnknownCompassPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("unknown compass point")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnknownCompassPoint {}
+
+impl Weather {
+    /// Mean wind direction during the previous hour.
+    pub fn typed_wind_direction(&self) -> Option<WindDirection> {
+        self.wind_direction.map(WindDirection::from)
+    }
+
+    /// Direction of the maximum wind gust during the previous hour.
+    pub fn typed_wind_gust_direction(&self) -> Option<WindDirection> {
+        self.wind_gust_direction.map(WindDirection::from)
+    }
+
+    /// u and v components of the mean wind in the unit of `wind_speed`, see
+    /// [`WindDirection::to_uv`].
+    #[cfg(feature = "std")]
+    pub fn wind_components(&self) -> Option<(f64, f64)> {
+        Some(self.typed_wind_direction()?.to_uv(self.wind_speed?))
+    }
+}
+
+impl CurrentWeather {
+    /// Mean wind direction of the shortest available interval.
+    pub fn typed_wind_direction(&self) -> Option<IntervalValue<WindDirection>> {
+        self.best_wind_direction().map(|best| IntervalValue {
+            value: best.value.into(),
+            interval: best.interval,
+        })
+    }
+
+    /// u and v components of the mean wind of the shortest interval with both
+    /// speed and direction, in the unit of the wind speed.
+    #[cfg(feature = "std")]
+    pub fn wind_components(&self) -> Option<IntervalValue<(f64, f64)>> {
+        use crate::current_weather::ObservationInterval;
+
+        [
+            (
+                self.wind_direction_10,
+                self.wind_speed_10,
+                ObservationInterval::Minutes10,
+            ),
+            (
+                self.wind_direction_30,
+                self.wind_speed_30,
+                ObservationInterval::Minutes30,
+            ),
+            (
+                self.wind_direction_60,
+                self.wind_speed_60,
+                ObservationInterval::Minutes60,
+            ),
+        ]
+        .into_iter()
+        .find_map(|(direction, speed, interval)| {
+            Some(IntervalValue {
+                value: WindDirection::from(direction?).to_uv(speed?),
+                interval,
+            })
+        })
+    }
+}
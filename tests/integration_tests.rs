@@ -30,6 +30,48 @@ mod types_tests {
         }
     }
 
+    #[test]
+    fn test_weather_icon_labels() {
+        assert_eq!(WeatherIcon::Rain.emoji(), "🌧");
+        assert_eq!(
+            WeatherIcon::PartlyCloudyNight.description_en(),
+            "Partly cloudy"
+        );
+        assert_eq!(WeatherIcon::Sleet.description_de(), "Schneeregen");
+        assert_eq!(WeatherIcon::Snow.description(Language::De), "Schnee");
+        assert_eq!(WeatherIcon::ClearDay.weather_icons_class(), "wi-day-sunny");
+        assert_eq!(WeatherIcon::Thunderstorm.material_symbol(), "thunderstorm");
+        assert_eq!(
+            WeatherIcon::PartlyCloudyDay.mdi_name(),
+            "mdi:weather-partly-cloudy"
+        );
+    }
+
+    #[test]
+    fn test_weather_parameter_names() {
+        for parameter in WeatherParameter::HOURLY
+            .iter()
+            .chain(&WeatherParameter::CURRENT)
+        {
+            let json = serde_json::to_string(parameter).unwrap();
+            let parsed: WeatherParameter = serde_json::from_str(&json).unwrap();
+            assert_eq!(&parsed, parameter);
+            assert!(!matches!(parsed, WeatherParameter::Other(_)));
+        }
+
+        let parsed: WeatherParameter = serde_json::from_str("\"ozone_60\"").unwrap();
+        assert_eq!(parsed, WeatherParameter::Other("ozone_60".to_string()));
+        assert_eq!(
+            parsed.hourly(),
+            Some(WeatherParameter::Other("ozone".to_string()))
+        );
+        assert_eq!(
+            WeatherParameter::Temperature.hourly(),
+            Some(WeatherParameter::Temperature)
+        );
+        assert_eq!(WeatherParameter::Sunshine30.hourly(), None);
+    }
+
     #[test]
     fn test_weather_condition_deserialization() {
         let json_values = vec![
@@ -64,6 +106,17 @@ mod types_tests {
         }
     }
 
+    #[test]
+    fn test_observation_type_other_round_trip() {
+        let result: ObservationType = serde_json::from_str("\"mosmix-l\"").unwrap();
+        assert_eq!(result, ObservationType::Other("mosmix-l".to_string()));
+        assert_eq!(serde_json::to_string(&result).unwrap(), "\"mosmix-l\"");
+        assert_eq!(
+            serde_json::to_string(&ObservationType::Synop).unwrap(),
+            "\"synop\""
+        );
+    }
+
     #[test]
     fn test_unit_type_deserialization() {
         let json_values = vec![("\"si\"", UnitType::Si), ("\"dwd\"", UnitType::Dwd)];
@@ -100,6 +153,29 @@ mod types_tests {
         }
     }
 
+    #[test]
+    fn test_alert_enums_are_case_insensitive_and_forward_compatible() {
+        let status: AlertStatus = serde_json::from_str("\"Actual\"").unwrap();
+        assert_eq!(status, AlertStatus::Actual);
+        let response_type: AlertResponseType = serde_json::from_str("\"AllClear\"").unwrap();
+        assert_eq!(response_type, AlertResponseType::AllClear);
+        let urgency: AlertUrgency = serde_json::from_str("\"FUTURE\"").unwrap();
+        assert_eq!(urgency, AlertUrgency::Future);
+
+        let certainty: AlertCertainty = serde_json::from_str("\"Unknown\"").unwrap();
+        assert_eq!(certainty, AlertCertainty::Other("Unknown".to_string()));
+        assert_eq!(serde_json::to_string(&certainty).unwrap(), "\"Unknown\"");
+        let category: AlertCategory = serde_json::from_str("\"Geo\"").unwrap();
+        assert_eq!(category.as_str(), "Geo");
+        let status: AlertStatus = serde_json::from_str("\"Exercise\"").unwrap();
+        assert_eq!(status, AlertStatus::Other("Exercise".to_string()));
+
+        assert_eq!(
+            serde_json::to_string(&AlertResponseType::AllClear).unwrap(),
+            "\"allclear\""
+        );
+    }
+
     #[test]
     fn test_maybe_compressed_precipitation_plain() {
         let json = "[[10, 20, 30], [40, 50, 60]]";
@@ -115,6 +191,154 @@ mod types_tests {
         }
     }
 
+    #[test]
+    fn test_maybe_compressed_precipitation_bytes() {
+        // Little-endian 10 and 20
+        let result: MaybeCompressedPrecipitation = serde_json::from_str("\"CgAUAA==\"").unwrap();
+        assert_eq!(result, MaybeCompressedPrecipitation::bytes(&[10, 20]));
+        assert_eq!(result.decode().unwrap(), vec![10, 20]);
+
+        // A trailing odd byte cannot be part of a value
+        assert!(serde_json::from_str::<MaybeCompressedPrecipitation>("\"CgAUAP8=\"").is_err());
+    }
+
+    #[test]
+    fn test_maybe_compressed_precipitation_compressed() {
+        let result: MaybeCompressedPrecipitation =
+            serde_json::from_str("\"eJzjYhBhAAAAVAAf\"").unwrap();
+        assert!(matches!(
+            result,
+            MaybeCompressedPrecipitation::Compressed(_)
+        ));
+        assert_eq!(result.decode().unwrap(), vec![10, 20]);
+
+        // Decoding into a buffer replaces its contents
+        let mut values = vec![1, 2, 3];
+        result.decode_into(&mut values).unwrap();
+        assert_eq!(values, vec![10, 20]);
+    }
+
+    #[test]
+    fn test_maybe_compressed_precipitation_high_ratio() {
+        // Mostly dry grids compress far beyond the initial output reservation
+        let mut values = vec![0u16; 400 * 400];
+        values[12_345] = 250;
+        let precipitation = MaybeCompressedPrecipitation::compressed(&values);
+
+        let json = serde_json::to_string(&precipitation).unwrap();
+        let result: MaybeCompressedPrecipitation = serde_json::from_str(&json).unwrap();
+        assert_eq!(result, precipitation);
+        assert_eq!(result.decode().unwrap(), values);
+    }
+
+    #[test]
+    fn test_maybe_compressed_precipitation_corrupt() {
+        // Starts like a zlib stream but is not one; only decoding finds out
+        let corrupt: MaybeCompressedPrecipitation = serde_json::from_str("\"eJz//w==\"").unwrap();
+        assert!(matches!(corrupt.decode(), Err(DecodeError::Zlib(_))));
+
+        // Valid zlib stream with the end cut off
+        let truncated: MaybeCompressedPrecipitation =
+            serde_json::from_str("\"eJzjYhBhAAAA\"").unwrap();
+        let error = truncated.decode().unwrap_err();
+        assert!(error.to_string().contains("truncated"));
+
+        let record = Radar {
+            timestamp: chrono::DateTime::parse_from_rfc3339("2023-08-07T12:00:00+00:00").unwrap(),
+            source: "RADOLAN::RV::2023-08-07T12:00:00+00:00".to_string(),
+            precipitation_5: truncated,
+        };
+        assert!(record.to_grid(&[0, 0, 0, 1]).is_none());
+        assert!(matches!(
+            record.decode_grid(&[0, 0, 0, 1]),
+            Err(DecodeError::TruncatedZlibStream)
+        ));
+    }
+
+    #[test]
+    fn test_maybe_compressed_precipitation_explicit_format() {
+        assert_eq!(
+            MaybeCompressedPrecipitation::decode_compressed("eJzjYhBhAAAAVAAf").unwrap(),
+            vec![10, 20]
+        );
+        // Raw bytes are not a zlib stream
+        assert!(matches!(
+            MaybeCompressedPrecipitation::decode_compressed("CgAUAA=="),
+            Err(DecodeError::InvalidZlibHeader)
+        ));
+        assert_eq!(
+            MaybeCompressedPrecipitation::decode_bytes("CgAUAA==").unwrap(),
+            vec![10, 20]
+        );
+        assert!(matches!(
+            MaybeCompressedPrecipitation::decode_bytes("not base64!"),
+            Err(DecodeError::Base64(_))
+        ));
+        assert!(matches!(
+            MaybeCompressedPrecipitation::decode_bytes("CgAUAP8="),
+            Err(DecodeError::OddByteLength(5))
+        ));
+    }
+
+    #[test]
+    fn test_maybe_compressed_precipitation_into_format() {
+        // Truncated base64 of a stream whose zlib header was damaged
+        let damaged: MaybeCompressedPrecipitation = serde_json::from_str("\"AAAAAA==\"").unwrap();
+        assert_eq!(damaged.format(), RadarCompressionFormat::Bytes);
+        assert!(matches!(
+            damaged
+                .clone()
+                .into_format(RadarCompressionFormat::Compressed),
+            Err(DecodeError::InvalidZlibHeader)
+        ));
+        assert_eq!(
+            damaged
+                .into_format(RadarCompressionFormat::Bytes)
+                .unwrap()
+                .decode()
+                .unwrap(),
+            vec![0, 0]
+        );
+
+        let plain = MaybeCompressedPrecipitation::Plain(vec![vec![1]]);
+        let error = plain
+            .into_format(RadarCompressionFormat::Compressed)
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            DecodeError::UnexpectedRadarFormat {
+                requested: RadarCompressionFormat::Compressed,
+                received: RadarCompressionFormat::Plain,
+            }
+        ));
+        assert_eq!(
+            error.to_string(),
+            "Requested compressed radar data, but the response contains plain data"
+        );
+        assert!(
+            MaybeCompressedPrecipitation::bytes(&[1])
+                .into_format(RadarCompressionFormat::Plain)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_maybe_compressed_precipitation_invalid() {
+        for json in [
+            "{}",
+            "42",
+            "[1, 2]",
+            "[[1, -2]]",
+            "[[\"a\"]]",
+            "\"not base64!\"",
+        ] {
+            assert!(
+                serde_json::from_str::<MaybeCompressedPrecipitation>(json).is_err(),
+                "{json} should not deserialize"
+            );
+        }
+    }
+
     #[test]
     fn test_weather_response_deserialization() {
         let json = r#"{
@@ -146,7 +370,10 @@ mod types_tests {
         }"#;
 
         let result: CurrentWeatherResponse = serde_json::from_str(json).unwrap();
-        assert_eq!(result.weather.timestamp, "2023-08-07T12:00:00Z");
+        assert_eq!(
+            result.weather.timestamp,
+            chrono::DateTime::parse_from_rfc3339("2023-08-07T12:00:00Z").unwrap()
+        );
         assert_eq!(result.weather.source_id, 1234);
         assert_eq!(result.weather.cloud_cover, Some(50.0));
         assert_eq!(result.weather.condition, Some(WeatherCondition::Dry));
@@ -154,6 +381,21 @@ mod types_tests {
         assert_eq!(result.weather.temperature, Some(22.5));
     }
 
+    #[test]
+    fn test_timestamp_keeps_utc_offset() {
+        let json = r#"{
+            "timestamp": "2023-08-07T14:00:00+02:00",
+            "source_id": 1234
+        }"#;
+
+        let result: Weather = serde_json::from_str(json).unwrap();
+        assert_eq!(result.timestamp.offset().local_minus_utc(), 7200);
+        assert_eq!(result.timestamp.to_rfc3339(), "2023-08-07T14:00:00+02:00");
+
+        let serialized = serde_json::to_value(&result).unwrap();
+        assert_eq!(serialized["timestamp"], "2023-08-07T14:00:00+02:00");
+    }
+
     #[test]
     fn test_alerts_response_deserialization() {
         let json = r#"{
@@ -171,6 +413,33 @@ mod types_tests {
 mod error_tests {
     use super::*;
 
+    #[test]
+    fn test_api_error_from_response() {
+        let error = BrightSkyApiError::from_response(
+            404,
+            br#"{"detail": "No sources match your criteria"}"#,
+        );
+        assert!(error.is_not_found());
+        assert_eq!(
+            error.to_string(),
+            "API error (HTTP 404): No sources match your criteria"
+        );
+
+        let error = BrightSkyApiError::from_response(
+            422,
+            br#"{"detail": [{"loc": ["query", "date"], "msg": "field required"}]}"#,
+        );
+        assert!(error.is_bad_request());
+        assert!(error.detail.unwrap().contains("field required"));
+
+        let error = BrightSkyApiError::from_response(502, b"Bad Gateway\n");
+        assert_eq!(error.detail.as_deref(), Some("Bad Gateway"));
+
+        let error = BrightSkyApiError::from_response(500, b"");
+        assert_eq!(error.detail, None);
+        assert_eq!(error.to_string(), "API error (HTTP 500)");
+    }
+
     #[test]
     fn test_date_not_set_error() {
         let result = WeatherQueryBuilder::new()
@@ -179,11 +448,142 @@ mod error_tests {
 
         assert!(result.is_err());
         match result.unwrap_err() {
-            BrightSkyError::DateNotSet => (),
+            BrightSkyError::Query(QueryError::DateNotSet) => (),
             _ => panic!("Expected DateNotSet error"),
         }
     }
 
+    #[test]
+    fn test_date_range_errors() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let build = |first, last: Option<NaiveDate>| {
+            let builder = WeatherQueryBuilder::new()
+                .with_date(first)
+                .with_lat_lon((52.52, 13.4));
+            match last {
+                Some(last) => builder.with_last_date(last),
+                None => builder,
+            }
+            .build()
+        };
+
+        assert!(matches!(
+            build(date(2023, 8, 7), Some(date(2023, 8, 6))),
+            Err(BrightSkyError::Query(QueryError::InvalidDateRange { .. }))
+        ));
+        assert!(matches!(
+            build(date(2009, 12, 31), None),
+            Err(BrightSkyError::Query(QueryError::DateBeforeDataStart(d))) if d == date(2009, 12, 31)
+        ));
+        assert!(matches!(
+            build(date(2023, 1, 1), Some(date(2023, 6, 1))),
+            Err(BrightSkyError::Query(QueryError::RangeTooLong(151)))
+        ));
+        assert!(build(date(2010, 1, 1), Some(date(2010, 1, 1))).is_ok());
+        assert!(build(date(2023, 1, 1), Some(date(2023, 4, 1))).is_ok());
+
+        let reversed = WeatherQueryBuilder::new()
+            .with_datetime_range(
+                chrono::DateTime::parse_from_rfc3339("2023-08-07T14:00:00+02:00").unwrap(),
+                chrono::DateTime::parse_from_rfc3339("2023-08-07T13:00:00+02:00").unwrap(),
+            )
+            .with_lat_lon((52.52, 13.4))
+            .build();
+        assert!(matches!(
+            reversed,
+            Err(BrightSkyError::Query(QueryError::InvalidDateRange { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_location_errors() {
+        let date = NaiveDate::from_ymd_opt(2023, 8, 7).unwrap();
+        assert!(matches!(
+            WeatherQueryBuilder::new().with_date(date).build(),
+            Err(BrightSkyError::Query(QueryError::MissingLocation))
+        ));
+        assert!(matches!(
+            CurrentWeatherQueryBuilder::new()
+                .with_dwd_station_id(Vec::<String>::new())
+                .build(),
+            Err(BrightSkyError::Query(QueryError::MissingLocation))
+        ));
+        assert!(matches!(
+            WeatherQueryBuilder::new()
+                .with_date(date)
+                .with_wmo_station_id(vec!["10315"])
+                .with_source_id(vec![1234])
+                .build(),
+            Err(BrightSkyError::Query(
+                QueryError::ConflictingLocationParameters("wmo_station_id", "source_id")
+            ))
+        ));
+        assert!(matches!(
+            RadarWeatherQueryBuilder::new()
+                .with_lat_lon((52.52, 13.4))
+                .with_bbox([0, 0, 10, 10])
+                .build(),
+            Err(BrightSkyError::Query(
+                QueryError::ConflictingLocationParameters("lat/lon", "bbox")
+            ))
+        ));
+        assert!(matches!(
+            AlertsQueryBuilder::new()
+                .with_lat_lon((52.52, 13.4))
+                .with_warn_cell_id(803159016)
+                .build(),
+            Err(BrightSkyError::Query(
+                QueryError::ConflictingLocationParameters("lat/lon", "warn_cell_id")
+            ))
+        ));
+
+        assert!(RadarWeatherQueryBuilder::new().build().is_ok());
+        assert!(matches!(
+            RadarWeatherQueryBuilder::new().require_location().build(),
+            Err(BrightSkyError::Query(QueryError::MissingLocation))
+        ));
+        assert!(
+            RadarWeatherQueryBuilder::new()
+                .require_location()
+                .with_lat_lon((52.52, 13.4))
+                .build()
+                .is_ok()
+        );
+        assert!(AlertsQueryBuilder::new().build().is_ok());
+        assert_eq!(
+            QueryError::ConflictingLocationParameters("lat/lon", "bbox").to_string(),
+            "Cannot combine lat/lon with bbox"
+        );
+    }
+
+    #[test]
+    fn test_invalid_station_id() {
+        let date = NaiveDate::from_ymd_opt(2023, 8, 7).unwrap();
+        let is_invalid = |result: Result<(), BrightSkyError>, expected: &str| matches!(result, Err(BrightSkyError::Query(QueryError::InvalidStationId(id))) if id == expected);
+
+        assert!(is_invalid(
+            WeatherQueryBuilder::new()
+                .with_date(date)
+                .with_dwd_station_id(vec!["01766", "1766"])
+                .build()
+                .map(drop),
+            "1766"
+        ));
+        assert!(is_invalid(
+            CurrentWeatherQueryBuilder::new()
+                .with_wmo_station_id(vec!["10-15".to_string()])
+                .build()
+                .map(drop),
+            "10-15"
+        ));
+        assert!(
+            CurrentWeatherQueryBuilder::new()
+                .with_dwd_station_id(vec!["P0035".to_string()])
+                .build()
+                .is_ok()
+        );
+    }
+
     #[test]
     fn test_invalid_latitude_error() {
         let result = CurrentWeatherQueryBuilder::new()
@@ -193,6 +593,43 @@ mod error_tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_invalid_latitude_error_variant() {
+        let is_invalid_latitude = |result: Result<(), BrightSkyError>| {
+            matches!(
+                result,
+                Err(BrightSkyError::Query(QueryError::InvalidLatitude(lat))) if lat == 91.0
+            )
+        };
+        let date = NaiveDate::from_ymd_opt(2023, 8, 7).unwrap();
+
+        assert!(is_invalid_latitude(
+            WeatherQueryBuilder::new()
+                .with_date(date)
+                .with_lat_lon((91.0, 13.4))
+                .build()
+                .map(drop)
+        ));
+        assert!(is_invalid_latitude(
+            CurrentWeatherQueryBuilder::new()
+                .with_lat_lon((91.0, 13.4))
+                .build()
+                .map(drop)
+        ));
+        assert!(is_invalid_latitude(
+            RadarWeatherQueryBuilder::new()
+                .with_lat_lon((91.0, 13.4))
+                .build()
+                .map(drop)
+        ));
+        assert!(is_invalid_latitude(
+            AlertsQueryBuilder::new()
+                .with_lat_lon((91.0, 13.4))
+                .build()
+                .map(drop)
+        ));
+    }
+
     #[test]
     fn test_invalid_longitude_error() {
         let result = CurrentWeatherQueryBuilder::new()
@@ -211,7 +648,7 @@ mod error_tests {
 
         assert!(result.is_err());
         match result.unwrap_err() {
-            BrightSkyError::InvalidMaxDistance(dist) => assert_eq!(dist, 500001),
+            BrightSkyError::Query(QueryError::InvalidMaxDistance(dist)) => assert_eq!(dist, 500001),
             _ => panic!("Expected InvalidMaxDistance error"),
         }
     }
@@ -219,10 +656,13 @@ mod error_tests {
     #[test]
     fn test_error_display() {
         let errors = vec![
-            BrightSkyError::DateNotSet,
-            BrightSkyError::InvalidLatitude(95.0),
-            BrightSkyError::InvalidLongitude(190.0),
-            BrightSkyError::InvalidMaxDistance(600000),
+            BrightSkyError::from(QueryError::DateNotSet),
+            BrightSkyError::from(QueryError::InvalidLatitude(95.0)),
+            BrightSkyError::from(QueryError::InvalidLongitude(190.0)),
+            BrightSkyError::from(QueryError::InvalidMaxDistance(600000)),
+            BrightSkyError::from(TransportError::Api(BrightSkyApiError::from_response(
+                404, b"",
+            ))),
         ];
 
         for error in errors {
@@ -264,15 +704,50 @@ mod query_builder_tests {
             .build()
             .unwrap();
 
-        assert_eq!(query.date, Some(date));
-        assert_eq!(query.last_date, Some(last_date));
-        assert_eq!(query.lat, Some("52.52".to_string()));
-        assert_eq!(query.lon, Some("13.4".to_string()));
+        assert_eq!(query.date, Some(QueryDate::Date(date)));
+        assert_eq!(query.last_date, Some(QueryDate::Date(last_date)));
+        assert_eq!(query.lat, Some(52.52));
+        assert_eq!(query.lon, Some(13.4));
         assert_eq!(query.max_dist, Some("25000".to_string()));
         assert_eq!(query.tz, Some("Europe/Berlin".to_string()));
         assert_eq!(query.units, Some(UnitType::Si));
     }
 
+    #[test]
+    fn test_weather_query_with_datetime_range() {
+        let start = chrono::DateTime::parse_from_rfc3339("2023-08-07T14:00:00+02:00").unwrap();
+        let end = chrono::DateTime::parse_from_rfc3339("2023-08-07T20:00:00+02:00").unwrap();
+
+        let query = WeatherQueryBuilder::new()
+            .with_datetime_range(start, end)
+            .with_lat_lon((52.52, 13.4))
+            .build()
+            .unwrap();
+        assert_eq!(query.date, Some(QueryDate::DateTime(start)));
+
+        let url = query.clone().to_url(BRIGHT_SKY_API).unwrap();
+        let pairs: Vec<_> = url.query_pairs().into_owned().collect();
+        assert!(pairs.contains(&("date".into(), "2023-08-07T14:00:00+02:00".into())));
+        assert!(pairs.contains(&("last_date".into(), "2023-08-07T20:00:00+02:00".into())));
+
+        let url = query.to_url_string(BRIGHT_SKY_API).unwrap();
+        assert!(url.contains("date=2023-08-07T14:00:00%2B02:00"));
+        assert!(url.contains("last_date=2023-08-07T20:00:00%2B02:00"));
+    }
+
+    #[test]
+    fn test_radar_query_with_datetime() {
+        let start = chrono::TimeZone::with_ymd_and_hms(&chrono::Utc, 2023, 8, 7, 12, 0, 0).unwrap();
+
+        let url = RadarWeatherQueryBuilder::new()
+            .with_datetime(start)
+            .build()
+            .unwrap()
+            .to_url_string(BRIGHT_SKY_API)
+            .unwrap();
+        assert!(url.contains("date=2023-08-07T12:00:00%2B00:00"));
+    }
+
     #[test]
     fn test_current_weather_query_builder_complete_flow() {
         let query = CurrentWeatherQueryBuilder::new()
@@ -282,12 +757,10 @@ mod query_builder_tests {
             .with_wmo_station_id(vec!["10315".to_string()])
             .with_source_id(vec![1234, 5678])
             .with_tz("Europe/Berlin")
-            .with_units(UnitType::Dwd)
-            .build()
-            .unwrap();
+            .with_units(UnitType::Dwd);
 
-        assert_eq!(query.lat, Some("52.52".to_string()));
-        assert_eq!(query.lon, Some("13.4".to_string()));
+        assert_eq!(query.lat, Some(52.52));
+        assert_eq!(query.lon, Some(13.4));
         assert_eq!(query.max_dist, Some("15000".to_string()));
         assert_eq!(
             query.dwd_station_id,
@@ -300,6 +773,106 @@ mod query_builder_tests {
         );
         assert_eq!(query.tz, Some("Europe/Berlin".to_string()));
         assert_eq!(query.units, Some(UnitType::Dwd));
+        assert!(matches!(
+            query.build(),
+            Err(BrightSkyError::Query(
+                QueryError::ConflictingLocationParameters("lat/lon", "dwd_station_id")
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_single_station_and_source_setters() {
+        let query = WeatherQueryBuilder::new()
+            .with_dwd_station("01766")
+            .with_dwd_station(String::from("00420"))
+            .with_wmo_station("10315")
+            .with_source(1234)
+            .with_source(5678);
+        assert_eq!(
+            query.dwd_station_id,
+            Some(vec!["01766".to_string(), "00420".to_string()])
+        );
+        assert_eq!(query.wmo_station_id, Some(vec!["10315".to_string()]));
+        assert_eq!(
+            query.source_id,
+            Some(vec!["1234".to_string(), "5678".to_string()])
+        );
+
+        let ids = ["01766", "00420"];
+        let query = CurrentWeatherQueryBuilder::new()
+            .with_dwd_station_id(ids)
+            .with_dwd_station("01048")
+            .with_source_id([1234].into_iter().chain(Some(5678)));
+        assert_eq!(
+            query.dwd_station_id,
+            Some(vec![
+                "01766".to_string(),
+                "00420".to_string(),
+                "01048".to_string()
+            ])
+        );
+        assert_eq!(
+            query.source_id,
+            Some(vec!["1234".to_string(), "5678".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_with_location_for_every_builder() {
+        let date = NaiveDate::from_ymd_opt(2023, 8, 7).unwrap();
+        let home = QueryLocation::LatLon {
+            lat: 52.52,
+            lon: 13.4,
+            max_dist: Some(5000),
+        };
+
+        let weather = WeatherQueryBuilder::new()
+            .with_date(date)
+            .with_dwd_station_id(vec!["01766"])
+            .with_location(home.clone())
+            .build()
+            .unwrap();
+        assert_eq!(weather.lat, Some(52.52));
+        assert_eq!(weather.max_dist.as_deref(), Some("5000"));
+        assert_eq!(weather.dwd_station_id, None);
+
+        let current = CurrentWeatherQueryBuilder::new()
+            .with_location(QueryLocation::Sources(vec![1234]))
+            .build()
+            .unwrap();
+        assert_eq!(current.source_id, Some(vec!["1234".to_string()]));
+
+        let radar = RadarWeatherQueryBuilder::new()
+            .with_bbox([0, 0, 10, 10])
+            .with_location(home.clone())
+            .build()
+            .unwrap();
+        assert_eq!(radar.bbox, None);
+        assert_eq!(radar.distance, Some(5000));
+
+        let alerts = AlertsQueryBuilder::new()
+            .with_location((52.52, 13.4))
+            .build()
+            .unwrap();
+        assert_eq!(alerts.lon, Some(13.4));
+
+        assert!(matches!(
+            RadarWeatherQueryBuilder::new()
+                .with_location(QueryLocation::WmoStations(vec!["10315".into()]))
+                .build(),
+            Err(BrightSkyError::Query(QueryError::UnsupportedLocation(
+                "wmo_station_id"
+            )))
+        ));
+        assert!(matches!(
+            AlertsQueryBuilder::new()
+                .with_location(QueryLocation::DwdStations(vec!["01766".into()]))
+                .build(),
+            Err(BrightSkyError::Query(QueryError::UnsupportedLocation(
+                "dwd_station_id"
+            )))
+        ));
     }
 
     #[test]
@@ -330,8 +903,8 @@ mod query_builder_tests {
             .build()
             .unwrap();
 
-        assert_eq!(query.lat, Some("52.0".to_string()));
-        assert_eq!(query.lon, Some("7.6".to_string()));
+        assert_eq!(query.lat, Some(52.0));
+        assert_eq!(query.lon, Some(7.6));
         assert_eq!(query.distance, Some(50000));
         assert_eq!(
             query.compression_format,
@@ -358,6 +931,45 @@ mod query_builder_tests {
         assert!(url.query().unwrap().contains("format=plain"));
     }
 
+    #[test]
+    fn test_radar_bbox() {
+        let url = RadarWeatherQueryBuilder::new()
+            .with_bbox(RadarBbox {
+                top: 100,
+                left: 200,
+                bottom: 299,
+                right: 399,
+            })
+            .build()
+            .unwrap()
+            .to_url_string(BRIGHT_SKY_API)
+            .unwrap();
+        assert_eq!(url, "https://api.brightsky.dev/radar?bbox=100,200,299,399");
+
+        assert!(
+            RadarWeatherQueryBuilder::new()
+                .with_bbox(RadarBbox::FULL)
+                .build()
+                .is_ok()
+        );
+        for invalid in [
+            [-1, 0, 10, 10],
+            [0, 0, 1200, 10],
+            [0, 0, 10, 1100],
+            [10, 0, 5, 10],
+            [0, 10, 10, 5],
+        ] {
+            let result = RadarWeatherQueryBuilder::new().with_bbox(invalid).build();
+            assert!(
+                matches!(
+                    result,
+                    Err(BrightSkyError::Query(QueryError::InvalidBbox(bbox))) if bbox.to_array() == invalid
+                ),
+                "{invalid:?}"
+            );
+        }
+    }
+
     #[test]
     fn test_alerts_query_builder() {
         let query = AlertsQueryBuilder::new()
@@ -366,8 +978,8 @@ mod query_builder_tests {
             .build()
             .unwrap();
 
-        assert_eq!(query.lat, Some("52.52".to_string()));
-        assert_eq!(query.lon, Some("13.4".to_string()));
+        assert_eq!(query.lat, Some(52.52));
+        assert_eq!(query.lon, Some(13.4));
         assert_eq!(query.tz, Some("Europe/Berlin".to_string()));
     }
 
@@ -375,7 +987,6 @@ mod query_builder_tests {
     fn test_alerts_query_url_generation() {
         let query = AlertsQueryBuilder::new()
             .with_lat_lon((52.52, 13.4))
-            .with_warn_cell_id(803159016)
             .with_tz("Europe/Berlin")
             .build()
             .unwrap();
@@ -385,9 +996,74 @@ mod query_builder_tests {
         assert_eq!(url.path(), "/alerts");
         assert!(url.query().unwrap().contains("lat=52.52"));
         assert!(url.query().unwrap().contains("lon=13.4"));
+
+        let query = AlertsQueryBuilder::new()
+            .with_warn_cell_id(803159016)
+            .build()
+            .unwrap();
+        let url = query.to_url(BRIGHT_SKY_API).unwrap();
         assert!(url.query().unwrap().contains("warn_cell_id=803159016"));
     }
 
+    #[test]
+    fn test_coordinate_precision() {
+        let url = WeatherQueryBuilder::new()
+            .with_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap())
+            .with_lat_lon((52.520008, 13.404954))
+            .build()
+            .unwrap()
+            .to_url_string(BRIGHT_SKY_API)
+            .unwrap();
+        assert_eq!(
+            url,
+            "https://api.brightsky.dev/weather?date=2023-08-07&lat=52.52&lon=13.405"
+        );
+
+        let url = AlertsQueryBuilder::new()
+            .with_coordinate_precision(6)
+            .with_lat_lon((52.520008, -0.0000001))
+            .build()
+            .unwrap()
+            .to_url_string(BRIGHT_SKY_API)
+            .unwrap();
+        assert_eq!(
+            url,
+            "https://api.brightsky.dev/alerts?lat=52.520008&lon=0.0"
+        );
+
+        // Precision set after the coordinates keeps all digits
+        let url = AlertsQueryBuilder::new()
+            .with_lat_lon((52.520008, 13.404954))
+            .with_coordinate_precision(6)
+            .build()
+            .unwrap()
+            .to_url_string(BRIGHT_SKY_API)
+            .unwrap();
+        assert_eq!(
+            url,
+            "https://api.brightsky.dev/alerts?lat=52.520008&lon=13.404954"
+        );
+
+        let url = RadarWeatherQueryBuilder::new()
+            .with_lat_lon((52.0, 7.65))
+            .with_coordinate_precision(1)
+            .build()
+            .unwrap()
+            .to_url_string(BRIGHT_SKY_API)
+            .unwrap();
+        assert_eq!(url, "https://api.brightsky.dev/radar?lat=52.0&lon=7.7");
+    }
+
+    #[test]
+    fn test_nan_coordinates_rejected() {
+        assert!(matches!(
+            CurrentWeatherQueryBuilder::new()
+                .with_lat_lon((f64::NAN, 13.4))
+                .build(),
+            Err(BrightSkyError::Query(QueryError::InvalidLatitude(_)))
+        ));
+    }
+
     #[test]
     fn test_url_string_generation() {
         let query = CurrentWeatherQueryBuilder::new()
@@ -402,3 +1078,4095 @@ mod query_builder_tests {
         assert!(url.contains("lon=13.4"));
     }
 }
+
+#[cfg(test)]
+mod poll_tests {
+    use super::*;
+    use brightsky::poll::WeatherPoller;
+
+    fn weather_record(timestamp: &str, source_id: i64) -> Weather {
+        serde_json::from_value(serde_json::json!({
+            "timestamp": timestamp,
+            "source_id": source_id,
+            "cloud_cover": null,
+            "condition": null,
+            "dew_point": null,
+            "icon": null,
+            "pressure_msl": null,
+            "relative_humidity": null,
+            "temperature": 20.0,
+            "visibility": null,
+            "fallback_source_ids": null,
+            "precipitation": null,
+            "solar": null,
+            "sunshine": null,
+            "wind_direction": null,
+            "wind_speed": null,
+            "wind_gust_direction": null,
+            "wind_gust_speed": null,
+            "precipitation_probability": null,
+            "precipitation_probability_6h": null
+        }))
+        .unwrap()
+    }
+
+    fn query() -> WeatherQuery {
+        WeatherQueryBuilder::new()
+            .with_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap())
+            .with_lat_lon((52.52, 13.4))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_poller_trims_known_records() {
+        let mut poller = WeatherPoller::new();
+
+        let first = WeatherResponse {
+            weather: vec![
+                weather_record("2023-08-07T00:00:00+00:00", 1),
+                weather_record("2023-08-07T01:00:00+00:00", 1),
+            ],
+            sources: vec![],
+        };
+        let new = poller.ingest(&query(), first.clone()).unwrap();
+        assert_eq!(new.weather.len(), 2);
+
+        // Same response again: nothing new
+        assert!(poller.ingest(&query(), first).is_none());
+
+        let second = WeatherResponse {
+            weather: vec![
+                weather_record("2023-08-07T01:00:00+00:00", 1),
+                weather_record("2023-08-07T02:00:00+00:00", 1),
+            ],
+            sources: vec![],
+        };
+        let new = poller.ingest(&query(), second).unwrap();
+        assert_eq!(new.weather.len(), 1);
+        assert_eq!(
+            new.weather[0].timestamp.to_rfc3339(),
+            "2023-08-07T02:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_poller_adjusts_date() {
+        let mut poller = WeatherPoller::new();
+        let response = WeatherResponse {
+            weather: vec![weather_record("2023-08-09T05:00:00+00:00", 1)],
+            sources: vec![],
+        };
+        poller.ingest(&query(), response);
+
+        let prepared = poller.prepare(query());
+        assert_eq!(
+            prepared.date,
+            NaiveDate::from_ymd_opt(2023, 8, 9).map(QueryDate::from)
+        );
+
+        let unadjusted = WeatherPoller::new().with_date_adjustment(false);
+        assert_eq!(
+            unadjusted.prepare(query()).date,
+            NaiveDate::from_ymd_opt(2023, 8, 7).map(QueryDate::from)
+        );
+    }
+
+    fn alert(alert_id: &str, expires: &str) -> Alert {
+        serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "alert_id": alert_id,
+            "status": "actual",
+            "effective": "2023-08-07T08:00:00+00:00",
+            "onset": "2023-08-07T10:00:00+00:00",
+            "expires": expires,
+            "headline_en": "Official WARNING of WIND GUSTS",
+            "headline_de": "Amtliche WARNUNG vor WINDBÖEN",
+            "description_en": "There is a risk of wind gusts.",
+            "description_de": "Es treten Windböen auf."
+        }))
+        .unwrap()
+    }
+
+    fn alerts_response(alerts: Vec<Alert>) -> AlertsResponse {
+        AlertsResponse {
+            alerts,
+            location: None,
+        }
+    }
+
+    #[test]
+    fn test_poll_schedule_device_jitter() {
+        use brightsky::poll::PollSchedule;
+        use std::time::Duration;
+
+        let interval = Duration::from_secs(600);
+        assert_eq!(PollSchedule::from(interval).offset(), Duration::ZERO);
+        assert_eq!(
+            PollSchedule::new(interval)
+                .with_device_jitter("device", Duration::ZERO)
+                .offset(),
+            Duration::ZERO
+        );
+
+        let mut buckets = [0usize; 10];
+        for i in 0..1000 {
+            let schedule =
+                PollSchedule::new(interval).with_device_jitter(&format!("device-{i}"), interval);
+            assert_eq!(schedule.interval(), interval);
+            assert!(schedule.offset() < interval);
+            buckets[(schedule.offset().as_secs() / 60) as usize] += 1;
+        }
+        // Roughly uniform over the interval
+        assert!(buckets.iter().all(|&count| count > 50), "{buckets:?}");
+    }
+
+    #[test]
+    fn test_alerts_watcher_events() {
+        use brightsky::poll::{AlertEvent, AlertsWatcher};
+
+        let mut watcher = AlertsWatcher::new();
+
+        let events = watcher.update(alerts_response(vec![
+            alert("a", "2023-08-07T18:00:00+00:00"),
+            alert("b", "2023-08-07T18:00:00+00:00"),
+        ]));
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|e| matches!(e, AlertEvent::New(_))));
+
+        // Unchanged alerts produce no events
+        let events = watcher.update(alerts_response(vec![
+            alert("a", "2023-08-07T18:00:00+00:00"),
+            alert("b", "2023-08-07T18:00:00+00:00"),
+        ]));
+        assert!(events.is_empty());
+
+        // A duplicate ID counts once, as in `AlertsDiff::between`
+        let events = watcher.update(alerts_response(vec![
+            alert("a", "2023-08-07T22:00:00+00:00"),
+            alert("c", "2023-08-07T18:00:00+00:00"),
+            alert("c", "2023-08-07T18:00:00+00:00"),
+        ]));
+        assert_eq!(
+            events,
+            vec![
+                AlertEvent::New(alert("c", "2023-08-07T18:00:00+00:00")),
+                AlertEvent::Updated(alert("a", "2023-08-07T22:00:00+00:00")),
+                AlertEvent::Expired(alert("b", "2023-08-07T18:00:00+00:00")),
+            ]
+        );
+
+        let active: Vec<&str> = watcher.active().map(|a| a.alert_id.as_str()).collect();
+        assert_eq!(active, vec!["a", "c"]);
+    }
+
+    #[test]
+    fn test_alerts_watcher_forward() {
+        use brightsky::poll::AlertsWatcher;
+        use brightsky::sink::AlertSink;
+        use std::sync::Mutex;
+
+        /// Records the notified alert IDs, failing for `fail`.
+        #[derive(Default)]
+        struct Recorder(Mutex<Vec<String>>);
+
+        impl AlertSink for Recorder {
+            type Error = String;
+
+            async fn notify(&self, alert: &Alert) -> Result<(), String> {
+                if alert.alert_id == "fail" {
+                    return Err("unreachable".to_string());
+                }
+                self.0.lock().unwrap().push(alert.alert_id.clone());
+                Ok(())
+            }
+        }
+
+        let sink = Recorder::default();
+        let mut watcher = AlertsWatcher::new();
+        let forward = |watcher: &mut AlertsWatcher, alerts| {
+            tokio_test::block_on(watcher.forward(alerts_response(alerts), &sink))
+        };
+
+        let sent = forward(
+            &mut watcher,
+            vec![
+                alert("a", "2023-08-07T18:00:00+00:00"),
+                alert("b", "2023-08-07T18:00:00+00:00"),
+            ],
+        );
+        assert_eq!(sent, Ok(2));
+        // The default `notify_event` skips lifted alerts
+        let sent = forward(&mut watcher, vec![alert("a", "2023-08-07T22:00:00+00:00")]);
+        assert_eq!(sent, Ok(2));
+        assert_eq!(*sink.0.lock().unwrap(), ["a", "b", "a"]);
+
+        let sent = forward(
+            &mut watcher,
+            vec![alert("fail", "2023-08-07T18:00:00+00:00")],
+        );
+        assert_eq!(sent, Err("unreachable".to_string()));
+        // Nothing was delivered, so nothing changed
+        let active: Vec<&str> = watcher.active().map(|a| a.alert_id.as_str()).collect();
+        assert_eq!(active, ["a"]);
+
+        // Changes before the failed one are recorded, the rest are sent again
+        let alerts = vec![
+            alert("c", "2023-08-07T18:00:00+00:00"),
+            alert("fail", "2023-08-07T18:00:00+00:00"),
+        ];
+        let sent = forward(&mut watcher, alerts.clone());
+        assert_eq!(sent, Err("unreachable".to_string()));
+        let active: Vec<&str> = watcher.active().map(|a| a.alert_id.as_str()).collect();
+        assert_eq!(active, ["a", "c"]);
+        assert_eq!(*sink.0.lock().unwrap(), ["a", "b", "a", "c"]);
+    }
+
+    #[test]
+    fn test_alerts_diff_between() {
+        use brightsky::poll::{AlertEvent, AlertsDiff};
+
+        let old = alerts_response(vec![
+            alert("a", "2023-08-07T18:00:00+00:00"),
+            alert("b", "2023-08-07T18:00:00+00:00"),
+            alert("c", "2023-08-07T18:00:00+00:00"),
+        ]);
+        let new = alerts_response(vec![
+            alert("d", "2023-08-07T18:00:00+00:00"),
+            alert("a", "2023-08-07T22:00:00+00:00"),
+            alert("c", "2023-08-07T18:00:00+00:00"),
+            // Listed again, e.g. for another warn cell
+            alert("d", "2023-08-07T18:00:00+00:00"),
+        ]);
+
+        let diff = AlertsDiff::between(&old, &new);
+        assert_eq!(diff.added, vec![alert("d", "2023-08-07T18:00:00+00:00")]);
+        assert_eq!(diff.updated, vec![alert("a", "2023-08-07T22:00:00+00:00")]);
+        assert_eq!(diff.removed, vec![alert("b", "2023-08-07T18:00:00+00:00")]);
+        assert_eq!(
+            diff.into_events(),
+            vec![
+                AlertEvent::New(alert("d", "2023-08-07T18:00:00+00:00")),
+                AlertEvent::Updated(alert("a", "2023-08-07T22:00:00+00:00")),
+                AlertEvent::Expired(alert("b", "2023-08-07T18:00:00+00:00")),
+            ]
+        );
+
+        assert!(AlertsDiff::between(&new, &new).is_empty());
+        let diff = AlertsDiff::between(&alerts_response(vec![]), &old);
+        assert_eq!(diff.added.len(), 3);
+    }
+}
+
+#[cfg(feature = "std")]
+mod humidity_tests {
+    use super::*;
+    use brightsky::humidity::{dew_point, relative_humidity};
+    use brightsky::units::Temperature;
+
+    fn record(json: &str) -> Weather {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_dew_point_and_relative_humidity() {
+        let temperature = Temperature::new(295.45, UnitType::Si);
+        let dew = dew_point(temperature, 52.0).unwrap();
+        assert_eq!(dew.units(), UnitType::Si);
+        assert!((dew.to_celsius() - 11.96).abs() < 0.01, "{dew:?}");
+        assert!((relative_humidity(temperature, dew) - 52.0).abs() < 1e-9);
+
+        assert!(dew_point(temperature, 0.0).is_none());
+        assert!(dew_point(temperature, f64::NAN).is_none());
+        let saturated = dew_point(temperature, 120.0).unwrap();
+        assert!((saturated.value() - 295.45).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fill_derived() {
+        let mut missing_humidity = record(
+            r#"{"timestamp": "2023-08-07T12:00:00+00:00", "source_id": 1, "temperature": 22.3, "dew_point": 11.7}"#,
+        );
+        missing_humidity.fill_derived(UnitType::Dwd);
+        assert_eq!(missing_humidity.relative_humidity, Some(51));
+        assert_eq!(missing_humidity.dew_point, Some(11.7));
+
+        let mut missing_dew_point = record(
+            r#"{"timestamp": "2023-08-07T12:00:00+00:00", "source_id": 1, "temperature": 295.45, "relative_humidity": 52}"#,
+        );
+        missing_dew_point.fill_derived(UnitType::Si);
+        let dew_point = missing_dew_point.dew_point.unwrap();
+        assert!((dew_point - 285.15).abs() < 1e-9, "{dew_point}");
+
+        let mut no_temperature = record(
+            r#"{"timestamp": "2023-08-07T12:00:00+00:00", "source_id": 1, "dew_point": 11.7}"#,
+        );
+        no_temperature.fill_derived(UnitType::Dwd);
+        assert_eq!(no_temperature.relative_humidity, None);
+    }
+}
+
+#[cfg(feature = "testing")]
+mod solar_tests {
+    use super::*;
+    use brightsky::solar::{is_daylight, solar_elevation, sun_times};
+    use chrono::{NaiveDate, TimeDelta, TimeZone, Utc};
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn assert_near(actual: chrono::DateTime<Utc>, expected: chrono::DateTime<Utc>) {
+        assert!(
+            (actual - expected).abs() < TimeDelta::minutes(2),
+            "{actual} != {expected}"
+        );
+    }
+
+    #[test]
+    fn test_sun_times() {
+        let berlin = Coordinates::new(52.52, 13.405).unwrap();
+        let winter = sun_times(berlin, date(2023, 12, 21));
+        assert_near(
+            winter.sunrise().unwrap(),
+            Utc.with_ymd_and_hms(2023, 12, 21, 7, 15, 0).unwrap(),
+        );
+        assert_near(
+            winter.sunset().unwrap(),
+            Utc.with_ymd_and_hms(2023, 12, 21, 14, 54, 0).unwrap(),
+        );
+        assert_near(
+            winter.solar_noon(),
+            Utc.with_ymd_and_hms(2023, 12, 21, 11, 4, 0).unwrap(),
+        );
+
+        let summer = sun_times(berlin, date(2023, 6, 21));
+        assert_near(
+            summer.sunset().unwrap(),
+            Utc.with_ymd_and_hms(2023, 6, 21, 19, 33, 0).unwrap(),
+        );
+        let noon = summer.solar_noon();
+        assert!((solar_elevation(berlin, &noon) - 60.9).abs() < 0.1);
+
+        let tromso = Coordinates::new(69.65, 18.96).unwrap();
+        let polar_night = sun_times(tromso, date(2023, 12, 21));
+        assert_eq!(polar_night.sunrise(), None);
+        assert_eq!(polar_night.daylight(), TimeDelta::zero());
+        assert_eq!(
+            sun_times(tromso, date(2023, 6, 21)).daylight(),
+            TimeDelta::days(1)
+        );
+    }
+
+    #[test]
+    fn test_harmonize_icons() {
+        let mut response = WeatherResponse::sample();
+        response.weather.truncate(2);
+        let source = &response.sources[0];
+        let station = Coordinates::new(source.lat, source.lon).unwrap();
+        response.weather[0].timestamp =
+            chrono::DateTime::parse_from_rfc3339("2023-08-07T23:00:00+00:00").unwrap();
+        response.weather[0].icon = Some(WeatherIcon::PartlyCloudyDay);
+        response.weather[1].timestamp =
+            chrono::DateTime::parse_from_rfc3339("2023-08-07T12:00:00+00:00").unwrap();
+        response.weather[1].icon = Some(WeatherIcon::ClearNight);
+
+        assert!(!is_daylight(station, &response.weather[0].timestamp));
+        response.harmonize_icons();
+        assert_eq!(
+            response.weather[0].icon,
+            Some(WeatherIcon::PartlyCloudyNight)
+        );
+        assert!(response.weather[0].icon.as_ref().unwrap().is_night());
+        assert_eq!(response.weather[1].icon, Some(WeatherIcon::ClearDay));
+        assert!(response.weather[1].icon.as_ref().unwrap().is_day());
+    }
+}
+
+#[cfg(feature = "std")]
+mod wind_tests {
+    use super::*;
+    use brightsky::Language;
+    use brightsky::aggregate::DailySummary;
+    use brightsky::wind::{CompassPoints, WindDirection};
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn test_compass_points() {
+        assert_eq!(WindDirection::new(0.0).compass_point(), "N");
+        assert_eq!(WindDirection::new(22.0).compass_point(), "NNE");
+        assert_eq!(WindDirection::new(349.0).compass_point(), "N");
+        assert_eq!(WindDirection::new(-90.0).compass_point(), "W");
+        assert_eq!(WindDirection::from(100).to_string(), "E");
+        assert_eq!(
+            WindDirection::new(67.5).to_compass(CompassPoints::Sixteen, Language::De),
+            "ONO"
+        );
+        assert_eq!(
+            WindDirection::new(100.0).to_compass(CompassPoints::Eight, Language::De),
+            "O"
+        );
+
+        assert_close("nne".parse::<WindDirection>().unwrap().degrees(), 22.5);
+        assert_close("SO".parse::<WindDirection>().unwrap().degrees(), 135.0);
+        assert!("NNNE".parse::<WindDirection>().is_err());
+    }
+
+    #[test]
+    fn test_uv_components() {
+        let (u, v) = WindDirection::new(270.0).to_uv(10.0);
+        assert_close(u, 10.0);
+        assert_close(v, 0.0);
+        let (u, v) = WindDirection::new(180.0).to_uv(5.0);
+        assert_close(u, 0.0);
+        assert_close(v, 5.0);
+
+        assert_close(WindDirection::from_uv(-1.0, -1.0).unwrap().degrees(), 45.0);
+        assert_eq!(WindDirection::from_uv(0.0, 0.0), None);
+    }
+
+    #[test]
+    fn test_circular_mean() {
+        let mean = WindDirection::circular_mean([350.0, 20.0].map(WindDirection::new)).unwrap();
+        assert_close(mean.degrees(), 5.0);
+        assert_eq!(WindDirection::circular_mean([]), None);
+        assert_eq!(
+            WindDirection::circular_mean([0.0, 180.0].map(WindDirection::new)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_record_helpers() {
+        let record: Weather = serde_json::from_str(
+            r#"{"timestamp": "2023-08-07T12:00:00+00:00", "source_id": 1, "wind_direction": 90, "wind_speed": 3.0}"#,
+        )
+        .unwrap();
+        assert_eq!(record.typed_wind_direction().unwrap().compass_point(), "E");
+        let (u, v) = record.wind_components().unwrap();
+        assert_close(u, -3.0);
+        assert_close(v, 0.0);
+
+        let current: CurrentWeather = serde_json::from_str(
+            r#"{"timestamp": "2023-08-07T12:00:00+00:00", "source_id": 1, "wind_direction_30": 180, "wind_direction_60": 0, "wind_speed_60": 2.0}"#,
+        )
+        .unwrap();
+        let direction = current.typed_wind_direction().unwrap();
+        assert_eq!(direction.value.compass_point(), "S");
+        assert_eq!(direction.interval, ObservationInterval::Minutes30);
+        let components = current.wind_components().unwrap();
+        assert_eq!(components.interval, ObservationInterval::Minutes60);
+        assert_close(components.value.1, -2.0);
+    }
+
+    #[test]
+    fn test_daily_wind_direction() {
+        let response: WeatherResponse = serde_json::from_str(
+            r#"{"weather": [
+                {"timestamp": "2023-08-07T01:00:00+00:00", "source_id": 1, "wind_direction": 350},
+                {"timestamp": "2023-08-07T02:00:00+00:00", "source_id": 1, "wind_direction": 20},
+                {"timestamp": "2023-08-07T03:00:00+00:00", "source_id": 1}
+            ], "sources": []}"#,
+        )
+        .unwrap();
+        let summaries: Vec<DailySummary> = response.daily_summary();
+        assert_close(summaries[0].wind_direction.unwrap().degrees(), 5.0);
+    }
+}
+
+#[cfg(test)]
+mod precipitation_tests {
+    use super::*;
+    use brightsky::precipitation::{AccumulationPeriod, RecentPrecipitation};
+
+    fn response() -> WeatherResponse {
+        serde_json::from_str(
+            r#"{"weather": [
+                {"timestamp": "2023-08-07T22:00:00+02:00", "source_id": 1, "precipitation": 0.2},
+                {"timestamp": "2023-08-07T23:00:00+02:00", "source_id": 1, "precipitation": 0.3},
+                {"timestamp": "2023-08-08T00:00:00+02:00", "source_id": 1, "precipitation": 1.0},
+                {"timestamp": "2023-08-08T01:00:00+02:00", "source_id": 1},
+                {"timestamp": "2023-08-08T02:00:00+02:00", "source_id": 1, "precipitation": 0.5}
+            ], "sources": []}"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_precipitation_accumulation() {
+        let response = response();
+
+        let three_hourly = response.precipitation_accumulation(AccumulationPeriod::Hours3);
+        assert_eq!(three_hourly.len(), 2);
+        assert_eq!(
+            three_hourly[0].start.to_rfc3339(),
+            "2023-08-07T21:00:00+02:00"
+        );
+        assert_eq!(
+            three_hourly[0].end.to_rfc3339(),
+            "2023-08-08T00:00:00+02:00"
+        );
+        assert_eq!(three_hourly[0].total, Some(1.5));
+        assert!(three_hourly[0].is_complete());
+        assert_eq!(three_hourly[1].total, Some(0.5));
+        assert_eq!(three_hourly[1].hours, 1);
+        assert_eq!(three_hourly[1].missing_hours, 2);
+
+        let daily = response.precipitation_accumulation(AccumulationPeriod::Daily);
+        assert_eq!(daily.len(), 2);
+        assert_eq!(daily[0].start.to_rfc3339(), "2023-08-07T00:00:00+02:00");
+        assert_eq!(daily[0].total, Some(1.5));
+        assert_eq!(daily[0].missing_hours, 21);
+    }
+
+    #[test]
+    fn test_accumulation_without_values() {
+        let mut response = response();
+        for record in &mut response.weather {
+            record.precipitation = None;
+        }
+        let six_hourly = response.precipitation_accumulation(AccumulationPeriod::Hours6);
+        assert_eq!(six_hourly.len(), 2);
+        assert_eq!(six_hourly[0].total, None);
+        assert_eq!(six_hourly[0].missing_hours, 6);
+    }
+
+    #[test]
+    fn test_recent_precipitation() {
+        let current: CurrentWeather = serde_json::from_str(
+            r#"{"timestamp": "2023-08-07T12:30:00+00:00", "source_id": 1, "precipitation_10": 0.1, "precipitation_30": 0.6, "precipitation_60": 0.4}"#,
+        )
+        .unwrap();
+        let recent = current.recent_precipitation();
+        assert_eq!(recent.minutes_60, Some(0.6));
+        assert!(recent.is_complete());
+        let total = recent.total().unwrap();
+        assert_eq!(total.interval, ObservationInterval::Minutes60);
+        assert_eq!(total.value, 0.6);
+        let rate = recent.rate().unwrap();
+        assert_eq!(rate.interval, ObservationInterval::Minutes10);
+        assert!((rate.value - 0.6).abs() < 1e-9);
+
+        let empty = RecentPrecipitation::default();
+        assert_eq!(empty.total(), None);
+        assert_eq!(empty.missing().count(), 3);
+    }
+}
+
+#[cfg(test)]
+mod units_tests {
+    use super::*;
+    use brightsky::units::{Pressure, Temperature, WindSpeed};
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn test_unit_conversions() {
+        let temperature = Temperature::new(20.0, UnitType::Dwd);
+        assert_close(temperature.to_kelvin(), 293.15);
+        assert_close(temperature.to_fahrenheit(), 68.0);
+        assert_eq!(temperature.convert(UnitType::Si).units(), UnitType::Si);
+
+        let pressure = Pressure::new(101325.0, UnitType::Si);
+        assert_close(pressure.to_hpa(), 1013.25);
+
+        let wind = WindSpeed::new(36.0, UnitType::Dwd);
+        assert_close(wind.to_ms(), 10.0);
+        assert_close(wind.convert(UnitType::Si).value(), 10.0);
+        assert_eq!(wind.to_string(), "36 km/h");
+    }
+
+    #[test]
+    fn test_convert_units() {
+        let mut record: Weather = serde_json::from_str(
+            r#"{"timestamp": "2023-08-07T12:00:00+00:00", "source_id": 1, "temperature": 20.0, "dew_point": 10.0, "pressure_msl": 1013.2, "wind_speed": 36.0, "wind_gust_speed": 72.0, "solar": 0.5, "sunshine": 30.0, "precipitation": 1.2, "relative_humidity": 60}"#,
+        )
+        .unwrap();
+        let original = record.clone();
+
+        record.convert_units(UnitType::Dwd, UnitType::Si);
+        assert_close(record.temperature.unwrap(), 293.15);
+        assert_close(record.dew_point.unwrap(), 283.15);
+        assert_close(record.pressure_msl.unwrap(), 101320.0);
+        assert_close(record.wind_speed.unwrap(), 10.0);
+        assert_close(record.wind_gust_speed.unwrap(), 20.0);
+        assert_close(record.solar.unwrap(), 1_800_000.0);
+        assert_close(record.sunshine.unwrap(), 1800.0);
+        assert_eq!(record.precipitation, Some(1.2));
+        assert_eq!(record.relative_humidity, Some(60));
+
+        record.convert_units(UnitType::Si, UnitType::Si);
+        assert_close(record.temperature.unwrap(), 293.15);
+
+        record.convert_units(UnitType::Si, UnitType::Dwd);
+        assert_close(record.temperature.unwrap(), 20.0);
+        assert_close(record.solar.unwrap(), 0.5);
+        assert_close(record.sunshine.unwrap(), original.sunshine.unwrap());
+
+        let mut current: CurrentWeather = serde_json::from_str(
+            r#"{"timestamp": "2023-08-07T12:00:00+00:00", "source_id": 1, "wind_gust_speed_30": 18.0, "solar_10": 0.1, "sunshine_60": 45.0}"#,
+        )
+        .unwrap();
+        current.convert_units(UnitType::Dwd, UnitType::Si);
+        assert_close(current.wind_gust_speed_30.unwrap(), 5.0);
+        assert_close(current.solar_10.unwrap(), 360_000.0);
+        assert_close(current.sunshine_60.unwrap(), 2700.0);
+    }
+
+    #[test]
+    fn test_typed_accessors() {
+        let json = r#"{
+            "timestamp": "2023-08-07T12:00:00+00:00",
+            "source_id": 1234,
+            "temperature": 22.5,
+            "pressure_msl": 1013.25
+        }"#;
+
+        let record: Weather = serde_json::from_str(json).unwrap();
+        let temperature = record.typed_temperature(UnitType::Dwd).unwrap();
+        assert_close(temperature.to_kelvin(), 295.65);
+        assert_close(
+            record.typed_pressure_msl(UnitType::Dwd).unwrap().to_pa(),
+            101325.0,
+        );
+        assert!(record.typed_wind_speed(UnitType::Dwd).is_none());
+    }
+}
+
+#[cfg(test)]
+mod radar_frames_tests {
+    use super::*;
+
+    fn radar_response(timestamps: &[&str]) -> RadarResponse {
+        let radar: Vec<serde_json::Value> = timestamps
+            .iter()
+            .map(|timestamp| {
+                serde_json::json!({
+                    "timestamp": timestamp,
+                    "source": "RADOLAN::RV::2023-08-07T12:05:00+00:00",
+                    "precipitation_5": [[0, 5], [10, 15]]
+                })
+            })
+            .collect();
+        serde_json::from_value(serde_json::json!({ "radar": radar })).unwrap()
+    }
+
+    #[test]
+    fn test_frame_interval_and_gaps() {
+        let response = radar_response(&[
+            "2023-08-07T12:00:00+00:00",
+            "2023-08-07T12:05:00+00:00",
+            "2023-08-07T12:10:00+00:00",
+            "2023-08-07T12:25:00+00:00",
+        ]);
+
+        assert_eq!(
+            response.frame_interval(),
+            Some(chrono::TimeDelta::minutes(5))
+        );
+        assert!(response.has_gaps());
+
+        let missing: Vec<String> = response
+            .missing_frames()
+            .iter()
+            .map(|t| t.to_rfc3339())
+            .collect();
+        assert_eq!(
+            missing,
+            vec!["2023-08-07T12:15:00+00:00", "2023-08-07T12:20:00+00:00"]
+        );
+    }
+
+    #[test]
+    fn test_observed_forecast_split() {
+        let response = radar_response(&[
+            "2023-08-07T12:00:00+00:00",
+            "2023-08-07T12:05:00+00:00",
+            "2023-08-07T12:10:00+00:00",
+        ]);
+
+        assert_eq!(response.observed().len(), 2);
+        assert_eq!(response.forecast().len(), 1);
+        assert!(response.radar[2].is_forecast());
+
+        let now = chrono::DateTime::parse_from_rfc3339("2023-08-07T12:00:00Z").unwrap();
+        let (past, future) = response.split_at(&now);
+        assert_eq!((past.len(), future.len()), (1, 2));
+    }
+
+    #[test]
+    fn test_frames_sorted_with_max_and_accumulation() {
+        let mut response = radar_response(&[
+            "2023-08-07T12:10:00+00:00",
+            "2023-08-07T12:00:00+00:00",
+            "2023-08-07T12:05:00+00:00",
+        ]);
+        response.bbox = Some(vec![0, 0, 1, 1]);
+        response.radar[2].precipitation_5 =
+            brightsky::types::MaybeCompressedPrecipitation::bytes(&[20, 0, 0, 65535]);
+
+        let frames: Vec<brightsky::RadarFrame> = response.frames().collect();
+        let times: Vec<String> = frames.iter().map(|f| f.time.to_rfc3339()).collect();
+        assert_eq!(
+            times,
+            vec![
+                "2023-08-07T12:00:00+00:00",
+                "2023-08-07T12:05:00+00:00",
+                "2023-08-07T12:10:00+00:00"
+            ]
+        );
+        assert_eq!(frames[1].grid.get(0, 0), Some(20));
+
+        let max = brightsky::RadarFrame::max(&frames).unwrap();
+        assert_eq!(max.data(), [20, 5, 10, 65535]);
+        let total = brightsky::RadarFrame::accumulate(&frames).unwrap();
+        assert_eq!(total.data(), [20, 10, 20, 65535]);
+
+        assert!(brightsky::RadarFrame::max(&[]).is_none());
+    }
+
+    #[test]
+    fn test_frames_skip_records_outside_bbox() {
+        let mut response = radar_response(&["2023-08-07T12:00:00+00:00"]);
+        response.bbox = Some(vec![0, 0, 2, 2]);
+        assert_eq!(response.frames().count(), 0);
+
+        let mut mismatched: Vec<brightsky::RadarFrame> =
+            radar_response(&["2023-08-07T12:00:00+00:00"])
+                .frames()
+                .collect();
+        assert!(mismatched.is_empty(), "no bbox means the full grid");
+
+        response.bbox = Some(vec![0, 0, 1, 1]);
+        mismatched.extend(response.frames());
+        mismatched.push(brightsky::RadarFrame {
+            time: mismatched[0].time,
+            grid: brightsky::RadarGrid::new(1, 1, vec![0]).unwrap(),
+        });
+        assert!(brightsky::RadarFrame::accumulate(&mismatched).is_none());
+    }
+
+    #[test]
+    fn test_frame_stats_and_total_accumulation() {
+        let mut response =
+            radar_response(&["2023-08-07T12:00:00+00:00", "2023-08-07T12:05:00+00:00"]);
+        response.bbox = Some(vec![0, 0, 1, 1]);
+
+        let frames: Vec<brightsky::RadarFrame> = response.frames().collect();
+        let stats = frames[0].stats();
+        assert_eq!(stats.pixels, 4);
+        assert_eq!(stats.rainy_pixels, 3);
+        assert_eq!(stats.max, 15);
+        assert_eq!(stats.mean, 7.5);
+        assert_eq!((stats.p50, stats.p90, stats.p99), (5, 15, 15));
+        assert_eq!(stats.rainy_fraction(), 0.75);
+
+        let total = response.total_accumulation_grid().unwrap().unwrap();
+        assert_eq!(total.data(), [0, 10, 20, 30]);
+        assert_eq!(total.percentile(0), Some(0));
+        assert_eq!(total.percentile(75), Some(20));
+        assert_eq!(total.percentile(100), Some(30));
+        assert_eq!(total.percentile(101), None);
+
+        let extremes = brightsky::RadarGrid::new(3, 1, vec![u16::MAX, 0, u16::MAX]).unwrap();
+        assert_eq!(extremes.percentile(34), Some(u16::MAX));
+        assert_eq!(extremes.stats().max, u16::MAX);
+
+        // Large enough for the histogram: every value once, in reverse
+        let values: Vec<u16> = (0..=u16::MAX).rev().collect();
+        let large = brightsky::RadarGrid::new(256, 256, values).unwrap();
+        let stats = large.stats();
+        assert_eq!((stats.pixels, stats.rainy_pixels), (65_536, 65_535));
+        assert_eq!((stats.p50, stats.p99), (32_767, 64_880));
+        assert_eq!(large.percentile(0), Some(0));
+        assert_eq!(large.percentile(100), Some(u16::MAX));
+
+        let empty = brightsky::RadarGrid::new(0, 0, vec![]).unwrap();
+        assert_eq!(empty.stats().mean, 0.0);
+        assert_eq!(empty.stats().rainy_fraction(), 0.0);
+        assert_eq!(empty.percentile(50), None);
+
+        response.radar.clear();
+        assert!(response.total_accumulation_grid().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_decode_all_reports_corrupt_frames() {
+        let mut response =
+            radar_response(&["2023-08-07T12:00:00+00:00", "2023-08-07T12:05:00+00:00"]);
+        response.bbox = Some(vec![0, 0, 1, 1]);
+        assert_eq!(response.decode_all().unwrap().len(), 2);
+
+        // A zlib header followed by garbage
+        response.radar[1].precipitation_5 = serde_json::from_str("\"eJz//w==\"").unwrap();
+        assert_eq!(response.frames().count(), 1);
+        assert!(matches!(
+            response.decode_all(),
+            Err(brightsky::DecodeError::Zlib(_))
+        ));
+        assert!(response.total_accumulation_grid().is_err());
+    }
+
+    #[test]
+    fn test_frame_interval_single_frame() {
+        let response = radar_response(&["2023-08-07T12:00:00+00:00"]);
+        assert_eq!(response.frame_interval(), None);
+        assert!(!response.has_gaps());
+    }
+}
+
+#[cfg(test)]
+mod aggregate_tests {
+    use super::*;
+
+    #[test]
+    fn test_daily_summary_day_boundaries() {
+        let json = r#"{
+            "weather": [
+                {"timestamp": "2023-08-07T22:00:00+02:00", "source_id": 1, "temperature": 18.0, "precipitation": 1.0},
+                {"timestamp": "2023-08-07T23:00:00+02:00", "source_id": 1, "temperature": 16.0, "precipitation": 2.0},
+                {"timestamp": "2023-08-08T00:00:00+02:00", "source_id": 1, "temperature": 15.0, "precipitation": 4.0},
+                {"timestamp": "2023-08-08T01:00:00+02:00", "source_id": 1, "temperature": 14.0, "precipitation": 8.0}
+            ],
+            "sources": []
+        }"#;
+
+        let response: WeatherResponse = serde_json::from_str(json).unwrap();
+        let days = response.daily_summary();
+        assert_eq!(days.len(), 2);
+
+        // Midnight record counts as instantaneous value for the 8th...
+        assert_eq!(days[0].date, NaiveDate::from_ymd_opt(2023, 8, 7).unwrap());
+        assert_eq!(days[0].records, 2);
+        let temperature = days[0].temperature.unwrap();
+        assert_eq!((temperature.min, temperature.max), (16.0, 18.0));
+        // ...but its precipitation fell during the last hour of the 7th
+        assert_eq!(days[0].precipitation_total(), Some(7.0));
+
+        assert_eq!(days[1].records, 2);
+        assert_eq!(days[1].temperature.unwrap().mean, 14.5);
+        assert_eq!(days[1].precipitation_total(), Some(8.0));
+        assert!(days[1].wind_speed.is_none());
+    }
+
+    #[test]
+    fn test_dominant_condition_and_icon_per_day() {
+        let json = r#"{
+            "weather": [
+                {"timestamp": "2023-08-07T06:00:00+02:00", "source_id": 1, "condition": "fog", "icon": "fog"},
+                {"timestamp": "2023-08-07T12:00:00+02:00", "source_id": 1, "condition": "dry", "icon": "clear-day"},
+                {"timestamp": "2023-08-07T15:00:00+02:00", "source_id": 1, "condition": "rain", "icon": "rain"},
+                {"timestamp": "2023-08-07T18:00:00+02:00", "source_id": 1, "condition": "hail", "icon": "hail"},
+                {"timestamp": "2023-08-08T02:00:00+02:00", "source_id": 1, "condition": "dry", "icon": "partly-cloudy-night"},
+                {"timestamp": "2023-08-08T03:00:00+02:00", "source_id": 1, "condition": "fog", "icon": "fog"},
+                {"timestamp": "2023-08-08T12:00:00+02:00", "source_id": 1, "condition": "dry", "icon": "partly-cloudy-day"},
+                {"timestamp": "2023-08-09T12:00:00+02:00", "source_id": 1}
+            ],
+            "sources": []
+        }"#;
+        let response: WeatherResponse = serde_json::from_str(json).unwrap();
+        let day = |d| NaiveDate::from_ymd_opt(2023, 8, d).unwrap();
+
+        // Precipitation wins by precedence, otherwise the most frequent value
+        assert_eq!(
+            response.dominant_condition_per_day(),
+            vec![
+                (day(7), WeatherCondition::Hail),
+                (day(8), WeatherCondition::Dry),
+            ]
+        );
+        assert_eq!(response.icon_for_day(day(7)), Some(WeatherIcon::Hail));
+        // Night icons count as their day variant
+        assert_eq!(
+            response.icon_for_day(day(8)),
+            Some(WeatherIcon::PartlyCloudyDay)
+        );
+        assert_eq!(response.icon_for_day(day(9)), None);
+    }
+}
+
+#[cfg(test)]
+mod sources_tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_fallback_sources() {
+        let json = r#"{
+            "weather": [{
+                "timestamp": "2023-08-07T12:00:00+00:00", "source_id": 1,
+                "temperature": 21.5, "wind_speed": 12.0, "condition": "dry",
+                "fallback_source_ids": {"wind_speed": 2, "visibility": 3}
+            }],
+            "sources": [
+                {"id": 1, "station_name": "Berlin-Tempelhof", "observation_type": "historical",
+                 "first_record": "2010-01-01T00:00:00+00:00", "last_record": "2023-08-07T12:00:00+00:00",
+                 "lat": 52.47, "lon": 13.40, "height": 48.0},
+                {"id": 2, "station_name": "Berlin-Dahlem", "observation_type": "historical",
+                 "first_record": "2010-01-01T00:00:00+00:00", "last_record": "2023-08-07T12:00:00+00:00",
+                 "lat": 52.45, "lon": 13.30, "height": 51.0}
+            ]
+        }"#;
+        let response: WeatherResponse = serde_json::from_str(json).unwrap();
+        let record = &response.weather[0];
+
+        assert_eq!(record.source_id_for(WeatherParameter::Temperature), 1);
+        assert_eq!(record.source_id_for(WeatherParameter::WindSpeed), 2);
+        // Fallback sources missing from `sources` do not resolve
+        assert_eq!(record.source_id_for(&WeatherParameter::Visibility), 3);
+        assert!(
+            record
+                .source_for(&WeatherParameter::Visibility, &response.sources)
+                .is_none()
+        );
+
+        let resolved = response.resolved().next().unwrap();
+        assert_eq!(resolved.source.unwrap().id, 1);
+        let wind_speed = resolved.wind_speed.unwrap();
+        assert_eq!(wind_speed.value, 12.0);
+        assert_eq!(
+            wind_speed.source.unwrap().station_name.as_deref(),
+            Some("Berlin-Dahlem")
+        );
+        assert_eq!(resolved.condition.unwrap().value, &WeatherCondition::Dry);
+        assert!(resolved.visibility.is_none());
+        assert!(resolved.dew_point.is_none());
+    }
+
+    #[test]
+    fn test_source_lookup() {
+        let json = r#"{
+            "weather": [
+                {"timestamp": "2023-08-07T12:00:00+00:00", "source_id": 2},
+                {"timestamp": "2023-08-07T13:00:00+00:00", "source_id": 3}
+            ],
+            "sources": [
+                {"id": 1, "station_name": "Berlin-Tempelhof", "observation_type": "historical",
+                 "first_record": "2010-01-01T00:00:00+00:00", "last_record": "2023-08-07T12:00:00+00:00",
+                 "lat": 52.47, "lon": 13.40, "height": 48.0},
+                {"id": 2, "station_name": "Berlin-Dahlem", "observation_type": "historical",
+                 "first_record": "2010-01-01T00:00:00+00:00", "last_record": "2023-08-07T12:00:00+00:00",
+                 "lat": 52.45, "lon": 13.30, "height": 51.0}
+            ]
+        }"#;
+        let response: WeatherResponse = serde_json::from_str(json).unwrap();
+
+        assert_eq!(response.source_by_id(1).unwrap().lat, 52.47);
+        assert!(response.source_by_id(3).is_none());
+        let first = &response.weather[0];
+        assert_eq!(
+            first
+                .primary_source(&response)
+                .unwrap()
+                .station_name
+                .as_deref(),
+            Some("Berlin-Dahlem")
+        );
+        assert!(response.weather[1].primary_source(&response).is_none());
+
+        let index = response.source_index();
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.get(1), response.source_by_id(1));
+        assert_eq!(index.primary_source(first), first.primary_source(&response));
+        assert!(index.primary_source(&response.weather[1]).is_none());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_nearest_stations() {
+        let source = |id: i64, lat: f64, lon: f64| {
+            serde_json::from_value::<Source>(serde_json::json!({
+                "id": id, "observation_type": "historical",
+                "first_record": "2010-01-01T00:00:00+00:00",
+                "last_record": "2023-08-07T12:00:00+00:00",
+                "lat": lat, "lon": lon, "height": 50.0
+            }))
+            .unwrap()
+        };
+        let sources = vec![
+            source(1, 52.0, 13.0),
+            source(2, 52.1, 13.0),
+            source(3, 52.0, 13.2),
+            // Listed twice, returned once
+            source(2, 52.1, 13.0),
+        ];
+
+        let nearest = brightsky::sources::nearest_stations(
+            &sources,
+            Coordinates::new(52.09, 13.0).unwrap(),
+            5,
+        );
+        let ids: Vec<i64> = nearest.iter().map(|(source, _)| source.id).collect();
+        assert_eq!(ids, vec![2, 1, 3]);
+        assert!((nearest[0].1 - 1_112.0).abs() < 1.0);
+        assert!(nearest.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+
+        assert_eq!(
+            brightsky::sources::nearest_stations(
+                &sources,
+                Coordinates::new(52.0, 13.0).unwrap(),
+                1
+            )
+            .len(),
+            1
+        );
+        assert!(
+            brightsky::sources::nearest_stations(
+                &sources,
+                Coordinates::new(52.0, 13.0).unwrap(),
+                0
+            )
+            .is_empty()
+        );
+    }
+}
+
+#[cfg(test)]
+mod rules_tests {
+    use super::*;
+    use brightsky::rules::{self, Op, Rule, UnknownField};
+    use brightsky::types::WeatherParameter;
+
+    #[test]
+    fn test_duration_requires_consecutive_hours() {
+        let json = r#"{
+            "weather": [
+                {"timestamp": "2023-08-07T10:00:00+00:00", "source_id": 1, "wind_gust_speed": 70.0},
+                {"timestamp": "2023-08-07T11:00:00+00:00", "source_id": 1, "wind_gust_speed": 65.0},
+                {"timestamp": "2023-08-07T12:00:00+00:00", "source_id": 1, "wind_gust_speed": 40.0},
+                {"timestamp": "2023-08-07T13:00:00+00:00", "source_id": 1, "wind_gust_speed": 80.0},
+                {"timestamp": "2023-08-07T15:00:00+00:00", "source_id": 1, "wind_gust_speed": 80.0},
+                {"timestamp": "2023-08-07T16:00:00+00:00", "source_id": 1}
+            ],
+            "sources": []
+        }"#;
+        let response: WeatherResponse = serde_json::from_str(json).unwrap();
+
+        let single = Rule::new(WeatherParameter::WindGustSpeed, Op::Gt, 60.0).unwrap();
+        let sustained = single.clone().with_duration(2);
+        let matches = rules::evaluate(&response, &[single, sustained]);
+
+        // The gap between 13:00 and 15:00 splits the runs
+        let spans: Vec<_> = matches
+            .iter()
+            .map(|m| {
+                (
+                    m.rule,
+                    m.start.format("%H").to_string(),
+                    m.end.format("%H").to_string(),
+                )
+            })
+            .collect();
+        let span = |rule, start: &str, end: &str| (rule, start.to_string(), end.to_string());
+        assert_eq!(
+            spans,
+            vec![
+                span(0, "10", "11"),
+                span(0, "13", "13"),
+                span(0, "15", "15"),
+                span(1, "10", "11"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_current_weather_fields() {
+        let json = r#"{
+            "weather": {
+                "timestamp": "2023-08-07T12:30:00+00:00",
+                "source_id": 1,
+                "temperature": -2.0,
+                "wind_speed_10": 15.0,
+                "wind_speed_60": 30.0
+            },
+            "sources": []
+        }"#;
+        let response: CurrentWeatherResponse = serde_json::from_str(json).unwrap();
+
+        let rules: Vec<Rule> = serde_json::from_str(
+            r#"[
+                {"field": "temperature", "op": "<", "value": 0.0, "duration": 6},
+                {"field": "wind_speed", "op": "gt", "value": 20.0},
+                {"field": "wind_speed_60", "op": ">", "value": 20.0}
+            ]"#,
+        )
+        .unwrap();
+        let matched: Vec<usize> = rules::evaluate(&response, &rules)
+            .iter()
+            .map(|m| m.rule)
+            .collect();
+        // `wind_speed` uses the 10-minute value
+        assert_eq!(matched, vec![0, 2]);
+    }
+
+    #[test]
+    fn test_rule_serialization() {
+        let rule = Rule::new(WeatherParameter::Precipitation, Op::Ge, 5.0)
+            .unwrap()
+            .with_duration(3);
+        let json = serde_json::to_string(&rule).unwrap();
+        assert_eq!(
+            json,
+            r#"{"field":"precipitation","op":">=","value":5.0,"duration":3}"#
+        );
+        assert_eq!(serde_json::from_str::<Rule>(&json).unwrap(), rule);
+        assert_eq!(rule.to_string(), "precipitation >= 5 for 3h");
+        assert!(serde_json::from_str::<Rule>(r#"{"field": "x", "op": "~", "value": 1}"#).is_err());
+    }
+
+    #[test]
+    fn test_unknown_fields_are_rejected() {
+        for field in ["humidity", "condition", "icon"] {
+            let json = format!(r#"{{"field": "{field}", "op": "!=", "value": 0.0}}"#);
+            let error = serde_json::from_str::<Rule>(&json).unwrap_err();
+            assert!(error.to_string().contains(field), "{error}");
+        }
+
+        assert_eq!(
+            Rule::new(WeatherParameter::Condition, Op::Eq, 1.0),
+            Err(UnknownField(WeatherParameter::Condition))
+        );
+        assert!(Rule::new(WeatherParameter::WindSpeed10, Op::Gt, 20.0).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod alert_filter_tests {
+    use super::*;
+    use chrono::DateTime;
+
+    fn alert(id: i64, onset: &str, expires: Option<&str>, severity: &str, code: i64) -> Alert {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "alert_id": id.to_string(),
+            "status": "actual",
+            "effective": "2023-08-07T06:00:00+00:00",
+            "onset": onset,
+            "expires": expires,
+            "severity": severity,
+            "event_code": code,
+            "headline_en": "",
+            "headline_de": "",
+            "description_en": "",
+            "description_de": ""
+        }))
+        .unwrap()
+    }
+
+    fn response() -> AlertsResponse {
+        AlertsResponse {
+            alerts: vec![
+                alert(1, "2023-08-07T12:00:00+00:00", None, "minor", 51),
+                alert(
+                    2,
+                    "2023-08-07T08:00:00+00:00",
+                    Some("2023-08-07T10:00:00+00:00"),
+                    "severe",
+                    51,
+                ),
+                alert(
+                    3,
+                    "2023-08-07T09:00:00+02:00",
+                    Some("2023-08-07T18:00:00+02:00"),
+                    "extreme",
+                    63,
+                ),
+            ],
+            location: None,
+        }
+    }
+
+    fn ids(response: &AlertsResponse) -> Vec<i64> {
+        response.alerts.iter().map(|alert| alert.id).collect()
+    }
+
+    #[test]
+    fn test_active_at_uses_onset_and_expires() {
+        let at = |time| DateTime::parse_from_rfc3339(time).unwrap();
+
+        assert_eq!(
+            ids(&response().active_at(&at("2023-08-07T09:00:00+00:00"))),
+            [2, 3]
+        );
+        // Expiry is exclusive, open-ended alerts stay active
+        assert_eq!(
+            ids(&response().active_at(&at("2023-08-07T10:00:00+00:00"))),
+            [3]
+        );
+        assert_eq!(
+            ids(&response().active_at(&at("2023-08-08T00:00:00+00:00"))),
+            [1]
+        );
+        assert_eq!(
+            ids(&response().upcoming_at(&at("2023-08-07T09:00:00+00:00"))),
+            [1]
+        );
+    }
+
+    #[test]
+    fn test_severity_and_event_code_filters() {
+        assert_eq!(ids(&response().min_severity(AlertSeverity::Severe)), [2, 3]);
+        assert_eq!(
+            ids(&response().min_severity(AlertSeverity::Minor)),
+            [1, 2, 3]
+        );
+        assert_eq!(ids(&response().by_event_code(&[51])), [1, 2]);
+        assert_eq!(
+            ids(&response()
+                .by_event_code(&[51])
+                .min_severity(AlertSeverity::Severe)),
+            [2]
+        );
+    }
+
+    #[test]
+    fn test_severity_and_urgency_ordering() {
+        assert!(AlertSeverity::Minor < AlertSeverity::Moderate);
+        assert!(AlertSeverity::Severe < AlertSeverity::Extreme);
+        let alerts = response().alerts;
+        let severe: Vec<i64> = alerts
+            .iter()
+            .filter(|alert| alert.severity >= Some(AlertSeverity::Severe))
+            .map(|alert| alert.id)
+            .collect();
+        assert_eq!(severe, [2, 3]);
+        assert_eq!(
+            alerts.iter().filter_map(|alert| alert.severity).max(),
+            Some(AlertSeverity::Extreme)
+        );
+
+        let other = AlertUrgency::Other("unknown".to_string());
+        assert!(AlertUrgency::Future < AlertUrgency::Immediate);
+        assert!(other < AlertUrgency::Future);
+        assert_eq!(
+            [AlertUrgency::Immediate, other.clone(), AlertUrgency::Future]
+                .into_iter()
+                .max(),
+            Some(AlertUrgency::Immediate)
+        );
+    }
+
+    #[test]
+    fn test_severity_colors() {
+        let severities = [
+            AlertSeverity::Minor,
+            AlertSeverity::Moderate,
+            AlertSeverity::Severe,
+            AlertSeverity::Extreme,
+        ];
+        for severity in severities {
+            let color = severity.dwd_color();
+            let [r, g, b] = color.rgb;
+            assert_eq!(color.hex, format!("#{r:02X}{g:02X}{b:02X}"));
+            assert_eq!(
+                severity.css_class(),
+                format!("alert-severity-{}", severity.as_str())
+            );
+        }
+        assert_eq!(AlertSeverity::Minor.dwd_color().hex, "#FFFF00");
+        assert_eq!(AlertSeverity::Extreme.dwd_color().rgb, [158, 70, 248]);
+    }
+
+    #[test]
+    fn test_alert_text_language_fallback() {
+        let mut alert = response().alerts.remove(0);
+        alert.headline_en = "Official WARNING of WIND GUSTS".to_string();
+        alert.headline_de = "Amtliche WARNUNG vor WINDBÖEN".to_string();
+        alert.description_de = "Es treten Windböen auf.".to_string();
+        alert.instruction_en = Some(String::new());
+        alert.event_de = Some("WINDBÖEN".to_string());
+
+        assert_eq!(
+            alert.headline(Language::En),
+            "Official WARNING of WIND GUSTS"
+        );
+        assert_eq!(
+            alert.headline(Language::De),
+            "Amtliche WARNUNG vor WINDBÖEN"
+        );
+        assert_eq!(alert.description(Language::En), "Es treten Windböen auf.");
+        assert_eq!(alert.instruction(Language::En), None);
+        assert_eq!(alert.event(Language::En), Some("WINDBÖEN"));
+
+        let location = |name_short: &str, district: &str, state: &str| Location {
+            warn_cell_id: 803159016,
+            name: format!("Stadt {name_short}"),
+            name_short: name_short.to_string(),
+            district: district.to_string(),
+            state: state.to_string(),
+            state_short: String::new(),
+        };
+        assert_eq!(
+            location("Göttingen", "Göttingen", "Niedersachsen").display_name(),
+            "Göttingen, Niedersachsen"
+        );
+        assert_eq!(
+            location("Hann. Münden", "Göttingen", "Niedersachsen").display_name(),
+            "Hann. Münden, Göttingen, Niedersachsen"
+        );
+        assert_eq!(
+            location("Berlin", "Berlin", "Berlin").display_name(),
+            "Berlin"
+        );
+    }
+
+    #[test]
+    fn test_sorted_by_onset_compares_instants() {
+        // 09:00+02:00 is 07:00 UTC
+        assert_eq!(ids(&response().sorted_by_onset()), [3, 2, 1]);
+    }
+}
+
+#[cfg(feature = "cap")]
+mod cap_tests {
+    use super::*;
+    use brightsky::cap::CapError;
+
+    const CAP: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<alert xmlns="urn:oasis:names:tc:emergency:cap:1.2">
+    <identifier>2.49.0.0.276.0.DWD.PVW.1691383740000.4c8a</identifier>
+    <sender>opendata@dwd.de</sender>
+    <sent>2023-08-07T06:49:00+02:00</sent>
+    <status>Actual</status>
+    <msgType>Update</msgType>
+    <scope>Public</scope>
+    <references>opendata@dwd.de,2.49.0.0.276.0.DWD.PVW.1691380000000.1,2023-08-07T05:46:40+02:00</references>
+    <info>
+        <language>de-DE</language>
+        <category>Met</category>
+        <event>WINDBÖEN</event>
+        <responseType>Prepare</responseType>
+        <urgency>Immediate</urgency>
+        <severity>Moderate</severity>
+        <certainty>Likely</certainty>
+        <eventCode><valueName>PROFILE_VERSION</valueName><value>2.1.11</value></eventCode>
+        <eventCode><valueName>II</valueName><value>51</value></eventCode>
+        <eventCode><valueName>GROUP</valueName><value>WIND</value></eventCode>
+        <onset>2023-08-07T08:00:00+02:00</onset>
+        <expires>2023-08-07T18:00:00+02:00</expires>
+        <headline>Amtliche WARNUNG vor WINDBÖEN</headline>
+        <description><![CDATA[Es treten Windböen um 55 km/h auf.]]></description>
+        <area>
+            <areaDesc>Stadt Göttingen</areaDesc>
+            <geocode><valueName>WARNCELLID</valueName><value>803159016</value></geocode>
+        </area>
+    </info>
+    <info>
+        <language>en-GB</language>
+        <category>Met</category>
+        <event>wind gusts</event>
+        <headline>Official WARNING of WIND GUSTS</headline>
+        <description>There is a risk of wind gusts at about 55 km/h.</description>
+        <instruction>Watch out for falling branches.</instruction>
+    </info>
+</alert>"#;
+
+    #[test]
+    fn test_cap_matches_json_alert() {
+        let expected: Alert = serde_json::from_value(serde_json::json!({
+            "id": 0,
+            "alert_id": "2.49.0.0.276.0.DWD.PVW.1691383740000.4c8a",
+            "status": "actual",
+            "effective": "2023-08-07T06:49:00+02:00",
+            "onset": "2023-08-07T08:00:00+02:00",
+            "expires": "2023-08-07T18:00:00+02:00",
+            "category": "met",
+            "response_type": "prepare",
+            "urgency": "immediate",
+            "severity": "moderate",
+            "certainty": "likely",
+            "event_code": 51,
+            "event_en": "wind gusts",
+            "event_de": "WINDBÖEN",
+            "headline_en": "Official WARNING of WIND GUSTS",
+            "headline_de": "Amtliche WARNUNG vor WINDBÖEN",
+            "description_en": "There is a risk of wind gusts at about 55 km/h.",
+            "description_de": "Es treten Windböen um 55 km/h auf.",
+            "instruction_en": "Watch out for falling branches.",
+            "instruction_de": null
+        }))
+        .unwrap();
+
+        assert_eq!(Alert::from_cap(CAP).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_cap_unknown_values_and_errors() {
+        let alert = Alert::from_cap(
+            &CAP.replace("Moderate", "Unknown")
+                .replace("Immediate", "Expected"),
+        )
+        .unwrap();
+        assert_eq!(alert.severity, None);
+        assert_eq!(
+            alert.urgency,
+            Some(AlertUrgency::Other("Expected".to_string()))
+        );
+
+        assert!(matches!(
+            Alert::from_cap(&CAP.replace("<onset>2023-08-07T08:00:00+02:00</onset>", "")),
+            Err(CapError::MissingElement("onset"))
+        ));
+        assert!(matches!(
+            Alert::from_cap(&CAP.replace("2023-08-07T18:00:00+02:00", "tomorrow")),
+            Err(CapError::InvalidTimestamp {
+                element: "expires",
+                ..
+            })
+        ));
+        assert!(matches!(
+            Alert::from_cap(&CAP.replace(":cap:1.2", ":cap:1.1")),
+            Err(CapError::NotCap)
+        ));
+        assert!(matches!(Alert::from_cap("<alert>"), Err(CapError::Xml(_))));
+    }
+}
+
+#[cfg(feature = "warn-cells")]
+mod warn_cells_tests {
+    use brightsky::ToBrightSkyUrl;
+    use brightsky::warn_cells::WarnCells;
+
+    const GEOJSON: &str = r#"{
+        "type": "FeatureCollection",
+        "features": [
+            {
+                "type": "Feature",
+                "properties": {"WARNCELLID": 100000001, "NAME": "Ring"},
+                "geometry": {
+                    "type": "Polygon",
+                    "coordinates": [
+                        [[0.0, 0.0], [4.0, 0.0], [4.0, 4.0], [0.0, 4.0], [0.0, 0.0]],
+                        [[1.0, 1.0], [3.0, 1.0], [3.0, 3.0], [1.0, 3.0], [1.0, 1.0]]
+                    ]
+                }
+            },
+            {
+                "type": "Feature",
+                "properties": {"warncellid": "100000002"},
+                "geometry": {
+                    "type": "MultiPolygon",
+                    "coordinates": [
+                        [[[1.5, 1.5], [2.5, 1.5], [2.0, 2.5], [1.5, 1.5]]],
+                        [[[10.0, 10.0], [11.0, 10.0], [11.0, 11.0], [10.0, 11.0], [10.0, 10.0]]]
+                    ]
+                }
+            },
+            {"type": "Feature", "properties": {"WARNCELLID": 3}, "geometry": {"type": "Point", "coordinates": [5.0, 5.0]}},
+            {"type": "Feature", "properties": null, "geometry": null}
+        ]
+    }"#;
+
+    #[test]
+    fn test_lookup_respects_holes_and_multi_polygons() {
+        let cells = WarnCells::from_slice(GEOJSON.as_bytes()).unwrap();
+        assert_eq!(cells.len(), 2);
+
+        // (lat, lon)
+        assert_eq!(cells.warn_cell_id_for((0.5, 0.5)), Some(100000001));
+        assert_eq!(cells.warn_cell_id_for((2.0, 1.2)), None);
+        assert_eq!(cells.warn_cell_id_for((1.8, 2.0)), Some(100000002));
+        assert_eq!(cells.warn_cell_id_for((10.5, 10.5)), Some(100000002));
+        assert_eq!(cells.warn_cell_id_for((5.0, 5.0)), None);
+
+        let ring = cells.get(100000001).unwrap();
+        assert_eq!(ring.name.as_deref(), Some("Ring"));
+        assert_eq!(ring.bbox, [0.0, 0.0, 4.0, 4.0]);
+        assert_eq!(cells.get(100000002).unwrap().name, None);
+    }
+
+    #[test]
+    fn test_alerts_query_for_coordinates() {
+        let cells = WarnCells::from_slice(GEOJSON.as_bytes()).unwrap();
+        let query = cells.alerts_query_for((0.5, 0.5)).unwrap().build().unwrap();
+        let url = query.to_url_string("https://api.brightsky.dev").unwrap();
+        assert!(url.contains("warn_cell_id=100000001"), "{url}");
+        assert!(cells.alerts_query_for((50.0, 50.0)).is_none());
+    }
+
+    #[test]
+    fn test_feature_without_id_is_an_error() {
+        let geojson = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "properties": {"NAME": "x"},
+             "geometry": {"type": "Polygon", "coordinates": [[[0, 0], [1, 0], [1, 1], [0, 0]]]}}
+        ]}"#;
+        assert!(WarnCells::from_slice(geojson.as_bytes()).is_err());
+    }
+}
+
+#[cfg(all(feature = "nominatim", feature = "testing"))]
+mod geocoding_tests {
+    use super::*;
+    use brightsky::geocoding::{Geocoder, Nominatim, NominatimError};
+    use brightsky::testing::MockHttpClient;
+
+    const MUENCHEN: &str = r#"[
+        {"lat": "48.1371079", "lon": "11.5753822", "name": "München",
+         "display_name": "München, Bayern, Deutschland",
+         "address": {"city": "München", "state": "Bayern", "country_code": "de"}},
+        {"lat": "not a number", "lon": "11.0", "name": "Broken", "display_name": "Broken"},
+        {"lat": "48.13", "lon": "11.57", "display_name": "80331, Altstadt, München",
+         "address": {"postcode": "80331"}}
+    ]"#;
+
+    #[test]
+    fn test_nominatim_search() {
+        let mock = MockHttpClient::new().with_fixture("/search", MUENCHEN);
+        let geocoder = Nominatim::new(mock.clone()).with_user_agent("tests/1.0");
+
+        let places = tokio_test::block_on(geocoder.search("München")).unwrap();
+        assert_eq!(places.len(), 2);
+        assert_eq!(places[0].name, "München");
+        assert_eq!(places[0].state.as_deref(), Some("Bayern"));
+        assert_eq!(places[0].coordinates.lat, 48.1371079);
+        assert_eq!(places[1].name, "80331");
+        assert_eq!(places[1].postal_code.as_deref(), Some("80331"));
+
+        let request = &mock.requests()[0];
+        assert_eq!(request.param("q"), Some("München"));
+        assert_eq!(request.param("countrycodes"), Some("de"));
+        assert_eq!(request.param("format"), Some("jsonv2"));
+        assert_eq!(request.header("user-agent"), Some("tests/1.0"));
+
+        let query = CurrentWeatherQueryBuilder::new()
+            .with_lat_lon(&places[0])
+            .build()
+            .unwrap();
+        let url = query.to_url_string("https://api.brightsky.dev").unwrap();
+        assert!(url.contains("lat=48.1371&lon=11.5754"), "{url}");
+    }
+
+    #[test]
+    fn test_nominatim_locate_and_errors() {
+        let mock = MockHttpClient::new()
+            .with_fixture("/search", "[]")
+            .with_response("/other/search", 403, "blocked");
+        let geocoder = Nominatim::new(mock.clone()).with_country_codes("");
+
+        assert_eq!(
+            tokio_test::block_on(geocoder.locate("Nowhere")).unwrap(),
+            None
+        );
+        assert_eq!(mock.requests()[0].param("countrycodes"), None);
+
+        let geocoder = Nominatim::new(mock).with_host("https://example.org/other/");
+        let err = tokio_test::block_on(geocoder.search("x")).unwrap_err();
+        assert!(matches!(err, NominatimError::Status(403)));
+    }
+}
+
+#[cfg(all(feature = "home-assistant", feature = "testing"))]
+mod home_assistant_tests {
+    use super::*;
+    use brightsky::home_assistant::{
+        HomeAssistant, HomeAssistantPublisher, MqttMessage, MqttPublisher, PublishError,
+    };
+    use brightsky::poll::AlertEvent;
+    use brightsky::testing::MockHttpClient;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<MqttMessage>>);
+
+    impl MqttPublisher for &Recorder {
+        type Error = std::convert::Infallible;
+
+        async fn publish(&self, message: MqttMessage) -> Result<(), Self::Error> {
+            self.0.lock().unwrap().push(message);
+            Ok(())
+        }
+    }
+
+    fn payload(messages: &[MqttMessage], topic: &str) -> serde_json::Value {
+        let message = messages
+            .iter()
+            .find(|message| message.topic == topic)
+            .unwrap_or_else(|| panic!("no message on {topic}"));
+        serde_json::from_str(&message.payload).unwrap()
+    }
+
+    #[test]
+    fn test_discovery_messages() {
+        let messages = HomeAssistant::new("home base")
+            .with_name("Weather")
+            .with_units(UnitType::Si)
+            .discovery();
+        assert!(messages.iter().all(|message| message.retain));
+
+        let temperature = payload(
+            &messages,
+            "homeassistant/sensor/home_base/temperature/config",
+        );
+        assert_eq!(temperature["unique_id"], "home_base_temperature");
+        assert_eq!(
+            temperature["state_topic"],
+            "brightsky/home_base/current_weather"
+        );
+        assert_eq!(
+            temperature["value_template"],
+            "{{ value_json.temperature }}"
+        );
+        assert_eq!(temperature["device_class"], "temperature");
+        assert_eq!(temperature["unit_of_measurement"], "K");
+        assert_eq!(temperature["state_class"], "measurement");
+        assert_eq!(temperature["device"]["name"], "Weather");
+
+        let condition = payload(&messages, "homeassistant/sensor/home_base/condition/config");
+        assert!(condition.get("unit_of_measurement").is_none());
+
+        let alert = payload(
+            &messages,
+            "homeassistant/binary_sensor/home_base/alert/config",
+        );
+        assert_eq!(alert["device_class"], "safety");
+        assert_eq!(alert["state_topic"], "brightsky/home_base/alerts");
+    }
+
+    #[test]
+    fn test_publisher_update() {
+        let recorder = Recorder::default();
+        let mock = MockHttpClient::new().with_sample_fixtures();
+        let client = BrightSkyClient::new(mock.clone());
+        let mut publisher = HomeAssistantPublisher::new(
+            client,
+            &recorder,
+            HomeAssistant::new("berlin"),
+            (52.52, 13.4),
+        );
+
+        tokio_test::block_on(publisher.update()).unwrap();
+        let messages = recorder.0.lock().unwrap().clone();
+        let paths: Vec<String> = mock.requests().into_iter().map(|r| r.path).collect();
+        assert_eq!(paths, ["/current_weather", "/alerts"]);
+
+        let weather = payload(&messages, "brightsky/berlin/current_weather");
+        assert_eq!(
+            weather["temperature"],
+            serde_json::to_value(CurrentWeatherResponse::sample().weather.temperature).unwrap()
+        );
+
+        let alerts = AlertsResponse::sample();
+        let summary = payload(&messages, "brightsky/berlin/alerts");
+        assert_eq!(summary["count"], alerts.alerts.len());
+        let sensors = messages
+            .iter()
+            .filter(|message| {
+                message
+                    .topic
+                    .starts_with("homeassistant/binary_sensor/berlin/alert_")
+            })
+            .count();
+        assert_eq!(sensors, alerts.alerts.len());
+
+        // Discovery is only published once
+        tokio_test::block_on(publisher.update()).unwrap();
+        let messages = recorder.0.lock().unwrap().clone();
+        let configs = messages
+            .iter()
+            .filter(|message| message.topic.ends_with("/temperature/config"))
+            .count();
+        assert_eq!(configs, 1);
+    }
+
+    #[test]
+    fn test_publisher_update_publishes_alerts_after_weather_error() {
+        let recorder = Recorder::default();
+        let mock = MockHttpClient::new().with_sample_fixtures().with_response(
+            "/current_weather",
+            500,
+            "Internal Server Error",
+        );
+        let mut publisher = HomeAssistantPublisher::new(
+            BrightSkyClient::new(mock),
+            &recorder,
+            HomeAssistant::new("berlin"),
+            (52.52, 13.4),
+        );
+
+        let result = tokio_test::block_on(publisher.update());
+        assert!(matches!(
+            result,
+            Err(PublishError::Client(ClientError::Api(_)))
+        ));
+        let messages = recorder.0.lock().unwrap();
+        assert!(
+            !messages
+                .iter()
+                .any(|message| message.topic == "brightsky/berlin/current_weather")
+        );
+        assert!(
+            messages
+                .iter()
+                .any(|message| message.topic == "brightsky/berlin/alerts")
+        );
+    }
+
+    #[test]
+    fn test_publisher_republishes_alerts_after_mqtt_error() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        /// Fails to publish alert sensors while `failing` is set.
+        #[derive(Default)]
+        struct Flaky {
+            failing: AtomicBool,
+            messages: Mutex<Vec<MqttMessage>>,
+        }
+
+        impl MqttPublisher for &Flaky {
+            type Error = String;
+
+            async fn publish(&self, message: MqttMessage) -> Result<(), Self::Error> {
+                if self.failing.load(Ordering::SeqCst) && message.topic.contains("/alert_") {
+                    return Err("disconnected".to_string());
+                }
+                self.messages.lock().unwrap().push(message);
+                Ok(())
+            }
+        }
+
+        let flaky = Flaky::default();
+        let mut publisher = HomeAssistantPublisher::new(
+            BrightSkyClient::new(MockHttpClient::new().with_sample_fixtures()),
+            &flaky,
+            HomeAssistant::new("berlin"),
+            (52.52, 13.4),
+        );
+
+        flaky.failing.store(true, Ordering::SeqCst);
+        let result = tokio_test::block_on(publisher.update());
+        assert!(matches!(result, Err(PublishError::Mqtt(_))));
+
+        flaky.failing.store(false, Ordering::SeqCst);
+        tokio_test::block_on(publisher.update()).unwrap();
+        let messages = flaky.messages.lock().unwrap();
+        let sensors = messages
+            .iter()
+            .filter(|message| {
+                message
+                    .topic
+                    .starts_with("homeassistant/binary_sensor/berlin/alert_")
+            })
+            .count();
+        assert_eq!(sensors, AlertsResponse::sample().alerts.len());
+    }
+
+    #[test]
+    fn test_publisher_run_until_stops_before_first_update() {
+        let recorder = Recorder::default();
+        let mock = MockHttpClient::new().with_sample_fixtures();
+        let mut publisher = HomeAssistantPublisher::new(
+            BrightSkyClient::new(mock.clone()),
+            &recorder,
+            HomeAssistant::new("berlin"),
+            (52.52, 13.4),
+        );
+
+        tokio_test::block_on(publisher.run_until(async {}));
+        assert!(mock.requests().is_empty());
+        assert!(recorder.0.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_expired_alert_removes_entity() {
+        let home_assistant = HomeAssistant::new("berlin");
+        let alert = AlertsResponse::sample().alerts.remove(0);
+
+        let added = home_assistant.alert_messages(&AlertEvent::New(alert.clone()));
+        let config: serde_json::Value = serde_json::from_str(&added[0].payload).unwrap();
+        assert_eq!(config["name"], alert.event_en.clone().unwrap());
+        assert!(added.iter().any(|message| message.payload == "ON"));
+
+        let removed = home_assistant.alert_messages(&AlertEvent::Expired(alert));
+        assert_eq!(removed.len(), 3);
+        assert_eq!(removed[0].topic, added[0].topic);
+        assert!(
+            removed
+                .iter()
+                .all(|message| message.payload.is_empty() && message.retain)
+        );
+    }
+}
+
+#[cfg(test)]
+mod radar_grid_tests {
+    use super::*;
+
+    fn radar(precipitation_5: MaybeCompressedPrecipitation) -> Radar {
+        Radar {
+            timestamp: chrono::DateTime::parse_from_rfc3339("2023-08-07T12:00:00+00:00").unwrap(),
+            source: "RADOLAN::RV::2023-08-07T12:00:00+00:00".to_string(),
+            precipitation_5,
+        }
+    }
+
+    #[test]
+    fn test_flat_data_to_grid() {
+        let record = radar(MaybeCompressedPrecipitation::bytes(&[0, 1, 2, 3, 4, 5]));
+        let grid = record.to_grid(&[10, 20, 11, 22]).unwrap();
+
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid.get(0, 0), Some(0));
+        assert_eq!(grid.get(1, 1), Some(4));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(0, 2), None);
+
+        let rows: Vec<&[u16]> = grid.rows().collect();
+        assert_eq!(rows, vec![&[0, 1, 2][..], &[3, 4, 5][..]]);
+    }
+
+    #[test]
+    fn test_plain_data_to_grid() {
+        let record = radar(MaybeCompressedPrecipitation::Plain(vec![
+            vec![7, 8],
+            vec![9, 10],
+        ]));
+        let grid = record.to_grid(&[0, 0, 1, 1]).unwrap();
+
+        assert_eq!(grid.get(1, 0), Some(8));
+        assert_eq!(grid.into_data(), vec![7, 8, 9, 10]);
+    }
+
+    #[test]
+    fn test_mismatched_bbox() {
+        let record = radar(MaybeCompressedPrecipitation::compressed(&[0; 6]));
+
+        assert!(record.to_grid(&[0, 0, 2, 2]).is_none());
+        assert!(record.to_grid(&[0, 0, 1]).is_none());
+        assert!(record.to_grid(&[5, 0, 0, 2]).is_none());
+        assert!(RadarGrid::new(2, 2, vec![0; 3]).is_none());
+    }
+
+    #[test]
+    fn test_render_ascii() {
+        // A rain cell in the lower right of a 6x4 grid
+        let mut data = vec![0; 24];
+        data[4 + 6 * 3] = 30;
+        data[5 + 6 * 3] = 600;
+        let grid = RadarGrid::new(6, 4, data).unwrap();
+
+        assert_eq!(grid.render_ascii_plain(6), "      \n    ▒█\n");
+        assert_eq!(grid.render_ascii_plain(3), "  █\n");
+        assert_eq!(
+            grid.render_ascii(6),
+            "      \n    \x1b[34m▒\x1b[31m█\x1b[0m\n"
+        );
+        assert_eq!(grid.render_ascii(0), "");
+    }
+}
+
+#[cfg(test)]
+mod coordinates_tests {
+    use super::*;
+
+    #[test]
+    fn test_coordinates_validation() {
+        assert!(Coordinates::new(52.52, 13.4).is_ok());
+        assert!(matches!(
+            Coordinates::new(91.0, 13.4),
+            Err(BrightSkyError::Query(QueryError::InvalidLatitude(_)))
+        ));
+        assert!(matches!(
+            Coordinates::new(52.52, -181.0),
+            Err(BrightSkyError::Query(QueryError::InvalidLongitude(_)))
+        ));
+    }
+
+    #[test]
+    fn test_builders_accept_coordinates() {
+        let berlin = Coordinates::new(52.52, 13.4).unwrap();
+
+        let query = CurrentWeatherQueryBuilder::new()
+            .with_lat_lon(berlin)
+            .build()
+            .unwrap();
+        assert_eq!(query.lat, Some(52.52));
+        assert_eq!(query.lon, Some(13.4));
+
+        let query = RadarWeatherQueryBuilder::new()
+            .with_lat_lon(berlin)
+            .build()
+            .unwrap();
+        assert_eq!(query.lat, Some(52.52));
+    }
+
+    #[test]
+    fn test_coordinates_try_from_tuple() {
+        assert_eq!(
+            Coordinates::try_from((52.52, 13.4)).unwrap(),
+            Coordinates::new(52.52, 13.4).unwrap()
+        );
+        assert!(matches!(
+            Coordinates::try_from((f64::NAN, 13.4)),
+            Err(BrightSkyError::Query(QueryError::InvalidLatitude(_)))
+        ));
+        assert!(matches!(
+            Coordinates::try_from((52.52, 181.0)),
+            Err(BrightSkyError::Query(QueryError::InvalidLongitude(_)))
+        ));
+
+        // Tuples passed as a location are checked when the query is built
+        assert!(matches!(
+            RadarWeatherQueryBuilder::new()
+                .with_location((52.52, f64::NAN))
+                .build(),
+            Err(BrightSkyError::Query(QueryError::InvalidLongitude(_)))
+        ));
+    }
+
+    #[test]
+    fn test_snap_to_grid() {
+        let grid = CoordinateFuzzing::Snap { cell_size: 0.5 };
+
+        let a = Coordinates::new(52.1, -13.1).unwrap().fuzzed(grid);
+        let b = Coordinates::new(52.4, -13.4).unwrap().fuzzed(grid);
+
+        assert_eq!(a, b);
+        assert_eq!(
+            a,
+            Coordinates {
+                lat: 52.25,
+                lon: -13.25
+            }
+        );
+        assert_eq!(a.fuzzed(grid), a);
+    }
+
+    #[test]
+    fn test_jitter_within_cell() {
+        let grid = CoordinateFuzzing::Jitter {
+            cell_size: 0.1,
+            seed: 42,
+        };
+        let original = Coordinates::new(52.5186, 13.3761).unwrap();
+        let fuzzed = original.fuzzed(grid);
+
+        assert!((52.5..52.6).contains(&fuzzed.lat));
+        assert!((13.3..13.4).contains(&fuzzed.lon));
+        assert_ne!(fuzzed, original);
+        // Same cell, same point
+        assert_eq!(Coordinates::new(52.55, 13.31).unwrap().fuzzed(grid), fuzzed);
+        assert_eq!(fuzzed.fuzzed(grid), fuzzed);
+
+        let other_seed = CoordinateFuzzing::Jitter {
+            cell_size: 0.1,
+            seed: 7,
+        };
+        assert_ne!(original.fuzzed(other_seed), fuzzed);
+    }
+
+    #[test]
+    fn test_builder_fuzzing_order() {
+        let grid = CoordinateFuzzing::Snap { cell_size: 0.1 };
+
+        let before = AlertsQueryBuilder::new()
+            .with_coordinate_fuzzing(grid)
+            .with_lat_lon((52.5186, 13.3761));
+        let after = AlertsQueryBuilder::new()
+            .with_lat_lon((52.5186, 13.3761))
+            .with_coordinate_fuzzing(grid);
+
+        assert_eq!(before, after);
+        assert_eq!(
+            before
+                .build()
+                .unwrap()
+                .to_url_string(BRIGHT_SKY_API)
+                .unwrap(),
+            "https://api.brightsky.dev/alerts?lat=52.55&lon=13.35"
+        );
+    }
+}
+
+#[cfg(test)]
+mod projection_tests {
+    use brightsky::projection::*;
+
+    #[test]
+    fn test_grid_corners() {
+        // Corners of the RADOLAN 1100x1200 grid published by DWD
+        let corners = [
+            ((0.0, 0.0), (55.86, 1.47)),
+            ((0.0, 1199.0), (45.70, 3.57)),
+            ((1099.0, 0.0), (55.84, 18.72)),
+            ((1099.0, 1199.0), (45.69, 16.58)),
+        ];
+
+        for ((x, y), (lat, lon)) in corners {
+            let (actual_lat, actual_lon) = pixel_to_latlon(x, y);
+            assert!((actual_lat - lat).abs() < 0.01, "{x},{y}: {actual_lat}");
+            assert!((actual_lon - lon).abs() < 0.01, "{x},{y}: {actual_lon}");
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for (lat, lon) in [(52.0, 7.6), (47.42, 10.98), (54.18, 7.89)] {
+            let (x, y) = latlon_to_pixel(lat, lon);
+            assert!((0.0..GRID_WIDTH as f64).contains(&x));
+            assert!((0.0..GRID_HEIGHT as f64).contains(&y));
+
+            let (actual_lat, actual_lon) = pixel_to_latlon(x, y);
+            assert!((actual_lat - lat).abs() < 1e-9);
+            assert!((actual_lon - lon).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_bbox_around() {
+        let center = brightsky::Coordinates::new(52.52, 13.4).unwrap();
+        let bbox = brightsky::RadarBbox::around(center, 30.0).unwrap();
+
+        // Every pixel whose center lies within the radius is inside the bbox
+        let (x, y) = latlon_to_pixel(center.lat, center.lon);
+        let (x, y) = (x.round() as i64, y.round() as i64);
+        for row in y - 40..=y + 40 {
+            for column in x - 40..=x + 40 {
+                let pixel = pixel_to_latlon(column as f64, row as f64);
+                let pixel = brightsky::Coordinates::try_from(pixel).unwrap();
+                if center.distance_to(pixel) <= 30_000.0 {
+                    assert!((bbox.top..=bbox.bottom).contains(&row));
+                    assert!((bbox.left..=bbox.right).contains(&column));
+                }
+            }
+        }
+        // ...and the bbox is not much larger than the circle
+        assert!(bbox.width() <= 66 && bbox.height() <= 66);
+
+        // Cut to the grid near its edge, one pixel for a zero radius
+        let corner = pixel_to_latlon(0.0, 0.0);
+        let bbox = brightsky::RadarBbox::around(corner, 10.0).unwrap();
+        assert_eq!((bbox.top, bbox.left), (0, 0));
+        let bbox = brightsky::RadarBbox::around(corner, 0.0).unwrap();
+        assert_eq!((bbox.width(), bbox.height()), (1, 1));
+
+        assert_eq!(brightsky::RadarBbox::around(center, f64::NAN), None);
+        assert_eq!(brightsky::RadarBbox::around((f64::NAN, 13.4), 30.0), None);
+    }
+}
+
+#[cfg(test)]
+mod observation_interval_tests {
+    use super::*;
+
+    #[test]
+    fn test_best_values_prefer_shortest_interval() {
+        let weather: CurrentWeather = serde_json::from_value(serde_json::json!({
+            "timestamp": "2023-08-07T12:30:00+00:00",
+            "source_id": 1234,
+            "precipitation_10": 0.2,
+            "precipitation_60": 1.4,
+            "sunshine_30": 12.0,
+            "wind_direction_60": 270,
+            "wind_gust_speed_30": 40.0,
+            "wind_gust_speed_60": 45.0
+        }))
+        .unwrap();
+
+        let precipitation = weather.best_precipitation().unwrap();
+        assert_eq!(precipitation.value, 0.2);
+        assert_eq!(precipitation.interval, ObservationInterval::Minutes10);
+
+        let sunshine = weather.best_sunshine().unwrap();
+        assert_eq!(sunshine.interval.minutes(), 30);
+
+        assert_eq!(
+            weather.best_wind_direction(),
+            Some(IntervalValue {
+                value: 270,
+                interval: ObservationInterval::Minutes60
+            })
+        );
+        assert_eq!(weather.best_wind_gust_speed().unwrap().value, 40.0);
+
+        assert!(weather.best_solar().is_none());
+        assert!(weather.best_wind_speed().is_none());
+    }
+}
+
+#[cfg(test)]
+mod hourly_conversion_tests {
+    use super::*;
+
+    #[test]
+    fn test_current_weather_as_hourly() {
+        let current: CurrentWeather = serde_json::from_value(serde_json::json!({
+            "timestamp": "2023-08-07T12:30:00+00:00",
+            "source_id": 1234,
+            "temperature": 22.3,
+            "relative_humidity": 55,
+            "precipitation_10": 0.1,
+            "precipitation_60": 0.7,
+            "sunshine_60": 35.0,
+            "wind_speed_10": 15.0,
+            "wind_speed_60": 12.0,
+            "wind_gust_direction_60": 240,
+            "fallback_source_ids": {
+                "pressure_msl": 11,
+                "wind_speed_10": 12,
+                "wind_speed_60": 13
+            }
+        }))
+        .unwrap();
+
+        let hourly = current.as_hourly();
+
+        assert_eq!(hourly.timestamp, current.timestamp);
+        assert_eq!(hourly.temperature, Some(22.3));
+        assert_eq!(hourly.relative_humidity, Some(55));
+        assert_eq!(hourly.precipitation, Some(0.7));
+        assert_eq!(hourly.sunshine, Some(35.0));
+        assert_eq!(hourly.wind_speed, Some(12.0));
+        assert_eq!(hourly.wind_gust_direction, Some(240));
+        assert_eq!(hourly.precipitation_probability, None);
+
+        let fallback = hourly.fallback_source_ids.unwrap();
+        assert_eq!(fallback.len(), 2);
+        assert_eq!(fallback.get(&WeatherParameter::PressureMsl), Some(&11));
+        assert_eq!(fallback.get(&WeatherParameter::WindSpeed), Some(&13));
+
+        let response = WeatherResponse {
+            weather: vec![current.as_hourly()],
+            sources: vec![],
+        };
+        let days = response.daily_summary();
+        assert_eq!(days[0].precipitation_total(), Some(0.7));
+    }
+}
+
+#[cfg(feature = "display")]
+mod display_tests {
+    use super::*;
+    use brightsky::display::{Table, TableOptions};
+    use brightsky::formatting::FormatOptions;
+
+    #[test]
+    fn test_sources_and_radar_tables() {
+        let sources: Vec<Source> = serde_json::from_value(serde_json::json!([
+            {"id": 7, "dwd_station_id": "01766", "wmo_station_id": "10315", "station_name": "Münster/Osnabrück",
+             "observation_type": "historical", "first_record": "2010-01-01T00:00:00+00:00",
+             "last_record": "2023-08-07T12:00:00+00:00", "lat": 52.13, "lon": 7.69, "height": 47.8,
+             "distance": 12345.0}
+        ]))
+        .unwrap();
+        let table = sources.as_slice().to_table();
+        let row = table.lines().nth(2).unwrap();
+        assert!(
+            row.starts_with(" 7  01766  10315  Münster/Osnabrück  historical"),
+            "{row}"
+        );
+        assert!(
+            row.ends_with("12.3        48  2010-01-01 - 2023-08-07"),
+            "{row}"
+        );
+
+        let radar: RadarResponse = serde_json::from_value(serde_json::json!({
+            "radar": [
+                {"timestamp": "2023-08-07T12:05:00+00:00", "source": "RADOLAN::RV::2023-08-07T12:00:00+00:00", "precipitation_5": [[0, 0], [150, 50]]},
+                {"timestamp": "2023-08-07T12:00:00+00:00", "source": "RADOLAN::RV::2023-08-07T12:00:00+00:00", "precipitation_5": [[0, 0], [0, 0]]}
+            ],
+            "bbox": [100, 200, 101, 201]
+        }))
+        .unwrap();
+        let table = radar.to_table();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(
+            lines[2],
+            "2023-08-07 12:00  observed    0.00    0.000      0.0"
+        );
+        assert_eq!(
+            lines[3],
+            "2023-08-07 12:05  forecast    1.50    0.500     50.0"
+        );
+    }
+
+    #[test]
+    fn test_weather_table_alignment() {
+        let response: WeatherResponse = serde_json::from_value(serde_json::json!({
+            "weather": [
+                {"timestamp": "2023-08-07T12:00:00+00:00", "source_id": 1, "temperature": 22.3, "condition": "dry"},
+                {"timestamp": "2023-08-07T13:00:00+00:00", "source_id": 1, "temperature": 9.5, "condition": "thunderstorm"}
+            ],
+            "sources": []
+        }))
+        .unwrap();
+
+        let table = response.to_table();
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("Time              Condition     Temp"));
+        assert!(lines[2].starts_with("2023-08-07 12:00  dry           22.3"));
+        assert!(lines[3].starts_with("2023-08-07 13:00  thunderstorm   9.5"));
+        assert!(!table.contains('\x1b'));
+
+        let colored = response.to_table_with(&TableOptions::new().with_color(true));
+        assert!(colored.contains("\x1b[1;33m2023-08-07 13:00"));
+        assert!(!colored.contains("\x1b[0m2023-08-07 12:00"));
+    }
+
+    #[test]
+    fn test_one_liner() {
+        let response: CurrentWeatherResponse = serde_json::from_value(serde_json::json!({
+            "weather": {
+                "timestamp": "2023-08-07T12:30:00+00:00",
+                "source_id": 2,
+                "temperature": -3.25,
+                "icon": "snow",
+                "wind_speed_30": 8.4,
+                "wind_direction_30": 85
+            },
+            "sources": [
+                {"id": 1, "dwd_station_id": "00001", "wmo_station_id": "00001", "station_name": "Elsewhere",
+                 "observation_type": "synop", "first_record": "2023-08-07T00:00:00+00:00",
+                 "last_record": "2023-08-07T12:30:00+00:00", "lat": 0.0, "lon": 0.0, "height": 0.0},
+                {"id": 2, "dwd_station_id": "00433", "wmo_station_id": "10384", "station_name": "Berlin-Tempelhof",
+                 "observation_type": "synop", "first_record": "2023-08-07T00:00:00+00:00",
+                 "last_record": "2023-08-07T12:30:00+00:00", "lat": 52.47, "lon": 13.4, "height": 48.4}
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            response.one_liner(Language::En),
+            "Berlin-Tempelhof -3.2°C ❄ wind 8 km/h E"
+        );
+        assert_eq!(
+            response.one_liner(Language::De),
+            "Berlin-Tempelhof -3.2°C ❄ Wind 8 km/h O"
+        );
+        assert!(
+            response
+                .one_liner_colored(Language::En)
+                .contains("\x1b[36m-3.2°C\x1b[0m")
+        );
+
+        let german = FormatOptions::summary().with_decimal_separator(',');
+        assert_eq!(
+            response.one_liner_with(Language::De, &german),
+            "Berlin-Tempelhof -3,2°C ❄ Wind 8 km/h O"
+        );
+        let terse = FormatOptions::new().with_decimal_places("temperature", 0);
+        assert_eq!(
+            response.one_liner_with(Language::En, &terse),
+            "Berlin-Tempelhof -3 ❄ wind 8.4 E"
+        );
+    }
+
+    #[test]
+    fn test_table_format_options() {
+        let response: WeatherResponse = serde_json::from_value(serde_json::json!({
+            "weather": [
+                {"timestamp": "2023-08-07T12:00:00+00:00", "source_id": 1, "temperature": 22.34, "wind_speed": 9.0}
+            ],
+            "sources": []
+        }))
+        .unwrap();
+
+        let format = FormatOptions::new()
+            .with_default_decimal_places(Some(1))
+            .with_decimal_separator(',')
+            .with_unit_suffix(true);
+        let table = response.to_table_with(&TableOptions::new().with_format(format));
+        let row = table.lines().nth(2).unwrap();
+        assert!(row.contains("22,3°C"));
+        assert!(row.contains("9,0 km/h"));
+
+        assert!(
+            response
+                .to_table()
+                .lines()
+                .nth(2)
+                .unwrap()
+                .contains("22.34")
+        );
+    }
+
+    #[test]
+    fn test_status_bar_output() {
+        let response: CurrentWeatherResponse = serde_json::from_value(serde_json::json!({
+            "weather": {
+                "timestamp": "2023-08-07T12:30:00+00:00",
+                "source_id": 1,
+                "temperature": 18.6,
+                "relative_humidity": 71,
+                "condition": "thunderstorm",
+                "icon": "thunderstorm",
+                "wind_speed_10": 22.0,
+                "wind_direction_10": 270,
+                "precipitation_10": 1.4
+            },
+            "sources": []
+        }))
+        .unwrap();
+
+        let waybar = response.waybar(Language::De);
+        assert_eq!(waybar.text, "⛈ 19°C");
+        assert_eq!(waybar.class, "thunderstorm");
+        assert_eq!(waybar.alt, "thunderstorm");
+        assert_eq!(
+            waybar.tooltip,
+            "12:30\nTemperatur: 18.6 °C\nLuftfeuchte: 71 %\nWind: 22 km/h W\nNiederschlag: 1.4 mm / 10 min"
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&waybar.to_json()).unwrap();
+        assert_eq!(json["class"], "thunderstorm");
+        assert_eq!(json["text"], "⛈ 19°C");
+
+        let block = response.i3bar(Language::En);
+        assert_eq!(block.full_text, "18.6°C ⛈ 71% RH, wind 22 km/h W");
+        assert_eq!(block.color.as_deref(), Some("#ebcb8b"));
+        assert!(block.to_json().contains("\"name\":\"weather\""));
+    }
+
+    #[test]
+    fn test_alerts_table() {
+        let response: AlertsResponse = serde_json::from_value(serde_json::json!({
+            "alerts": [{
+                "id": 1,
+                "alert_id": "a",
+                "status": "actual",
+                "effective": "2023-08-07T08:00:00+00:00",
+                "onset": "2023-08-07T10:00:00+00:00",
+                "severity": "severe",
+                "event_en": "wind gusts",
+                "headline_en": "Official WARNING of WIND GUSTS",
+                "headline_de": "",
+                "description_en": "",
+                "description_de": ""
+            }],
+            "location": null
+        }))
+        .unwrap();
+
+        let table = response.to_table_with(&TableOptions::new().with_color(true));
+
+        assert!(table.contains("severe"));
+        assert!(table.contains("wind gusts"));
+        assert!(table.contains("\x1b[31m"));
+    }
+}
+
+#[cfg(feature = "testing")]
+mod testing_tests {
+    use super::*;
+    use brightsky::testing::MockHttpClient;
+
+    /// A radar record as sent for `format=compressed`.
+    fn compressed_record(timestamp: &str, source: &str, values: &[u16]) -> serde_json::Value {
+        serde_json::json!({
+            "timestamp": timestamp,
+            "source": format!("RADOLAN::RV::{source}"),
+            "precipitation_5": MaybeCompressedPrecipitation::compressed(values),
+        })
+    }
+
+    #[test]
+    fn test_mock_client_fixtures_and_requests() {
+        let mock = MockHttpClient::new()
+            .with_fixture("weather", r#"{"weather": [], "sources": []}"#)
+            .with_response("/alerts", 500, "Internal Server Error");
+        let client = BrightSkyClient::new(mock.clone()).with_host("http://localhost:8080");
+
+        tokio_test::block_on(async {
+            let query = WeatherQueryBuilder::new()
+                .with_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap())
+                .with_tz("Europe/Berlin")
+                .with_dwd_station_id(vec!["01766"])
+                .build()
+                .unwrap();
+            let response: WeatherResponse = client.get(query).await.unwrap();
+            assert!(response.weather.is_empty());
+
+            let alerts: Result<AlertsResponse, _> =
+                client.get(AlertsQueryBuilder::new().build().unwrap()).await;
+            assert!(matches!(
+                alerts,
+                Err(ClientError::Api(BrightSkyApiError { status: 500, .. }))
+            ));
+
+            let current: Result<CurrentWeatherResponse, _> = client
+                .get(
+                    CurrentWeatherQueryBuilder::new()
+                        .with_lat_lon((52.52, 13.4))
+                        .build()
+                        .unwrap(),
+                )
+                .await;
+            assert!(matches!(current, Err(ClientError::Http(_))));
+
+            assert!(matches!(
+                BrightSkyError::from(alerts.unwrap_err()),
+                BrightSkyError::Transport(TransportError::Api(_))
+            ));
+            assert!(matches!(
+                BrightSkyError::from(current.unwrap_err()),
+                BrightSkyError::Transport(TransportError::Http(_))
+            ));
+        });
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].path, "/weather");
+        assert_eq!(requests[0].param("tz"), Some("Europe/Berlin"));
+        assert_eq!(requests[0].param("dwd_station_id"), Some("01766"));
+        assert_eq!(
+            mock.last_request().unwrap().path,
+            "/current_weather".to_string()
+        );
+
+        mock.clear_requests();
+        assert!(mock.requests().is_empty());
+    }
+
+    #[test]
+    fn test_forecast_groups_records_by_day() {
+        let mock = MockHttpClient::new().with_sample_fixtures();
+        let client = BrightSkyClient::new(mock.clone());
+
+        let forecast = tokio_test::block_on(
+            client
+                .forecast((52.1357, 7.7))
+                .with_tz("Europe/Berlin")
+                .with_start_date(NaiveDate::from_ymd_opt(2023, 8, 6).unwrap())
+                .days(2),
+        )
+        .unwrap();
+
+        assert_eq!(forecast.days.len(), 2);
+        assert_eq!(
+            forecast.days[0].date,
+            NaiveDate::from_ymd_opt(2023, 8, 6).unwrap()
+        );
+        assert!(forecast.days[0].hours.is_empty());
+        assert_eq!(forecast.days[1].hours.len(), 8);
+        assert_eq!(forecast.hours().count(), 8);
+        assert!(!forecast.sources.is_empty());
+
+        let request = mock.last_request().unwrap();
+        assert_eq!(request.path, "/weather");
+        assert_eq!(request.param("date"), Some("2023-08-06"));
+        assert_eq!(request.param("last_date"), Some("2023-08-08"));
+        assert_eq!(request.param("lat"), Some("52.1357"));
+        assert_eq!(request.param("tz"), Some("Europe/Berlin"));
+    }
+
+    #[test]
+    fn test_client_builder_passes_options() {
+        let client: BrightSkyClient<MockHttpClient> = BrightSkyClientBuilder::new()
+            .timeout(std::time::Duration::from_secs(10))
+            .user_agent("weather-station/1.0")
+            .host("https://weather.example.org")
+            .build()
+            .unwrap();
+
+        assert_eq!(client.host(), "https://weather.example.org");
+        let options = client.http_client().options();
+        assert_eq!(options.timeout, Some(std::time::Duration::from_secs(10)));
+        assert_eq!(options.connect_timeout, None);
+        assert_eq!(options.user_agent.as_deref(), Some("weather-station/1.0"));
+
+        let query = AlertsQueryBuilder::new().build().unwrap();
+        let alerts: AlertsResponse = tokio_test::block_on(client.get(query)).unwrap();
+        assert!(!alerts.alerts.is_empty());
+    }
+
+    #[test]
+    fn test_client_sends_default_headers() {
+        let mock = MockHttpClient::new().with_sample_fixtures();
+        let client = BrightSkyClient::new(mock.clone())
+            .with_user_agent("first/1.0")
+            .with_header("X-Request-Source", "tests")
+            .with_user_agent("weather-station/1.0 (ops@example.org)");
+        assert_eq!(client.headers().len(), 2);
+
+        let query = AlertsQueryBuilder::new().build().unwrap();
+        let _: AlertsResponse = tokio_test::block_on(client.get(query)).unwrap();
+
+        let request = mock.last_request().unwrap();
+        assert_eq!(
+            request.header("user-agent"),
+            Some("weather-station/1.0 (ops@example.org)")
+        );
+        assert_eq!(request.header("x-request-source"), Some("tests"));
+
+        let built: BrightSkyClient<MockHttpClient> = BrightSkyClientBuilder::new()
+            .header("x-request-source", "first")
+            .header("X-Request-Source", "builder")
+            .build()
+            .unwrap();
+        assert_eq!(
+            built.http_client().options().user_agent.as_deref(),
+            Some(DEFAULT_USER_AGENT)
+        );
+        assert_eq!(
+            built.headers(),
+            [("X-Request-Source".to_string(), "builder".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_get_weather_records_matches_response() {
+        let mock = MockHttpClient::new().with_sample_fixtures();
+        let client = BrightSkyClient::new(mock);
+
+        let query = WeatherQueryBuilder::new()
+            .with_lat_lon((52.52, 13.4))
+            .with_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap())
+            .build()
+            .unwrap();
+        let records = tokio_test::block_on(client.get_weather_records(query)).unwrap();
+        let records: Vec<Weather> = records.collect::<Result<_, _>>().unwrap();
+
+        assert!(!records.is_empty());
+        assert_eq!(records, WeatherResponse::sample().weather);
+    }
+
+    #[test]
+    fn test_get_value_returns_untyped_json() {
+        let mock = MockHttpClient::new().with_fixture(
+            "/alerts",
+            r#"{"alerts": [], "location": null, "new_field": {"since": "v3"}}"#,
+        );
+        let client = BrightSkyClient::new(mock);
+
+        let query = AlertsQueryBuilder::new().build().unwrap();
+        let value = tokio_test::block_on(client.get_value(query)).unwrap();
+        assert_eq!(value["new_field"]["since"], "v3");
+        assert!(value["alerts"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_get_raw_returns_error_responses() {
+        let mock =
+            MockHttpClient::new().with_response("/alerts", 404, r#"{"detail": "Not Found"}"#);
+        let client = BrightSkyClient::new(mock);
+
+        let query = AlertsQueryBuilder::new().build().unwrap();
+        let response = tokio_test::block_on(client.get_raw(query)).unwrap();
+        assert_eq!(response.status, 404);
+        assert_eq!(response.body, br#"{"detail": "Not Found"}"#);
+    }
+
+    #[test]
+    fn test_get_lenient_repairs_alerts() {
+        let mock = MockHttpClient::new().with_fixture(
+            "/alerts",
+            r#"{
+                "alerts": [
+                    {"id": 1, "alert_id": "a", "status": "actual",
+                     "effective": "2023-08-07T08:00:00+00:00", "onset": "broken",
+                     "headline_en": "", "headline_de": "", "description_en": "", "description_de": ""},
+                    {"id": 2, "alert_id": "b", "status": "actual",
+                     "effective": "2023-08-07T08:00:00+00:00", "onset": "2023-08-07T09:00:00+00:00",
+                     "severity": 7, "expires": null, "headline_en": "Wind", "headline_de": "Wind",
+                     "description_en": "", "description_de": "", "area": "coast"}
+                ],
+                "location": null,
+                "generated_at": "2023-08-07T08:00:00+00:00"
+            }"#,
+        );
+        let client = BrightSkyClient::new(mock);
+
+        let query = AlertsQueryBuilder::new().build().unwrap();
+        let response: brightsky::lenient::Lenient<AlertsResponse> =
+            tokio_test::block_on(client.get_lenient(query)).unwrap();
+
+        assert_eq!(response.value.alerts.len(), 1);
+        assert_eq!(response.value.alerts[0].id, 2);
+        assert_eq!(response.value.alerts[0].severity, None);
+
+        let paths: Vec<&str> = response.warnings.iter().map(|w| w.path.as_str()).collect();
+        assert_eq!(paths, ["alerts[0]", "alerts[0].severity"]);
+        assert!(response.warnings[0].message.contains("at alerts[0].onset"));
+
+        assert_eq!(response.extra.len(), 2);
+        assert_eq!(response.extra["alerts[0].area"], "coast");
+        assert!(response.extra.contains_key("generated_at"));
+    }
+
+    #[test]
+    fn test_forecast_provenance() {
+        let mock = MockHttpClient::new().with_sample_fixtures();
+        let client = BrightSkyClient::new(mock).with_host("https://weather.example.org");
+
+        let before = chrono::Utc::now();
+        let forecast = tokio_test::block_on(
+            client
+                .forecast((52.1357, 7.7))
+                .with_units(UnitType::Si)
+                .with_start_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap())
+                .days(1),
+        )
+        .unwrap();
+
+        let provenance = forecast.provenance.unwrap();
+        assert_eq!(provenance.host, "https://weather.example.org");
+        assert_eq!(
+            provenance.requests,
+            [
+                "https://weather.example.org/weather?date=2023-08-07&last_date=2023-08-08&lat=52.1357&lon=7.7&units=si"
+            ]
+        );
+        assert_eq!(provenance.endpoints(), ["/weather"]);
+        assert_eq!(provenance.units, UnitType::Si);
+        assert!(!provenance.cached);
+        assert!(provenance.fetched_at >= before);
+    }
+
+    #[test]
+    fn test_history_chunks_and_merges() {
+        let mock = MockHttpClient::new().with_sample_fixtures();
+        let client = BrightSkyClient::new(mock.clone());
+
+        let start = NaiveDate::from_ymd_opt(2023, 8, 1).unwrap();
+        let end = NaiveDate::from_ymd_opt(2023, 8, 25).unwrap();
+        let request = client
+            .get_history((52.1357, 7.7), start..end)
+            .with_chunk_days(10)
+            .with_concurrency(2);
+        assert_eq!(
+            request.chunks(),
+            vec![
+                start..NaiveDate::from_ymd_opt(2023, 8, 11).unwrap(),
+                NaiveDate::from_ymd_opt(2023, 8, 11).unwrap()
+                    ..NaiveDate::from_ymd_opt(2023, 8, 21).unwrap(),
+                NaiveDate::from_ymd_opt(2023, 8, 21).unwrap()..end,
+            ]
+        );
+
+        let history = tokio_test::block_on(request.fetch()).unwrap();
+
+        // Every chunk returned the same fixture, which is only kept once
+        let sample = WeatherResponse::sample();
+        assert_eq!(history.weather.len(), sample.weather.len());
+        assert_eq!(history.sources.len(), sample.sources.len());
+        assert!(
+            history
+                .weather
+                .windows(2)
+                .all(|w| w[0].timestamp < w[1].timestamp)
+        );
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].param("date"), Some("2023-08-01"));
+        assert_eq!(requests[0].param("last_date"), Some("2023-08-11"));
+        assert_eq!(requests[2].param("date"), Some("2023-08-21"));
+        assert_eq!(requests[2].param("last_date"), Some("2023-08-25"));
+    }
+
+    #[test]
+    fn test_history_empty_range_and_errors() {
+        let mock = MockHttpClient::new().with_response("/weather", 400, "{}");
+        let client = BrightSkyClient::new(mock.clone());
+        let day = NaiveDate::from_ymd_opt(2023, 8, 1).unwrap();
+
+        let empty =
+            tokio_test::block_on(client.get_history((52.52, 13.4), day..day).fetch()).unwrap();
+        assert!(empty.weather.is_empty());
+        assert!(mock.requests().is_empty());
+
+        let end = NaiveDate::from_ymd_opt(2023, 12, 1).unwrap();
+        let failed = tokio_test::block_on(
+            client
+                .get_history((52.52, 13.4), day..end)
+                .with_concurrency(1)
+                .fetch(),
+        );
+        assert!(matches!(
+            failed,
+            Err(ClientError::Api(BrightSkyApiError { status: 400, .. }))
+        ));
+        assert_eq!(mock.requests().len(), 1);
+    }
+
+    #[test]
+    fn test_history_provenance_lists_every_chunk() {
+        let mock = MockHttpClient::new().with_sample_fixtures();
+        let client = BrightSkyClient::new(mock.clone());
+        let start = NaiveDate::from_ymd_opt(2023, 8, 1).unwrap();
+        let end = NaiveDate::from_ymd_opt(2023, 8, 25).unwrap();
+
+        let (history, provenance) = tokio_test::block_on(
+            client
+                .get_history((52.1357, 7.7), start..end)
+                .with_chunk_days(10)
+                .with_units(UnitType::Si)
+                .fetch_with_provenance(),
+        )
+        .unwrap();
+        assert_eq!(
+            history.weather.len(),
+            WeatherResponse::sample().weather.len()
+        );
+        assert_eq!(provenance.requests.len(), 3);
+        assert!(provenance.requests[0].contains("date=2023-08-01&last_date=2023-08-11"));
+        assert!(provenance.requests[2].contains("date=2023-08-21&last_date=2023-08-25"));
+        assert_eq!(provenance.endpoints(), ["/weather"]);
+        assert_eq!(provenance.units, UnitType::Si);
+
+        let (_, empty) = tokio_test::block_on(
+            client
+                .get_history((52.52, 13.4), end..end)
+                .fetch_with_provenance(),
+        )
+        .unwrap();
+        assert!(empty.requests.is_empty());
+        assert_eq!(empty.host, client.host());
+    }
+
+    #[test]
+    fn test_nowcast_reads_pixel_at_location() {
+        let mock = MockHttpClient::new().with_sample_fixtures();
+        let client = BrightSkyClient::new(mock.clone());
+
+        let series = tokio_test::block_on(client.nowcast((52.65, 6.58))).unwrap();
+        let values: Vec<f64> = series.iter().map(|(_, mm)| *mm).collect();
+        assert_eq!(values, [0.15, 0.12, 0.06]);
+        assert_eq!(series[0].0.to_rfc3339(), "2023-08-08T11:45:00+00:00");
+
+        let request = mock.last_request().unwrap();
+        assert_eq!(request.path, "/radar");
+        assert_eq!(request.param("lat"), Some("52.65"));
+        assert_eq!(request.param("distance"), Some("2000"));
+        assert_eq!(request.param("format"), Some("compressed"));
+    }
+
+    #[test]
+    fn test_get_radar_decodes_requested_format() {
+        let mock = MockHttpClient::new().with_sample_fixtures();
+        let client = BrightSkyClient::new(mock);
+
+        let query = RadarWeatherQueryBuilder::new()
+            .with_bbox([0, 0, 3, 5])
+            .build()
+            .unwrap();
+        assert_eq!(query.response_format(), RadarCompressionFormat::Compressed);
+        let response = tokio_test::block_on(client.get_radar(query.clone())).unwrap();
+        assert_eq!(response.decode_all().unwrap().len(), 3);
+
+        // The sample fixture is compressed, not plain
+        let plain = query
+            .into_builder()
+            .with_compression_format(RadarCompressionFormat::Plain)
+            .build()
+            .unwrap();
+        let error = tokio_test::block_on(client.get_radar(plain)).unwrap_err();
+        assert!(matches!(
+            error,
+            ClientError::Decode(DecodeError::UnexpectedRadarFormat { .. })
+        ));
+    }
+
+    #[test]
+    fn test_nowcast_skips_frames_before_latest_observation() {
+        let radar = serde_json::json!({
+            "radar": [
+                compressed_record("2023-08-08T11:45:00+00:00", "2023-08-08T11:45:00+00:00", &[0, 1, 2, 3]),
+                compressed_record("2023-08-08T11:40:00+00:00", "2023-08-08T11:40:00+00:00", &[0, 1, 2, 4]),
+                compressed_record("2023-08-08T11:50:00+00:00", "2023-08-08T11:45:00+00:00", &[0, 1, 2, 50])
+            ],
+            "bbox": [10, 20, 11, 21],
+            "latlon_position": {"x": 20.8, "y": 11.2}
+        });
+        let mock = MockHttpClient::new().with_fixture("/radar", radar.to_string());
+        let client = BrightSkyClient::new(mock);
+
+        let series = tokio_test::block_on(client.nowcast((52.52, 13.4))).unwrap();
+        let times: Vec<String> = series
+            .iter()
+            .map(|(t, _)| t.format("%H:%M").to_string())
+            .collect();
+        assert_eq!(times, ["11:45", "11:50"]);
+        assert_eq!(series[1].1, 0.5);
+    }
+
+    #[test]
+    fn test_precipitation_outlook_blends_radar_and_forecast() {
+        let radar = serde_json::json!({
+            "radar": [
+                compressed_record("2023-08-08T12:00:00+00:00", "2023-08-08T12:00:00+00:00", &[40]),
+                compressed_record("2023-08-08T13:30:00+00:00", "2023-08-08T12:00:00+00:00", &[40])
+            ],
+            "bbox": [10, 20, 10, 20],
+            "latlon_position": {"x": 20.2, "y": 10.4}
+        });
+        let mock = MockHttpClient::new()
+            .with_fixture("/radar", radar.to_string())
+            .with_fixture(
+                "/weather",
+                r#"{
+                    "weather": [
+                        {"timestamp": "2023-08-08T13:00:00+00:00", "source_id": 1, "precipitation": 1.2, "precipitation_probability": 60},
+                        {"timestamp": "2023-08-08T14:00:00+00:00", "source_id": 1, "precipitation": 2.4, "precipitation_probability": 80}
+                    ],
+                    "sources": []
+                }"#,
+            );
+        let client = BrightSkyClient::new(mock.clone());
+
+        let outlook = tokio_test::block_on(client.precipitation_outlook((52.52, 13.4))).unwrap();
+        assert_eq!(outlook.len(), 24);
+
+        // Radar alone during the first hour
+        assert_eq!(outlook[0].time.to_rfc3339(), "2023-08-08T12:00:00+00:00");
+        assert_eq!(outlook[0].precipitation, 0.4);
+        assert_eq!(outlook[0].probability, Some(60));
+        // Forecast alone without a radar frame
+        assert_eq!(outlook[1].radar, None);
+        assert!((outlook[1].precipitation - 0.1).abs() < 1e-9);
+        // Halfway through the second hour
+        let step = outlook
+            .iter()
+            .find(|s| s.radar.is_some() && s.time.format("%H:%M").to_string() == "13:30")
+            .unwrap();
+        assert!((step.precipitation - 0.3).abs() < 1e-9);
+        assert_eq!(step.probability, Some(80));
+
+        let request = mock.last_request().unwrap();
+        assert_eq!(request.path, "/weather");
+        assert_eq!(request.param("date"), Some("2023-08-08T12:00:00+00:00"));
+        assert_eq!(
+            request.param("last_date"),
+            Some("2023-08-08T15:00:00+00:00")
+        );
+
+        let (steps, provenance) =
+            tokio_test::block_on(client.precipitation_outlook_with_provenance((52.52, 13.4)))
+                .unwrap();
+        assert_eq!(steps, outlook);
+        assert_eq!(provenance.endpoints(), ["/radar", "/weather"]);
+        assert!(!provenance.cached);
+    }
+
+    #[test]
+    fn test_precipitation_at_position_without_location() {
+        let mut response = RadarResponse::sample();
+        assert_eq!(response.precipitation_at_position().len(), 3);
+        response.latlon_position = None;
+        assert!(response.precipitation_at_position().is_empty());
+    }
+}
+
+#[cfg(test)]
+mod weather_records_tests {
+    use super::*;
+
+    #[test]
+    fn test_records_skip_members_before_weather() {
+        let body = br#"{
+            "sources": [{"id": 1, "nested": {"weather": [1, 2]}}],
+            "note": "x, y]",
+            "count": 2,
+            "weather": [
+                {"timestamp": "2023-08-07T13:00:00+02:00", "source_id": 1, "temperature": 24.0},
+                {"timestamp": "2023-08-07T14:00:00+02:00", "source_id": 2, "temperature": 26.0}
+            ]
+        }"#;
+
+        let records: Vec<Weather> = WeatherRecords::from_slice(body)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].source_id, 2);
+
+        assert_eq!(WeatherRecords::from_slice(br#"{"sources": []}"#).count(), 0);
+        assert_eq!(
+            WeatherRecords::from_slice(br#" { "weather" : [ ] } "#).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_records_stop_at_first_error() {
+        let body = br#"{"weather": [
+            {"timestamp": "2023-08-07T13:00:00+02:00", "source_id": 1},
+            {"timestamp": "not a time", "source_id": 2},
+            {"timestamp": "2023-08-07T15:00:00+02:00", "source_id": 3}
+        ]}"#;
+        let results: Vec<_> = WeatherRecords::from_slice(body).collect();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+
+        let results: Vec<_> =
+            WeatherRecords::from_slice(br#"{"weather": [{"source_id": 1"#).collect();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_err());
+        assert!(WeatherRecords::from_slice(b"[]").next().unwrap().is_err());
+    }
+}
+
+#[cfg(all(feature = "metrics", feature = "testing"))]
+mod metrics_tests {
+    use super::*;
+    use brightsky::metrics::{ERRORS_TOTAL, REQUEST_DURATION_SECONDS, REQUESTS_TOTAL};
+    use brightsky::testing::MockHttpClient;
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+    #[test]
+    fn test_client_records_requests_and_errors() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let mock = MockHttpClient::new().with_sample_fixtures().with_response(
+            "/alerts",
+            503,
+            r#"{"detail": "Unavailable"}"#,
+        );
+        let client = BrightSkyClient::new(mock);
+
+        ::metrics::with_local_recorder(&recorder, || {
+            for _ in 0..2 {
+                let query = CurrentWeatherQueryBuilder::new()
+                    .with_lat_lon((52.52, 13.4))
+                    .build()
+                    .unwrap();
+                let result: Result<CurrentWeatherResponse, _> =
+                    tokio_test::block_on(client.get(query));
+                assert!(result.is_ok());
+            }
+            let query = AlertsQueryBuilder::new().build().unwrap();
+            let result: Result<AlertsResponse, _> = tokio_test::block_on(client.get(query));
+            assert!(result.is_err());
+        });
+
+        let mut counters = Vec::new();
+        let mut histograms = 0;
+        for (key, _, _, value) in snapshotter.snapshot().into_vec() {
+            let key = key.key();
+            let labels: Vec<String> = key
+                .labels()
+                .map(|label| format!("{}={}", label.key(), label.value()))
+                .collect();
+            match value {
+                DebugValue::Counter(count) => {
+                    counters.push((key.name().to_string(), labels.join(","), count))
+                }
+                DebugValue::Histogram(values) if key.name() == REQUEST_DURATION_SECONDS => {
+                    histograms += values.len()
+                }
+                _ => {}
+            }
+        }
+        counters.sort();
+
+        let counter =
+            |name: &str, labels: &str, count| (name.to_string(), labels.to_string(), count);
+        assert_eq!(
+            counters,
+            vec![
+                counter(ERRORS_TOTAL, "endpoint=alerts,code=503", 1),
+                counter(REQUESTS_TOTAL, "endpoint=alerts", 1),
+                counter(REQUESTS_TOTAL, "endpoint=current_weather", 2),
+            ]
+        );
+        assert_eq!(histograms, 3);
+    }
+}
+
+#[cfg(feature = "testing")]
+mod fixture_tests {
+    use super::*;
+    use brightsky::testing::MockHttpClient;
+
+    #[test]
+    fn test_samples_parse() {
+        let current = CurrentWeatherResponse::sample();
+        assert_eq!(current.sources[0].station_name, "Berlin-Tempelhof");
+
+        let weather = WeatherResponse::sample();
+        assert_eq!(weather.daily_summary().len(), 1);
+        assert_eq!(weather.sources.len(), 2);
+
+        let alerts = AlertsResponse::sample();
+        assert_eq!(alerts.alerts.len(), 2);
+        assert_eq!(alerts.location.unwrap().warn_cell_id, 803159016);
+    }
+
+    #[test]
+    fn test_current_weather_fallback_source() {
+        let current = CurrentWeatherResponse::sample();
+        let visibility = current
+            .weather
+            .source_for(&WeatherParameter::Visibility, &current.sources)
+            .unwrap();
+        assert_eq!(visibility.station_name, "Berlin-Tegel");
+        let temperature = current
+            .weather
+            .source_for(&WeatherParameter::Temperature, &current.sources)
+            .unwrap();
+        assert_eq!(temperature.station_name, "Berlin-Tempelhof");
+    }
+
+    #[test]
+    fn test_radar_samples_agree() {
+        let compressed = RadarResponse::sample();
+        let bytes = RadarResponse::sample_bytes();
+        let plain = RadarResponse::sample_plain();
+
+        assert!(matches!(
+            compressed.radar[0].precipitation_5,
+            MaybeCompressedPrecipitation::Compressed(_)
+        ));
+        assert!(matches!(
+            bytes.radar[0].precipitation_5,
+            MaybeCompressedPrecipitation::Bytes(_)
+        ));
+
+        let bbox = compressed.bbox.as_deref().unwrap();
+        for ((a, b), c) in compressed.radar.iter().zip(&bytes.radar).zip(&plain.radar) {
+            let grid = a.to_grid(bbox).unwrap();
+            assert_eq!(grid, b.to_grid(bbox).unwrap());
+            assert_eq!(grid, c.to_grid(bbox).unwrap());
+        }
+        assert_eq!(compressed.observed().len(), 1);
+        assert_eq!(compressed.forecast().len(), 2);
+    }
+
+    #[test]
+    fn test_responses_round_trip() {
+        fn round_trip<T>(response: T)
+        where
+            T: serde::Serialize + serde::de::DeserializeOwned + PartialEq + std::fmt::Debug,
+        {
+            let json = serde_json::to_string(&response).unwrap();
+            assert_eq!(serde_json::from_str::<T>(&json).unwrap(), response);
+        }
+
+        round_trip(RadarResponse::sample());
+        round_trip(RadarResponse::sample_bytes());
+        round_trip(RadarResponse::sample_plain());
+        round_trip(WeatherResponse::sample());
+        round_trip(CurrentWeatherResponse::sample());
+        round_trip(AlertsResponse::sample());
+    }
+
+    #[test]
+    fn test_precipitation_serializes_in_api_format() {
+        let compressed = serde_json::to_value(RadarResponse::sample()).unwrap();
+        let encoded = compressed["radar"][0]["precipitation_5"].as_str().unwrap();
+        assert_eq!(
+            MaybeCompressedPrecipitation::decode_compressed(encoded).unwrap(),
+            RadarResponse::sample().radar[0]
+                .precipitation_5
+                .decode()
+                .unwrap()
+        );
+
+        let bytes = MaybeCompressedPrecipitation::bytes(&[1, 256]);
+        assert_eq!(serde_json::to_value(&bytes).unwrap(), "AQAAAQ==");
+
+        let plain = MaybeCompressedPrecipitation::Plain(vec![vec![1, 2], vec![3, 4]]);
+        assert_eq!(
+            serde_json::to_value(&plain).unwrap(),
+            serde_json::json!([[1, 2], [3, 4]])
+        );
+    }
+
+    #[test]
+    fn test_mock_client_sample_fixtures() {
+        let client = BrightSkyClient::new(MockHttpClient::new().with_sample_fixtures());
+
+        tokio_test::block_on(async {
+            let query = RadarWeatherQueryBuilder::new().build().unwrap();
+            let radar: RadarResponse = client.get(query).await.unwrap();
+            assert_eq!(radar, RadarResponse::sample());
+        });
+    }
+}
+
+#[cfg(feature = "testing")]
+mod export_tests {
+    use super::*;
+    use brightsky::export::ExportProfile;
+    use brightsky::formatting::FormatOptions;
+
+    fn csv(profile: ExportProfile) -> String {
+        let mut out = Vec::new();
+        WeatherResponse::sample()
+            .write_csv(&mut out, profile)
+            .unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_full_csv_contains_station_columns() {
+        let csv = csv(ExportProfile::Full);
+        let mut lines = csv.lines();
+
+        let header = lines.next().unwrap();
+        assert!(header.starts_with(
+            "timestamp,source_id,dwd_station_id,wmo_station_id,station_name,lat,lon,height,"
+        ));
+        assert_eq!(lines.count(), 8);
+        assert!(csv.contains("238685,01766,10315,Münster/Osnabrück,52.1344,7.6969,47.8,"));
+    }
+
+    #[test]
+    fn test_anonymized_csv_strips_identifiers() {
+        let csv = csv(ExportProfile::anonymized());
+        let header = csv.lines().next().unwrap();
+
+        assert!(header.starts_with("timestamp,lat,lon,cloud_cover,"));
+        for identifier in [
+            "source_id",
+            "station_name",
+            "height",
+            "238685",
+            "01766",
+            "10315",
+            "Münster",
+        ] {
+            assert!(!csv.contains(identifier), "{} in export", identifier);
+        }
+        assert!(
+            csv.lines()
+                .nth(1)
+                .unwrap()
+                .starts_with("2023-08-07T00:00:00+00:00,52.13,7.7,88.0,dry,")
+        );
+    }
+
+    #[test]
+    fn test_anonymized_coordinate_precision() {
+        let csv = csv(ExportProfile::Anonymized {
+            coordinate_precision: 0,
+        });
+        assert!(
+            csv.lines()
+                .nth(1)
+                .unwrap()
+                .starts_with("2023-08-07T00:00:00+00:00,52.0,8.0,")
+        );
+    }
+
+    #[test]
+    fn test_csv_format_options() {
+        let format = FormatOptions::new()
+            .with_decimal_places("temperature", 0)
+            .with_decimal_separator(',')
+            .with_unit_suffix(true);
+        let mut out = Vec::new();
+        WeatherResponse::sample()
+            .write_csv_with(&mut out, ExportProfile::anonymized(), &format)
+            .unwrap();
+        let csv = String::from_utf8(out).unwrap();
+
+        let mut lines = csv.lines();
+        let header = lines.next().unwrap();
+        assert!(header.starts_with("timestamp,lat,lon,cloud_cover [%],condition,dew_point [°C],"));
+        assert!(header.contains(",temperature [°C],"));
+        // Comma decimals are quoted, the temperature of 16.4 is rounded
+        assert!(
+            lines
+                .next()
+                .unwrap()
+                .starts_with("2023-08-07T00:00:00+00:00,\"52,13\",\"7,7\",\"88,0\",dry,\"13,1\",")
+        );
+        assert!(csv.contains(",16,"));
+    }
+
+    #[test]
+    fn test_csv_quotes_fields() {
+        let mut response = WeatherResponse::sample();
+        for source in &mut response.sources {
+            source.station_name = Some("Münster, \"Flughafen\"".to_string());
+        }
+
+        let mut out = Vec::new();
+        response.write_csv(&mut out, ExportProfile::Full).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        assert!(csv.contains(",\"Münster, \"\"Flughafen\"\"\","));
+    }
+
+    #[test]
+    fn test_geojson_export() {
+        let response = WeatherResponse::sample();
+
+        let full = response.to_geojson(ExportProfile::Full);
+        assert_eq!(full["type"], "FeatureCollection");
+        let features = full["features"].as_array().unwrap();
+        assert_eq!(features.len(), 8);
+        assert_eq!(
+            features[0]["geometry"]["coordinates"],
+            serde_json::json!([7.6969, 52.1344])
+        );
+        assert_eq!(features[0]["properties"]["dwd_station_id"], "01766");
+        assert_eq!(features[0]["properties"]["icon"], "cloudy");
+        assert!(features[0]["properties"].get("lat").is_none());
+
+        let anonymized = response.to_geojson(ExportProfile::anonymized());
+        let feature = &anonymized["features"][0];
+        assert_eq!(
+            feature["geometry"]["coordinates"],
+            serde_json::json!([7.7, 52.13])
+        );
+        assert!(feature["properties"].get("source_id").is_none());
+        assert!(feature["properties"].get("station_name").is_none());
+        assert_eq!(feature["properties"]["temperature"], 16.4);
+    }
+
+    #[test]
+    fn test_line_protocol() {
+        let lines = WeatherResponse::sample().to_line_protocol("weather", &[("units", "dwd")]);
+        assert_eq!(lines.lines().count(), 8);
+        let first = lines.lines().next().unwrap();
+        assert!(
+            first
+                .starts_with("weather,units=dwd,dwd_station_id=01766,observation_type=historical "),
+            "{first}"
+        );
+        assert!(first.contains(",icon=\"cloudy\","), "{first}");
+        assert!(first.contains(",temperature=16.4,"), "{first}");
+
+        let current = CurrentWeatherResponse::sample().to_line_protocol(
+            "current weather",
+            &[("location", "Berlin, Mitte"), ("empty", "")],
+        );
+        assert!(
+            current.starts_with(
+                "current\\ weather,location=Berlin\\,\\ Mitte,dwd_station_id=00433,observation_type=synop cloud_cover=75.0,"
+            ),
+            "{current}"
+        );
+        assert!(current.contains(",relative_humidity=52i,"));
+        assert!(current.contains(",source_id=1228955i,"));
+        assert!(!current.contains("fallback_source_ids"));
+        assert!(current.ends_with(" 1691411400000000000\n"), "{current}");
+    }
+}
+
+#[cfg(test)]
+mod attribution_tests {
+    use brightsky::{attribution, attribution_html};
+
+    #[test]
+    fn test_attribution_converts_to_utc() {
+        let timestamp = chrono::DateTime::parse_from_rfc3339("2023-08-07T01:30:00+02:00").unwrap();
+
+        let text = attribution(timestamp);
+        assert!(text.starts_with("Source: Deutscher Wetterdienst (DWD)"));
+        assert!(text.contains("2023-08-06 23:30 UTC"));
+    }
+
+    #[test]
+    fn test_attribution_html() {
+        let timestamp = chrono::DateTime::parse_from_rfc3339("2023-08-07T12:00:00+00:00").unwrap();
+
+        assert_eq!(
+            attribution_html(timestamp),
+            "Source: <a href=\"https://www.dwd.de/\">Deutscher Wetterdienst (DWD)</a>, data as of \
+             <time datetime=\"2023-08-07T12:00:00+00:00\">2023-08-07 12:00 UTC</time>, \
+             via <a href=\"https://brightsky.dev/\">Bright Sky</a>"
+        );
+    }
+}
+
+#[cfg(test)]
+mod history_tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Answers every request with an empty response after yielding a few times,
+    /// recording the highest number of requests in flight.
+    #[derive(Default)]
+    struct SlowClient {
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    impl HttpClient for SlowClient {
+        type Error = std::convert::Infallible;
+
+        async fn get(&self, _url: &str) -> Result<HttpResponse, Self::Error> {
+            let current = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(current, Ordering::SeqCst);
+            for _ in 0..3 {
+                tokio::task::yield_now().await;
+            }
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            Ok(HttpResponse {
+                status: 200,
+                body: br#"{"weather": [], "sources": []}"#.to_vec(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_history_bounds_concurrency() {
+        let client = BrightSkyClient::new(SlowClient::default());
+        let start = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let end = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+
+        let history = tokio_test::block_on(
+            client
+                .get_history((52.52, 13.4), start..end)
+                .with_concurrency(3)
+                .fetch(),
+        )
+        .unwrap();
+
+        assert!(history.weather.is_empty());
+        assert_eq!(client.http_client().max_in_flight.load(Ordering::SeqCst), 3);
+    }
+}
+
+#[cfg(test)]
+mod batch_tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Answers every request with an empty response after yielding a few times,
+    /// recording the highest number of requests in flight. Requests for
+    /// station `00000` get a 404.
+    #[derive(Default)]
+    struct SlowClient {
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    impl HttpClient for SlowClient {
+        type Error = std::convert::Infallible;
+
+        async fn get(&self, url: &str) -> Result<HttpResponse, Self::Error> {
+            let current = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(current, Ordering::SeqCst);
+            for _ in 0..3 {
+                tokio::task::yield_now().await;
+            }
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            if url.contains("dwd_station_id=00000") {
+                return Ok(HttpResponse {
+                    status: 404,
+                    body: br#"{"detail": "No sources match your criteria"}"#.to_vec(),
+                });
+            }
+            Ok(HttpResponse {
+                status: 200,
+                body: br#"{"weather": [], "sources": []}"#.to_vec(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_get_many_keeps_order_and_errors() {
+        let client = BrightSkyClient::new(SlowClient::default()).with_batch_concurrency(2);
+        let stations = ["01766", "00000", "10315", "01234", "05678"];
+        let queries = stations.iter().map(|station| {
+            WeatherQueryBuilder::new()
+                .with_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap())
+                .with_dwd_station_id(vec![*station])
+                .build()
+                .unwrap()
+        });
+
+        let results = tokio_test::block_on(client.get_many::<WeatherResponse>(queries));
+
+        assert_eq!(results.len(), 5);
+        assert!(results[0].is_ok());
+        assert!(matches!(
+            &results[1],
+            Err(ClientError::Api(error)) if error.is_not_found()
+        ));
+        assert!(results[2..].iter().all(Result::is_ok));
+        assert_eq!(client.http_client().max_in_flight.load(Ordering::SeqCst), 2);
+    }
+}
+
+#[cfg(all(feature = "stream", feature = "testing"))]
+mod pipeline_tests {
+    use super::*;
+    use brightsky::export::ExportProfile;
+    use brightsky::pipeline::{
+        ChannelSink, CsvAppender, Pipeline, PipelineConfig, PipelineError, WeatherSink,
+    };
+    use brightsky::testing::MockHttpClient;
+    use std::time::Duration;
+
+    /// Collects batches in memory, failing while `fail` is set.
+    #[derive(Default)]
+    struct MemorySink {
+        batches: Vec<(String, WeatherResponse)>,
+        fail: bool,
+    }
+
+    impl WeatherSink for MemorySink {
+        type Error = std::io::Error;
+
+        async fn write(&mut self, station: &str, batch: &WeatherResponse) -> std::io::Result<()> {
+            if self.fail {
+                return Err(std::io::Error::other("disk full"));
+            }
+            self.batches.push((station.to_string(), batch.clone()));
+            Ok(())
+        }
+    }
+
+    /// A `/weather` fixture with two records from today, which survive the
+    /// pipeline's lookback cutoff.
+    fn todays_mock() -> MockHttpClient {
+        let today = chrono::Utc::now().date_naive();
+        MockHttpClient::new().with_fixture(
+            "/weather",
+            format!(
+                r#"{{"weather": [
+                    {{"timestamp": "{today}T00:00:00+00:00", "source_id": 1, "temperature": 12.5}},
+                    {{"timestamp": "{today}T01:00:00+00:00", "source_id": 1, "temperature": 12.1}}
+                ], "sources": []}}"#
+            ),
+        )
+    }
+
+    #[test]
+    fn test_pipeline_writes_new_records_once() {
+        let mock = todays_mock();
+        let config = PipelineConfig::new(["01766", "10315"], Duration::from_secs(3600))
+            .with_tz("Europe/Berlin");
+        let mut pipeline = Pipeline::new(
+            BrightSkyClient::new(mock.clone()),
+            config,
+            MemorySink::default(),
+        );
+
+        let written = tokio_test::block_on(pipeline.tick()).unwrap();
+        assert_eq!(written, 4);
+        assert_eq!(pipeline.sink().batches.len(), 2);
+        assert_eq!(pipeline.sink().batches[1].0, "10315");
+
+        assert_eq!(tokio_test::block_on(pipeline.tick()).unwrap(), 0);
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 4);
+        assert_eq!(requests[0].param("dwd_station_id"), Some("01766"));
+        assert_eq!(requests[0].param("tz"), Some("Europe/Berlin"));
+        assert!(requests[0].param("last_date").is_some());
+    }
+
+    #[test]
+    fn test_pipeline_keeps_sources_of_new_records() {
+        let config = PipelineConfig::new(["01766"], Duration::from_secs(3600));
+        let mock = MockHttpClient::new().with_sample_fixtures();
+        let mut pipeline = Pipeline::new(BrightSkyClient::new(mock), config, MemorySink::default());
+
+        assert_eq!(tokio_test::block_on(pipeline.tick()).unwrap(), 8);
+        let batch = &pipeline.sink().batches[0].1;
+        assert_eq!(batch.sources.len(), 1);
+        assert_eq!(batch.sources[0].id, batch.weather[0].source_id);
+    }
+
+    #[test]
+    fn test_pipeline_retries_after_sink_error() {
+        let config = PipelineConfig::new(["01766"], Duration::from_secs(3600));
+        let sink = MemorySink {
+            fail: true,
+            ..Default::default()
+        };
+        let mut pipeline = Pipeline::new(BrightSkyClient::new(todays_mock()), config, sink);
+
+        let failed = tokio_test::block_on(pipeline.tick());
+        assert!(
+            matches!(failed, Err(PipelineError::Sink { ref station, .. }) if station == "01766")
+        );
+
+        pipeline.sink_mut().fail = false;
+        assert_eq!(tokio_test::block_on(pipeline.tick()).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_pipeline_fetch_error() {
+        let mock = MockHttpClient::new().with_response("/weather", 500, "Internal Server Error");
+        let config = PipelineConfig::new(["01766"], Duration::from_secs(3600));
+        let mut pipeline = Pipeline::new(BrightSkyClient::new(mock), config, MemorySink::default());
+
+        let failed = tokio_test::block_on(pipeline.tick());
+        assert!(matches!(
+            failed,
+            Err(PipelineError::Fetch {
+                error: ClientError::Api(_),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_pipeline_run_until_stops_before_first_tick() {
+        let mock = todays_mock();
+        let config = PipelineConfig::new(["01766"], Duration::from_secs(3600));
+        let mut pipeline = Pipeline::new(
+            BrightSkyClient::new(mock.clone()),
+            config,
+            MemorySink::default(),
+        );
+
+        tokio_test::block_on(pipeline.run_until(async {})).unwrap();
+        assert!(mock.requests().is_empty());
+        assert!(pipeline.sink().batches.is_empty());
+    }
+
+    #[test]
+    fn test_channel_sink_forwards_batches() {
+        let (sink, mut receiver) = ChannelSink::new(2);
+        let config = PipelineConfig::new(["01766", "10315"], Duration::from_secs(3600));
+        let mut pipeline = Pipeline::new(BrightSkyClient::new(todays_mock()), config, sink);
+
+        assert_eq!(tokio_test::block_on(pipeline.tick()).unwrap(), 4);
+        let (station, batch) = receiver.try_recv().unwrap();
+        assert_eq!(station, "01766");
+        assert_eq!(batch.weather.len(), 2);
+        assert_eq!(receiver.try_recv().unwrap().0, "10315");
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_until_shutdown_ends_stream() {
+        use futures_util::{StreamExt, stream};
+
+        let items = tokio_test::block_on(
+            brightsky::poll::until_shutdown(stream::iter(1..), async {}).collect::<Vec<_>>(),
+        );
+        assert!(items.is_empty());
+
+        let items = tokio_test::block_on(
+            brightsky::poll::until_shutdown(stream::iter(1..4), std::future::pending())
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(items, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_csv_appender_writes_header_once() {
+        let path =
+            std::env::temp_dir().join(format!("brightsky-appender-{}.csv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let batch = WeatherResponse::sample();
+
+        for _ in 0..2 {
+            let mut appender = CsvAppender::open(&path, ExportProfile::anonymized()).unwrap();
+            tokio_test::block_on(appender.write("01766", &batch)).unwrap();
+        }
+
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            csv.lines()
+                .filter(|line| line.starts_with("timestamp,"))
+                .count(),
+            1
+        );
+        assert_eq!(csv.lines().count(), 1 + 2 * batch.weather.len());
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_sqlite_sink_replaces_rewritten_records() {
+        use brightsky::pipeline::SqliteSink;
+
+        let mut sink = SqliteSink::new(rusqlite::Connection::open_in_memory().unwrap()).unwrap();
+        let batch = WeatherResponse::sample();
+        tokio_test::block_on(sink.write("01766", &batch)).unwrap();
+        tokio_test::block_on(sink.write("01766", &batch)).unwrap();
+
+        let connection = sink.connection();
+        let rows: usize = connection
+            .query_row("SELECT COUNT(*) FROM weather", (), |row| row.get(0))
+            .unwrap();
+        assert_eq!(rows, batch.weather.len());
+
+        let (station, temperature): (String, Option<f64>) = connection
+            .query_row(
+                "SELECT station, json_extract(record, '$.temperature') FROM weather
+                 ORDER BY timestamp LIMIT 1",
+                (),
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(station, "01766");
+        assert_eq!(temperature, batch.weather[0].temperature);
+    }
+
+    #[cfg(feature = "home-assistant")]
+    #[test]
+    fn test_mqtt_sink_publishes_batches_per_station() {
+        use brightsky::home_assistant::{MqttMessage, MqttPublisher};
+        use brightsky::pipeline::MqttSink;
+        use std::sync::Mutex;
+
+        #[derive(Default)]
+        struct Recorder(Mutex<Vec<MqttMessage>>);
+
+        impl MqttPublisher for &Recorder {
+            type Error = std::convert::Infallible;
+
+            async fn publish(&self, message: MqttMessage) -> Result<(), Self::Error> {
+                self.0.lock().unwrap().push(message);
+                Ok(())
+            }
+        }
+
+        let recorder = Recorder::default();
+        let config = PipelineConfig::new(["01766", "10315"], Duration::from_secs(3600));
+        let sink = MqttSink::new(&recorder, "brightsky/weather/");
+        let mut pipeline = Pipeline::new(BrightSkyClient::new(todays_mock()), config, sink);
+
+        assert_eq!(tokio_test::block_on(pipeline.tick()).unwrap(), 4);
+        let messages = recorder.0.lock().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].topic, "brightsky/weather/10315");
+        assert!(!messages[1].retain);
+        let batch: WeatherResponse = serde_json::from_str(&messages[0].payload).unwrap();
+        assert_eq!(batch.weather.len(), 2);
+    }
+}
+
+#[cfg(test)]
+mod formatting_tests {
+    use brightsky::formatting::FormatOptions;
+    use brightsky::types::UnitType;
+
+    #[test]
+    fn test_default_prints_values_unchanged() {
+        let options = FormatOptions::default();
+        assert_eq!(options.format_value("temperature", 22.3), "22.3");
+        assert_eq!(options.format_value("visibility", 45280.0), "45280");
+        assert_eq!(options.places("temperature"), None);
+    }
+
+    #[test]
+    fn test_decimal_places_per_field() {
+        let options = FormatOptions::new()
+            .with_decimal_places("wind_speed", 0)
+            .with_decimal_places("wind_speed_60", 2)
+            .with_default_decimal_places(Some(1));
+
+        assert_eq!(options.format_value("wind_speed_10", 15.46), "15");
+        assert_eq!(options.format_value("wind_speed_60", 15.46), "15.46");
+        assert_eq!(options.format_value("pressure_msl", 1012.46), "1012.5");
+    }
+
+    #[test]
+    fn test_unit_suffixes() {
+        let dwd = FormatOptions::new().with_unit_suffix(true);
+        assert_eq!(dwd.format_value("dew_point", 11.7), "11.7°C");
+        assert_eq!(dwd.format_value("pressure_msl", 1012.5), "1012.5 hPa");
+        assert_eq!(dwd.format_value("wind_direction_30", 240.0), "240°");
+        assert_eq!(
+            dwd.format_value("precipitation_probability_6h", 40.0),
+            "40%"
+        );
+        assert_eq!(dwd.format_value("unknown_field", 1.5), "1.5");
+
+        let si = dwd.with_units(UnitType::Si);
+        assert_eq!(si.format_value("temperature", 295.45), "295.45 K");
+        assert_eq!(si.format_value("wind_gust_speed", 8.8), "8.8 m/s");
+        assert_eq!(si.unit("sunshine_60"), Some("s"));
+    }
+}
+
+#[cfg(feature = "chrono-tz")]
+mod chrono_tz_tests {
+    use super::*;
+    use chrono_tz::{America::New_York, Europe::Berlin};
+
+    #[test]
+    fn test_with_tz_accepts_tz_values() {
+        let weather = WeatherQueryBuilder::new()
+            .with_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap())
+            .with_lat_lon((52.52, 13.4))
+            .with_tz(Berlin)
+            .build()
+            .unwrap();
+        assert!(
+            weather
+                .to_url_string(BRIGHT_SKY_API)
+                .unwrap()
+                .contains("tz=Europe/Berlin")
+        );
+
+        let alerts = AlertsQueryBuilder::new().with_tz(New_York).build().unwrap();
+        assert_eq!(alerts.tz.as_deref(), Some("America/New_York"));
+    }
+
+    #[test]
+    fn test_invalid_timezone_names_are_rejected() {
+        let is_invalid_tz = |result: Result<(), BrightSkyError>| matches!(result, Err(BrightSkyError::Query(QueryError::InvalidTimezone(tz))) if tz == "Mars/Olympus");
+
+        assert!(is_invalid_tz(
+            WeatherQueryBuilder::new()
+                .with_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap())
+                .with_tz("Mars/Olympus")
+                .build()
+                .map(drop)
+        ));
+        assert!(is_invalid_tz(
+            CurrentWeatherQueryBuilder::new()
+                .with_tz("Mars/Olympus")
+                .build()
+                .map(drop)
+        ));
+        assert!(is_invalid_tz(
+            RadarWeatherQueryBuilder::new()
+                .with_tz("Mars/Olympus")
+                .build()
+                .map(drop)
+        ));
+        assert!(is_invalid_tz(
+            AlertsQueryBuilder::new()
+                .with_tz("Mars/Olympus")
+                .build()
+                .map(drop)
+        ));
+        assert!(
+            CurrentWeatherQueryBuilder::new()
+                .with_lat_lon((52.52, 13.4))
+                .with_tz("UTC")
+                .build()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_timestamp_in() {
+        let record: Weather =
+            serde_json::from_str(r#"{"timestamp": "2023-01-07T12:00:00+00:00", "source_id": 1}"#)
+                .unwrap();
+        let local = record.timestamp_in(Berlin);
+        assert_eq!(local.to_rfc3339(), "2023-01-07T13:00:00+01:00");
+        assert_eq!(local, record.timestamp);
+
+        let current = CurrentWeatherResponse::sample();
+        assert_eq!(
+            current
+                .weather
+                .timestamp_in(New_York)
+                .format("%H:%M")
+                .to_string(),
+            "08:30"
+        );
+    }
+}
+
+#[cfg(feature = "render")]
+mod render_tests {
+    use brightsky::RadarGrid;
+    use brightsky::render::{ColorRamp, RenderStyle};
+
+    #[test]
+    fn test_color_ramp_bins() {
+        let ramp = ColorRamp::new()
+            .with_bin(0.5, [0, 255, 0, 255])
+            .with_bin(0.1, [0, 0, 255, 255])
+            .with_bin(0.5, [255, 0, 0, 255]);
+
+        assert_eq!(ramp.color(0), [0, 0, 0, 0]);
+        assert_eq!(ramp.color(9), [0, 0, 0, 0]);
+        assert_eq!(ramp.color(10), [0, 0, 255, 255]);
+        assert_eq!(ramp.color(49), [0, 0, 255, 255]);
+        assert_eq!(ramp.color(50), [255, 0, 0, 255]);
+        assert_eq!(ramp.color(u16::MAX), [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_grayscale_scales_and_clips() {
+        let grid = RadarGrid::new(2, 2, vec![0, 50, 100, 400]).unwrap();
+        let image = grid
+            .to_image(&RenderStyle::Grayscale { max_mm: 1.0 })
+            .into_luma8();
+
+        assert_eq!(image.dimensions(), (2, 2));
+        let values: Vec<u8> = image.pixels().map(|p| p.0[0]).collect();
+        assert_eq!(values, [0, 128, 255, 255]);
+    }
+
+    #[test]
+    fn test_png_round_trip() {
+        let grid = RadarGrid::new(3, 2, vec![0, 1, 20, 60, 150, 1200]).unwrap();
+        let style = RenderStyle::default();
+
+        let png = grid.to_png(&style).unwrap();
+        let decoded = brightsky::render::image::load_from_memory(&png)
+            .unwrap()
+            .into_rgba8();
+        assert_eq!(decoded, grid.to_image(&style).into_rgba8());
+        assert_eq!(decoded.get_pixel(0, 0).0[3], 0);
+        assert_eq!(decoded.get_pixel(2, 1).0, ColorRamp::default().color(1200));
+    }
+}
+
+#[cfg(all(feature = "geojson", feature = "testing"))]
+mod geojson_tests {
+    use super::*;
+    use brightsky::Coordinates;
+    use serde_json::json;
+
+    #[test]
+    fn test_geometry_ring_is_closed_once() {
+        let open = Geometry {
+            geometry_type: "Polygon".into(),
+            coordinates: vec![vec![6.5, 52.7], vec![6.6, 52.7], vec![6.6, 52.6]],
+        };
+        let geojson = open.to_geojson();
+        assert_eq!(geojson["type"], "Polygon");
+        assert_eq!(
+            geojson["coordinates"],
+            json!([[[6.5, 52.7], [6.6, 52.7], [6.6, 52.6], [6.5, 52.7]]])
+        );
+
+        let closed = Geometry {
+            geometry_type: "Polygon".into(),
+            coordinates: geojson["coordinates"][0]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|p| serde_json::from_value(p.clone()).unwrap())
+                .collect(),
+        };
+        assert_eq!(closed.to_geojson(), geojson);
+    }
+
+    #[test]
+    fn test_radar_feature() {
+        let response: RadarResponse =
+            serde_json::from_str(brightsky::testing::fixtures::RADAR_PLAIN).unwrap();
+        let feature = response.to_geojson();
+
+        assert_eq!(feature["type"], "Feature");
+        assert_eq!(feature["geometry"]["type"], "Polygon");
+        assert_eq!(
+            feature["geometry"]["coordinates"][0]
+                .as_array()
+                .unwrap()
+                .len(),
+            5
+        );
+        assert_eq!(feature["properties"]["frames"], 3);
+        assert_eq!(
+            feature["properties"]["latlon_position"],
+            json!({"x": 302.0, "y": 401.0})
+        );
+        assert_eq!(
+            feature["properties"]["first_timestamp"],
+            "2023-08-08T11:45:00+00:00"
+        );
+
+        let empty = RadarResponse::default().to_geojson();
+        assert!(empty["geometry"].is_null());
+        assert!(empty["properties"]["last_timestamp"].is_null());
+    }
+
+    #[test]
+    fn test_alerts_feature_collection() {
+        let response: AlertsResponse =
+            serde_json::from_str(brightsky::testing::fixtures::ALERTS).unwrap();
+        let location = response.location.clone().unwrap();
+
+        let collection = response.to_geojson(Some(Coordinates {
+            lat: 52.52,
+            lon: 13.4,
+        }));
+        assert_eq!(collection["type"], "FeatureCollection");
+        let features = collection["features"].as_array().unwrap();
+        assert_eq!(features.len(), response.alerts.len());
+        assert_eq!(
+            features[0]["geometry"],
+            json!({"type": "Point", "coordinates": [13.4, 52.52]})
+        );
+        assert_eq!(
+            features[0]["properties"]["alert_id"],
+            response.alerts[0].alert_id
+        );
+        assert_eq!(
+            features[0]["properties"]["warn_cell_id"],
+            location.warn_cell_id
+        );
+
+        let without_point = response.to_geojson(None);
+        assert!(without_point["features"][0]["geometry"].is_null());
+    }
+}
+
+#[cfg(feature = "config")]
+mod config_tests {
+    use super::*;
+
+    #[test]
+    fn test_weather_query_from_config() {
+        let query: WeatherQuery = serde_json::from_str(
+            r#"{
+                "date": "2023-08-07",
+                "last_date": "2023-08-08T12:00:00+02:00",
+                "dwd_station_id": ["01766", "00420"],
+                "units": "si"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(query.date.unwrap().to_string(), "2023-08-07");
+        assert_eq!(
+            query.last_date.unwrap().to_string(),
+            "2023-08-08T12:00:00+02:00"
+        );
+        assert_eq!(
+            query.dwd_station_id,
+            Some(vec!["01766".to_string(), "00420".to_string()])
+        );
+        assert_eq!(query.units, Some(UnitType::Si));
+    }
+
+    #[test]
+    fn test_radar_and_alerts_query_from_config() {
+        let radar: RadarWeatherQuery = serde_json::from_str(
+            r#"{"lat": 52.52012, "lon": 13.4, "coordinate_precision": 2, "distance": 5000, "format": "plain"}"#,
+        )
+        .unwrap();
+        assert_eq!(radar.lat, Some(52.52012));
+        assert_eq!(radar.coordinate_precision, 2);
+        assert_eq!(radar.distance, Some(5000));
+        assert_eq!(
+            radar.compression_format,
+            Some(RadarCompressionFormat::Plain)
+        );
+
+        let alerts: AlertsQuery = serde_json::from_str(r#"{"warn_cell_id": 803159016}"#).unwrap();
+        assert_eq!(alerts.warn_cell_id.as_deref(), Some("803159016"));
+    }
+
+    #[test]
+    fn test_query_config_round_trip() {
+        let weather = WeatherQueryBuilder::new()
+            .with_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap())
+            .with_lat_lon((52.52, 13.4))
+            .with_max_dist(5000)
+            .with_units(UnitType::Dwd)
+            .build()
+            .unwrap();
+        let json = serde_json::to_string(&weather).unwrap();
+        assert_eq!(
+            json,
+            r#"{"lat":52.52,"lon":13.4,"date":"2023-08-07","max_dist":5000,"units":"dwd"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<WeatherQuery>(&json).unwrap(),
+            weather
+        );
+
+        let radar = RadarWeatherQueryBuilder::new()
+            .with_coordinate_precision(2)
+            .with_lat_lon((52.52012, 13.4))
+            .with_compression_format(RadarCompressionFormat::Bytes)
+            .build()
+            .unwrap();
+        let json = serde_json::to_string(&radar).unwrap();
+        assert_eq!(
+            json,
+            r#"{"lat":52.52,"lon":13.4,"coordinate_precision":2,"format":"bytes"}"#
+        );
+        // The coordinates are stored as sent
+        assert_eq!(
+            serde_json::from_str::<RadarWeatherQuery>(&json)
+                .unwrap()
+                .to_url_string(BRIGHT_SKY_API)
+                .unwrap(),
+            radar.to_url_string(BRIGHT_SKY_API).unwrap()
+        );
+
+        let current = CurrentWeatherQueryBuilder::new()
+            .with_source_id(vec![1234])
+            .build()
+            .unwrap();
+        let json = serde_json::to_string(&current).unwrap();
+        assert_eq!(json, r#"{"source_id":[1234]}"#);
+        assert_eq!(
+            serde_json::from_str::<CurrentWeatherQuery>(&json).unwrap(),
+            current
+        );
+
+        let alerts = AlertsQueryBuilder::new()
+            .with_warn_cell_id(803159016)
+            .build()
+            .unwrap();
+        assert_eq!(alerts.clone(), alerts);
+        let json = serde_json::to_string(&alerts).unwrap();
+        assert_eq!(json, r#"{"warn_cell_id":803159016}"#);
+        assert_eq!(serde_json::from_str::<AlertsQuery>(&json).unwrap(), alerts);
+    }
+
+    #[test]
+    fn test_invalid_config_is_rejected() {
+        let errors = [
+            // Missing date fails `build()`
+            serde_json::from_str::<WeatherQuery>(r#"{"lat": 52.52, "lon": 13.4}"#)
+                .map(|_| ())
+                .unwrap_err(),
+            serde_json::from_str::<CurrentWeatherQuery>(r#"{"lat": 52.52}"#)
+                .map(|_| ())
+                .unwrap_err(),
+            serde_json::from_str::<CurrentWeatherQuery>(r#"{"latitude": 52.52}"#)
+                .map(|_| ())
+                .unwrap_err(),
+            serde_json::from_str::<RadarWeatherQuery>(r#"{"date": "yesterday"}"#)
+                .map(|_| ())
+                .unwrap_err(),
+            serde_json::from_str::<RadarWeatherQuery>(r#"{"format": "png"}"#)
+                .map(|_| ())
+                .unwrap_err(),
+        ];
+
+        let messages: Vec<String> = errors.iter().map(|err| err.to_string()).collect();
+        assert!(messages[0].contains("Date is required"), "{}", messages[0]);
+        assert!(messages[1].contains("`lat` and `lon`"), "{}", messages[1]);
+        assert!(messages[2].contains("unknown field"), "{}", messages[2]);
+        assert!(messages[3].contains("RFC 3339"), "{}", messages[3]);
+        assert!(messages[4].contains("unknown variant"), "{}", messages[4]);
+    }
+}
+
+#[cfg(feature = "heapless")]
+mod bounded_tests {
+    use brightsky::bounded;
+
+    const CURRENT_WEATHER: &str = include_str!("../fixtures/current_weather.json");
+
+    #[test]
+    fn test_bounded_current_weather_matches_heap_types() {
+        let bounded: bounded::CurrentWeatherResponse =
+            bounded::CurrentWeatherResponse::from_slice(CURRENT_WEATHER.as_bytes()).unwrap();
+        let full: brightsky::types::CurrentWeatherResponse =
+            serde_json::from_str(CURRENT_WEATHER).unwrap();
+
+        assert_eq!(bounded.weather.timestamp, full.weather.timestamp);
+        assert_eq!(bounded.weather.temperature, full.weather.temperature);
+        assert_eq!(bounded.weather.icon, full.weather.icon);
+        assert_eq!(bounded.weather.condition, full.weather.condition);
+        assert_eq!(
+            bounded.weather.wind_gust_speed_60,
+            full.weather.wind_gust_speed_60
+        );
+        assert_eq!(bounded.sources.len(), full.sources.len());
+        for (bounded, full) in bounded.sources.iter().zip(&full.sources) {
+            assert_eq!(bounded.dwd_station_id, full.dwd_station_id.as_str());
+            assert_eq!(bounded.station_name, full.station_name.as_str());
+            assert_eq!(bounded.observation_type, full.observation_type.as_str());
+            assert_eq!(bounded.distance, full.distance);
+        }
+    }
+
+    #[test]
+    fn test_bounded_capacity_and_escapes() {
+        // Two sources do not fit into one
+        assert!(
+            bounded::CurrentWeatherResponse::<1>::from_slice(CURRENT_WEATHER.as_bytes()).is_err()
+        );
+
+        let escaped = CURRENT_WEATHER.replace("Berlin-Tempelhof", "M\\u00fcnster");
+        let response =
+            bounded::CurrentWeatherResponse::<4>::from_slice(escaped.as_bytes()).unwrap();
+        assert_eq!(response.sources[0].station_name, "Münster");
+    }
+}
@@ -384,4 +384,974 @@ mod ext_tests {
         assert_eq!(response.weather.len(), 1);
         assert_eq!(response.weather[0].temperature, Some(25.0));
     }
+
+    #[tokio::test]
+    async fn test_reqwest_ext_api_error() {
+        use brightsky::ext::ReqwestBrightSkyError;
+
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/current_weather"))
+            .respond_with(
+                ResponseTemplate::new(404)
+                    .set_body_string(r#"{"detail": "No sources match your criteria"}"#),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let query = CurrentWeatherQueryBuilder::new()
+            .with_lat_lon((10.0, 10.0))
+            .build()
+            .unwrap();
+
+        let result: Result<CurrentWeatherResponse, _> = client
+            .get_brightsky_with_host(query, &mock_server.uri())
+            .await;
+
+        match result {
+            Err(ReqwestBrightSkyError::Api(error)) => {
+                assert!(error.is_not_found());
+                assert_eq!(
+                    error.detail.as_deref(),
+                    Some("No sources match your criteria")
+                );
+            }
+            other => panic!("expected API error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reqwest_http_client() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/alerts"))
+            .and(query_param("warn_cell_id", "803159016"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"alerts": []}"#))
+            .mount(&mock_server)
+            .await;
+
+        let client = BrightSkyClient::new(reqwest::Client::new()).with_host(&mock_server.uri());
+
+        let query = AlertsQueryBuilder::new()
+            .with_warn_cell_id(803159016)
+            .build()
+            .unwrap();
+        let response: AlertsResponse = client.get(query).await.unwrap();
+        assert!(response.alerts.is_empty());
+
+        // No mock for this path, wiremock answers with 404
+        let query = CurrentWeatherQueryBuilder::new()
+            .with_lat_lon((52.52, 13.4))
+            .build()
+            .unwrap();
+        let result: Result<CurrentWeatherResponse, _> = client.get(query).await;
+        assert!(matches!(
+            result,
+            Err(ClientError::Api(BrightSkyApiError { status: 404, .. }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_reqwest_ext_future_is_send() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/alerts"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"alerts": []}"#))
+            .mount(&mock_server)
+            .await;
+
+        // Futures must stay `Send` on native targets so they can be spawned
+        let host = mock_server.uri();
+        let handle = tokio::spawn(async move {
+            let client = reqwest::Client::new();
+            let query = AlertsQueryBuilder::new().build().unwrap();
+            client
+                .get_brightsky_with_host::<_, AlertsResponse>(query, &host)
+                .await
+        });
+
+        let response = handle.await.unwrap().unwrap();
+        assert!(response.alerts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_client_builder_applies_options() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/alerts"))
+            .and(wiremock::matchers::header(
+                "user-agent",
+                "weather-station/1.0",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"alerts": []}"#))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/current_weather"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_delay(std::time::Duration::from_secs(5))
+                    .set_body_string("{}"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let client: BrightSkyClient<reqwest::Client> = BrightSkyClientBuilder::new()
+            .host(&mock_server.uri())
+            .user_agent("weather-station/1.0")
+            .header("X-Request-Source", "tests")
+            .timeout(std::time::Duration::from_millis(200))
+            .connect_timeout(std::time::Duration::from_secs(1))
+            .build()
+            .unwrap();
+
+        let query = AlertsQueryBuilder::new().build().unwrap();
+        let alerts: AlertsResponse = client.get(query).await.unwrap();
+        assert!(alerts.alerts.is_empty());
+
+        let query = CurrentWeatherQueryBuilder::new()
+            .with_lat_lon((52.52, 13.4))
+            .build()
+            .unwrap();
+        let timed_out = client.get::<_, CurrentWeatherResponse>(query).await;
+        match timed_out {
+            Err(ClientError::Http(err)) => assert!(err.is_timeout()),
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "compression")]
+    #[tokio::test]
+    async fn test_client_decompresses_responses() {
+        use flate2::{
+            Compression,
+            write::{GzEncoder, ZlibEncoder},
+        };
+        use std::io::Write;
+
+        let body = r#"{"alerts": [], "location": null}"#;
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(body.as_bytes()).unwrap();
+        let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+        zlib.write_all(body.as_bytes()).unwrap();
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/alerts"))
+            .and(query_param("warn_cell_id", "1"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-encoding", "gzip")
+                    .set_body_bytes(gzip.finish().unwrap()),
+            )
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/alerts"))
+            .and(query_param("warn_cell_id", "2"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-encoding", "deflate")
+                    .set_body_bytes(zlib.finish().unwrap()),
+            )
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/alerts"))
+            .and(query_param("warn_cell_id", "3"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![0x1f, 0x8b, 8, 0]))
+            .mount(&mock_server)
+            .await;
+
+        let client = BrightSkyClient::new(reqwest::Client::new()).with_host(&mock_server.uri());
+        let query = AlertsQueryBuilder::new()
+            .with_warn_cell_id(3)
+            .build()
+            .unwrap();
+        let truncated = client.get::<_, AlertsResponse>(query).await;
+        assert!(matches!(
+            truncated,
+            Err(ClientError::Decode(DecodeError::ContentEncoding))
+        ));
+
+        for warn_cell_id in [1, 2] {
+            let query = AlertsQueryBuilder::new()
+                .with_warn_cell_id(warn_cell_id)
+                .build()
+                .unwrap();
+            let response: AlertsResponse = client.get(query).await.unwrap();
+            assert!(response.alerts.is_empty());
+        }
+
+        let uncompressed = client.with_compression(false);
+        let query = AlertsQueryBuilder::new()
+            .with_warn_cell_id(1)
+            .build()
+            .unwrap();
+        let failed = uncompressed.get::<_, AlertsResponse>(query).await;
+        assert!(matches!(failed, Err(ClientError::Json(_))));
+
+        let requests = mock_server.received_requests().await.unwrap();
+        let accept_encoding: Vec<Option<&str>> = requests
+            .iter()
+            .map(|request| {
+                request
+                    .headers
+                    .get("accept-encoding")
+                    .map(|value| value.to_str().unwrap())
+            })
+            .collect();
+        assert_eq!(
+            accept_encoding,
+            [
+                Some("gzip, deflate"),
+                Some("gzip, deflate"),
+                Some("gzip, deflate"),
+                None
+            ]
+        );
+    }
+
+    #[cfg(feature = "compression")]
+    #[tokio::test]
+    async fn test_reqwest_ext_decompresses_responses() {
+        use flate2::{Compression, write::GzEncoder};
+        use std::io::Write;
+
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(br#"{"alerts": [], "location": null}"#)
+            .unwrap();
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/alerts"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(gzip.finish().unwrap()))
+            .mount(&mock_server)
+            .await;
+
+        let query = AlertsQueryBuilder::new().build().unwrap();
+        let response: AlertsResponse = reqwest::Client::new()
+            .get_brightsky_with_host(query, &mock_server.uri())
+            .await
+            .unwrap();
+        assert!(response.alerts.is_empty());
+
+        let requests = mock_server.received_requests().await.unwrap();
+        assert_eq!(
+            requests[0].headers.get("accept-encoding").unwrap(),
+            "gzip, deflate"
+        );
+    }
+
+    #[cfg(feature = "compression")]
+    #[tokio::test]
+    async fn test_client_max_response_bytes() {
+        use flate2::{Compression, write::GzEncoder};
+        use std::io::Write;
+
+        // Compresses to a few dozen bytes, but inflates to 10 KB
+        let padded = format!(
+            r#"{{"alerts": [], "location": null{}}}"#,
+            " ".repeat(10_000)
+        );
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(padded.as_bytes()).unwrap();
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/alerts"))
+            .and(query_param("warn_cell_id", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(gzip.finish().unwrap()))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/alerts"))
+            .and(query_param("warn_cell_id", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(padded))
+            .mount(&mock_server)
+            .await;
+
+        let client = BrightSkyClient::new(reqwest::Client::new())
+            .with_host(&mock_server.uri())
+            .with_max_response_bytes(1_000);
+        for warn_cell_id in [1, 2] {
+            let query = AlertsQueryBuilder::new()
+                .with_warn_cell_id(warn_cell_id)
+                .build()
+                .unwrap();
+            let result = client.get::<_, AlertsResponse>(query).await;
+            assert!(
+                matches!(
+                    result,
+                    Err(ClientError::Decode(DecodeError::ResponseTooLarge {
+                        limit: 1_000
+                    }))
+                ),
+                "warn cell {warn_cell_id}: {result:?}"
+            );
+        }
+
+        let client = client.with_max_response_bytes(20_000);
+        let query = AlertsQueryBuilder::new()
+            .with_warn_cell_id(1)
+            .build()
+            .unwrap();
+        let response: AlertsResponse = client.get(query).await.unwrap();
+        assert!(response.alerts.is_empty());
+
+        let requests = mock_server.received_requests().await.unwrap();
+        assert_eq!(
+            requests[0].headers.get("accept-encoding").unwrap(),
+            "gzip, deflate"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reqwest_stops_reading_past_the_limit() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // A chunked body of 100 KB without a `Content-Length`
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/alerts", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).await;
+            let head = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n";
+            stream.write_all(head.as_bytes()).await.unwrap();
+            for _ in 0..100 {
+                let chunk = format!("3e8\r\n{}\r\n", " ".repeat(1_000));
+                if stream.write_all(chunk.as_bytes()).await.is_err() {
+                    return;
+                }
+            }
+            let _ = stream.write_all(b"0\r\n\r\n").await;
+        });
+
+        let client = reqwest::Client::new();
+        let response = HttpClient::get_with_limit(&client, &url, &[], 5_000)
+            .await
+            .unwrap();
+        assert!(response.is_none());
+    }
+}
+
+#[cfg(feature = "stream")]
+mod stream_tests {
+    use super::*;
+    use brightsky::poll;
+    use futures_util::StreamExt;
+    use std::time::Duration;
+
+    fn response(timestamps: &[&str]) -> WeatherResponse {
+        let weather = timestamps
+            .iter()
+            .map(|timestamp| {
+                serde_json::from_value(serde_json::json!({
+                    "timestamp": timestamp,
+                    "source_id": 1,
+                    "temperature": 20.0
+                }))
+                .unwrap()
+            })
+            .collect();
+        WeatherResponse {
+            weather,
+            sources: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_weather_stream_yields_each_record_once() {
+        let mut responses = vec![
+            response(&["2023-08-07T00:00:00+00:00", "2023-08-07T01:00:00+00:00"]),
+            response(&["2023-08-07T01:00:00+00:00", "2023-08-07T02:00:00+00:00"]),
+            response(&["2023-08-07T02:00:00+00:00", "2023-08-07T03:00:00+00:00"]),
+        ]
+        .into_iter();
+
+        let query = WeatherQueryBuilder::new()
+            .with_date(NaiveDate::from_ymd_opt(2023, 8, 7).unwrap())
+            .with_dwd_station_id(vec!["01766"])
+            .build()
+            .unwrap();
+
+        let stream = poll::weather_stream(query, Duration::ZERO, move |_query| {
+            let next = responses.next();
+            async move { next.ok_or("exhausted") }
+        });
+
+        let timestamps: Vec<String> = stream
+            .take(4)
+            .map(|record| record.unwrap().timestamp.to_rfc3339())
+            .collect()
+            .await;
+
+        assert_eq!(
+            timestamps,
+            vec![
+                "2023-08-07T00:00:00+00:00",
+                "2023-08-07T01:00:00+00:00",
+                "2023-08-07T02:00:00+00:00",
+                "2023-08-07T03:00:00+00:00",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_alerts_stream_reports_changes() {
+        use brightsky::poll::AlertEvent;
+
+        let alerts = |ids: &[&str]| -> AlertsResponse {
+            let alerts: Vec<serde_json::Value> = ids
+                .iter()
+                .map(|id| {
+                    serde_json::json!({
+                        "id": 1,
+                        "alert_id": id,
+                        "status": "actual",
+                        "effective": "2023-08-07T08:00:00+00:00",
+                        "onset": "2023-08-07T10:00:00+00:00",
+                        "headline_en": "",
+                        "headline_de": "",
+                        "description_en": "",
+                        "description_de": ""
+                    })
+                })
+                .collect();
+            serde_json::from_value(serde_json::json!({ "alerts": alerts })).unwrap()
+        };
+
+        let mut responses = vec![
+            Ok(alerts(&["a"])),
+            Err("temporary failure"),
+            Ok(alerts(&["a", "b"])),
+            Ok(alerts(&["b"])),
+        ]
+        .into_iter();
+
+        let query = AlertsQueryBuilder::new()
+            .with_warn_cell_id(803159016)
+            .build()
+            .unwrap();
+
+        let stream = poll::alerts_stream(query, Duration::ZERO, move |_query| {
+            let next = responses.next().unwrap_or(Err("exhausted"));
+            async move { next }
+        });
+
+        let events: Vec<String> = stream
+            .take(4)
+            .map(|event| match event {
+                Ok(AlertEvent::New(alert)) => format!("new {}", alert.alert_id),
+                Ok(AlertEvent::Updated(alert)) => format!("updated {}", alert.alert_id),
+                Ok(AlertEvent::Expired(alert)) => format!("expired {}", alert.alert_id),
+                Err(err) => err.to_string(),
+            })
+            .collect()
+            .await;
+
+        assert_eq!(
+            events,
+            vec!["new a", "temporary failure", "new b", "expired a"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_alerts_scan_paces_requests() {
+        use brightsky::poll::RateLimit;
+
+        let rate = RateLimit::new(2, Duration::from_millis(60));
+        assert_eq!(rate.min_interval(), Duration::from_millis(30));
+
+        let queries = [803159016, 0, 805314000].into_iter().map(|cell| {
+            AlertsQueryBuilder::new()
+                .with_warn_cell_id(cell)
+                .build()
+                .unwrap()
+        });
+        let started = std::time::Instant::now();
+        let scan = poll::alerts_scan(queries, rate, |query| {
+            let result = match query.warn_cell_id.as_deref() {
+                Some("0") => Err("unknown cell"),
+                _ => Ok(serde_json::from_str::<AlertsResponse>(r#"{"alerts": []}"#).unwrap()),
+            };
+            async move { result }
+        });
+
+        let results: Vec<(String, bool)> = scan
+            .map(|(query, result)| (query.warn_cell_id.clone().unwrap(), result.is_ok()))
+            .collect()
+            .await;
+
+        assert_eq!(
+            results,
+            vec![
+                ("803159016".to_string(), true),
+                ("0".to_string(), false),
+                ("805314000".to_string(), true)
+            ]
+        );
+        assert!(started.elapsed() >= Duration::from_millis(60));
+    }
+}
+
+#[cfg(feature = "axum")]
+mod service_tests {
+    use super::*;
+    use brightsky::service::{WeatherService, axum};
+    use tower::ServiceExt;
+
+    async fn get(router: &axum::Router, uri: &str) -> (u16, String) {
+        let request = axum::http::Request::get(uri)
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        let status = response.status().as_u16();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_router_caches_alerts() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/alerts"))
+            .and(query_param("lat", "52.52"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string(r#"{"alerts": [], "location": null}"#),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = BrightSkyClient::new(reqwest::Client::new()).with_host(&mock_server.uri());
+        let router = WeatherService::new(client, (52.52, 13.4)).router();
+
+        for _ in 0..2 {
+            let (status, body) = get(&router, "/alerts").await;
+            assert_eq!(status, 200);
+            let response: AlertsResponse = serde_json::from_str(&body).unwrap();
+            assert!(response.alerts.is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_service_sends_one_request_for_concurrent_misses() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/alerts"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{"alerts": [], "location": null}"#)
+                    .set_delay(std::time::Duration::from_millis(100)),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = BrightSkyClient::new(reqwest::Client::new()).with_host(&mock_server.uri());
+        let service = WeatherService::new(client, (52.52, 13.4));
+
+        let (first, second, third) = tokio::join!(
+            service.alerts_with_provenance(),
+            service.alerts_with_provenance(),
+            service.alerts_with_provenance(),
+        );
+        let cached = [first, second, third].map(|result| result.unwrap().1.cached);
+        assert_eq!(cached.iter().filter(|cached| !**cached).count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_service_reports_cached_provenance() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/alerts"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string(r#"{"alerts": [], "location": null}"#),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = BrightSkyClient::new(reqwest::Client::new()).with_host(&mock_server.uri());
+        let service = WeatherService::new(client, (52.52, 13.4));
+
+        let (_, live) = service.alerts_with_provenance().await.unwrap();
+        let (_, cached) = service.alerts_with_provenance().await.unwrap();
+        assert!(!live.cached);
+        assert!(cached.cached);
+        assert_eq!(cached.requests, live.requests);
+        assert_eq!(cached.fetched_at, live.fetched_at);
+        assert_eq!(live.endpoints(), ["/alerts"]);
+    }
+
+    #[tokio::test]
+    async fn test_router_reports_upstream_errors() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/current_weather"))
+            .respond_with(ResponseTemplate::new(500).set_body_string("Internal Server Error"))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let client = BrightSkyClient::new(reqwest::Client::new()).with_host(&mock_server.uri());
+        let router = WeatherService::new(client, (52.52, 13.4)).router();
+
+        // Errors are not cached
+        for _ in 0..2 {
+            let (status, _) = get(&router, "/current_weather").await;
+            assert_eq!(status, 502);
+        }
+        assert_eq!(get(&router, "/unknown").await.0, 404);
+    }
+
+    #[tokio::test]
+    async fn test_service_ttl_zero_disables_cache() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/alerts"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"alerts": []}"#))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let client = BrightSkyClient::new(reqwest::Client::new()).with_host(&mock_server.uri());
+        let service =
+            WeatherService::new(client, (52.52, 13.4)).with_ttl(std::time::Duration::ZERO);
+        service.alerts().await.unwrap();
+        service.alerts().await.unwrap();
+    }
+}
+
+#[cfg(feature = "influx")]
+mod influx_tests {
+    use super::*;
+    use brightsky::influx::{InfluxError, InfluxWriter};
+    use wiremock::matchers::{body_string_contains, header};
+
+    const CURRENT_WEATHER: &str = r#"{
+        "weather": {"timestamp": "2023-08-07T12:00:00+00:00", "source_id": 1234, "temperature": 22.3},
+        "sources": [{
+            "id": 1234, "dwd_station_id": "01766", "wmo_station_id": "10315",
+            "station_name": "Münster/Osnabrück", "observation_type": "synop",
+            "first_record": "2010-01-01T00:00:00+00:00", "last_record": "2023-08-07T12:00:00+00:00",
+            "lat": 52.1347, "lon": 7.6969, "height": 48.0, "distance": 5420.3
+        }]
+    }"#;
+
+    #[tokio::test]
+    async fn test_influx_writer_v2() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v2/write"))
+            .and(query_param("org", "home"))
+            .and(query_param("bucket", "weather"))
+            .and(query_param("precision", "ns"))
+            .and(header("authorization", "Token secret"))
+            .and(body_string_contains(
+                "current_weather,units=si,location=berlin,dwd_station_id=01766,observation_type=synop source_id=1234i,temperature=22.3 1691409600000000000",
+            ))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let writer = InfluxWriter::v2(
+            reqwest::Client::new(),
+            &mock_server.uri(),
+            "home",
+            "weather",
+        )
+        .unwrap()
+        .with_token("secret")
+        .with_tag("location", "berlin")
+        .with_units(UnitType::Si);
+        let response: CurrentWeatherResponse = serde_json::from_str(CURRENT_WEATHER).unwrap();
+        writer.write_current_weather(&response).await.unwrap();
+
+        // Nothing to write, nothing sent
+        let empty = WeatherResponse {
+            weather: vec![],
+            sources: vec![],
+        };
+        writer.write_weather(&empty).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_influx_writer_reports_errors() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/write"))
+            .and(query_param("db", "weather"))
+            .respond_with(ResponseTemplate::new(400).set_body_string("unable to parse"))
+            .mount(&mock_server)
+            .await;
+
+        let writer =
+            InfluxWriter::v1(reqwest::Client::new(), &mock_server.uri(), "weather").unwrap();
+        let response: CurrentWeatherResponse = serde_json::from_str(CURRENT_WEATHER).unwrap();
+        match writer.write_current_weather(&response).await {
+            Err(InfluxError::Status { status, body }) => {
+                assert_eq!(status, 400);
+                assert_eq!(body, "unable to parse");
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+}
+
+#[cfg(feature = "reqwest")]
+mod sink_tests {
+    use super::*;
+    use brightsky::poll::AlertEvent;
+    use brightsky::sink::{AlertSink, WebhookSink};
+    use wiremock::matchers::{body_partial_json, header};
+
+    fn alert() -> Alert {
+        serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "alert_id": "2.49.0.0.276.0.DWD.PVW.1",
+            "status": "actual",
+            "effective": "2023-08-07T08:00:00+00:00",
+            "onset": "2023-08-07T10:00:00+00:00",
+            "expires": null,
+            "severity": "severe",
+            "headline_en": "Official WARNING of GALE-FORCE GUSTS",
+            "headline_de": "Amtliche UNWETTERWARNUNG vor ORKANBÖEN",
+            "description_en": "",
+            "description_de": ""
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_webhook_sink_posts_json() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .and(header("authorization", "Bearer secret"))
+            .and(body_partial_json(serde_json::json!({
+                "event": "expired",
+                "alert": {"alert_id": "2.49.0.0.276.0.DWD.PVW.1", "severity": "severe"}
+            })))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .and(body_partial_json(serde_json::json!({
+                "alert_id": "2.49.0.0.276.0.DWD.PVW.1"
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let sink = WebhookSink::new(
+            reqwest::Client::new(),
+            format!("{}/hook", mock_server.uri()),
+        )
+        .with_header("Authorization", "Bearer secret");
+        sink.notify_event(&AlertEvent::Expired(alert()))
+            .await
+            .unwrap();
+        sink.notify(&alert()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_webhook_sink_reports_error_status() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&mock_server)
+            .await;
+
+        let sink = WebhookSink::new(reqwest::Client::new(), mock_server.uri());
+        let err = sink.notify(&alert()).await.unwrap_err();
+        assert_eq!(err.status().map(|status| status.as_u16()), Some(500));
+    }
+}
+
+#[cfg(feature = "reqwless")]
+mod reqwless_tests {
+    use super::*;
+    use brightsky::ext::reqwless_get;
+    use core::net::{IpAddr, Ipv4Addr, SocketAddr};
+    use embedded_io_adapters::tokio_1::FromTokio;
+    use embedded_nal_async::{AddrType, Dns, TcpConnect};
+    use reqwless::client::HttpClient;
+
+    /// Plain TCP over tokio, standing in for `embassy_net::tcp::client::TcpClient`.
+    struct TokioTcp;
+
+    impl TcpConnect for TokioTcp {
+        type Error = std::io::Error;
+        type Connection<'a> = FromTokio<tokio::net::TcpStream>;
+
+        async fn connect<'a>(
+            &'a self,
+            remote: SocketAddr,
+        ) -> Result<Self::Connection<'a>, Self::Error> {
+            Ok(FromTokio::new(
+                tokio::net::TcpStream::connect(remote).await?,
+            ))
+        }
+    }
+
+    struct LoopbackDns;
+
+    impl Dns for LoopbackDns {
+        type Error = std::io::Error;
+
+        async fn get_host_by_name(&self, _: &str, _: AddrType) -> Result<IpAddr, Self::Error> {
+            Ok(IpAddr::V4(Ipv4Addr::LOCALHOST))
+        }
+
+        async fn get_host_by_address(&self, _: IpAddr, _: &mut [u8]) -> Result<usize, Self::Error> {
+            Err(std::io::ErrorKind::Unsupported.into())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reqwless_get() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/current_weather"))
+            .and(query_param("lat", "52.52"))
+            .and(query_param("lon", "13.4"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{
+                    "weather": {"timestamp": "2023-08-07T12:00:00+00:00", "source_id": 1234, "temperature": 22.3},
+                    "sources": [{
+                        "id": 1234, "dwd_station_id": "01766", "wmo_station_id": "10315",
+                        "station_name": "Münster/Osnabrück", "observation_type": "synop",
+                        "first_record": "2010-01-01T00:00:00+00:00", "last_record": "2023-08-07T12:00:00+00:00",
+                        "lat": 52.1347, "lon": 7.6969, "height": 48.0, "distance": 5420.3
+                    }]
+                }"#,
+            ))
+            .mount(&mock_server)
+            .await;
+
+        let mut client = HttpClient::new(&TokioTcp, &LoopbackDns);
+        let query = CurrentWeatherQueryBuilder::new()
+            .with_lat_lon((52.52, 13.4))
+            .build()
+            .unwrap();
+        let mut rx_buf = [0; 4096];
+        let response: CurrentWeatherResponse =
+            reqwless_get(&mut client, query, &mock_server.uri(), &mut rx_buf)
+                .await
+                .unwrap();
+        assert_eq!(response.weather.temperature, Some(22.3));
+        assert_eq!(response.sources[0].station_name, "Münster/Osnabrück");
+    }
+
+    #[tokio::test]
+    async fn test_reqwless_get_errors() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/alerts"))
+            .respond_with(
+                ResponseTemplate::new(400).set_body_string(r#"{"detail": "Invalid warn_cell_id"}"#),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let mut client = HttpClient::new(&TokioTcp, &LoopbackDns);
+        let query = AlertsQueryBuilder::new()
+            .with_warn_cell_id(1)
+            .build()
+            .unwrap();
+        let mut rx_buf = [0; 4096];
+        let result: Result<AlertsResponse, _> =
+            reqwless_get(&mut client, query, &mock_server.uri(), &mut rx_buf).await;
+        match result {
+            Err(BrightSkyError::Transport(TransportError::Api(error))) => {
+                assert_eq!(error.status, 400);
+                assert_eq!(error.detail.as_deref(), Some("Invalid warn_cell_id"));
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+
+        // The response does not fit into the buffer
+        let query = CurrentWeatherQueryBuilder::new()
+            .with_lat_lon((52.52, 13.4))
+            .build()
+            .unwrap();
+        let mut rx_buf = [0; 16];
+        let result: Result<CurrentWeatherResponse, _> =
+            reqwless_get(&mut client, query, &mock_server.uri(), &mut rx_buf).await;
+        assert!(matches!(
+            result,
+            Err(BrightSkyError::Transport(TransportError::Http(_)))
+        ));
+    }
+
+    #[cfg(feature = "compression")]
+    #[tokio::test]
+    async fn test_reqwless_get_decompresses_responses() {
+        use brightsky::ext::{ReqwlessOptions, reqwless_get_with};
+        use flate2::{Compression, write::GzEncoder};
+        use std::io::Write;
+
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(br#"{"alerts": [], "location": null}"#)
+            .unwrap();
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/alerts"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(gzip.finish().unwrap()))
+            .mount(&mock_server)
+            .await;
+
+        let mut client = HttpClient::new(&TokioTcp, &LoopbackDns);
+        let mut rx_buf = [0; 4096];
+        let query = AlertsQueryBuilder::new().build().unwrap();
+        let response: AlertsResponse =
+            reqwless_get(&mut client, query, &mock_server.uri(), &mut rx_buf)
+                .await
+                .unwrap();
+        assert!(response.alerts.is_empty());
+
+        let options = ReqwlessOptions::new()
+            .with_user_agent("weather-station/1.0")
+            .with_compression(false);
+        let query = AlertsQueryBuilder::new().build().unwrap();
+        let result: Result<AlertsResponse, _> =
+            reqwless_get_with(&mut client, query, &mock_server.uri(), &mut rx_buf, options).await;
+        assert!(matches!(
+            result,
+            Err(BrightSkyError::Decode(DecodeError::Json(_)))
+        ));
+
+        let requests = mock_server.received_requests().await.unwrap();
+        let header = |index: usize, name: &str| {
+            requests[index]
+                .headers
+                .get(name)
+                .map(|value| value.to_str().unwrap().to_string())
+        };
+        assert_eq!(header(0, "user-agent").as_deref(), Some(DEFAULT_USER_AGENT));
+        assert_eq!(
+            header(0, "accept-encoding").as_deref(),
+            Some("gzip, deflate")
+        );
+        assert_eq!(
+            header(1, "user-agent").as_deref(),
+            Some("weather-station/1.0")
+        );
+        assert_eq!(header(1, "accept-encoding"), None);
+    }
 }